@@ -117,7 +117,7 @@ impl Buf {
 
     /// Return slice for copying data from Buf to user space.
     pub(crate) fn buf_to_read(&self, max: usize) -> &[u8] {
-        let ptr = self.data.as_ptr() as *const u8;
+        let ptr = unsafe { self.data.as_ptr().add(self.offset) as *const u8 };
         let len = max.min(self.init - self.offset);
         unsafe { std::slice::from_raw_parts(ptr, len) }
     }
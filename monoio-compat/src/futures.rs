@@ -0,0 +1,281 @@
+//! Adapters between futures-io traits and monoio IO traits.
+//!
+//! [`StreamWrapper`] implements futures-io `AsyncRead`, `AsyncBufRead` and
+//! `AsyncWrite` for monoio IO types, and [`RentWrapper`] implements
+//! `AsyncReadRent`, `AsyncWriteRent` and `AsyncBufRead` for futures-io types.
+
+use std::{
+    future::Future,
+    io,
+    io::IoSlice,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+use monoio::{
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
+    io::{AsyncReadRent, AsyncWriteRent},
+    BufResult,
+};
+
+use crate::StreamWrapper;
+
+impl<T: AsyncReadRent + Unpin + 'static> AsyncRead for StreamWrapper<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut read_buf = tokio::io::ReadBuf::new(buf);
+        std::task::ready!(tokio::io::AsyncRead::poll_read(self, cx, &mut read_buf))?;
+        Poll::Ready(Ok(read_buf.filled().len()))
+    }
+}
+
+impl<T: AsyncReadRent + Unpin + 'static> AsyncBufRead for StreamWrapper<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        std::task::ready!(this.poll_fill_read_buf(cx))?;
+        Poll::Ready(Ok(this.buffered(usize::MAX)))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_buffered(amt);
+    }
+}
+
+impl<T: AsyncWriteRent + Unpin + 'static> AsyncWrite for StreamWrapper<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write(self, cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        tokio::io::AsyncWrite::poll_flush(self, cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        tokio::io::AsyncWrite::poll_shutdown(self, cx)
+    }
+}
+
+/// A wrapper for stream which impl futures-io AsyncRead and AsyncWrite.
+/// The Wrapper will impl AsyncReadRent and AsyncWriteRent, and AsyncBufRead
+/// if the stream impl futures-io AsyncBufRead.
+///
+/// Like [`crate::RentWrapper`], the owned buffer is filled or consumed
+/// directly and no data is lost when a future is dropped.
+pub struct RentWrapper<T> {
+    stream: T,
+}
+
+impl<T> RentWrapper<T> {
+    /// Creates a new `RentWrapper` from a futures-io `AsyncRead` or
+    /// `AsyncWrite`.
+    pub fn new(stream: T) -> Self {
+        Self { stream }
+    }
+
+    /// Consume self and get inner T.
+    pub fn into_inner(self) -> T {
+        self.stream
+    }
+
+    /// Get a reference to inner T.
+    pub fn get_ref(&self) -> &T {
+        &self.stream
+    }
+
+    /// Get a mutable reference to inner T.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.stream
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncReadRent for RentWrapper<T> {
+    fn read<B: IoBufMut>(&mut self, buf: B) -> impl Future<Output = BufResult<usize, B>> {
+        ReadFuture {
+            stream: &mut self.stream,
+            buf: Some(buf),
+        }
+    }
+
+    fn readv<B: IoVecBufMut>(&mut self, buf: B) -> impl Future<Output = BufResult<usize, B>> {
+        ReadvFuture {
+            stream: &mut self.stream,
+            buf: Some(buf),
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWriteRent for RentWrapper<T> {
+    fn write<B: IoBuf>(&mut self, buf: B) -> impl Future<Output = BufResult<usize, B>> {
+        WriteFuture {
+            stream: &mut self.stream,
+            buf: Some(buf),
+        }
+    }
+
+    fn writev<B: IoVecBuf>(&mut self, buf_vec: B) -> impl Future<Output = BufResult<usize, B>> {
+        WritevFuture {
+            stream: &mut self.stream,
+            buf: Some(buf_vec),
+        }
+    }
+
+    fn flush(&mut self) -> impl Future<Output = io::Result<()>> {
+        std::future::poll_fn(|cx| Pin::new(&mut self.stream).poll_flush(cx))
+    }
+
+    fn shutdown(&mut self) -> impl Future<Output = io::Result<()>> {
+        std::future::poll_fn(|cx| Pin::new(&mut self.stream).poll_close(cx))
+    }
+}
+
+impl<T: AsyncBufRead + Unpin> monoio::io::AsyncBufRead for RentWrapper<T> {
+    type FillBufFuture<'a>
+        = FillBufFuture<'a, T>
+    where
+        Self: 'a;
+
+    fn fill_buf(&mut self) -> Self::FillBufFuture<'_> {
+        FillBufFuture {
+            stream: Some(&mut self.stream),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        Pin::new(&mut self.stream).consume(amt);
+    }
+}
+
+struct ReadFuture<'a, T, B> {
+    stream: &'a mut T,
+    buf: Option<B>,
+}
+
+// The buffer is owned and never pinned.
+impl<'a, T, B> Unpin for ReadFuture<'a, T, B> {}
+
+impl<'a, T: AsyncRead + Unpin, B: IoBufMut> Future for ReadFuture<'a, T, B> {
+    type Output = BufResult<usize, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let buf = this.buf.as_mut().expect("future polled after completion");
+        let slice = unsafe { init_slice(buf.write_ptr(), buf.bytes_total()) };
+        let r = std::task::ready!(Pin::new(&mut *this.stream).poll_read(cx, slice)).map(|n| {
+            unsafe { buf.set_init(n) };
+            n
+        });
+        Poll::Ready((r, this.buf.take().unwrap()))
+    }
+}
+
+struct ReadvFuture<'a, T, B> {
+    stream: &'a mut T,
+    buf: Option<B>,
+}
+
+impl<'a, T, B> Unpin for ReadvFuture<'a, T, B> {}
+
+impl<'a, T: AsyncRead + Unpin, B: IoVecBufMut> Future for ReadvFuture<'a, T, B> {
+    type Output = BufResult<usize, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let buf = this.buf.as_mut().expect("future polled after completion");
+        // Read into the first non-empty iovec only, as a short read is allowed.
+        let iovecs =
+            unsafe { std::slice::from_raw_parts(buf.write_iovec_ptr(), buf.write_iovec_len()) };
+        let slice = match iovecs.iter().find(|iovec| iovec.iov_len != 0) {
+            Some(iovec) => unsafe { init_slice(iovec.iov_base as *mut u8, iovec.iov_len) },
+            None => return Poll::Ready((Ok(0), this.buf.take().unwrap())),
+        };
+        let r = std::task::ready!(Pin::new(&mut *this.stream).poll_read(cx, slice)).map(|n| {
+            unsafe { buf.set_init(n) };
+            n
+        });
+        Poll::Ready((r, this.buf.take().unwrap()))
+    }
+}
+
+// futures-io reads into initialized memory only.
+unsafe fn init_slice<'a>(ptr: *mut u8, len: usize) -> &'a mut [u8] {
+    std::ptr::write_bytes(ptr, 0, len);
+    std::slice::from_raw_parts_mut(ptr, len)
+}
+
+struct WriteFuture<'a, T, B> {
+    stream: &'a mut T,
+    buf: Option<B>,
+}
+
+impl<'a, T, B> Unpin for WriteFuture<'a, T, B> {}
+
+impl<'a, T: AsyncWrite + Unpin, B: IoBuf> Future for WriteFuture<'a, T, B> {
+    type Output = BufResult<usize, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let buf = this.buf.as_ref().expect("future polled after completion");
+        let slice = unsafe { std::slice::from_raw_parts(buf.read_ptr(), buf.bytes_init()) };
+        let r = std::task::ready!(Pin::new(&mut *this.stream).poll_write(cx, slice));
+        Poll::Ready((r, this.buf.take().unwrap()))
+    }
+}
+
+struct WritevFuture<'a, T, B> {
+    stream: &'a mut T,
+    buf: Option<B>,
+}
+
+impl<'a, T, B> Unpin for WritevFuture<'a, T, B> {}
+
+impl<'a, T: AsyncWrite + Unpin, B: IoVecBuf> Future for WritevFuture<'a, T, B> {
+    type Output = BufResult<usize, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let buf = this.buf.as_ref().expect("future polled after completion");
+        let iovecs =
+            unsafe { std::slice::from_raw_parts(buf.read_iovec_ptr(), buf.read_iovec_len()) };
+        let slices: Vec<IoSlice<'_>> = iovecs
+            .iter()
+            .map(|iovec| {
+                IoSlice::new(unsafe {
+                    std::slice::from_raw_parts(iovec.iov_base as *const u8, iovec.iov_len)
+                })
+            })
+            .collect();
+        let r = std::task::ready!(Pin::new(&mut *this.stream).poll_write_vectored(cx, &slices));
+        Poll::Ready((r, this.buf.take().unwrap()))
+    }
+}
+
+/// Future returned by `RentWrapper::fill_buf`.
+pub struct FillBufFuture<'a, T> {
+    stream: Option<&'a mut T>,
+}
+
+impl<'a, T: AsyncBufRead + Unpin> Future for FillBufFuture<'a, T> {
+    type Output = io::Result<&'a [u8]>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let stream = this.stream.take().expect("future polled after completion");
+        match Pin::new(&mut *stream).poll_fill_buf(cx) {
+            // # Safety
+            // The stream is borrowed for 'a and not used by this future again.
+            Poll::Ready(r) => Poll::Ready(r.map(|slice| unsafe { &*(slice as *const [u8]) })),
+            Poll::Pending => {
+                this.stream = Some(stream);
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Serve hyper services on monoio.
+//!
+//! [`serve`] runs the accept loop and spawns every connection on the current
+//! thread, so the service and its futures do not have to be `Send`. Use
+//! [`serve_with_shutdown`] to stop accepting on a signal and wait for in-flight
+//! connections to finish.
+//!
+//! It lives here rather than in `monoio::net`, as it builds on the tokio IO
+//! adapters of this crate, which depends on monoio, so monoio can not
+//! re-export it.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    error::Error as StdError,
+    future::{poll_fn, Future},
+    io,
+    pin::pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use hyper::{body::HttpBody, server::conn::Http, service::Service, Body, Request, Response};
+use monoio::net::TcpListener;
+
+use crate::TcpStreamCompat;
+
+/// Executor which spawns hyper background tasks on the current thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HyperExecutor;
+
+impl<F> hyper::rt::Executor<F> for HyperExecutor
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    fn execute(&self, fut: F) {
+        monoio::spawn(fut);
+    }
+}
+
+/// Serve connections accepted from the listener with the given service.
+///
+/// The service is cloned for each connection. It only returns when accept
+/// fails.
+pub async fn serve<S, B>(listener: TcpListener, service: S) -> io::Result<()>
+where
+    S: Service<Request<Body>, Response = Response<B>> + Clone + 'static,
+    S::Error: Into<Box<dyn StdError + Send + Sync>>,
+    S::Future: 'static,
+    B: HttpBody + 'static,
+    B::Error: Into<Box<dyn StdError + Send + Sync>>,
+{
+    serve_with_shutdown(listener, service, std::future::pending()).await
+}
+
+/// Like [`serve`], but shut down gracefully when `signal` completes.
+///
+/// Once the signal fires the listener stops accepting, every connection is
+/// asked to finish its in-flight requests and close, and this function returns
+/// after all of them are done. Idle keep-alive connections are closed right
+/// away.
+pub async fn serve_with_shutdown<S, B, F>(
+    listener: TcpListener,
+    service: S,
+    signal: F,
+) -> io::Result<()>
+where
+    S: Service<Request<Body>, Response = Response<B>> + Clone + 'static,
+    S::Error: Into<Box<dyn StdError + Send + Sync>>,
+    S::Future: 'static,
+    B: HttpBody + 'static,
+    B::Error: Into<Box<dyn StdError + Send + Sync>>,
+    F: Future<Output = ()>,
+{
+    let http = Http::new().with_executor(HyperExecutor);
+    let graceful = Rc::new(Graceful::default());
+    let mut signal = pin!(signal);
+
+    loop {
+        let mut accept = pin!(listener.accept());
+        let accepted = poll_fn(|cx| {
+            if signal.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+            accept.as_mut().poll(cx).map(Some)
+        })
+        .await;
+        let stream = match accepted {
+            Some(r) => r?.0,
+            None => break,
+        };
+
+        let conn = http.serve_connection(TcpStreamCompat::new(stream), service.clone());
+        let watcher = Watcher::new(graceful.clone());
+        monoio::spawn(async move {
+            let mut conn = pin!(conn);
+            let mut notified = false;
+            let _ = poll_fn(|cx| {
+                if !notified && watcher.poll_shutdown(cx).is_ready() {
+                    conn.as_mut().graceful_shutdown();
+                    notified = true;
+                }
+                conn.as_mut().poll(cx)
+            })
+            .await;
+        });
+    }
+
+    graceful.shutdown().await;
+    Ok(())
+}
+
+/// Shutdown state shared by the accept loop and connections.
+#[derive(Default)]
+struct Graceful {
+    shutdown: Cell<bool>,
+    next_id: Cell<u64>,
+    alive: Cell<usize>,
+    // Wakers of the connections which are waiting for shutdown, keyed by id.
+    watchers: RefCell<HashMap<u64, Waker>>,
+    drained: RefCell<Option<Waker>>,
+}
+
+impl Graceful {
+    /// Notify all connections and wait for them to finish.
+    async fn shutdown(&self) {
+        self.shutdown.set(true);
+        let watchers = std::mem::take(&mut *self.watchers.borrow_mut());
+        for (_, waker) in watchers {
+            waker.wake();
+        }
+        poll_fn(|cx| {
+            if self.alive.get() == 0 {
+                return Poll::Ready(());
+            }
+            *self.drained.borrow_mut() = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await;
+    }
+}
+
+/// Registration of a connection, alive until the connection task ends.
+struct Watcher {
+    id: u64,
+    graceful: Rc<Graceful>,
+}
+
+impl Watcher {
+    fn new(graceful: Rc<Graceful>) -> Self {
+        let id = graceful.next_id.get();
+        graceful.next_id.set(id + 1);
+        graceful.alive.set(graceful.alive.get() + 1);
+        Self { id, graceful }
+    }
+
+    fn poll_shutdown(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.graceful.shutdown.get() {
+            return Poll::Ready(());
+        }
+        let mut watchers = self.graceful.watchers.borrow_mut();
+        match watchers.get_mut(&self.id) {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            Some(waker) => *waker = cx.waker().clone(),
+            None => {
+                watchers.insert(self.id, cx.waker().clone());
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.graceful.watchers.borrow_mut().remove(&self.id);
+        let alive = self.graceful.alive.get() - 1;
+        self.graceful.alive.set(alive);
+        if alive == 0 {
+            if let Some(waker) = self.graceful.drained.borrow_mut().take() {
+                waker.wake();
+            }
+        }
+    }
+}
@@ -1,13 +1,19 @@
 //! For compat with tokio AsyncRead and AsyncWrite.
 
-#![feature(new_uninit)]
-
 mod box_future;
 mod buf;
+#[cfg(feature = "futures")]
+pub mod futures;
+#[cfg(feature = "hyper")]
+pub mod hyper;
 
+mod rent_wrapper;
 mod safe_wrapper;
+#[cfg(feature = "tower")]
+pub mod service;
 mod tcp_unsafe;
 
+pub use rent_wrapper::RentWrapper;
 pub use safe_wrapper::StreamWrapper;
 pub use tcp_unsafe::TcpStreamCompat as TcpStreamCompatUnsafe;
 pub use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
@@ -71,4 +77,200 @@ mod tests {
         monoio::spawn(server);
         client.await;
     }
+
+    #[cfg(unix)]
+    #[monoio::test_all]
+    async fn test_unix_rw_shutdown() {
+        use crate::UnixStreamCompat;
+
+        let (a, b) = monoio::net::UnixStream::pair().unwrap();
+        let mut a = UnixStreamCompat::new(a);
+        let mut b = UnixStreamCompat::new(b);
+
+        a.write_all(b"hello").await.unwrap();
+        a.shutdown().await.unwrap();
+        let mut buf = Vec::new();
+        b.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(buf, b"hello");
+    }
+
+    #[monoio::test_all]
+    async fn test_rent_wrapper() {
+        use monoio::io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt};
+
+        use crate::RentWrapper;
+
+        let (client, mut server) = tokio::io::duplex(64);
+        let mut client = RentWrapper::new(client);
+        monoio::spawn(async move {
+            let mut buf = [0u8; 10];
+            server.read_exact(&mut buf).await.unwrap();
+            buf[0] += 1;
+            server.write_all(&buf).await.unwrap();
+        });
+
+        let (r, _) = client.write_all(vec![65u8; 10]).await;
+        r.unwrap();
+        client.flush().await.unwrap();
+        let (r, buf) = client.read_exact(vec![0u8; 10]).await;
+        r.unwrap();
+        assert_eq!(buf[0], 66);
+        assert_eq!(&buf[1..], &[65u8; 9]);
+
+        // Server is gone, so reads hit eof.
+        let (r, buf) = client.read(Vec::with_capacity(10)).await;
+        assert_eq!(r.unwrap(), 0);
+        assert!(buf.is_empty());
+
+        let buf: monoio::buf::VecBuf = vec![vec![1, 2], vec![3]].into();
+        let (r, _) = client.writev(buf).await;
+        assert!(r.is_err());
+        client.shutdown().await.unwrap();
+    }
+
+    #[cfg(all(unix, feature = "futures"))]
+    #[monoio::test_all]
+    async fn test_futures_io() {
+        use ::futures::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Cursor};
+        use monoio::io::{AsyncBufRead, AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt};
+
+        use crate::{futures::RentWrapper, UnixStreamCompat};
+
+        // monoio stream used as futures-io.
+        let (a, b) = monoio::net::UnixStream::pair().unwrap();
+        let mut a = UnixStreamCompat::new(a);
+        let mut b = UnixStreamCompat::new(b);
+        AsyncWriteExt::write_all(&mut a, b"hello\nworld")
+            .await
+            .unwrap();
+        AsyncWriteExt::close(&mut a).await.unwrap();
+        let mut line = String::new();
+        b.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "hello\n");
+        let mut rest = String::new();
+        AsyncReadExt::read_to_string(&mut b, &mut rest)
+            .await
+            .unwrap();
+        assert_eq!(rest, "world");
+
+        // futures-io types used as monoio streams.
+        let mut reader = RentWrapper::new(BufReader::new(Cursor::new(b"hello".to_vec())));
+        assert_eq!(reader.fill_buf().await.unwrap(), b"hello");
+        reader.consume(2);
+        let (r, buf) = reader.read_exact(vec![0; 3]).await;
+        r.unwrap();
+        assert_eq!(buf, b"llo");
+
+        let mut writer = RentWrapper::new(Cursor::new(Vec::new()));
+        let (r, _) = writer.write_all(b"hello".to_vec()).await;
+        r.unwrap();
+        let buf: monoio::buf::VecBuf = vec![b" ".to_vec(), b"world".to_vec()].into();
+        let (r, _) = writer.writev(buf).await;
+        assert_eq!(r.unwrap(), 6);
+        assert_eq!(writer.into_inner().into_inner(), b"hello world");
+    }
+
+    #[cfg(feature = "tower")]
+    #[monoio::test_all]
+    async fn test_tower_service() {
+        use std::{cell::Cell, rc::Rc};
+
+        use tower_service::Service;
+
+        use crate::service::{local_service_fn, FromTower, IntoTower, LocalService};
+
+        let counter = Rc::new(Cell::new(0));
+        let c = counter.clone();
+        let svc = local_service_fn(move |req: u32| {
+            let c = c.clone();
+            async move {
+                monoio::spawn(async {}).await;
+                c.set(c.get() + 1);
+                Ok::<_, ()>(req + 1)
+            }
+        });
+
+        let mut tower_svc = IntoTower::new(svc);
+        std::future::poll_fn(|cx| tower_svc.poll_ready(cx))
+            .await
+            .unwrap();
+        assert_eq!(tower_svc.call(1).await, Ok(2));
+
+        let local_svc = FromTower::new(tower_svc);
+        assert_eq!(local_svc.call(2).await, Ok(3));
+        assert_eq!(local_svc.call(3).await, Ok(4));
+        assert_eq!(counter.get(), 3);
+    }
+
+    // The tokio traits imported above are implemented by monoio streams with the
+    // `tokio-compat` feature, which makes the rent methods ambiguous.
+    #[cfg(feature = "hyper")]
+    mod serve {
+        #[monoio::test_all]
+        async fn test_hyper_serve() {
+            use hyper::{service::service_fn, Body, Request, Response};
+            use monoio::io::{AsyncReadRent, AsyncWriteRentExt};
+
+            let listener = monoio::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let service = service_fn(|_req: Request<Body>| async {
+                Ok::<_, std::convert::Infallible>(Response::new(Body::from("hello monoio")))
+            });
+            let client = monoio::spawn(async move {
+                let mut conn = monoio::net::TcpStream::connect(addr).await.unwrap();
+                let req = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n".to_vec();
+                let (r, _) = conn.write_all(req).await;
+                r.unwrap();
+                let mut resp = Vec::new();
+                while !resp.ends_with(b"hello monoio") {
+                    let (r, buf) = conn.read(Vec::with_capacity(1024)).await;
+                    assert!(r.unwrap() > 0);
+                    resp.extend_from_slice(&buf);
+                }
+                assert!(resp.starts_with(b"HTTP/1.1 200 OK"));
+            });
+            crate::hyper::serve_with_shutdown(listener, service, client)
+                .await
+                .unwrap();
+        }
+
+        #[monoio::test_all]
+        async fn test_hyper_shutdown_closes_keep_alive() {
+            use std::{cell::RefCell, rc::Rc};
+
+            use hyper::{service::service_fn, Body, Request, Response};
+            use monoio::io::{AsyncReadRent, AsyncWriteRentExt};
+
+            let listener = monoio::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let service = service_fn(|_req: Request<Body>| async {
+                Ok::<_, std::convert::Infallible>(Response::new(Body::from("hello monoio")))
+            });
+            let kept = Rc::new(RefCell::new(None));
+            let shutdown = {
+                let kept = kept.clone();
+                async move {
+                    let mut conn = monoio::net::TcpStream::connect(addr).await.unwrap();
+                    let req = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n".to_vec();
+                    conn.write_all(req).await.0.unwrap();
+                    let mut resp = Vec::new();
+                    while !resp.ends_with(b"hello monoio") {
+                        let (r, buf) = conn.read(Vec::with_capacity(1024)).await;
+                        assert!(r.unwrap() > 0);
+                        resp.extend_from_slice(&buf);
+                    }
+                    // The client keeps the connection open across shutdown.
+                    *kept.borrow_mut() = Some(conn);
+                }
+            };
+            crate::hyper::serve_with_shutdown(listener, service, shutdown)
+                .await
+                .unwrap();
+
+            // The server closed the idle connection.
+            let mut conn = kept.borrow_mut().take().unwrap();
+            let (r, _) = conn.read(Vec::with_capacity(16)).await;
+            assert_eq!(r.unwrap(), 0);
+        }
+    }
 }
@@ -0,0 +1,243 @@
+use std::{
+    future::Future,
+    io,
+    io::IoSlice,
+    mem::MaybeUninit,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use monoio::{
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
+    io::{AsyncReadRent, AsyncWriteRent},
+    BufResult,
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A wrapper for stream which impl tokio AsyncRead and AsyncWrite.
+/// The Wrapper will impl AsyncReadRent and AsyncWriteRent.
+/// Mainly used for reusing foreign IO types(like TLS streams) with monoio
+/// native code.
+///
+/// A poll-based read or write either completes in place or does nothing, so
+/// the owned buffer is filled or consumed directly and no data is lost when a
+/// future is dropped.
+pub struct RentWrapper<T> {
+    stream: T,
+}
+
+impl<T> RentWrapper<T> {
+    /// Creates a new `RentWrapper` from a tokio `AsyncRead` or `AsyncWrite`.
+    pub fn new(stream: T) -> Self {
+        Self { stream }
+    }
+
+    /// Consume self and get inner T.
+    pub fn into_inner(self) -> T {
+        self.stream
+    }
+
+    /// Get a reference to inner T.
+    pub fn get_ref(&self) -> &T {
+        &self.stream
+    }
+
+    /// Get a mutable reference to inner T.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.stream
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncReadRent for RentWrapper<T> {
+    fn read<B: IoBufMut>(&mut self, buf: B) -> impl Future<Output = BufResult<usize, B>> {
+        ReadFuture {
+            stream: &mut self.stream,
+            buf: Some(buf),
+        }
+    }
+
+    fn readv<B: IoVecBufMut>(&mut self, buf: B) -> impl Future<Output = BufResult<usize, B>> {
+        ReadvFuture {
+            stream: &mut self.stream,
+            buf: Some(buf),
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWriteRent for RentWrapper<T> {
+    fn write<B: IoBuf>(&mut self, buf: B) -> impl Future<Output = BufResult<usize, B>> {
+        WriteFuture {
+            stream: &mut self.stream,
+            buf: Some(buf),
+        }
+    }
+
+    fn writev<B: IoVecBuf>(&mut self, buf_vec: B) -> impl Future<Output = BufResult<usize, B>> {
+        WritevFuture {
+            stream: &mut self.stream,
+            buf: Some(buf_vec),
+        }
+    }
+
+    fn flush(&mut self) -> impl Future<Output = std::io::Result<()>> {
+        FlushFuture {
+            stream: &mut self.stream,
+        }
+    }
+
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
+        ShutdownFuture {
+            stream: &mut self.stream,
+        }
+    }
+}
+
+/// Future returned by `RentWrapper::read`.
+pub struct ReadFuture<'a, T, B> {
+    stream: &'a mut T,
+    buf: Option<B>,
+}
+
+// The buffer is owned and never pinned.
+impl<'a, T, B> Unpin for ReadFuture<'a, T, B> {}
+
+impl<'a, T: AsyncRead + Unpin, B: IoBufMut> Future for ReadFuture<'a, T, B> {
+    type Output = BufResult<usize, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let buf = this.buf.as_mut().expect("future polled after completion");
+        let slice = unsafe {
+            std::slice::from_raw_parts_mut(
+                buf.write_ptr() as *mut MaybeUninit<u8>,
+                buf.bytes_total(),
+            )
+        };
+        let r = poll_read_to(&mut *this.stream, cx, slice);
+        let r = std::task::ready!(r).map(|n| {
+            unsafe { buf.set_init(n) };
+            n
+        });
+        Poll::Ready((r, this.buf.take().unwrap()))
+    }
+}
+
+/// Future returned by `RentWrapper::readv`.
+pub struct ReadvFuture<'a, T, B> {
+    stream: &'a mut T,
+    buf: Option<B>,
+}
+
+impl<'a, T, B> Unpin for ReadvFuture<'a, T, B> {}
+
+impl<'a, T: AsyncRead + Unpin, B: IoVecBufMut> Future for ReadvFuture<'a, T, B> {
+    type Output = BufResult<usize, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let buf = this.buf.as_mut().expect("future polled after completion");
+        // Read into the first non-empty iovec only, as a short read is allowed.
+        let iovecs =
+            unsafe { std::slice::from_raw_parts(buf.write_iovec_ptr(), buf.write_iovec_len()) };
+        let slice = match iovecs.iter().find(|iovec| iovec.iov_len != 0) {
+            Some(iovec) => unsafe {
+                std::slice::from_raw_parts_mut(
+                    iovec.iov_base as *mut MaybeUninit<u8>,
+                    iovec.iov_len,
+                )
+            },
+            None => return Poll::Ready((Ok(0), this.buf.take().unwrap())),
+        };
+        let r = poll_read_to(&mut *this.stream, cx, slice);
+        let r = std::task::ready!(r).map(|n| {
+            unsafe { buf.set_init(n) };
+            n
+        });
+        Poll::Ready((r, this.buf.take().unwrap()))
+    }
+}
+
+fn poll_read_to<T: AsyncRead + Unpin>(
+    stream: &mut T,
+    cx: &mut Context<'_>,
+    slice: &mut [MaybeUninit<u8>],
+) -> Poll<io::Result<usize>> {
+    let mut read_buf = ReadBuf::uninit(slice);
+    std::task::ready!(Pin::new(stream).poll_read(cx, &mut read_buf))?;
+    Poll::Ready(Ok(read_buf.filled().len()))
+}
+
+/// Future returned by `RentWrapper::write`.
+pub struct WriteFuture<'a, T, B> {
+    stream: &'a mut T,
+    buf: Option<B>,
+}
+
+impl<'a, T, B> Unpin for WriteFuture<'a, T, B> {}
+
+impl<'a, T: AsyncWrite + Unpin, B: IoBuf> Future for WriteFuture<'a, T, B> {
+    type Output = BufResult<usize, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let buf = this.buf.as_ref().expect("future polled after completion");
+        let slice = unsafe { std::slice::from_raw_parts(buf.read_ptr(), buf.bytes_init()) };
+        let r = std::task::ready!(Pin::new(&mut *this.stream).poll_write(cx, slice));
+        Poll::Ready((r, this.buf.take().unwrap()))
+    }
+}
+
+/// Future returned by `RentWrapper::writev`.
+pub struct WritevFuture<'a, T, B> {
+    stream: &'a mut T,
+    buf: Option<B>,
+}
+
+impl<'a, T, B> Unpin for WritevFuture<'a, T, B> {}
+
+impl<'a, T: AsyncWrite + Unpin, B: IoVecBuf> Future for WritevFuture<'a, T, B> {
+    type Output = BufResult<usize, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let buf = this.buf.as_ref().expect("future polled after completion");
+        let iovecs =
+            unsafe { std::slice::from_raw_parts(buf.read_iovec_ptr(), buf.read_iovec_len()) };
+        let slices: Vec<IoSlice<'_>> = iovecs
+            .iter()
+            .map(|iovec| {
+                IoSlice::new(unsafe {
+                    std::slice::from_raw_parts(iovec.iov_base as *const u8, iovec.iov_len)
+                })
+            })
+            .collect();
+        let r = std::task::ready!(Pin::new(&mut *this.stream).poll_write_vectored(cx, &slices));
+        Poll::Ready((r, this.buf.take().unwrap()))
+    }
+}
+
+/// Future returned by `RentWrapper::flush`.
+pub struct FlushFuture<'a, T> {
+    stream: &'a mut T,
+}
+
+impl<'a, T: AsyncWrite + Unpin> Future for FlushFuture<'a, T> {
+    type Output = io::Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.get_mut().stream).poll_flush(cx)
+    }
+}
+
+/// Future returned by `RentWrapper::shutdown`.
+pub struct ShutdownFuture<'a, T> {
+    stream: &'a mut T,
+}
+
+impl<'a, T: AsyncWrite + Unpin> Future for ShutdownFuture<'a, T> {
+    type Output = io::Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.get_mut().stream).poll_shutdown(cx)
+    }
+}
@@ -54,50 +54,77 @@ impl<T> StreamWrapper<T> {
     }
 }
 
-impl<T: AsyncReadRent + Unpin + 'static> tokio::io::AsyncRead for StreamWrapper<T> {
-    fn poll_read(
-        self: std::pin::Pin<&mut Self>,
+impl<T: AsyncReadRent + Unpin + 'static> StreamWrapper<T> {
+    /// Read into the inner buffer if it is empty. It is left empty on eof.
+    pub(crate) fn poll_fill_read_buf(
+        &mut self,
         cx: &mut std::task::Context<'_>,
-        buf: &mut tokio::io::ReadBuf<'_>,
     ) -> std::task::Poll<std::io::Result<()>> {
-        let this = self.get_mut();
-
         loop {
             // if the future not armed, this means maybe buffer has data.
-            if !this.read_fut.armed() {
-                // if there is some data left in our buf, copy it and return.
-                let read_buf_mut = unsafe { this.read_buf.as_mut().unwrap_unchecked() };
-                if !read_buf_mut.is_empty() {
-                    // copy directly from inner buf to buf
-                    let our_buf = read_buf_mut.buf_to_read(buf.remaining());
-                    let our_buf_len = our_buf.len();
-                    buf.put_slice(our_buf);
-                    unsafe { read_buf_mut.advance_offset(our_buf_len) };
+            if !self.read_fut.armed() {
+                // # Safety
+                // We always make sure the read_buf is Some when no future is armed.
+                if !unsafe { self.read_buf.as_ref().unwrap_unchecked() }.is_empty() {
                     return std::task::Poll::Ready(Ok(()));
                 }
 
                 // there is no data in buffer. we will construct the future
-                let buf = unsafe { this.read_buf.take().unwrap_unchecked() };
+                let buf = unsafe { self.read_buf.take().unwrap_unchecked() };
                 // we must leak the stream
-                #[allow(clippy::cast_ref_to_mut)]
-                let stream = unsafe { &mut *(&this.stream as *const T as *mut T) };
-                this.read_fut.arm_future(AsyncReadRent::read(stream, buf));
+                #[allow(invalid_reference_casting)]
+                let stream = unsafe { &mut *(&self.stream as *const T as *mut T) };
+                self.read_fut.arm_future(AsyncReadRent::read(stream, buf));
             }
 
             // the future slot is armed now. we will poll it.
-            let (ret, buf) = match this.read_fut.poll(cx) {
+            let (ret, buf) = match self.read_fut.poll(cx) {
                 std::task::Poll::Ready(out) => out,
                 std::task::Poll::Pending => {
                     return std::task::Poll::Pending;
                 }
             };
-            this.read_buf = Some(buf);
+            self.read_buf = Some(buf);
             if ret? == 0 {
                 // on eof, return directly; otherwise goto next loop.
                 return std::task::Poll::Ready(Ok(()));
             }
         }
     }
+
+    /// Data in the inner buffer, up to `max` bytes.
+    pub(crate) fn buffered(&self, max: usize) -> &[u8] {
+        self.read_buf
+            .as_ref()
+            .map_or(&[][..], |read_buf| read_buf.buf_to_read(max))
+    }
+
+    /// Mark `amt` bytes of the inner buffer as read.
+    pub(crate) fn consume_buffered(&mut self, amt: usize) {
+        if let Some(read_buf) = self.read_buf.as_mut() {
+            let amt = amt.min(read_buf.buf_to_read(usize::MAX).len());
+            // # Safety
+            // The data before the new offset is initialized.
+            unsafe { read_buf.advance_offset(amt) };
+        }
+    }
+}
+
+impl<T: AsyncReadRent + Unpin + 'static> tokio::io::AsyncRead for StreamWrapper<T> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        std::task::ready!(this.poll_fill_read_buf(cx))?;
+        // copy directly from inner buf to buf
+        let our_buf = this.buffered(buf.remaining());
+        let our_buf_len = our_buf.len();
+        buf.put_slice(our_buf);
+        this.consume_buffered(our_buf_len);
+        std::task::Poll::Ready(Ok(()))
+    }
 }
 
 impl<T: AsyncWriteRent + Unpin + 'static> tokio::io::AsyncWrite for StreamWrapper<T> {
@@ -145,7 +172,7 @@ impl<T: AsyncWriteRent + Unpin + 'static> tokio::io::AsyncWrite for StreamWrappe
         unsafe { owned_buf.set_init(len) };
 
         // we must leak the stream
-        #[allow(clippy::cast_ref_to_mut)]
+        #[allow(invalid_reference_casting)]
         let stream = unsafe { &mut *(&this.stream as *const T as *mut T) };
         this.write_fut
             .arm_future(AsyncWriteRentExt::write_all(stream, owned_buf));
@@ -184,7 +211,7 @@ impl<T: AsyncWriteRent + Unpin + 'static> tokio::io::AsyncWrite for StreamWrappe
         }
 
         if !this.flush_fut.armed() {
-            #[allow(clippy::cast_ref_to_mut)]
+            #[allow(invalid_reference_casting)]
             let stream = unsafe { &mut *(&this.stream as *const T as *mut T) };
             this.flush_fut.arm_future(stream.flush());
         }
@@ -211,7 +238,7 @@ impl<T: AsyncWriteRent + Unpin + 'static> tokio::io::AsyncWrite for StreamWrappe
         }
 
         if !this.shutdown_fut.armed() {
-            #[allow(clippy::cast_ref_to_mut)]
+            #[allow(invalid_reference_casting)]
             let stream = unsafe { &mut *(&this.stream as *const T as *mut T) };
             this.shutdown_fut.arm_future(stream.shutdown());
         }
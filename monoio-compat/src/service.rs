@@ -0,0 +1,218 @@
+//! Adapters between tower `Service` and monoio local service.
+//!
+//! Tasks in monoio are `!Send`, so a service usually holds `Rc`s and returns
+//! `!Send` futures. [`LocalService`] models such a service: it takes `&self`
+//! and its future may borrow from it. Use [`IntoTower`] to put it into a tower
+//! middleware stack, and [`FromTower`] to call a tower stack as a
+//! [`LocalService`] again.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+/// A boxed future which is not `Send`.
+pub type LocalBoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// An asynchronous function from `Request` to `Response` which runs on the
+/// current thread.
+///
+/// Unlike tower `Service`, there is no `poll_ready` and `call` takes `&self`,
+/// so the service can be shared between tasks of the same thread without
+/// cloning.
+pub trait LocalService<Request> {
+    /// Responses given by the service.
+    type Response;
+    /// Errors produced by the service.
+    type Error;
+    /// The future response value.
+    type Future<'cx>: Future<Output = Result<Self::Response, Self::Error>>
+    where
+        Self: 'cx;
+
+    /// Process the request and return the response asynchronously.
+    fn call(&self, req: Request) -> Self::Future<'_>;
+}
+
+impl<Request, S: LocalService<Request>> LocalService<Request> for Rc<S> {
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future<'cx>
+        = S::Future<'cx>
+    where
+        Self: 'cx;
+
+    #[inline]
+    fn call(&self, req: Request) -> Self::Future<'_> {
+        (**self).call(req)
+    }
+}
+
+/// Create a [`LocalService`] from an async function.
+pub fn local_service_fn<F>(f: F) -> LocalServiceFn<F> {
+    LocalServiceFn { f }
+}
+
+/// A [`LocalService`] implemented by a closure. See [`local_service_fn`].
+#[derive(Clone, Copy, Debug)]
+pub struct LocalServiceFn<F> {
+    f: F,
+}
+
+impl<F, Request, R, E, Fut> LocalService<Request> for LocalServiceFn<F>
+where
+    F: Fn(Request) -> Fut,
+    Fut: Future<Output = Result<R, E>>,
+{
+    type Response = R;
+    type Error = E;
+    type Future<'cx>
+        = Fut
+    where
+        Self: 'cx;
+
+    #[inline]
+    fn call(&self, req: Request) -> Self::Future<'_> {
+        (self.f)(req)
+    }
+}
+
+/// Wrap a [`LocalService`] as a tower `Service`.
+///
+/// The service is always ready, and the returned futures are boxed and `!Send`.
+/// Cloning it only clones an `Rc`, so it fits middlewares which clone the
+/// inner service for each request.
+pub struct IntoTower<S> {
+    inner: Rc<S>,
+}
+
+impl<S> IntoTower<S> {
+    /// Wrap a [`LocalService`].
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner: Rc::new(inner),
+        }
+    }
+
+    /// Wrap a shared [`LocalService`].
+    pub fn from_rc(inner: Rc<S>) -> Self {
+        Self { inner }
+    }
+
+    /// Get the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S> Clone for IntoTower<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S, Request> tower_service::Service<Request> for IntoTower<S>
+where
+    S: LocalService<Request> + 'static,
+    Request: 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = LocalBoxFuture<'static, Result<S::Response, S::Error>>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let inner = self.inner.clone();
+        Box::pin(async move { inner.call(req).await })
+    }
+}
+
+/// Wrap a tower `Service` as a [`LocalService`].
+///
+/// For each call the inner service is cloned, then driven until ready before
+/// the request is dispatched. This is the usual way to share a tower service,
+/// and most middlewares are cheap to clone.
+#[derive(Clone, Debug)]
+pub struct FromTower<T> {
+    inner: T,
+}
+
+impl<T> FromTower<T> {
+    /// Wrap a tower `Service`.
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    /// Get the inner service.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consume self and return the inner service.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, Request> LocalService<Request> for FromTower<T>
+where
+    T: tower_service::Service<Request> + Clone,
+{
+    type Response = T::Response;
+    type Error = T::Error;
+    type Future<'cx>
+        = FromTowerFuture<T, Request>
+    where
+        Self: 'cx;
+
+    #[inline]
+    fn call(&self, req: Request) -> Self::Future<'_> {
+        FromTowerFuture {
+            state: State::NotReady(self.inner.clone(), Some(req)),
+        }
+    }
+}
+
+/// Future returned by [`FromTower`].
+pub struct FromTowerFuture<T: tower_service::Service<Request>, Request> {
+    state: State<T, Request>,
+}
+
+enum State<T: tower_service::Service<Request>, Request> {
+    NotReady(T, Option<Request>),
+    Called(Pin<Box<T::Future>>),
+}
+
+// The inner future is boxed and the service and request are never pinned.
+impl<T: tower_service::Service<Request>, Request> Unpin for FromTowerFuture<T, Request> {}
+
+impl<T, Request> Future for FromTowerFuture<T, Request>
+where
+    T: tower_service::Service<Request>,
+{
+    type Output = Result<T::Response, T::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.state {
+                State::NotReady(svc, req) => {
+                    if let Err(e) = std::task::ready!(svc.poll_ready(cx)) {
+                        return Poll::Ready(Err(e));
+                    }
+                    let req = req.take().expect("future polled after completion");
+                    this.state = State::Called(Box::pin(svc.call(req)));
+                }
+                State::Called(fut) => return fut.as_mut().poll(cx),
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+/* C API of monoio, see monoio-ffi/src/lib.rs for details. */
+
+#ifndef MONOIO_H
+#define MONOIO_H
+
+#include <stddef.h>
+#include <stdint.h>
+#include <sys/types.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct monoio_runtime_t monoio_runtime_t;
+typedef struct monoio_stream_t monoio_stream_t;
+
+/* Callback of a spawned task. */
+typedef void (*monoio_callback_t)(void *user_data);
+/* Callback of an IO operation, result is the number of bytes transferred or a
+ * negated errno. */
+typedef void (*monoio_io_callback_t)(void *user_data, ssize_t result);
+
+/* Functions returning int return 0 on success or a negated errno. */
+
+int monoio_runtime_new(uint32_t entries, monoio_runtime_t **out);
+void monoio_runtime_free(monoio_runtime_t *rt);
+int monoio_runtime_fd(const monoio_runtime_t *rt);
+int monoio_runtime_turn(monoio_runtime_t *rt);
+int64_t monoio_runtime_next_timeout(const monoio_runtime_t *rt);
+int monoio_runtime_run(monoio_runtime_t *rt);
+
+int monoio_spawn(const monoio_runtime_t *rt, monoio_callback_t cb, void *user_data);
+
+int monoio_read_at(const monoio_runtime_t *rt, int fd, uint8_t *buf, size_t len,
+                   uint64_t offset, monoio_io_callback_t cb, void *user_data);
+int monoio_write_at(const monoio_runtime_t *rt, int fd, const uint8_t *buf, size_t len,
+                    uint64_t offset, monoio_io_callback_t cb, void *user_data);
+
+int monoio_stream_new(const monoio_runtime_t *rt, int fd, monoio_stream_t **out);
+void monoio_stream_close(monoio_stream_t *stream);
+int monoio_stream_read(const monoio_stream_t *stream, uint8_t *buf, size_t len,
+                       monoio_io_callback_t cb, void *user_data);
+int monoio_stream_write(const monoio_stream_t *stream, const uint8_t *buf, size_t len,
+                        monoio_io_callback_t cb, void *user_data);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MONOIO_H */
@@ -0,0 +1,558 @@
+//! C API to embed monoio.
+//!
+//! The API is callback based, see `include/monoio.h` for the declarations.
+//! A runtime and everything created from it must only be used on the thread
+//! which created the runtime. Functions returning `int` return 0 on success or
+//! a negated errno. IO callbacks receive the number of bytes transferred or a
+//! negated errno.
+//!
+//! Callbacks are invoked while the runtime is driven by
+//! [`monoio_runtime_turn`] or [`monoio_runtime_run`]. They may submit new
+//! operations, but must not drive or free the runtime.
+
+#![allow(non_camel_case_types)]
+
+use std::{
+    cell::{Cell, UnsafeCell},
+    ffi::{c_int, c_void},
+    future::{poll_fn, Future},
+    io,
+    os::unix::io::{AsRawFd, FromRawFd, IntoRawFd},
+    rc::Rc,
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+use monoio::{
+    buf::RawBuf,
+    fs::File,
+    io::{AsyncReadRent, AsyncWriteRent, OwnedReadHalf, OwnedWriteHalf, Splitable},
+    net::TcpStream,
+    time::TimeDriver,
+    FusionDriver, FusionRuntime, RuntimeBuilder,
+};
+
+#[cfg(target_os = "linux")]
+type Runtime = FusionRuntime<TimeDriver<monoio::IoUringDriver>, TimeDriver<monoio::LegacyDriver>>;
+#[cfg(not(target_os = "linux"))]
+type Runtime = FusionRuntime<TimeDriver<monoio::LegacyDriver>>;
+
+/// Callback of a spawned task.
+pub type monoio_callback_t = Option<unsafe extern "C" fn(user_data: *mut c_void)>;
+
+/// Callback of an IO operation.
+pub type monoio_io_callback_t = Option<unsafe extern "C" fn(user_data: *mut c_void, result: isize)>;
+
+/// A monoio runtime.
+pub struct monoio_runtime_t {
+    rt: UnsafeCell<Runtime>,
+    // Set while the runtime is driven. The runtime is borrowed mutably then,
+    // so callbacks use the current runtime context instead of `rt`.
+    running: Cell<bool>,
+    pending: Rc<Pending>,
+}
+
+/// A stream socket registered to a runtime.
+pub struct monoio_stream_t {
+    rt: *const monoio_runtime_t,
+    // Taken while an operation is in flight.
+    read: Rc<Cell<Option<OwnedReadHalf<TcpStream>>>>,
+    write: Rc<Cell<Option<OwnedWriteHalf<TcpStream>>>>,
+}
+
+/// Count of tasks which have not invoked their callbacks.
+#[derive(Default)]
+struct Pending {
+    count: Cell<usize>,
+    idle: Cell<Option<Waker>>,
+}
+
+struct PendingGuard(Rc<Pending>);
+
+impl PendingGuard {
+    fn new(pending: &Rc<Pending>) -> Self {
+        pending.count.set(pending.count.get() + 1);
+        Self(pending.clone())
+    }
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        let count = self.0.count.get() - 1;
+        self.0.count.set(count);
+        if count == 0 {
+            if let Some(waker) = self.0.idle.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl monoio_runtime_t {
+    fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        if self.running.get() {
+            f()
+        } else {
+            unsafe { &*self.rt.get() }.enter(f)
+        }
+    }
+
+    fn spawn<F: Future<Output = ()> + 'static>(&self, fut: F) {
+        let guard = PendingGuard::new(&self.pending);
+        self.enter(|| {
+            monoio::spawn(async move {
+                fut.await;
+                drop(guard);
+            })
+        });
+    }
+
+    fn drive<R>(&self, f: impl FnOnce(&mut Runtime) -> R) -> Result<R, c_int> {
+        if self.running.replace(true) {
+            return Err(-libc::EBUSY);
+        }
+        let r = f(unsafe { &mut *self.rt.get() });
+        self.running.set(false);
+        Ok(r)
+    }
+}
+
+/// A file borrowed from the caller, which is not closed on drop.
+struct BorrowedFile(Option<File>);
+
+impl BorrowedFile {
+    fn get(&self) -> &File {
+        self.0.as_ref().unwrap()
+    }
+
+    fn release(mut self) {
+        let _ = self.0.take().unwrap().into_raw_fd();
+    }
+}
+
+impl Drop for BorrowedFile {
+    fn drop(&mut self) {
+        // The op is canceled and may still refer to the fd.
+        if let Some(file) = self.0.take() {
+            std::mem::forget(file);
+        }
+    }
+}
+
+fn errno(e: &io::Error) -> c_int {
+    -e.raw_os_error().unwrap_or(libc::EIO)
+}
+
+fn io_result(res: io::Result<usize>) -> isize {
+    match res {
+        Ok(n) => n as isize,
+        Err(e) => errno(&e) as isize,
+    }
+}
+
+/// Create a runtime with timer enabled. `entries` is the io_uring queue size,
+/// 0 for the default.
+///
+/// # Safety
+///
+/// `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_runtime_new(
+    entries: u32,
+    out: *mut *mut monoio_runtime_t,
+) -> c_int {
+    if out.is_null() {
+        return -libc::EINVAL;
+    }
+    let mut builder = RuntimeBuilder::<FusionDriver>::new();
+    if entries != 0 {
+        builder = builder.with_entries(entries);
+    }
+    match builder.enable_timer().build() {
+        Ok(rt) => {
+            let rt = monoio_runtime_t {
+                rt: UnsafeCell::new(rt),
+                running: Cell::new(false),
+                pending: Rc::new(Pending::default()),
+            };
+            *out = Box::into_raw(Box::new(rt));
+            0
+        }
+        Err(e) => errno(&e),
+    }
+}
+
+/// Free a runtime. Pending operations are dropped without invoking their
+/// callbacks.
+///
+/// # Safety
+///
+/// `rt` must be created by [`monoio_runtime_new`] and not be driven.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_runtime_free(rt: *mut monoio_runtime_t) {
+    if !rt.is_null() {
+        drop(Box::from_raw(rt));
+    }
+}
+
+/// Get the fd of the runtime. It becomes readable when the runtime has IO to
+/// process.
+///
+/// # Safety
+///
+/// `rt` must be a valid runtime.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_runtime_fd(rt: *const monoio_runtime_t) -> c_int {
+    (*(*rt).rt.get()).as_raw_fd()
+}
+
+/// Run ready tasks and process finished IO without blocking. Returns 1 if
+/// there are still ready tasks, then the caller should turn again soon.
+/// Otherwise returns 0, and the caller can wait for the runtime fd to be
+/// readable or the next timeout to elapse.
+///
+/// # Safety
+///
+/// `rt` must be a valid runtime.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_runtime_turn(rt: *mut monoio_runtime_t) -> c_int {
+    (*rt).drive(|rt| rt.turn() as c_int).unwrap_or_else(|e| e)
+}
+
+/// Milliseconds until the earliest timer expires, or -1 if there is none.
+///
+/// # Safety
+///
+/// `rt` must be a valid runtime.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_runtime_next_timeout(rt: *const monoio_runtime_t) -> i64 {
+    match (*(*rt).rt.get()).next_timeout() {
+        // Round up so the timer is expired when the caller wakes up.
+        Some(d) => (d + Duration::from_nanos(999_999))
+            .as_millis()
+            .try_into()
+            .unwrap_or(i64::MAX),
+        None => -1,
+    }
+}
+
+/// Block until the callbacks of all submitted tasks and operations are
+/// invoked.
+///
+/// # Safety
+///
+/// `rt` must be a valid runtime.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_runtime_run(rt: *mut monoio_runtime_t) -> c_int {
+    let pending = (*rt).pending.clone();
+    (*rt)
+        .drive(|rt| {
+            rt.block_on(poll_fn(|cx| {
+                if pending.count.get() == 0 {
+                    return Poll::Ready(());
+                }
+                pending.idle.set(Some(cx.waker().clone()));
+                Poll::Pending
+            }))
+        })
+        .err()
+        .unwrap_or(0)
+}
+
+/// Invoke `cb` on the runtime as a task.
+///
+/// # Safety
+///
+/// `rt` must be a valid runtime.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_spawn(
+    rt: *const monoio_runtime_t,
+    cb: monoio_callback_t,
+    user_data: *mut c_void,
+) -> c_int {
+    let Some(cb) = cb else {
+        return -libc::EINVAL;
+    };
+    (*rt).spawn(async move { cb(user_data) });
+    0
+}
+
+/// Read from `fd` at `offset` into `buf`. The fd is not owned by the runtime.
+///
+/// # Safety
+///
+/// `rt` must be a valid runtime, and `buf` must be valid for `len` bytes until
+/// `cb` is invoked.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_read_at(
+    rt: *const monoio_runtime_t,
+    fd: c_int,
+    buf: *mut u8,
+    len: usize,
+    offset: u64,
+    cb: monoio_io_callback_t,
+    user_data: *mut c_void,
+) -> c_int {
+    let Some(cb) = cb else {
+        return -libc::EINVAL;
+    };
+    (*rt).spawn(async move {
+        let file = BorrowedFile(Some(File::from_raw_fd(fd)));
+        let (res, _) = file.get().read_at(RawBuf::new(buf, len), offset).await;
+        file.release();
+        cb(user_data, io_result(res));
+    });
+    0
+}
+
+/// Write `buf` to `fd` at `offset`. The fd is not owned by the runtime.
+///
+/// # Safety
+///
+/// `rt` must be a valid runtime, and `buf` must be valid for `len` bytes until
+/// `cb` is invoked.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_write_at(
+    rt: *const monoio_runtime_t,
+    fd: c_int,
+    buf: *const u8,
+    len: usize,
+    offset: u64,
+    cb: monoio_io_callback_t,
+    user_data: *mut c_void,
+) -> c_int {
+    let Some(cb) = cb else {
+        return -libc::EINVAL;
+    };
+    (*rt).spawn(async move {
+        let file = BorrowedFile(Some(File::from_raw_fd(fd)));
+        let (res, _) = file.get().write_at(RawBuf::new(buf, len), offset).await;
+        file.release();
+        cb(user_data, io_result(res));
+    });
+    0
+}
+
+/// Register a connected stream socket to the runtime. The runtime owns the fd
+/// on success.
+///
+/// # Safety
+///
+/// `rt` must be a valid runtime which outlives the stream, and `out` must be a
+/// valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_stream_new(
+    rt: *const monoio_runtime_t,
+    fd: c_int,
+    out: *mut *mut monoio_stream_t,
+) -> c_int {
+    if out.is_null() {
+        return -libc::EINVAL;
+    }
+    let std_stream = std::net::TcpStream::from_raw_fd(fd);
+    if let Err(e) = std_stream.set_nonblocking(true) {
+        let _ = std_stream.into_raw_fd();
+        return errno(&e);
+    }
+    match (*rt).enter(|| TcpStream::from_std(std_stream)) {
+        Ok(stream) => {
+            let (read, write) = stream.into_split();
+            let stream = monoio_stream_t {
+                rt,
+                read: Rc::new(Cell::new(Some(read))),
+                write: Rc::new(Cell::new(Some(write))),
+            };
+            *out = Box::into_raw(Box::new(stream));
+            0
+        }
+        Err(e) => errno(&e),
+    }
+}
+
+/// Close the stream. The fd is closed after in-flight operations finish.
+///
+/// # Safety
+///
+/// `stream` must be created by [`monoio_stream_new`].
+#[no_mangle]
+pub unsafe extern "C" fn monoio_stream_close(stream: *mut monoio_stream_t) {
+    if stream.is_null() {
+        return;
+    }
+    let stream = Box::from_raw(stream);
+    (*stream.rt).enter(|| drop(stream));
+}
+
+/// Read from the stream into `buf`. Only one read can be in flight, otherwise
+/// `-EBUSY` is returned.
+///
+/// # Safety
+///
+/// `stream` must be a valid stream, and `buf` must be valid for `len` bytes
+/// until `cb` is invoked.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_stream_read(
+    stream: *const monoio_stream_t,
+    buf: *mut u8,
+    len: usize,
+    cb: monoio_io_callback_t,
+    user_data: *mut c_void,
+) -> c_int {
+    let Some(cb) = cb else {
+        return -libc::EINVAL;
+    };
+    let stream = &*stream;
+    let Some(mut half) = stream.read.take() else {
+        return -libc::EBUSY;
+    };
+    let slot = stream.read.clone();
+    (*stream.rt).spawn(async move {
+        let (res, _) = half.read(RawBuf::new(buf, len)).await;
+        slot.set(Some(half));
+        cb(user_data, io_result(res));
+    });
+    0
+}
+
+/// Write `buf` to the stream. Only one write can be in flight, otherwise
+/// `-EBUSY` is returned. The write may be partial.
+///
+/// # Safety
+///
+/// `stream` must be a valid stream, and `buf` must be valid for `len` bytes
+/// until `cb` is invoked.
+#[no_mangle]
+pub unsafe extern "C" fn monoio_stream_write(
+    stream: *const monoio_stream_t,
+    buf: *const u8,
+    len: usize,
+    cb: monoio_io_callback_t,
+    user_data: *mut c_void,
+) -> c_int {
+    let Some(cb) = cb else {
+        return -libc::EINVAL;
+    };
+    let stream = &*stream;
+    let Some(mut half) = stream.write.take() else {
+        return -libc::EBUSY;
+    };
+    let slot = stream.write.clone();
+    (*stream.rt).spawn(async move {
+        let (res, _) = half.write(RawBuf::new(buf, len)).await;
+        slot.set(Some(half));
+        cb(user_data, io_result(res));
+    });
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        io::{Read, Write},
+        os::unix::{io::AsRawFd, net::UnixStream},
+        ptr,
+    };
+
+    use super::*;
+
+    type Results = RefCell<Vec<isize>>;
+
+    unsafe extern "C" fn push_result(user_data: *mut c_void, result: isize) {
+        (*(user_data as *const Results)).borrow_mut().push(result);
+    }
+
+    unsafe extern "C" fn push_zero(user_data: *mut c_void) {
+        push_result(user_data, 0);
+    }
+
+    fn new_runtime() -> *mut monoio_runtime_t {
+        let mut rt = ptr::null_mut();
+        assert_eq!(unsafe { monoio_runtime_new(0, &mut rt) }, 0);
+        rt
+    }
+
+    #[test]
+    fn file_read_write() {
+        let rt = new_runtime();
+        let results = Results::default();
+        let data = &results as *const Results as *mut c_void;
+        let mut file = tempfile::tempfile().unwrap();
+        let fd = file.as_raw_fd();
+
+        unsafe {
+            assert_eq!(monoio_spawn(rt, Some(push_zero), data), 0);
+            let msg = b"hello";
+            monoio_write_at(rt, fd, msg.as_ptr(), msg.len(), 2, Some(push_result), data);
+            assert_eq!(monoio_runtime_run(rt), 0);
+            assert_eq!(*results.borrow(), [0, 5]);
+
+            let mut buf = [0; 4];
+            monoio_read_at(
+                rt,
+                fd,
+                buf.as_mut_ptr(),
+                buf.len(),
+                3,
+                Some(push_result),
+                data,
+            );
+            assert_eq!(monoio_runtime_run(rt), 0);
+            assert_eq!(*results.borrow(), [0, 5, 4]);
+            assert_eq!(&buf, b"ello");
+            monoio_runtime_free(rt);
+        }
+
+        // The fd is still owned by the file.
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).unwrap();
+        assert_eq!(content, b"\0\0hello");
+    }
+
+    #[test]
+    fn stream_turn() {
+        let rt = new_runtime();
+        let results = Results::default();
+        let data = &results as *const Results as *mut c_void;
+        let (a, mut b) = UnixStream::pair().unwrap();
+
+        unsafe {
+            let mut stream = ptr::null_mut();
+            assert_eq!(monoio_stream_new(rt, a.into_raw_fd(), &mut stream), 0);
+            let mut buf = [0; 16];
+            let r =
+                monoio_stream_read(stream, buf.as_mut_ptr(), buf.len(), Some(push_result), data);
+            assert_eq!(r, 0);
+            let r =
+                monoio_stream_read(stream, buf.as_mut_ptr(), buf.len(), Some(push_result), data);
+            assert_eq!(r, -libc::EBUSY);
+            let msg = b"ping";
+            let r = monoio_stream_write(stream, msg.as_ptr(), msg.len(), Some(push_result), data);
+            assert_eq!(r, 0);
+
+            // Act as the host event loop.
+            b.write_all(b"pong").unwrap();
+            while results.borrow().len() < 2 {
+                if monoio_runtime_turn(rt) == 1 {
+                    continue;
+                }
+                let mut pfd = libc::pollfd {
+                    fd: monoio_runtime_fd(rt),
+                    events: libc::POLLIN,
+                    revents: 0,
+                };
+                libc::poll(&mut pfd, 1, 100);
+            }
+            results.borrow_mut().sort();
+            assert_eq!(*results.borrow(), [4, 4]);
+            assert_eq!(&buf[..4], b"pong");
+            assert_eq!(monoio_runtime_next_timeout(rt), -1);
+
+            monoio_stream_close(stream);
+            monoio_runtime_free(rt);
+        }
+        let mut buf = [0; 4];
+        b.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+}
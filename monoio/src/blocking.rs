@@ -1,6 +1,10 @@
 //! Blocking tasks related.
 
-use std::{future::Future, sync::Arc, task::Poll};
+use std::{
+    future::Future,
+    sync::{Arc, OnceLock},
+    task::Poll,
+};
 
 use threadpool::{Builder as ThreadPoolBuilder, ThreadPool as ThreadPoolImpl};
 
@@ -17,12 +21,8 @@ pub trait ThreadPool {
     fn schedule_task(&self, task: BlockingTask);
 }
 
-/// Error on waiting blocking task.
-#[derive(Debug, Clone, Copy)]
-pub enum JoinError {
-    /// Task is canceled.
-    Canceled,
-}
+#[doc(no_inline)]
+pub use crate::task::JoinError;
 
 /// BlockingTask is contrusted by monoio, ThreadPool impl
 /// will exeucte it with `.run()`.
@@ -82,6 +82,23 @@ pub enum BlockingStrategy {
     Panic,
     /// Execute with current thread when `spawn_blocking`.
     ExecuteLocal,
+    /// Execute on a thread pool shared by all runtimes of the process. The
+    /// pool is started on the first `spawn_blocking`, with one thread per
+    /// available CPU.
+    SharedPool,
+}
+
+static SHARED_POOL: OnceLock<DefaultThreadPool> = OnceLock::new();
+
+fn shared_pool() -> &'static DefaultThreadPool {
+    SHARED_POOL.get_or_init(|| {
+        let num_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name("monoio-blocking".to_string())
+            .build();
+        DefaultThreadPool { pool }
+    })
 }
 
 /// `spawn_blocking` is used for executing a task(without async) with heavy computation or blocking
@@ -98,26 +115,41 @@ where
     let (task, join) = new_task(DEFAULT_THREAD_ID, fut, NoopScheduler);
     crate::runtime::CURRENT.with(|inner| {
         let handle = &inner.blocking_handle;
-        match handle {
-            BlockingHandle::Attached(shared) => shared.schedule_task(BlockingTask {
-                task: Some(task),
-                blocking_vtable: blocking_vtable::<R>(),
-            }),
-            BlockingHandle::Empty(BlockingStrategy::ExecuteLocal) => task.run(),
+        let pool: &dyn ThreadPool = match handle {
+            BlockingHandle::Attached(shared) => shared.as_ref(),
+            BlockingHandle::Empty(BlockingStrategy::SharedPool) => shared_pool(),
+            BlockingHandle::Empty(BlockingStrategy::ExecuteLocal) => return task.run(),
             BlockingHandle::Empty(BlockingStrategy::Panic) => {
-                // For users: if you see this panic, you have 2 choices:
+                // For users: if you see this panic, you have 3 choices:
                 // 1. attach a shared thread pool to execute blocking tasks
-                // 2. set runtime blocking strategy to `BlockingStrategy::ExecuteLocal`
-                // Note: solution 2 will execute blocking task on current thread and may block other
+                // 2. set runtime blocking strategy to `BlockingStrategy::SharedPool`
+                // 3. set runtime blocking strategy to `BlockingStrategy::ExecuteLocal`
+                // Note: solution 3 will execute blocking task on current thread and may block other
                 // tasks This may cause other tasks high latency.
                 panic!("execute blocking task without thread pool attached")
             }
-        }
+        };
+        pool.schedule_task(BlockingTask {
+            task: Some(task),
+            blocking_vtable: blocking_vtable::<R>(),
+        });
     });
 
     join
 }
 
+/// Whether a thread pool is attached to the current runtime or the shared
+/// pool is used, so blocking io can be moved off the thread instead of done in
+/// place.
+pub(crate) fn pool_attached() -> bool {
+    crate::runtime::CURRENT.with(|inner| {
+        matches!(
+            inner.blocking_handle,
+            BlockingHandle::Attached(_) | BlockingHandle::Empty(BlockingStrategy::SharedPool)
+        )
+    })
+}
+
 /// DefaultThreadPool is a simple wrapped `threadpool::ThreadPool` that implememt
 /// `monoio::blocking::ThreadPool`. You may use this implementation, or you can use your own thread
 /// pool implementation.
@@ -182,7 +214,12 @@ where
     ) -> std::task::Poll<Self::Output> {
         let me = &mut *self;
         let func = me.0.take().expect("blocking task ran twice.");
-        Poll::Ready(Ok(func()))
+        // Catch the panic here so the pool thread does not unwind and the
+        // `JoinHandle` still gets a result.
+        Poll::Ready(
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(func))
+                .map_err(|_| JoinError::Panic),
+        )
     }
 }
 
@@ -190,7 +227,7 @@ where
 mod tests {
     use std::sync::Arc;
 
-    use super::DefaultThreadPool;
+    use super::{DefaultThreadPool, JoinError};
 
     /// NaiveThreadPool always create a new thread on executing tasks.
     struct NaiveThreadPool;
@@ -280,7 +317,7 @@ mod tests {
             .unwrap();
         rt.block_on(async {
             let ret = crate::spawn_blocking(|| 1).await;
-            assert!(matches!(ret, Err(super::JoinError::Canceled)));
+            assert!(matches!(ret, Err(JoinError::Canceled)));
         });
     }
 
@@ -326,4 +363,43 @@ mod tests {
             assert_eq!(result4.unwrap(), "hello spawn_blocking4!");
         });
     }
+
+    #[test]
+    fn panic_caught() {
+        let shared_pool = Arc::new(DefaultThreadPool::new(1));
+        let mut rt = crate::RuntimeBuilder::<crate::FusionDriver>::new()
+            .attach_thread_pool(shared_pool)
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let ret = crate::spawn_blocking(|| panic!("blocking task panicked")).await;
+            assert!(matches!(ret, Err(JoinError::Panic)));
+            assert_eq!(crate::spawn_blocking(|| 1).await.unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn shared_pool() {
+        let threads: Vec<_> = (0..2)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    let mut rt = crate::RuntimeBuilder::<crate::FusionDriver>::new()
+                        .with_blocking_strategy(super::BlockingStrategy::SharedPool)
+                        .enable_timer()
+                        .build()
+                        .unwrap();
+                    rt.block_on(async {
+                        let result = crate::spawn_blocking(|| {
+                            std::thread::current().name().map(str::to_string)
+                        })
+                        .await;
+                        assert_eq!(result.unwrap().as_deref(), Some("monoio-blocking"));
+                    });
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    }
 }
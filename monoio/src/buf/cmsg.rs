@@ -0,0 +1,221 @@
+use std::{fmt, mem, os::unix::prelude::RawFd, ptr};
+
+/// An owned buffer of ancillary (control) messages, sent or received along
+/// with the data of `sendmsg` and `recvmsg`.
+///
+/// To send, push messages with [`push`](Self::push) or
+/// [`push_fds`](Self::push_fds). To receive, create it with enough
+/// [`capacity`](Self::capacity) for the expected messages, which can be
+/// computed with [`space`](Self::space), and read them with
+/// [`iter`](Self::iter).
+#[derive(Clone, Default)]
+pub struct CmsgBuf {
+    // Backed by `u64`s, so every `cmsghdr` in it is aligned.
+    buf: Vec<u64>,
+    len: usize,
+}
+
+impl CmsgBuf {
+    /// Create an empty buffer which can hold `capacity` bytes of messages.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: vec![0; capacity.div_ceil(8)],
+            len: 0,
+        }
+    }
+
+    /// Bytes a message with `data_len` bytes of data takes in the buffer.
+    pub fn space(data_len: usize) -> usize {
+        unsafe { libc::CMSG_SPACE(data_len as _) as usize }
+    }
+
+    /// Bytes the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.buf.len() * 8
+    }
+
+    /// Bytes of messages in the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there is no message in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all messages, keeping the capacity.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Append a message of the given level and type, e.g. `libc::IPPROTO_IP`
+    /// and `libc::IP_PKTINFO`. The buffer grows if needed.
+    pub fn push(&mut self, level: i32, ty: i32, data: &[u8]) {
+        let space = Self::space(data.len());
+        if self.len + space > self.capacity() {
+            self.buf.resize((self.len + space).div_ceil(8), 0);
+        }
+        let header = libc::cmsghdr {
+            cmsg_len: unsafe { libc::CMSG_LEN(data.len() as _) } as _,
+            cmsg_level: level,
+            cmsg_type: ty,
+        };
+        unsafe {
+            let base = self.buf.as_mut_ptr().cast::<u8>().add(self.len);
+            // Zero the padding too, the kernel may not accept garbage in it.
+            ptr::write_bytes(base, 0, space);
+            ptr::write(base.cast::<libc::cmsghdr>(), header);
+            ptr::copy_nonoverlapping(data.as_ptr(), base.add(header_len()), data.len());
+        }
+        self.len += space;
+    }
+
+    /// Append a `SCM_RIGHTS` message passing `fds` to the peer of a Unix
+    /// socket.
+    pub fn push_fds(&mut self, fds: &[RawFd]) {
+        let data =
+            unsafe { std::slice::from_raw_parts(fds.as_ptr().cast::<u8>(), mem::size_of_val(fds)) };
+        self.push(libc::SOL_SOCKET, libc::SCM_RIGHTS, data);
+    }
+
+    /// Iterate over the messages in the buffer.
+    pub fn iter(&self) -> CmsgIter<'_> {
+        CmsgIter {
+            buf: &self.as_bytes()[..self.len],
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.buf.as_ptr().cast::<u8>(), self.capacity()) }
+    }
+
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buf.as_mut_ptr().cast()
+    }
+
+    /// # Safety
+    ///
+    /// The first `len` bytes must be valid messages, e.g. written by the
+    /// kernel.
+    pub(crate) unsafe fn set_len(&mut self, len: usize) {
+        self.len = len.min(self.capacity());
+    }
+}
+
+impl<'a> IntoIterator for &'a CmsgBuf {
+    type Item = Cmsg<'a>;
+    type IntoIter = CmsgIter<'a>;
+
+    fn into_iter(self) -> CmsgIter<'a> {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for CmsgBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An ancillary message in a [`CmsgBuf`].
+#[derive(Debug, Clone, Copy)]
+pub struct Cmsg<'a> {
+    level: i32,
+    ty: i32,
+    data: &'a [u8],
+}
+
+impl<'a> Cmsg<'a> {
+    /// The level of the message, e.g. `libc::SOL_SOCKET`.
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+
+    /// The type of the message, e.g. `libc::SCM_RIGHTS`.
+    pub fn ty(&self) -> i32 {
+        self.ty
+    }
+
+    /// The data of the message.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// The fds passed by a `SCM_RIGHTS` message, or `None` for other
+    /// messages. The fds are owned by the receiver, and must be closed by it.
+    pub fn fds(&self) -> Option<Vec<RawFd>> {
+        if (self.level, self.ty) != (libc::SOL_SOCKET, libc::SCM_RIGHTS) {
+            return None;
+        }
+        let fds = self
+            .data
+            .chunks_exact(mem::size_of::<RawFd>())
+            .map(|b| RawFd::from_ne_bytes(b.try_into().unwrap()))
+            .collect();
+        Some(fds)
+    }
+}
+
+/// An iterator over the messages in a [`CmsgBuf`].
+#[derive(Debug, Clone)]
+pub struct CmsgIter<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for CmsgIter<'a> {
+    type Item = Cmsg<'a>;
+
+    fn next(&mut self) -> Option<Cmsg<'a>> {
+        if self.buf.len() < header_len() {
+            return None;
+        }
+        // Safety: the buffer starts at a message, which is aligned.
+        let header = unsafe { ptr::read(self.buf.as_ptr().cast::<libc::cmsghdr>()) };
+        let len = header.cmsg_len as usize;
+        if len < header_len() || len > self.buf.len() {
+            self.buf = &[];
+            return None;
+        }
+        let cmsg = Cmsg {
+            level: header.cmsg_level,
+            ty: header.cmsg_type,
+            data: &self.buf[header_len()..len],
+        };
+        let space = CmsgBuf::space(len - header_len()).min(self.buf.len());
+        self.buf = &self.buf[space..];
+        Some(cmsg)
+    }
+}
+
+// Offset of the data in a message.
+fn header_len() -> usize {
+    unsafe { libc::CMSG_LEN(0) as usize }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_and_iter() {
+        let mut buf = CmsgBuf::with_capacity(CmsgBuf::space(8));
+        buf.push_fds(&[3, 4]);
+        // Grows past the capacity.
+        buf.push(libc::IPPROTO_IP, libc::IP_TOS, &[1]);
+        assert_eq!(buf.len(), CmsgBuf::space(8) + CmsgBuf::space(1));
+        assert!(buf.capacity() >= buf.len());
+
+        let cmsgs = buf.iter().collect::<Vec<_>>();
+        assert_eq!(cmsgs.len(), 2);
+        assert_eq!(cmsgs[0].fds(), Some(vec![3, 4]));
+        assert_eq!(cmsgs[1].level(), libc::IPPROTO_IP);
+        assert_eq!(cmsgs[1].ty(), libc::IP_TOS);
+        assert_eq!(cmsgs[1].data(), [1]);
+        assert_eq!(cmsgs[1].fds(), None);
+
+        buf.clear();
+        assert!(buf.is_empty());
+        assert_eq!(buf.iter().count(), 0);
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    alloc::{self, Layout},
+    fmt,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
+
+use super::{IoBuf, IoBufMut};
+
+/// A zeroed heap buffer with an aligned address and capacity, as required by
+/// files opened with [`OpenOptions::direct`](crate::fs::OpenOptions::direct).
+///
+/// The length of a write must be aligned too, pad the data with
+/// [`set_len`](DmaBuf::set_len) if needed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{buf::DmaBuf, fs::OpenOptions};
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let file = OpenOptions::new()
+///         .write(true)
+///         .create(true)
+///         .direct(true)
+///         .open("foo.txt")
+///         .await?;
+///     let mut buf = DmaBuf::new(4096);
+///     buf.extend_from_slice(b"hello");
+///     buf.set_len(4096);
+///     file.write_all_at(buf, 0).await.0?;
+///     Ok(())
+/// }
+/// ```
+pub struct DmaBuf {
+    ptr: NonNull<u8>,
+    layout: Layout,
+    len: usize,
+}
+
+impl DmaBuf {
+    /// Alignment of [`DmaBuf::new`], the page size, which satisfies the
+    /// logical block size of common filesystems.
+    pub const DEFAULT_ALIGN: usize = 4096;
+
+    /// Allocate a buffer of at least `capacity` bytes aligned to
+    /// [`DEFAULT_ALIGN`](Self::DEFAULT_ALIGN).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity overflows `isize`.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_align(capacity, Self::DEFAULT_ALIGN)
+    }
+
+    /// Allocate a buffer of at least `capacity` bytes, rounded up to a
+    /// multiple of `align`, at an address aligned to `align`, e.g. 512.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two, or the capacity overflows
+    /// `isize`.
+    pub fn with_align(capacity: usize, align: usize) -> Self {
+        let layout = Layout::from_size_align(capacity.max(1), align)
+            .expect("invalid dma buffer layout")
+            .pad_to_align();
+        // Safety: the size of the layout is not zero.
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        Self {
+            ptr,
+            layout,
+            len: 0,
+        }
+    }
+
+    /// Total size of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Alignment of the address and capacity.
+    pub fn align(&self) -> usize {
+        self.layout.align()
+    }
+
+    /// Set the length of initialized data to 0.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Set the length of initialized data. The bytes past the data written so
+    /// far are zeros, or left from previous data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` exceeds the capacity.
+    pub fn set_len(&mut self, len: usize) {
+        assert!(len <= self.capacity(), "dma buffer length exceeds capacity");
+        self.len = len;
+    }
+
+    /// Copy data to the end of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough room.
+    pub fn extend_from_slice(&mut self, data: &[u8]) {
+        assert!(
+            self.len + data.len() <= self.capacity(),
+            "not enough room in dma buffer"
+        );
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                self.ptr.as_ptr().add(self.len),
+                data.len(),
+            );
+        }
+        self.len += data.len();
+    }
+}
+
+// Safety: the buffer is owned, like a `Vec<u8>`.
+unsafe impl Send for DmaBuf {}
+unsafe impl Sync for DmaBuf {}
+
+impl Drop for DmaBuf {
+    fn drop(&mut self) {
+        // Safety: allocated with the same layout in `with_align`.
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+impl Deref for DmaBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for DmaBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl fmt::Debug for DmaBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DmaBuf")
+            .field("len", &self.len)
+            .field("capacity", &self.capacity())
+            .field("align", &self.align())
+            .finish()
+    }
+}
+
+unsafe impl IoBuf for DmaBuf {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+}
+
+unsafe impl IoBufMut for DmaBuf {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.capacity()
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.len = pos;
+    }
+}
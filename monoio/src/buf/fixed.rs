@@ -0,0 +1,271 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    future::poll_fn,
+    io,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+    task::{Poll, Waker},
+};
+
+use super::{pool::Arena, IoBuf, IoBufMut};
+
+/// A pool of buffers which can be registered with the io_uring ring.
+///
+/// [`File::read_fixed_at`](crate::fs::File::read_fixed_at) and
+/// [`File::write_fixed_at`](crate::fs::File::write_fixed_at) on buffers of a
+/// registered pool use the fixed-buffer opcodes, so the kernel does not have
+/// to pin the pages for every op.
+///
+/// A ring holds one set of registered buffers. Registering fails with `EBUSY`
+/// if another pool, or the pool set by
+/// [`RuntimeBuilder::with_buffer_pool`](crate::RuntimeBuilder::with_buffer_pool),
+/// is registered. On the legacy driver, or before the pool is registered, the
+/// buffers are read and written as regular memory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{buf::FixedBufPool, fs::File};
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let pool = FixedBufPool::new(4096, 16)?;
+///     pool.register()?;
+///
+///     let file = File::open("foo.txt").await?;
+///     let (res, buf) = file.read_fixed_at(pool.get().await, 0).await;
+///     let n = res?;
+///     println!("The bytes: {:?}", &buf[..n]);
+///     Ok(())
+/// }
+/// ```
+pub struct FixedBufPool {
+    shared: Rc<Shared>,
+}
+
+struct Shared {
+    arena: Rc<Arena>,
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    registration: RefCell<Option<crate::driver::BufferRegistration>>,
+    // Tasks waiting for a buffer.
+    waiters: RefCell<Vec<Waker>>,
+}
+
+impl FixedBufPool {
+    /// Allocate `count` zeroed buffers of `buf_size` bytes. The total size
+    /// must not exceed 1GiB.
+    pub fn new(buf_size: usize, count: usize) -> io::Result<Self> {
+        Ok(Self {
+            shared: Rc::new(Shared {
+                arena: Arena::new(buf_size, count)?,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                registration: RefCell::new(None),
+                waiters: RefCell::new(Vec::new()),
+            }),
+        })
+    }
+
+    /// Register the buffers with the ring of the current runtime. It does
+    /// nothing on the legacy driver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a monoio runtime with the io_uring driver
+    /// enabled.
+    pub fn register(&self) -> io::Result<()> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        {
+            let iovec = self.shared.arena.iovec();
+            let registration =
+                crate::driver::CURRENT.with(|inner| inner.register_buffers(&[iovec]))?;
+            *self.shared.registration.borrow_mut() = registration;
+        }
+        Ok(())
+    }
+
+    /// Unregister the buffers. Dropping the pool and all of its buffers
+    /// unregisters them too.
+    pub fn unregister(&self) -> io::Result<()> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(registration) = self.shared.registration.borrow_mut().take() {
+            return registration.unregister();
+        }
+        Ok(())
+    }
+
+    /// Whether the buffers are registered with a ring which is alive.
+    pub fn is_registered(&self) -> bool {
+        self.shared.fixed_index().is_some()
+    }
+
+    /// Size of each buffer.
+    pub fn buf_size(&self) -> usize {
+        self.shared.arena.buf_size()
+    }
+
+    /// Take a free buffer, or `None` if all of them are in use.
+    pub fn try_get(&self) -> Option<FixedBuf> {
+        let slot = self.shared.arena.take()?;
+        Some(FixedBuf {
+            shared: self.shared.clone(),
+            slot,
+            len: 0,
+        })
+    }
+
+    /// Take a free buffer, waiting until one is dropped if all of them are in
+    /// use.
+    pub async fn get(&self) -> FixedBuf {
+        poll_fn(|cx| match self.try_get() {
+            Some(buf) => Poll::Ready(buf),
+            None => {
+                self.shared.waiters.borrow_mut().push(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl fmt::Debug for FixedBufPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBufPool")
+            .field("buf_size", &self.buf_size())
+            .field("registered", &self.is_registered())
+            .finish()
+    }
+}
+
+impl Shared {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn fixed_index(&self) -> Option<u16> {
+        // The arena is registered as a single buffer.
+        self.registration
+            .borrow()
+            .as_ref()
+            .filter(|registration| registration.is_alive())
+            .map(|_| 0)
+    }
+
+    #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+    fn fixed_index(&self) -> Option<u16> {
+        None
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(registration) = self.registration.get_mut().take() {
+            let _ = registration.unregister();
+        }
+    }
+}
+
+/// A buffer taken from a [`FixedBufPool`]. It goes back to the pool on drop.
+pub struct FixedBuf {
+    shared: Rc<Shared>,
+    slot: usize,
+    len: usize,
+}
+
+impl FixedBuf {
+    /// Total size of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.shared.arena.buf_size()
+    }
+
+    /// Set the length of initialized data to 0.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Copy data to the end of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough room.
+    pub fn extend_from_slice(&mut self, data: &[u8]) {
+        assert!(
+            self.len + data.len() <= self.capacity(),
+            "not enough room in fixed buffer"
+        );
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), self.ptr().add(self.len), data.len());
+        }
+        self.len += data.len();
+    }
+
+    /// Index of the registered buffer, if the pool is registered.
+    #[allow(unused)]
+    pub(crate) fn fixed_index(&self) -> Option<u16> {
+        self.shared.fixed_index()
+    }
+
+    fn ptr(&self) -> *mut u8 {
+        self.shared.arena.slot_ptr(self.slot)
+    }
+}
+
+impl Drop for FixedBuf {
+    fn drop(&mut self) {
+        self.shared.arena.put(self.slot);
+        for waker in self.shared.waiters.borrow_mut().drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl Deref for FixedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
+    }
+}
+
+impl DerefMut for FixedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr(), self.len) }
+    }
+}
+
+impl fmt::Debug for FixedBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBuf")
+            .field("len", &self.len)
+            .field("capacity", &self.capacity())
+            .field("fixed_index", &self.fixed_index())
+            .finish()
+    }
+}
+
+unsafe impl IoBuf for FixedBuf {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+}
+
+unsafe impl IoBufMut for FixedBuf {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.ptr()
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.capacity()
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.len = pos;
+    }
+}
@@ -15,10 +15,31 @@ pub use io_vec_buf::{IoVecBuf, IoVecBufMut, VecBuf};
 mod slice;
 pub use slice::{IoVecWrapper, IoVecWrapperMut, Slice, SliceMut};
 
+pub mod pool;
+
+mod fixed;
+pub use fixed::{FixedBuf, FixedBufPool};
+
+mod dma;
+pub use dma::DmaBuf;
+
+mod provided;
+pub use provided::ProvidedBuf;
+
+#[cfg(unix)]
+mod cmsg;
+#[cfg(unix)]
+pub use cmsg::{Cmsg, CmsgBuf, CmsgIter};
+
+mod recoverable;
+pub use recoverable::{RecoverHandle, Recoverable};
+
 mod raw_buf;
 pub use raw_buf::{RawBuf, RawBufVectored};
 
 mod vec_wrapper;
+#[cfg(unix)]
+pub(crate) use vec_wrapper::SingleIoVec;
 pub(crate) use vec_wrapper::{read_vec_meta, write_vec_meta};
 
 pub(crate) fn deref(buf: &impl IoBuf) -> &[u8] {
@@ -0,0 +1,285 @@
+//! Buffers allocated from the arena registered with the runtime.
+//!
+//! With [`RuntimeBuilder::with_buffer_pool`](crate::RuntimeBuilder::with_buffer_pool),
+//! the io_uring driver allocates an arena of equally sized buffers and
+//! registers it with the ring. Reads and writes of files and sockets on
+//! buffers from [`get`] then use the fixed-buffer opcodes, so the kernel does
+//! not have to pin the pages for every op. Slices of these buffers work too.
+//!
+//! When no arena is registered on the current thread, the requested capacity
+//! is larger than a pool buffer, or all of them are in use, [`get`] returns a
+//! heap buffer instead, so code using the pool runs on every driver.
+//!
+//! Writes to sockets with a fixed buffer do not set `MSG_NOSIGNAL`. Rust
+//! programs ignore `SIGPIPE` by default, so it only matters if the program
+//! restores the default handler.
+
+#![cfg_attr(not(all(target_os = "linux", feature = "iouring")), allow(dead_code))]
+
+use std::{
+    alloc::{alloc_zeroed, dealloc, Layout},
+    cell::{Cell, RefCell},
+    fmt, io,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
+
+use super::{IoBuf, IoBufMut};
+
+// Page size, so pool buffers can also be used with O_DIRECT.
+const ARENA_ALIGN: usize = 4096;
+// Max length of a registered buffer.
+const MAX_ARENA_SIZE: usize = 1 << 30;
+
+thread_local! {
+    static ARENA: RefCell<Option<Rc<Arena>>> = const { RefCell::new(None) };
+    // Address range of the registered arena, checked for every op.
+    static REGISTERED: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+pub(crate) struct Arena {
+    ptr: *mut u8,
+    layout: Layout,
+    buf_size: usize,
+    free: RefCell<Vec<usize>>,
+}
+
+impl Arena {
+    pub(crate) fn new(buf_size: usize, count: usize) -> io::Result<Rc<Self>> {
+        let size = buf_size
+            .checked_mul(count)
+            .filter(|size| *size > 0 && *size <= MAX_ARENA_SIZE)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "buffer pool size must be in (0, 1GiB]",
+                )
+            })?;
+        let layout = Layout::from_size_align(size, ARENA_ALIGN)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // Zeroed, so pool buffers never expose uninitialized memory.
+        let ptr = unsafe { alloc_zeroed(layout) };
+        if ptr.is_null() {
+            return Err(io::ErrorKind::OutOfMemory.into());
+        }
+        Ok(Rc::new(Self {
+            ptr,
+            layout,
+            buf_size,
+            free: RefCell::new((0..count).rev().collect()),
+        }))
+    }
+
+    pub(crate) fn buf_size(&self) -> usize {
+        self.buf_size
+    }
+
+    /// Take a free buffer, returns its slot.
+    pub(crate) fn take(&self) -> Option<usize> {
+        self.free.borrow_mut().pop()
+    }
+
+    /// Give a buffer taken back.
+    pub(crate) fn put(&self, slot: usize) {
+        self.free.borrow_mut().push(slot);
+    }
+
+    pub(crate) fn slot_ptr(&self, slot: usize) -> *mut u8 {
+        unsafe { self.ptr.add(slot * self.buf_size) }
+    }
+
+    pub(crate) fn iovec(&self) -> libc::iovec {
+        libc::iovec {
+            iov_base: self.ptr as _,
+            iov_len: self.layout.size(),
+        }
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.ptr, self.layout) };
+    }
+}
+
+/// Make buffers of `get` come from the arena, which is registered with the
+/// ring at index 0.
+pub(crate) fn install(arena: Rc<Arena>) {
+    let start = arena.ptr as usize;
+    REGISTERED.with(|r| r.set((start, start + arena.layout.size())));
+    ARENA.with(|a| *a.borrow_mut() = Some(arena));
+}
+
+pub(crate) fn uninstall() {
+    REGISTERED.with(|r| r.set((0, 0)));
+    ARENA.with(|a| a.borrow_mut().take());
+}
+
+/// Index of the registered buffer containing the memory, if any.
+#[inline]
+pub(crate) fn registered_index(ptr: *const u8, len: usize) -> Option<u16> {
+    let (start, end) = REGISTERED.with(|r| r.get());
+    let ptr = ptr as usize;
+    (ptr >= start && ptr + len <= end && start != end).then_some(0)
+}
+
+/// Get a buffer with at least `capacity` bytes, from the registered arena if
+/// possible.
+pub fn get(capacity: usize) -> PoolBuf {
+    let slot = ARENA.with(|a| {
+        let arena = a.borrow();
+        let arena = arena.as_ref().filter(|arena| capacity <= arena.buf_size)?;
+        let slot = arena.take()?;
+        Some((arena.clone(), slot))
+    });
+    let inner = match slot {
+        Some((arena, slot)) => Inner::Arena { arena, slot },
+        None => Inner::Heap(Vec::with_capacity(capacity)),
+    };
+    PoolBuf { inner, len: 0 }
+}
+
+enum Inner {
+    Arena { arena: Rc<Arena>, slot: usize },
+    Heap(Vec<u8>),
+}
+
+/// A buffer returned by [`get`]. Buffers from the arena go back to it on drop.
+pub struct PoolBuf {
+    inner: Inner,
+    len: usize,
+}
+
+impl PoolBuf {
+    /// Whether the buffer is from the registered arena.
+    pub fn is_registered(&self) -> bool {
+        matches!(self.inner, Inner::Arena { .. })
+    }
+
+    /// Total size of the buffer.
+    pub fn capacity(&self) -> usize {
+        match &self.inner {
+            Inner::Arena { arena, .. } => arena.buf_size,
+            Inner::Heap(vec) => vec.capacity(),
+        }
+    }
+
+    /// Set the length of initialized data to 0.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Copy data to the end of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough room.
+    pub fn extend_from_slice(&mut self, data: &[u8]) {
+        assert!(
+            self.len + data.len() <= self.capacity(),
+            "not enough room in pool buffer"
+        );
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), self.ptr().add(self.len), data.len());
+        }
+        self.len += data.len();
+    }
+
+    fn ptr(&self) -> *mut u8 {
+        match &self.inner {
+            Inner::Arena { arena, slot } => arena.slot_ptr(*slot),
+            Inner::Heap(vec) => vec.as_ptr() as *mut u8,
+        }
+    }
+}
+
+impl Drop for PoolBuf {
+    fn drop(&mut self) {
+        if let Inner::Arena { arena, slot } = &self.inner {
+            arena.put(*slot);
+        }
+    }
+}
+
+impl Deref for PoolBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
+    }
+}
+
+impl DerefMut for PoolBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr(), self.len) }
+    }
+}
+
+impl fmt::Debug for PoolBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolBuf")
+            .field("len", &self.len)
+            .field("capacity", &self.capacity())
+            .field("registered", &self.is_registered())
+            .finish()
+    }
+}
+
+unsafe impl IoBuf for PoolBuf {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+}
+
+unsafe impl IoBufMut for PoolBuf {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.ptr()
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.capacity()
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.len = pos;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arena_slots() {
+        let arena = Arena::new(16, 2).unwrap();
+        install(arena.clone());
+        let mut a = get(16);
+        let b = get(8);
+        // Exhausted or too large, so from the heap.
+        let c = get(8);
+        let d = get(32);
+        assert!(a.is_registered() && b.is_registered());
+        assert!(!c.is_registered() && !d.is_registered());
+        assert_eq!(registered_index(b.read_ptr(), 16), Some(0));
+        assert_eq!(registered_index(b.read_ptr(), 17), None);
+        assert_eq!(registered_index(c.read_ptr(), 8), None);
+
+        a.extend_from_slice(b"hello");
+        assert_eq!(&a[..], b"hello");
+        drop(a);
+        assert!(get(16).is_registered());
+
+        uninstall();
+        assert!(!get(8).is_registered());
+        assert_eq!(registered_index(b.read_ptr(), 16), None);
+    }
+}
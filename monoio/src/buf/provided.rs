@@ -0,0 +1,127 @@
+use std::{fmt, ops::Deref};
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use {crate::driver::BufRing, std::rc::Rc};
+
+use super::IoBuf;
+
+/// A buffer picked by the kernel from a ring of provided buffers, returned by
+/// ops like [`TcpStream::recv_provided`](crate::net::TcpStream::recv_provided).
+/// It goes back to the ring on drop, so the kernel can pick it again.
+///
+/// When no ring is set up by
+/// [`RuntimeBuilder::with_provided_buffers`](crate::RuntimeBuilder::with_provided_buffers),
+/// all of its buffers are in use, or with the legacy driver, the data is read
+/// into a heap buffer instead.
+pub struct ProvidedBuf {
+    inner: Inner,
+    offset: usize,
+    len: usize,
+}
+
+enum Inner {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    Ring {
+        ring: Rc<BufRing>,
+        bid: u16,
+    },
+    Heap(Vec<u8>),
+}
+
+impl ProvidedBuf {
+    pub(crate) fn heap(buf: Vec<u8>) -> Self {
+        Self {
+            len: buf.len(),
+            offset: 0,
+            inner: Inner::Heap(buf),
+        }
+    }
+
+    /// Take the buffer picked for a completion with `len` bytes received. An
+    /// empty buffer is returned if nothing is received.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn from_completion(ring: &Rc<BufRing>, flags: u32, len: u32) -> Self {
+        let bid = match io_uring::cqueue::buffer_select(flags) {
+            Some(bid) => bid,
+            None => return Self::heap(Vec::new()),
+        };
+        ring.take();
+        if len == 0 {
+            ring.recycle(bid);
+            return Self::heap(Vec::new());
+        }
+        Self {
+            inner: Inner::Ring {
+                ring: ring.clone(),
+                bid,
+            },
+            offset: 0,
+            len: len as usize,
+        }
+    }
+
+    /// Keep `len` bytes from `offset`, for completions which put other data,
+    /// like a `recvmsg` header, before the received bytes.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn narrow(&mut self, offset: usize, len: usize) {
+        debug_assert!(offset + len <= self.len);
+        self.offset += offset;
+        self.len = len;
+    }
+
+    /// Whether the buffer is from a ring of provided buffers.
+    pub fn is_provided(&self) -> bool {
+        match self.inner {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Ring { .. } => true,
+            Inner::Heap(_) => false,
+        }
+    }
+
+    fn ptr(&self) -> *const u8 {
+        let base = match &self.inner {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Ring { ring, bid } => ring.buf_ptr(*bid),
+            Inner::Heap(vec) => vec.as_ptr(),
+        };
+        unsafe { base.add(self.offset) }
+    }
+}
+
+impl Drop for ProvidedBuf {
+    fn drop(&mut self) {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Inner::Ring { ring, bid } = &self.inner {
+            ring.recycle(*bid);
+        }
+    }
+}
+
+impl Deref for ProvidedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
+    }
+}
+
+impl fmt::Debug for ProvidedBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvidedBuf")
+            .field("len", &self.len)
+            .field("provided", &self.is_provided())
+            .finish()
+    }
+}
+
+unsafe impl IoBuf for ProvidedBuf {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+}
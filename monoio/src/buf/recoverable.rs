@@ -0,0 +1,164 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use super::{IoBuf, IoBufMut};
+
+enum Slot<T> {
+    // The buffer is still owned by the `Recoverable`.
+    Pending(Option<Waker>),
+    // The `Recoverable` is dropped and gave the buffer back.
+    Returned(T),
+    // The buffer is taken by `Recoverable::into_inner` or the handle.
+    Taken,
+}
+
+/// A buffer which can be recovered by its paired [`RecoverHandle`] after the
+/// future owning it is dropped.
+///
+/// Dropping a read or write future, e.g. when it loses a `select!`, drops the
+/// buffer with it once the op is finished. If the buffer is wrapped in a
+/// `Recoverable`, it is given to the handle instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use monoio::{buf::Recoverable, io::AsyncReadRent, net::TcpStream};
+///
+/// async fn read_or_timeout(stream: &mut TcpStream, buf: Vec<u8>) -> Vec<u8> {
+///     let (buf, handle) = Recoverable::new(buf);
+///     monoio::select! {
+///         (_, buf) = stream.read(buf) => buf.into_inner(),
+///         _ = monoio::time::sleep(Duration::from_secs(1)) => handle.await.unwrap(),
+///     }
+/// }
+/// ```
+///
+/// With io_uring the buffer comes back when the canceled op completes. The
+/// op is canceled right away only with the `async-cancel` feature, otherwise
+/// it waits for the fd to be ready. Bytes read by a canceled op are not
+/// counted in the buffer.
+pub struct Recoverable<T> {
+    buf: Option<T>,
+    slot: Rc<RefCell<Slot<T>>>,
+}
+
+/// Handle to recover the buffer of a dropped [`Recoverable`].
+///
+/// It resolves to the buffer when the `Recoverable` is dropped, or to `None`
+/// if the buffer is taken back by [`Recoverable::into_inner`].
+pub struct RecoverHandle<T> {
+    slot: Rc<RefCell<Slot<T>>>,
+}
+
+impl<T> Recoverable<T> {
+    /// Wrap the buffer and create the paired handle.
+    pub fn new(buf: T) -> (Self, RecoverHandle<T>) {
+        let slot = Rc::new(RefCell::new(Slot::Pending(None)));
+        (
+            Self {
+                buf: Some(buf),
+                slot: slot.clone(),
+            },
+            RecoverHandle { slot },
+        )
+    }
+
+    /// Get a reference to the underlying buffer.
+    pub fn get_ref(&self) -> &T {
+        self.buf.as_ref().unwrap()
+    }
+
+    /// Get a mutable reference to the underlying buffer.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.buf.as_mut().unwrap()
+    }
+
+    /// Take the buffer back. The paired handle resolves to `None` then.
+    pub fn into_inner(mut self) -> T {
+        let buf = self.buf.take().unwrap();
+        self.finish(Slot::Taken);
+        buf
+    }
+
+    fn finish(&self, state: Slot<T>) {
+        let old = std::mem::replace(&mut *self.slot.borrow_mut(), state);
+        if let Slot::Pending(Some(waker)) = old {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Recoverable<T> {
+    fn drop(&mut self) {
+        if let Some(buf) = self.buf.take() {
+            self.finish(Slot::Returned(buf));
+        }
+    }
+}
+
+impl<T> RecoverHandle<T> {
+    /// Take the buffer if the `Recoverable` has been dropped.
+    pub fn try_recover(&mut self) -> Option<T> {
+        let mut slot = self.slot.borrow_mut();
+        match &*slot {
+            Slot::Returned(_) => match std::mem::replace(&mut *slot, Slot::Taken) {
+                Slot::Returned(buf) => Some(buf),
+                _ => unreachable!(),
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<T> Future for RecoverHandle<T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(buf) = self.try_recover() {
+            return Poll::Ready(Some(buf));
+        }
+        match &mut *self.slot.borrow_mut() {
+            Slot::Pending(waker) => {
+                *waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            _ => Poll::Ready(None),
+        }
+    }
+}
+
+unsafe impl<T: IoBuf> IoBuf for Recoverable<T> {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.get_ref().read_ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.get_ref().bytes_init()
+    }
+}
+
+unsafe impl<T: IoBufMut> IoBufMut for Recoverable<T> {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.get_mut().write_ptr()
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.get_mut().bytes_total()
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.get_mut().set_init(pos)
+    }
+}
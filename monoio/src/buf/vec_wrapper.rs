@@ -1,4 +1,4 @@
-use super::{IoVecBuf, IoVecBufMut};
+use super::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut};
 
 pub(crate) struct IoVecMeta {
     #[cfg(unix)]
@@ -80,7 +80,7 @@ impl IoVecMeta {
                         return;
                     }
                     std::cmp::Ordering::Greater => {
-                        unsafe { iovec.iov_base.add(amt) };
+                        iovec.iov_base = unsafe { iovec.iov_base.add(amt) };
                         iovec.iov_len -= amt;
                         self.offset = offset;
                         return;
@@ -103,7 +103,7 @@ unsafe impl IoVecBuf for IoVecMeta {
     }
     #[cfg(unix)]
     fn read_iovec_len(&self) -> usize {
-        self.data.len()
+        self.data.len() - self.offset
     }
 }
 
@@ -116,7 +116,7 @@ unsafe impl IoVecBufMut for IoVecMeta {
     fn write_iovec_len(&mut self) -> usize {
         #[cfg(unix)]
         {
-            self.data.len()
+            self.data.len() - self.offset
         }
         #[cfg(windows)]
         unimplemented!()
@@ -127,6 +127,72 @@ unsafe impl IoVecBufMut for IoVecMeta {
     }
 }
 
+/// A single buffer as an iovec buffer, for ops taking iovecs like sendmsg.
+#[cfg(unix)]
+pub(crate) struct SingleIoVec<T> {
+    buf: T,
+    // Boxed, so ops can keep a pointer to it while the wrapper moves.
+    iovec: Box<libc::iovec>,
+}
+
+#[cfg(unix)]
+#[allow(unused)]
+impl<T> SingleIoVec<T> {
+    /// Wrap the initialized bytes of `buf`, to be read.
+    pub(crate) fn new(buf: T) -> Self
+    where
+        T: IoBuf,
+    {
+        let iovec = Box::new(libc::iovec {
+            iov_base: buf.read_ptr() as _,
+            iov_len: buf.bytes_init(),
+        });
+        Self { buf, iovec }
+    }
+
+    /// Wrap the whole capacity of `buf`, to be written.
+    pub(crate) fn new_mut(mut buf: T) -> Self
+    where
+        T: IoBufMut,
+    {
+        let iovec = Box::new(libc::iovec {
+            iov_base: buf.write_ptr() as _,
+            iov_len: buf.bytes_total(),
+        });
+        Self { buf, iovec }
+    }
+
+    pub(crate) fn into_inner(self) -> T {
+        self.buf
+    }
+}
+
+#[cfg(unix)]
+unsafe impl<T: IoBuf> IoVecBuf for SingleIoVec<T> {
+    fn read_iovec_ptr(&self) -> *const libc::iovec {
+        &*self.iovec
+    }
+
+    fn read_iovec_len(&self) -> usize {
+        1
+    }
+}
+
+#[cfg(unix)]
+unsafe impl<T: IoBufMut> IoVecBufMut for SingleIoVec<T> {
+    fn write_iovec_ptr(&mut self) -> *mut libc::iovec {
+        &mut *self.iovec
+    }
+
+    fn write_iovec_len(&mut self) -> usize {
+        1
+    }
+
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.buf.set_init(pos);
+    }
+}
+
 #[cfg(unix)]
 #[cfg(test)]
 mod tests {
@@ -154,4 +220,18 @@ mod tests {
         assert_eq!(meta.data[1].iov_len, 20);
         assert_eq!(meta.data[2].iov_len, 30);
     }
+
+    #[test]
+    fn test_consume() {
+        let iovec = VecBuf::from(vec![b"hello".to_vec(), b"world".to_vec()]);
+        let mut meta = read_vec_meta(&iovec);
+        meta.consume(2);
+        meta.consume(3);
+        meta.consume(1);
+        assert_eq!(meta.read_iovec_len(), 1);
+        let iovec = unsafe { *meta.read_iovec_ptr() };
+        let rest =
+            unsafe { std::slice::from_raw_parts(iovec.iov_base as *const u8, iovec.iov_len) };
+        assert_eq!(rest, b"orld");
+    }
 }
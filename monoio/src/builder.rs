@@ -1,4 +1,4 @@
-use std::{io, marker::PhantomData};
+use std::{io, marker::PhantomData, time::Duration};
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 use crate::driver::IoUringDriver;
@@ -17,6 +17,29 @@ use crate::{
 pub struct RuntimeBuilder<D> {
     // iouring entries
     entries: Option<u32>,
+    // iouring builder
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    urb: io_uring::Builder,
+    // registered buffer pool, as buffer size and count
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    buffer_pool: Option<(usize, usize)>,
+    // fixed file table size
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fixed_files: Option<u32>,
+    // provided buffer ring, as buffer size and count
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    provided_buffers: Option<(usize, u16)>,
+    // submission queue length at which ops are submitted right away
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    submit_watermark: Option<u32>,
+    // op slots allocated up front, and max number of in-flight ops
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    op_slab: (usize, Option<usize>),
+    // busy poll duration before park
+    spin: Option<Duration>,
+    // cpu the runtime thread is bound to
+    #[cfg(feature = "utils")]
+    cpu: Option<usize>,
     // blocking handle
     #[cfg(feature = "sync")]
     blocking_handle: crate::blocking::BlockingHandle,
@@ -28,7 +51,6 @@ scoped_thread_local!(pub(crate) static BUILD_THREAD_ID: usize);
 
 impl<T> Default for RuntimeBuilder<T> {
     /// Create a default runtime builder
-    #[must_use]
     fn default() -> Self {
         RuntimeBuilder::<T>::new()
     }
@@ -40,6 +62,21 @@ impl<T> RuntimeBuilder<T> {
     pub fn new() -> Self {
         Self {
             entries: None,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb: io_uring::IoUring::builder(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool: None,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files: None,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers: None,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark: None,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab: (0, None),
+            spin: None,
+            #[cfg(feature = "utils")]
+            cpu: None,
             #[cfg(feature = "sync")]
             blocking_handle: crate::blocking::BlockingStrategy::Panic.into(),
             _mark: PhantomData,
@@ -81,6 +118,7 @@ direct_build!(TimeDriver<LegacyDriver>);
 #[cfg(all(unix, feature = "legacy"))]
 impl Buildable for LegacyDriver {
     fn build(this: &RuntimeBuilder<Self>) -> io::Result<Runtime<LegacyDriver>> {
+        this.bind_cpu()?;
         let thread_id = gen_id();
         #[cfg(feature = "sync")]
         let blocking_handle = this.blocking_handle.clone();
@@ -91,9 +129,10 @@ impl Buildable for LegacyDriver {
                 None => LegacyDriver::new()?,
             };
             #[cfg(feature = "sync")]
-            let context = crate::runtime::Context::new(blocking_handle);
+            let mut context = crate::runtime::Context::new(blocking_handle);
             #[cfg(not(feature = "sync"))]
-            let context = crate::runtime::Context::new();
+            let mut context = crate::runtime::Context::new();
+            context.spin = this.spin;
             Ok(Runtime { driver, context })
         })
     }
@@ -102,27 +141,127 @@ impl Buildable for LegacyDriver {
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 impl Buildable for IoUringDriver {
     fn build(this: &RuntimeBuilder<Self>) -> io::Result<Runtime<IoUringDriver>> {
+        this.bind_cpu()?;
         let thread_id = gen_id();
         #[cfg(feature = "sync")]
         let blocking_handle = this.blocking_handle.clone();
 
         BUILD_THREAD_ID.set(&thread_id, || {
-            let driver = match this.entries {
-                Some(entries) => IoUringDriver::new_with_entries(entries)?,
-                None => IoUringDriver::new()?,
+            let mut driver = match this.entries {
+                Some(entries) => IoUringDriver::new_with_entries(&this.urb, entries)?,
+                None => IoUringDriver::new(&this.urb)?,
             };
+            if let Some((buf_size, count)) = this.buffer_pool {
+                driver.register_buffer_pool(buf_size, count)?;
+            }
+            if let Some(slots) = this.fixed_files {
+                driver.register_fixed_files(slots)?;
+            }
+            if let Some((buf_size, count)) = this.provided_buffers {
+                driver.register_provided_buffers(buf_size, count)?;
+            }
+            if let Some(watermark) = this.submit_watermark {
+                driver.set_submit_watermark(watermark);
+            }
+            let (capacity, limit) = this.op_slab;
+            driver.set_op_slab(capacity, limit);
             #[cfg(feature = "sync")]
-            let context = crate::runtime::Context::new(blocking_handle);
+            let mut context = crate::runtime::Context::new(blocking_handle);
             #[cfg(not(feature = "sync"))]
-            let context = crate::runtime::Context::new();
+            let mut context = crate::runtime::Context::new();
+            context.spin = this.spin;
             Ok(Runtime { driver, context })
         })
     }
 }
 
+impl<D: Driver> RuntimeBuilder<D> {
+    /// Build the runtime on a driver created by the caller.
+    ///
+    /// It is for drivers implemented outside of the crate, see [`Driver`].
+    /// Options only used by the built-in drivers, like entries, are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread can not be bound to the cpu set by
+    /// [`bind_to_cpu`](Self::bind_to_cpu).
+    pub fn build_with(&self, driver: D) -> Runtime<D> {
+        self.bind_cpu()
+            .expect("unable to bind runtime thread to cpu");
+        let thread_id = gen_id();
+        #[cfg(feature = "sync")]
+        let blocking_handle = self.blocking_handle.clone();
+
+        BUILD_THREAD_ID.set(&thread_id, || {
+            #[cfg(feature = "sync")]
+            let mut context = crate::runtime::Context::new(blocking_handle);
+            #[cfg(not(feature = "sync"))]
+            let mut context = crate::runtime::Context::new();
+            context.spin = self.spin;
+            #[cfg(feature = "sync")]
+            {
+                let unpark =
+                    crate::driver::UnparkHandle::Custom(std::sync::Arc::new(driver.unpark()));
+                context.custom = Some(crate::driver::thread::CustomRegistration::new(
+                    thread_id, unpark,
+                ));
+            }
+            Runtime { driver, context }
+        })
+    }
+
+    /// Build the runtime with timer on a driver created by the caller.
+    pub fn build_with_timer(&self, driver: D) -> Runtime<TimeDriver<D>> {
+        let Runtime {
+            driver,
+            mut context,
+        } = self.build_with(driver);
+        let timer_driver = TimeDriver::new(driver, Clock::new());
+        context.time_handle = Some(timer_driver.handle.clone());
+        Runtime {
+            driver: timer_driver,
+            context,
+        }
+    }
+}
+
 impl<D> RuntimeBuilder<D> {
     const MIN_ENTRIES: u32 = 256;
 
+    fn bind_cpu(&self) -> io::Result<()> {
+        #[cfg(feature = "utils")]
+        if let Some(cpu) = self.cpu {
+            crate::utils::bind_to_cpu_set(Some(cpu))?;
+        }
+        Ok(())
+    }
+
+    // Same options for another driver.
+    #[allow(unused)]
+    fn with_driver<T>(&self) -> RuntimeBuilder<T> {
+        RuntimeBuilder {
+            entries: self.entries,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb: self.urb.clone(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool: self.buffer_pool,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files: self.fixed_files,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers: self.provided_buffers,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark: self.submit_watermark,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab: self.op_slab,
+            spin: self.spin,
+            #[cfg(feature = "utils")]
+            cpu: self.cpu,
+            #[cfg(feature = "sync")]
+            blocking_handle: self.blocking_handle.clone(),
+            _mark: PhantomData,
+        }
+    }
+
     /// Set io_uring entries, min size is 256 and the default size is 1024.
     #[must_use]
     pub fn with_entries(mut self, entries: u32) -> Self {
@@ -134,6 +273,204 @@ impl<D> RuntimeBuilder<D> {
         self.entries = Some(entries);
         self
     }
+
+    /// Enable SQPOLL, a kernel thread will poll the submission queue so
+    /// submitting does not require a syscall. The thread goes to sleep after
+    /// being idle for given duration, and it will be waked up on the next
+    /// submission. The number of wakeups is reported by
+    /// [`DriverStats::sqpoll_wakeups`](crate::stats::DriverStats::sqpoll_wakeups).
+    ///
+    /// Note: only available for io_uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_sqpoll(mut self, idle: std::time::Duration) -> Self {
+        let idle = idle.as_millis().min(u32::MAX as u128) as u32;
+        self.urb.setup_sqpoll(idle);
+        self
+    }
+
+    /// Keep polling the driver for given duration before parking when there
+    /// is no task to run. It reduces wakeup latency at the cost of CPU.
+    #[must_use]
+    pub fn with_spin(mut self, spin: Duration) -> Self {
+        self.spin = Some(spin);
+        self
+    }
+
+    /// Bind the thread building the runtime to the given cpu, usually one
+    /// runtime per core in a thread-per-core deployment. The cores available
+    /// to the process are listed by [`available_cpus`](crate::utils::available_cpus).
+    ///
+    /// Note: binding is a no-op on platforms other than linux, android and
+    /// dragonfly.
+    #[cfg(feature = "utils")]
+    #[must_use]
+    pub fn bind_to_cpu(mut self, core_id: usize) -> Self {
+        self.cpu = Some(core_id);
+        self
+    }
+
+    /// Apply the preset of the given [`Profile`]. Knobs set after it override
+    /// the preset.
+    #[must_use]
+    pub fn profile(mut self, profile: Profile) -> Self {
+        match profile {
+            Profile::LowLatency => {
+                self.entries = None;
+                self.spin = Some(Duration::from_micros(50));
+            }
+            Profile::Throughput => {
+                self.entries = Some(4096);
+                self.spin = None;
+            }
+        }
+        self
+    }
+
+    /// Bind the SQPOLL thread to given cpu, which is useful to co-locate it
+    /// with the NIC IRQ cpu. It only takes effect when SQPOLL is enabled.
+    ///
+    /// Note: only available for io_uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_sqpoll_cpu(mut self, cpu: u32) -> Self {
+        self.urb.setup_sqpoll_cpu(cpu);
+        self
+    }
+
+    /// Create the ring with `IORING_SETUP_IOPOLL`. Completions of reads and
+    /// writes are busy-polled from the device instead of signaled by
+    /// interrupts, which lowers latency on NVMe devices with poll queues.
+    ///
+    /// Only reads and writes of files opened with `O_DIRECT` can be submitted
+    /// to such a ring. With the legacy feature, opening, syncing and closing
+    /// files run as syscalls; other operations such as sockets fail. The
+    /// thread spins while IO is in flight, and sleeps otherwise.
+    ///
+    /// Note: only available for io_uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_iopoll(mut self) -> Self {
+        self.urb.setup_iopoll();
+        self
+    }
+
+    /// Customize the `io_uring::Builder` the ring is created with, for the
+    /// setup flags not covered by other knobs, e.g. the completion queue size:
+    ///
+    /// ```no_run
+    /// let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+    ///     .uring_builder(|b| b.setup_cqsize(8192).setup_clamp())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// It shares the builder with [`with_sqpoll`](Self::with_sqpoll) and
+    /// [`with_iopoll`](Self::with_iopoll), so the last one called wins for the
+    /// flags they set. The ring size is still set with
+    /// [`with_entries`](Self::with_entries). Flags which leave the ring
+    /// disabled, like `setup_r_disabled`, make the runtime unusable.
+    ///
+    /// With the `sync` feature, the thread sleeps on an eventfd signaled by
+    /// completions and by other threads, which `setup_defer_taskrun` does not
+    /// work with, so building fails with it or with `setup_single_issuer`.
+    ///
+    /// Note: only available for io_uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_builder(
+        mut self,
+        f: impl FnOnce(&mut io_uring::Builder) -> &mut io_uring::Builder,
+    ) -> Self {
+        f(&mut self.urb);
+        self
+    }
+
+    /// Register `count` buffers of `buf_size` bytes with the ring. Buffers
+    /// from [`buf::pool::get`](crate::buf::pool::get) are taken from them, and
+    /// file and socket reads and writes on them use the fixed-buffer opcodes.
+    /// The total size must not exceed 1GiB.
+    ///
+    /// Note: only available for io_uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_buffer_pool(mut self, buf_size: usize, count: usize) -> Self {
+        self.buffer_pool = Some((buf_size, count));
+        self
+    }
+
+    /// Register a table of `slots` fixed files with the ring. Files and
+    /// sockets on which `register_fixed` is called take a free slot, and
+    /// their ops refer to the slot instead of the fd, which saves the kernel
+    /// looking up the fd for every op.
+    ///
+    /// Note: only available for io_uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_fixed_files(mut self, slots: u32) -> Self {
+        self.fixed_files = Some(slots);
+        self
+    }
+
+    /// Provide `count` buffers of `buf_size` bytes to the kernel through a
+    /// buffer ring. Ops like
+    /// [`TcpStream::recv_provided`](crate::net::TcpStream::recv_provided) let
+    /// the kernel pick one of them once data arrives, so idle connections do
+    /// not hold a buffer. Buffers go back to the ring when dropped.
+    ///
+    /// `count` must be in `1..=32768`. Note: only available for io_uring
+    /// driver, and kernel 5.19 or later.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_provided_buffers(mut self, buf_size: usize, count: u16) -> Self {
+        self.provided_buffers = Some((buf_size, count));
+        self
+    }
+
+    /// Submit ops to the kernel as soon as `sqes` entries are queued, instead
+    /// of waiting for the runtime to park or the submission queue to fill up.
+    /// A lower watermark gets ops to the kernel sooner at the cost of more
+    /// `io_uring_enter` calls; 1 submits every op right away. To submit at a
+    /// chosen point instead, see [`flush`](crate::runtime::flush).
+    ///
+    /// Note: only available for io_uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_submit_watermark(mut self, sqes: u32) -> Self {
+        self.submit_watermark = Some(sqes.max(1));
+        self
+    }
+
+    /// Allocate the slots tracking in-flight ops for `capacity` ops up front,
+    /// so they are not allocated at peak load. The slots are kept once
+    /// allocated, and more are allocated past `capacity` without moving the
+    /// existing ones.
+    ///
+    /// With a `limit`, ops submitted while `limit` ops are in flight are not
+    /// submitted, and fail with `EBUSY` once awaited.
+    ///
+    /// Note: only available for io_uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_op_slab(mut self, capacity: usize, limit: Option<usize>) -> Self {
+        self.op_slab = (capacity, limit);
+        self
+    }
+}
+
+/// Presets of builder knobs for common workloads, see
+/// [`RuntimeBuilder::profile`].
+///
+/// Ring flags which require recent kernels are never set, so a profile does
+/// not make building fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// Default ring size, and spin for 50us before parking, so a completion
+    /// arriving shortly after the last task is handled without a wakeup.
+    LowLatency,
+    /// A ring of 4096 entries so more operations are batched into one
+    /// submission, and park as soon as there is nothing to run.
+    Throughput,
 }
 
 // ===== FusionDriver =====
@@ -150,6 +487,21 @@ impl RuntimeBuilder<FusionDriver> {
         if crate::utils::detect_uring() {
             let builder = RuntimeBuilder::<IoUringDriver> {
                 entries: self.entries,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                urb: self.urb.clone(),
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                buffer_pool: self.buffer_pool,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                fixed_files: self.fixed_files,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                provided_buffers: self.provided_buffers,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                submit_watermark: self.submit_watermark,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                op_slab: self.op_slab,
+                spin: self.spin,
+                #[cfg(feature = "utils")]
+                cpu: self.cpu,
                 #[cfg(feature = "sync")]
                 blocking_handle: self.blocking_handle.clone(),
                 _mark: PhantomData,
@@ -159,6 +511,21 @@ impl RuntimeBuilder<FusionDriver> {
         } else {
             let builder = RuntimeBuilder::<LegacyDriver> {
                 entries: self.entries,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                urb: self.urb.clone(),
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                buffer_pool: self.buffer_pool,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                fixed_files: self.fixed_files,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                provided_buffers: self.provided_buffers,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                submit_watermark: self.submit_watermark,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                op_slab: self.op_slab,
+                spin: self.spin,
+                #[cfg(feature = "utils")]
+                cpu: self.cpu,
                 #[cfg(feature = "sync")]
                 blocking_handle: self.blocking_handle.clone(),
                 _mark: PhantomData,
@@ -173,6 +540,21 @@ impl RuntimeBuilder<FusionDriver> {
     pub fn build(&self) -> io::Result<crate::FusionRuntime<LegacyDriver>> {
         let builder = RuntimeBuilder::<LegacyDriver> {
             entries: self.entries,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb: self.urb.clone(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool: self.buffer_pool,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files: self.fixed_files,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers: self.provided_buffers,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark: self.submit_watermark,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab: self.op_slab,
+            spin: self.spin,
+            #[cfg(feature = "utils")]
+            cpu: self.cpu,
             #[cfg(feature = "sync")]
             blocking_handle: self.blocking_handle.clone(),
             _mark: PhantomData,
@@ -185,12 +567,66 @@ impl RuntimeBuilder<FusionDriver> {
     pub fn build(&self) -> io::Result<crate::FusionRuntime<IoUringDriver>> {
         let builder = RuntimeBuilder::<IoUringDriver> {
             entries: self.entries,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb: self.urb.clone(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool: self.buffer_pool,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files: self.fixed_files,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers: self.provided_buffers,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark: self.submit_watermark,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab: self.op_slab,
+            spin: self.spin,
+            #[cfg(feature = "utils")]
+            cpu: self.cpu,
             #[cfg(feature = "sync")]
             blocking_handle: self.blocking_handle.clone(),
             _mark: PhantomData,
         };
         Ok(builder.build()?.into())
     }
+
+    /// Build the runtime on io_uring if it is usable, otherwise on the legacy
+    /// driver.
+    ///
+    /// io_uring is usable if the kernel supports the needed opcodes (5.6+) and
+    /// creating a ring is permitted, which may not be under seccomp in
+    /// containers. Unlike [`build`](Self::build), it also falls back if the
+    /// ring can not be set up with the given options, e.g. SQPOLL is not
+    /// permitted or the locked memory limit is exceeded.
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    pub fn build_auto(&self) -> io::Result<crate::FusionRuntime<IoUringDriver, LegacyDriver>> {
+        if crate::utils::detect_uring() {
+            match self.with_driver::<IoUringDriver>().build() {
+                Ok(runtime) => {
+                    info!("io_uring driver built");
+                    return Ok(runtime.into());
+                }
+                Err(_e) => {
+                    info!("io_uring driver build failed, fallback to legacy: {}", _e);
+                }
+            }
+        }
+        info!("legacy driver built");
+        Ok(self.with_driver::<LegacyDriver>().build()?.into())
+    }
+
+    /// Build the runtime. Only the legacy driver is enabled, so it is the same
+    /// as [`build`](Self::build).
+    #[cfg(all(unix, not(all(target_os = "linux", feature = "iouring"))))]
+    pub fn build_auto(&self) -> io::Result<crate::FusionRuntime<LegacyDriver>> {
+        self.build()
+    }
+
+    /// Build the runtime. Only the io_uring driver is enabled, so it is the
+    /// same as [`build`](Self::build).
+    #[cfg(all(target_os = "linux", feature = "iouring", not(feature = "legacy")))]
+    pub fn build_auto(&self) -> io::Result<crate::FusionRuntime<IoUringDriver>> {
+        self.build()
+    }
 }
 
 #[cfg(any(all(target_os = "linux", feature = "iouring"), feature = "legacy"))]
@@ -203,6 +639,21 @@ impl RuntimeBuilder<TimeDriver<FusionDriver>> {
         if crate::utils::detect_uring() {
             let builder = RuntimeBuilder::<TimeDriver<IoUringDriver>> {
                 entries: self.entries,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                urb: self.urb.clone(),
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                buffer_pool: self.buffer_pool,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                fixed_files: self.fixed_files,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                provided_buffers: self.provided_buffers,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                submit_watermark: self.submit_watermark,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                op_slab: self.op_slab,
+                spin: self.spin,
+                #[cfg(feature = "utils")]
+                cpu: self.cpu,
                 #[cfg(feature = "sync")]
                 blocking_handle: self.blocking_handle.clone(),
                 _mark: PhantomData,
@@ -212,6 +663,21 @@ impl RuntimeBuilder<TimeDriver<FusionDriver>> {
         } else {
             let builder = RuntimeBuilder::<TimeDriver<LegacyDriver>> {
                 entries: self.entries,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                urb: self.urb.clone(),
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                buffer_pool: self.buffer_pool,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                fixed_files: self.fixed_files,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                provided_buffers: self.provided_buffers,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                submit_watermark: self.submit_watermark,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                op_slab: self.op_slab,
+                spin: self.spin,
+                #[cfg(feature = "utils")]
+                cpu: self.cpu,
                 #[cfg(feature = "sync")]
                 blocking_handle: self.blocking_handle.clone(),
                 _mark: PhantomData,
@@ -226,6 +692,21 @@ impl RuntimeBuilder<TimeDriver<FusionDriver>> {
     pub fn build(&self) -> io::Result<crate::FusionRuntime<TimeDriver<LegacyDriver>>> {
         let builder = RuntimeBuilder::<TimeDriver<LegacyDriver>> {
             entries: self.entries,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb: self.urb.clone(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool: self.buffer_pool,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files: self.fixed_files,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers: self.provided_buffers,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark: self.submit_watermark,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab: self.op_slab,
+            spin: self.spin,
+            #[cfg(feature = "utils")]
+            cpu: self.cpu,
             #[cfg(feature = "sync")]
             blocking_handle: self.blocking_handle.clone(),
             _mark: PhantomData,
@@ -238,12 +719,74 @@ impl RuntimeBuilder<TimeDriver<FusionDriver>> {
     pub fn build(&self) -> io::Result<crate::FusionRuntime<TimeDriver<IoUringDriver>>> {
         let builder = RuntimeBuilder::<TimeDriver<IoUringDriver>> {
             entries: self.entries,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb: self.urb.clone(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool: self.buffer_pool,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files: self.fixed_files,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers: self.provided_buffers,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark: self.submit_watermark,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab: self.op_slab,
+            spin: self.spin,
+            #[cfg(feature = "utils")]
+            cpu: self.cpu,
             #[cfg(feature = "sync")]
             blocking_handle: self.blocking_handle.clone(),
             _mark: PhantomData,
         };
         Ok(builder.build()?.into())
     }
+
+    /// Build the runtime on io_uring if it is usable, otherwise on the legacy
+    /// driver.
+    ///
+    /// io_uring is usable if the kernel supports the needed opcodes (5.6+) and
+    /// creating a ring is permitted, which may not be under seccomp in
+    /// containers. Unlike [`build`](Self::build), it also falls back if the
+    /// ring can not be set up with the given options, e.g. SQPOLL is not
+    /// permitted or the locked memory limit is exceeded.
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    pub fn build_auto(
+        &self,
+    ) -> io::Result<crate::FusionRuntime<TimeDriver<IoUringDriver>, TimeDriver<LegacyDriver>>> {
+        if crate::utils::detect_uring() {
+            match self.with_driver::<TimeDriver<IoUringDriver>>().build() {
+                Ok(runtime) => {
+                    info!("io_uring driver with timer built");
+                    return Ok(runtime.into());
+                }
+                Err(_e) => {
+                    info!(
+                        "io_uring driver with timer build failed, fallback to legacy: {}",
+                        _e
+                    );
+                }
+            }
+        }
+        info!("legacy driver with timer built");
+        Ok(self
+            .with_driver::<TimeDriver<LegacyDriver>>()
+            .build()?
+            .into())
+    }
+
+    /// Build the runtime. Only the legacy driver is enabled, so it is the same
+    /// as [`build`](Self::build).
+    #[cfg(all(unix, not(all(target_os = "linux", feature = "iouring"))))]
+    pub fn build_auto(&self) -> io::Result<crate::FusionRuntime<TimeDriver<LegacyDriver>>> {
+        self.build()
+    }
+
+    /// Build the runtime. Only the io_uring driver is enabled, so it is the
+    /// same as [`build`](Self::build).
+    #[cfg(all(target_os = "linux", feature = "iouring", not(feature = "legacy")))]
+    pub fn build_auto(&self) -> io::Result<crate::FusionRuntime<TimeDriver<IoUringDriver>>> {
+        self.build()
+    }
 }
 
 // ===== enable_timer related =====
@@ -269,6 +812,21 @@ where
             mut context,
         } = Buildable::build(&RuntimeBuilder::<D> {
             entries: this.entries,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb: this.urb.clone(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool: this.buffer_pool,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files: this.fixed_files,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers: this.provided_buffers,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark: this.submit_watermark,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab: this.op_slab,
+            spin: this.spin,
+            #[cfg(feature = "utils")]
+            cpu: this.cpu,
             #[cfg(feature = "sync")]
             blocking_handle: this.blocking_handle.clone(),
             _mark: PhantomData,
@@ -295,12 +853,42 @@ impl<D: time_wrap::TimeWrapable> RuntimeBuilder<D> {
     pub fn enable_timer(self) -> RuntimeBuilder<TimeDriver<D>> {
         let Self {
             entries,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab,
+            spin,
+            #[cfg(feature = "utils")]
+            cpu,
             #[cfg(feature = "sync")]
             blocking_handle,
             ..
         } = self;
         RuntimeBuilder {
             entries,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            urb,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            buffer_pool,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            fixed_files,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            provided_buffers,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            submit_watermark,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            op_slab,
+            spin,
+            #[cfg(feature = "utils")]
+            cpu,
             #[cfg(feature = "sync")]
             blocking_handle,
             _mark: PhantomData,
@@ -323,7 +911,10 @@ impl<D: time_wrap::TimeWrapable> RuntimeBuilder<D> {
     /// If `BlockingStrategy::Panic` is used, it will panic if `spawn_blocking` on this thread.
     /// If `BlockingStrategy::ExecuteLocal` is used, it will execute with current thread, and may
     /// cause tasks high latency.
-    /// Attaching a thread pool is recommended if `spawn_blocking` will be used.
+    /// If `BlockingStrategy::SharedPool` is used, it will execute on a thread pool shared by all
+    /// runtimes, started on the first `spawn_blocking`.
+    /// Attaching a thread pool or using the shared pool is recommended if `spawn_blocking` will be
+    /// used.
     #[cfg(feature = "sync")]
     #[must_use]
     pub fn with_blocking_strategy(mut self, strategy: crate::blocking::BlockingStrategy) -> Self {
@@ -0,0 +1,36 @@
+use std::io;
+
+use bytes::BytesMut;
+
+/// Decoding of frames from the bytes read.
+pub trait Decoder {
+    /// The type of decoded frames.
+    type Item;
+
+    /// The type of unrecoverable frame decoding errors. IO errors are
+    /// converted into it.
+    type Error: From<io::Error>;
+
+    /// Decode a frame from the start of `src`, consuming the bytes of the
+    /// frame. Return `Ok(None)` if `src` does not hold a full frame yet, so
+    /// more bytes are read before it is called again.
+    ///
+    /// Reserving the space for the rest of the frame in `src` helps reading it
+    /// in fewer syscalls.
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error>;
+
+    /// Called when there is nothing more to read. It is called until it
+    /// returns `Ok(None)`.
+    ///
+    /// By default, it decodes the frames left in `src` and fails if the bytes
+    /// left are not a full frame.
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => Err(
+                io::Error::new(io::ErrorKind::UnexpectedEof, "bytes remaining on stream").into(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use std::io;
+
+use bytes::BytesMut;
+
+/// Encoding of frames into the bytes to write.
+pub trait Encoder<Item> {
+    /// The type of frame encoding errors. IO errors are converted into it.
+    type Error: From<io::Error>;
+
+    /// Encode a frame at the end of `dst`.
+    fn encode(&mut self, item: Item, dst: &mut BytesMut) -> Result<(), Self::Error>;
+}
@@ -0,0 +1,421 @@
+use std::{future::Future, io};
+
+use bytes::{Buf, BytesMut};
+
+use super::{Decoder, Encoder};
+use crate::io::{sink::Sink, stream::Stream, AsyncReadRent, AsyncWriteRent};
+
+const INITIAL_CAPACITY: usize = 8 * 1024;
+
+/// A [`Stream`] of frames decoded from an [`AsyncReadRent`] and a [`Sink`] of
+/// frames encoded to an [`AsyncWriteRent`], both done by the codec `U`.
+///
+/// The bytes read are buffered until they hold a frame. The frames sent are
+/// buffered until the buffer reaches the backpressure boundary, or until the
+/// sink is flushed.
+///
+/// Bytes are read into a separate buffer and appended to the read buffer once
+/// the read completes, so dropping the future of `next` keeps the bytes read
+/// before. The write buffer is moved into the io while writing, so it is lost
+/// if the future of `send`, `flush` or `close` is dropped before it completes.
+pub struct Framed<T, U> {
+    io: T,
+    codec: U,
+    read: ReadFrame,
+    write: WriteFrame,
+}
+
+/// A [`Stream`] of frames decoded from an [`AsyncReadRent`] by the decoder
+/// `D`, see [`Framed`].
+pub struct FramedRead<T, D> {
+    io: T,
+    decoder: D,
+    read: ReadFrame,
+}
+
+/// A [`Sink`] of frames encoded to an [`AsyncWriteRent`] by the encoder `E`,
+/// see [`Framed`].
+pub struct FramedWrite<T, E> {
+    io: T,
+    encoder: E,
+    write: WriteFrame,
+}
+
+impl<T, U> Framed<T, U> {
+    /// Create a `Framed` with the default buffer capacity.
+    pub fn new(io: T, codec: U) -> Self {
+        Self::with_capacity(io, codec, INITIAL_CAPACITY)
+    }
+
+    /// Create a `Framed` with `capacity` bytes reserved for the read buffer.
+    pub fn with_capacity(io: T, codec: U, capacity: usize) -> Self {
+        Self {
+            io,
+            codec,
+            read: ReadFrame::new(capacity),
+            write: WriteFrame::new(),
+        }
+    }
+
+    /// Gets a reference to the underlying io.
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// Gets a mutable reference to the underlying io.
+    ///
+    /// It is inadvisable to directly read from or write to the underlying io.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// Gets a reference to the codec.
+    pub fn codec(&self) -> &U {
+        &self.codec
+    }
+
+    /// Gets a mutable reference to the codec.
+    pub fn codec_mut(&mut self) -> &mut U {
+        &mut self.codec
+    }
+
+    /// Gets a reference to the read buffer.
+    pub fn read_buffer(&self) -> &BytesMut {
+        &self.read.buf
+    }
+
+    /// Gets a mutable reference to the read buffer.
+    pub fn read_buffer_mut(&mut self) -> &mut BytesMut {
+        &mut self.read.buf
+    }
+
+    /// Gets a reference to the write buffer.
+    pub fn write_buffer(&self) -> &BytesMut {
+        &self.write.buf
+    }
+
+    /// Gets a mutable reference to the write buffer.
+    pub fn write_buffer_mut(&mut self) -> &mut BytesMut {
+        &mut self.write.buf
+    }
+
+    /// Set the number of buffered bytes after which `send` writes the buffer
+    /// out before returning. Defaults to 8 KiB.
+    pub fn set_backpressure_boundary(&mut self, boundary: usize) {
+        self.write.boundary = boundary;
+    }
+
+    /// Consumes the `Framed`, returning the underlying io.
+    ///
+    /// Note that any data left in the buffers is lost.
+    pub fn into_inner(self) -> T {
+        self.io
+    }
+}
+
+impl<T, D> FramedRead<T, D> {
+    /// Create a `FramedRead` with the default buffer capacity.
+    pub fn new(io: T, decoder: D) -> Self {
+        Self::with_capacity(io, decoder, INITIAL_CAPACITY)
+    }
+
+    /// Create a `FramedRead` with `capacity` bytes reserved for the read
+    /// buffer.
+    pub fn with_capacity(io: T, decoder: D, capacity: usize) -> Self {
+        Self {
+            io,
+            decoder,
+            read: ReadFrame::new(capacity),
+        }
+    }
+
+    /// Gets a reference to the underlying io.
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// Gets a mutable reference to the underlying io.
+    ///
+    /// It is inadvisable to directly read from the underlying io.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// Gets a reference to the decoder.
+    pub fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Gets a mutable reference to the decoder.
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Gets a reference to the read buffer.
+    pub fn read_buffer(&self) -> &BytesMut {
+        &self.read.buf
+    }
+
+    /// Gets a mutable reference to the read buffer.
+    pub fn read_buffer_mut(&mut self) -> &mut BytesMut {
+        &mut self.read.buf
+    }
+
+    /// Consumes the `FramedRead`, returning the underlying io.
+    ///
+    /// Note that any data left in the read buffer is lost.
+    pub fn into_inner(self) -> T {
+        self.io
+    }
+}
+
+impl<T, E> FramedWrite<T, E> {
+    /// Create a `FramedWrite`.
+    pub fn new(io: T, encoder: E) -> Self {
+        Self {
+            io,
+            encoder,
+            write: WriteFrame::new(),
+        }
+    }
+
+    /// Gets a reference to the underlying io.
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// Gets a mutable reference to the underlying io.
+    ///
+    /// It is inadvisable to directly write to the underlying io.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// Gets a reference to the encoder.
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Gets a mutable reference to the encoder.
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Gets a reference to the write buffer.
+    pub fn write_buffer(&self) -> &BytesMut {
+        &self.write.buf
+    }
+
+    /// Gets a mutable reference to the write buffer.
+    pub fn write_buffer_mut(&mut self) -> &mut BytesMut {
+        &mut self.write.buf
+    }
+
+    /// Set the number of buffered bytes after which `send` writes the buffer
+    /// out before returning. Defaults to 8 KiB.
+    pub fn set_backpressure_boundary(&mut self, boundary: usize) {
+        self.write.boundary = boundary;
+    }
+
+    /// Consumes the `FramedWrite`, returning the underlying io.
+    ///
+    /// Note that any data left in the write buffer is lost.
+    pub fn into_inner(self) -> T {
+        self.io
+    }
+}
+
+struct ReadFrame {
+    buf: BytesMut,
+    // Moved into the io while reading, so `buf` is kept if the read is
+    // dropped.
+    scratch: BytesMut,
+    eof: bool,
+}
+
+impl ReadFrame {
+    fn new(capacity: usize) -> Self {
+        Self {
+            buf: BytesMut::with_capacity(capacity),
+            scratch: BytesMut::with_capacity(capacity),
+            eof: false,
+        }
+    }
+
+    async fn next<T: AsyncReadRent, D: Decoder>(
+        &mut self,
+        io: &mut T,
+        decoder: &mut D,
+    ) -> Option<Result<D::Item, D::Error>> {
+        loop {
+            if self.eof {
+                return decoder.decode_eof(&mut self.buf).transpose();
+            }
+            match decoder.decode(&mut self.buf) {
+                Ok(None) => {}
+                res => return res.transpose(),
+            }
+
+            let mut scratch = std::mem::take(&mut self.scratch);
+            if scratch.capacity() == 0 {
+                // Lost with a dropped read.
+                scratch.reserve(INITIAL_CAPACITY);
+            }
+            let (res, mut scratch) = io.read(scratch).await;
+            self.buf.extend_from_slice(&scratch);
+            scratch.clear();
+            self.scratch = scratch;
+            match res {
+                Ok(0) => self.eof = true,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+struct WriteFrame {
+    buf: BytesMut,
+    boundary: usize,
+}
+
+impl WriteFrame {
+    fn new() -> Self {
+        Self {
+            buf: BytesMut::with_capacity(INITIAL_CAPACITY),
+            boundary: INITIAL_CAPACITY,
+        }
+    }
+
+    // Write out the buffer if it reaches the backpressure boundary.
+    async fn write_if_full<T: AsyncWriteRent>(&mut self, io: &mut T) -> io::Result<()> {
+        if self.buf.len() >= self.boundary {
+            self.write_all(io).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_all<T: AsyncWriteRent>(&mut self, io: &mut T) -> io::Result<()> {
+        while !self.buf.is_empty() {
+            let (res, buf) = io.write(std::mem::take(&mut self.buf)).await;
+            self.buf = buf;
+            match res {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write frame to transport",
+                    ))
+                }
+                Ok(n) => self.buf.advance(n),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    async fn flush<T: AsyncWriteRent>(&mut self, io: &mut T) -> io::Result<()> {
+        self.write_all(io).await?;
+        io.flush().await
+    }
+
+    async fn close<T: AsyncWriteRent>(&mut self, io: &mut T) -> io::Result<()> {
+        self.flush(io).await?;
+        io.shutdown().await
+    }
+}
+
+impl<T: AsyncReadRent, U: Decoder> Stream for Framed<T, U> {
+    type Item = Result<U::Item, U::Error>;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        self.read.next(&mut self.io, &mut self.codec)
+    }
+}
+
+impl<T: AsyncReadRent, D: Decoder> Stream for FramedRead<T, D> {
+    type Item = Result<D::Item, D::Error>;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        self.read.next(&mut self.io, &mut self.decoder)
+    }
+}
+
+impl<T: AsyncWriteRent, U: Encoder<I>, I> Sink<I> for Framed<T, U> {
+    type Error = U::Error;
+
+    type SendFuture<'a>
+        = impl Future<Output = Result<(), Self::Error>>
+    where
+        Self: 'a;
+
+    type FlushFuture<'a>
+        = impl Future<Output = Result<(), Self::Error>> + 'a
+    where
+        Self: 'a;
+
+    type CloseFuture<'a>
+        = impl Future<Output = Result<(), Self::Error>> + 'a
+    where
+        Self: 'a;
+
+    fn send(&mut self, item: I) -> Self::SendFuture<'_> {
+        let res = self.codec.encode(item, &mut self.write.buf);
+        async move {
+            res?;
+            Ok(self.write.write_if_full(&mut self.io).await?)
+        }
+    }
+
+    fn flush(&mut self) -> Self::FlushFuture<'_> {
+        async move { Ok(self.write.flush(&mut self.io).await?) }
+    }
+
+    fn close(&mut self) -> Self::CloseFuture<'_> {
+        async move { Ok(self.write.close(&mut self.io).await?) }
+    }
+}
+
+impl<T: AsyncWriteRent, E: Encoder<I>, I> Sink<I> for FramedWrite<T, E> {
+    type Error = E::Error;
+
+    type SendFuture<'a>
+        = impl Future<Output = Result<(), Self::Error>>
+    where
+        Self: 'a;
+
+    type FlushFuture<'a>
+        = impl Future<Output = Result<(), Self::Error>> + 'a
+    where
+        Self: 'a;
+
+    type CloseFuture<'a>
+        = impl Future<Output = Result<(), Self::Error>> + 'a
+    where
+        Self: 'a;
+
+    fn send(&mut self, item: I) -> Self::SendFuture<'_> {
+        let res = self.encoder.encode(item, &mut self.write.buf);
+        async move {
+            res?;
+            Ok(self.write.write_if_full(&mut self.io).await?)
+        }
+    }
+
+    fn flush(&mut self) -> Self::FlushFuture<'_> {
+        async move { Ok(self.write.flush(&mut self.io).await?) }
+    }
+
+    fn close(&mut self) -> Self::CloseFuture<'_> {
+        async move { Ok(self.write.close(&mut self.io).await?) }
+    }
+}
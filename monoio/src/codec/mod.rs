@@ -0,0 +1,17 @@
+//! Adaptors from [`AsyncReadRent`](crate::io::AsyncReadRent) and
+//! [`AsyncWriteRent`](crate::io::AsyncWriteRent) to a
+//! [`Stream`](crate::io::stream::Stream) and a [`Sink`](crate::io::sink::Sink)
+//! of frames.
+//!
+//! A protocol implements [`Decoder`] to parse frames out of the bytes read and
+//! [`Encoder`] to serialize frames into the bytes to write, then wraps the io
+//! with [`Framed`], or with [`FramedRead`] and [`FramedWrite`] for the two
+//! halves.
+
+mod decoder;
+mod encoder;
+mod framed;
+
+pub use decoder::Decoder;
+pub use encoder::Encoder;
+pub use framed::{Framed, FramedRead, FramedWrite};
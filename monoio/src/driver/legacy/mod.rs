@@ -3,12 +3,16 @@
 use std::{
     cell::UnsafeCell,
     io,
+    os::unix::prelude::{AsRawFd, RawFd},
     rc::Rc,
     task::{Context, Poll},
     time::Duration,
 };
 
-use self::{ready::Ready, scheduled_io::ScheduledIo};
+use self::{
+    ready::{Direction, Ready},
+    scheduled_io::ScheduledIo,
+};
 use super::{
     op::{CompletionMeta, Op, OpAble},
     Driver, Inner, CURRENT,
@@ -17,6 +21,8 @@ use crate::utils::slab::Slab;
 
 pub(crate) mod ready;
 mod scheduled_io;
+#[cfg(target_os = "linux")]
+mod timer;
 
 #[cfg(feature = "sync")]
 mod waker;
@@ -28,6 +34,9 @@ pub(crate) struct LegacyInner {
     events: Option<mio::Events>,
     poll: mio::Poll,
 
+    #[cfg(target_os = "linux")]
+    timer: timer::TimerFd,
+
     #[cfg(feature = "sync")]
     shared_waker: std::sync::Arc<waker::EventWaker>,
 
@@ -47,6 +56,8 @@ pub struct LegacyDriver {
 
 #[cfg(feature = "sync")]
 const TOKEN_WAKEUP: mio::Token = mio::Token(1 << 31);
+#[cfg(target_os = "linux")]
+const TOKEN_TIMER: mio::Token = mio::Token((1 << 31) + 1);
 
 impl LegacyDriver {
     const DEFAULT_ENTRIES: u32 = 1024;
@@ -57,6 +68,8 @@ impl LegacyDriver {
 
     pub(crate) fn new_with_entries(entries: u32) -> io::Result<Self> {
         let poll = mio::Poll::new()?;
+        #[cfg(target_os = "linux")]
+        let timer = timer::TimerFd::new(poll.registry(), TOKEN_TIMER)?;
 
         #[cfg(feature = "sync")]
         let shared_waker = std::sync::Arc::new(waker::EventWaker::new(mio::Waker::new(
@@ -72,6 +85,8 @@ impl LegacyDriver {
             io_dispatch: Slab::new(),
             events: Some(mio::Events::with_capacity(entries as usize)),
             poll,
+            #[cfg(target_os = "linux")]
+            timer,
             #[cfg(feature = "sync")]
             shared_waker,
             #[cfg(feature = "sync")]
@@ -126,6 +141,18 @@ impl LegacyDriver {
             timeout = Some(Duration::ZERO);
         }
 
+        // Wait for a non-zero timeout with the timerfd, which is more precise
+        // than the poller.
+        #[cfg(target_os = "linux")]
+        match timeout {
+            Some(duration) if !duration.is_zero() => {
+                inner.timer.set(Some(duration))?;
+                timeout = None;
+            }
+            None => inner.timer.set(None)?,
+            _ => {}
+        }
+
         // here we borrow 2 mut self, but its safe.
         let events = unsafe { (*self.inner.get()).events.as_mut().unwrap_unchecked() };
         match inner.poll.poll(events, timeout) {
@@ -137,11 +164,14 @@ impl LegacyDriver {
             let token = event.token();
 
             #[cfg(feature = "sync")]
-            if token != TOKEN_WAKEUP {
-                inner.dispatch(token, Ready::from_mio(event));
+            if token == TOKEN_WAKEUP {
+                continue;
+            }
+            #[cfg(target_os = "linux")]
+            if token == TOKEN_TIMER {
+                inner.timer.fired();
+                continue;
             }
-
-            #[cfg(not(feature = "sync"))]
             inner.dispatch(token, Ready::from_mio(event));
         }
         Ok(())
@@ -219,7 +249,14 @@ impl LegacyInner {
         let mut scheduled_io = inner.io_dispatch.get(index).expect("scheduled_io lost");
         let ref_mut = scheduled_io.as_mut();
         loop {
-            ready!(ref_mut.poll_readiness(cx, direction));
+            let ready = ready!(ref_mut.poll_readiness(cx, direction));
+            if ready.is_canceled() {
+                ref_mut.clear_canceled(direction);
+                return Poll::Ready(CompletionMeta {
+                    result: Err(io::Error::from_raw_os_error(libc::ECANCELED)),
+                    flags: 0,
+                });
+            }
             match OpAble::legacy_call(data) {
                 Ok(n) => {
                     return Poll::Ready(CompletionMeta {
@@ -241,18 +278,49 @@ impl LegacyInner {
         }
     }
 
+    /// Cancel the op waiting for `direction` readiness of the io at `index`.
+    pub(crate) fn cancel_op(this: &Rc<UnsafeCell<Self>>, index: usize, direction: Direction) {
+        let inner = unsafe { &mut *this.get() };
+        if let Some(mut scheduled_io) = inner.io_dispatch.get(index) {
+            scheduled_io.as_mut().cancel(direction);
+        }
+    }
+
+    pub(crate) fn stats(this: &Rc<UnsafeCell<Self>>) -> crate::stats::DriverStats {
+        let inner = unsafe { &*this.get() };
+        crate::stats::DriverStats {
+            slab_used: inner.io_dispatch.len(),
+            slab_capacity: inner.io_dispatch.capacity(),
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn submit_with_data<T>(
         this: &Rc<UnsafeCell<LegacyInner>>,
         data: T,
+        timeout: Option<Duration>,
     ) -> io::Result<Op<T>>
     where
         T: OpAble,
     {
+        // A cancel left by a previous op which was dropped before seeing it
+        // does not apply to the new op.
+        if let Some((direction, index)) = data.legacy_interest() {
+            let inner = unsafe { &mut *this.get() };
+            if let Some(mut scheduled_io) = inner.io_dispatch.get(index) {
+                scheduled_io.as_mut().clear_canceled(direction);
+            }
+        }
         Ok(Op {
             driver: Inner::Legacy(this.clone()),
             // useless for legacy
             index: 0,
             data: Some(data),
+            // Nothing is in flight in the kernel, so the op is simply given up
+            // when the timer fires.
+            deadline: timeout.map(|timeout| Box::pin(crate::time::sleep(timeout))),
+            #[cfg(feature = "tracing")]
+            trace: None,
         })
     }
 }
@@ -286,6 +354,13 @@ impl Driver for LegacyDriver {
     }
 }
 
+impl AsRawFd for LegacyDriver {
+    /// The underlying poller, readable when there are IO events to process.
+    fn as_raw_fd(&self) -> RawFd {
+        unsafe { (*self.inner.get()).poll.as_raw_fd() }
+    }
+}
+
 impl Drop for LegacyDriver {
     fn drop(&mut self) {
         // Deregister thread id
@@ -1,5 +1,5 @@
 //! Copied from tokio.
-//! Ready and Interest.
+//! Ready.
 
 use std::{fmt, ops};
 
@@ -7,6 +7,8 @@ const READABLE: u8 = 0b0_01;
 const WRITABLE: u8 = 0b0_10;
 const READ_CLOSED: u8 = 0b0_0100;
 const WRITE_CLOSED: u8 = 0b0_1000;
+const READ_CANCELED: u8 = 0b01_0000;
+const WRITE_CANCELED: u8 = 0b10_0000;
 
 /// Describes the readiness state of an I/O resources.
 ///
@@ -31,11 +33,17 @@ impl Ready {
     /// Returns a `Ready` representing write closed readiness.
     pub(crate) const WRITE_CLOSED: Ready = Ready(WRITE_CLOSED);
 
+    /// Returns a `Ready` representing a canceled read.
+    pub(crate) const READ_CANCELED: Ready = Ready(READ_CANCELED);
+
+    /// Returns a `Ready` representing a canceled write.
+    pub(crate) const WRITE_CANCELED: Ready = Ready(WRITE_CANCELED);
+
     // Must remain crate-private to avoid adding a public dependency on Mio.
     pub(crate) fn from_mio(event: &mio::event::Event) -> Ready {
         let mut ready = Ready::EMPTY;
 
-        #[cfg(all(target_os = "freebsd", feature = "net"))]
+        #[cfg(target_os = "freebsd")]
         {
             if event.is_aio() {
                 ready |= Ready::READABLE;
@@ -90,6 +98,11 @@ impl Ready {
         self.contains(Ready::WRITE_CLOSED)
     }
 
+    /// Returns `true` if the value includes a canceled read or write.
+    pub(crate) fn is_canceled(self) -> bool {
+        !(self & (Ready::READ_CANCELED | Ready::WRITE_CANCELED)).is_empty()
+    }
+
     /// Returns true if `self` is a superset of `other`.
     ///
     /// `other` may represent more than one readiness operations, in which case
@@ -101,44 +114,6 @@ impl Ready {
     }
 }
 
-/// Readiness event interest.
-///
-/// Specifies the readiness events the caller is interested in when awaiting on
-/// I/O resource readiness states.
-#[derive(Clone, Copy, Eq, PartialEq)]
-pub(crate) struct Interest(mio::Interest);
-
-impl Interest {
-    /// Add together two `Interest` values.
-    ///
-    /// This function works from a `const` context.
-    pub(crate) const fn add(self, other: Interest) -> Interest {
-        Interest(self.0.add(other.0))
-    }
-}
-
-impl ops::BitOr for Interest {
-    type Output = Self;
-
-    #[inline]
-    fn bitor(self, other: Self) -> Self {
-        self.add(other)
-    }
-}
-
-impl ops::BitOrAssign for Interest {
-    #[inline]
-    fn bitor_assign(&mut self, other: Self) {
-        self.0 = (*self | other).0;
-    }
-}
-
-impl fmt::Debug for Interest {
-    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(fmt)
-    }
-}
-
 impl ops::BitOr<Ready> for Ready {
     type Output = Ready;
 
@@ -180,6 +155,7 @@ impl fmt::Debug for Ready {
             .field("is_writable", &self.is_writable())
             .field("is_read_closed", &self.is_read_closed())
             .field("is_write_closed", &self.is_write_closed())
+            .field("is_canceled", &self.is_canceled())
             .finish()
     }
 }
@@ -193,8 +169,15 @@ pub(crate) enum Direction {
 impl Direction {
     pub(crate) fn mask(self) -> Ready {
         match self {
-            Direction::Read => Ready::READABLE | Ready::READ_CLOSED,
-            Direction::Write => Ready::WRITABLE | Ready::WRITE_CLOSED,
+            Direction::Read => Ready::READABLE | Ready::READ_CLOSED | Ready::READ_CANCELED,
+            Direction::Write => Ready::WRITABLE | Ready::WRITE_CLOSED | Ready::WRITE_CANCELED,
+        }
+    }
+
+    pub(crate) fn canceled(self) -> Ready {
+        match self {
+            Direction::Read => Ready::READ_CANCELED,
+            Direction::Write => Ready::WRITE_CANCELED,
         }
     }
 }
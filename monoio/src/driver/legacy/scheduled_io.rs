@@ -39,6 +39,23 @@ impl ScheduledIo {
         }
     }
 
+    /// Cancel the op waiting for readiness in `direction`. It is woken up and
+    /// finds the canceled bit set.
+    pub(crate) fn cancel(&mut self, direction: Direction) {
+        self.readiness |= direction.canceled();
+        let slot = match direction {
+            Direction::Read => &mut self.reader,
+            Direction::Write => &mut self.writer,
+        };
+        if let Some(waker) = slot.take() {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn clear_canceled(&mut self, direction: Direction) {
+        self.readiness = self.readiness - direction.canceled();
+    }
+
     pub(crate) fn clear_readiness(&mut self, direction: Direction) {
         self.readiness = self.readiness - direction.mask();
     }
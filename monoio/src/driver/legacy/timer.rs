@@ -0,0 +1,67 @@
+//! Park timeout of the legacy driver based on timerfd.
+//!
+//! The poller only waits in milliseconds, while the uring driver waits with a
+//! timeout op in nanoseconds. A timerfd registered to the poller gives the
+//! legacy driver the same resolution.
+
+use std::{
+    io,
+    os::unix::io::{AsRawFd, FromRawFd, OwnedFd},
+    time::Duration,
+};
+
+pub(crate) struct TimerFd {
+    fd: OwnedFd,
+    armed: bool,
+}
+
+impl TimerFd {
+    pub(crate) fn new(registry: &mio::Registry, token: mio::Token) -> io::Result<Self> {
+        let fd = crate::syscall!(timerfd_create(
+            libc::CLOCK_MONOTONIC,
+            libc::TFD_NONBLOCK | libc::TFD_CLOEXEC
+        ))?;
+        // Safety: the fd is just created and owned by nothing else.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        registry.register(
+            &mut mio::unix::SourceFd(&fd.as_raw_fd()),
+            token,
+            mio::Interest::READABLE,
+        )?;
+        Ok(Self { fd, armed: false })
+    }
+
+    /// Fire once after `duration`, which must not be zero, or disarm the timer
+    /// with `None`.
+    pub(crate) fn set(&mut self, duration: Option<Duration>) -> io::Result<()> {
+        if duration.is_none() && !self.armed {
+            return Ok(());
+        }
+        let zero = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        let value = duration.map_or(zero, |d| libc::timespec {
+            tv_sec: d.as_secs().min(libc::time_t::MAX as u64) as libc::time_t,
+            tv_nsec: d.subsec_nanos() as _,
+        });
+        let spec = libc::itimerspec {
+            it_interval: zero,
+            it_value: value,
+        };
+        crate::syscall!(timerfd_settime(
+            self.fd.as_raw_fd(),
+            0,
+            &spec,
+            std::ptr::null_mut()
+        ))?;
+        self.armed = duration.is_some();
+        Ok(())
+    }
+
+    /// Mark the timer fired. The expirations are left unread, since setting
+    /// the timer resets them, and the poller is edge-triggered.
+    pub(crate) fn fired(&mut self) {
+        self.armed = false;
+    }
+}
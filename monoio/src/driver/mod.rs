@@ -30,14 +30,18 @@ pub use self::legacy::LegacyDriver;
 // }
 #[cfg(all(unix, feature = "legacy"))]
 use self::legacy::LegacyInner;
-use self::op::{CompletionMeta, Op, OpAble};
+use self::op::{CompletionMeta, Op, OpAble, OpCanceller};
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 pub use self::uring::IoUringDriver;
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 use self::uring::UringInner;
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) use self::uring::{BufRing, BufferRegistration, FixedFile};
 
 /// Unpark a runtime of another thread.
+#[cfg(feature = "sync")]
 pub(crate) mod unpark {
+    /// Wake a parked driver from another thread.
     #[allow(unreachable_pub)]
     pub trait Unpark: Sync + Send + 'static {
         /// Unblocks a thread that is blocked by the associated `Park` handle.
@@ -54,12 +58,14 @@ pub(crate) mod unpark {
     }
 }
 
+#[cfg(feature = "sync")]
 impl unpark::Unpark for Box<dyn unpark::Unpark> {
     fn unpark(&self) -> io::Result<()> {
         (**self).unpark()
     }
 }
 
+#[cfg(feature = "sync")]
 impl unpark::Unpark for std::sync::Arc<dyn unpark::Unpark> {
     fn unpark(&self) -> io::Result<()> {
         (**self).unpark()
@@ -67,6 +73,14 @@ impl unpark::Unpark for std::sync::Arc<dyn unpark::Unpark> {
 }
 
 /// Core driver trait.
+///
+/// Besides the built-in drivers, it can be implemented outside of the crate
+/// to run the runtime on another backend, like a simulation or a userspace
+/// IO stack. Such a driver is passed to
+/// [`RuntimeBuilder::build_with`](crate::RuntimeBuilder::build_with). Tasks,
+/// timers and cross thread wakeups work on it, but IO types of this crate
+/// need a built-in driver, so the driver is expected to come with its own
+/// IO types.
 pub trait Driver {
     /// Run with driver TLS.
     fn with<R>(&self, f: impl FnOnce() -> R) -> R;
@@ -96,14 +110,21 @@ pub(crate) enum Inner {
 }
 
 impl Inner {
-    fn submit_with<T: OpAble>(&self, data: T) -> io::Result<Op<T>> {
-        match self {
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused)
+    )]
+    fn submit_with<T: OpAble>(&self, data: T, timeout: Option<Duration>) -> io::Result<Op<T>> {
+        #[cfg(feature = "tracing")]
+        let trace = op::OpTrace::new(&data);
+        #[allow(clippy::let_and_return)]
+        let op = match self {
             #[cfg(windows)]
             _ => unimplemented!(),
             #[cfg(all(target_os = "linux", feature = "iouring"))]
-            Inner::Uring(this) => UringInner::submit_with_data(this, data),
+            Inner::Uring(this) => UringInner::submit_with_data(this, data, timeout),
             #[cfg(all(unix, feature = "legacy"))]
-            Inner::Legacy(this) => LegacyInner::submit_with_data(this, data),
+            Inner::Legacy(this) => LegacyInner::submit_with_data(this, data, timeout),
             #[cfg(all(
                 not(feature = "legacy"),
                 not(all(target_os = "linux", feature = "iouring"))
@@ -111,7 +132,13 @@ impl Inner {
             _ => {
                 util::feature_panic();
             }
-        }
+        };
+        #[cfg(feature = "tracing")]
+        let op = op.map(|mut op| {
+            op.trace = Some(trace);
+            op
+        });
+        op
     }
 
     #[allow(unused)]
@@ -157,6 +184,186 @@ impl Inner {
         }
     }
 
+    /// Cancel an op waited for by another task.
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused)
+    )]
+    fn cancel(&self, op: &OpCanceller) {
+        match self {
+            #[cfg(windows)]
+            _ => unimplemented!(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::cancel_op(this, op.index),
+            #[cfg(all(unix, feature = "legacy"))]
+            Inner::Legacy(this) => {
+                if let Some(direction) = op.direction {
+                    LegacyInner::cancel_op(this, op.index, direction);
+                }
+            }
+            #[cfg(all(
+                not(feature = "legacy"),
+                not(all(target_os = "linux", feature = "iouring"))
+            ))]
+            _ => {
+                util::feature_panic();
+            }
+        }
+    }
+
+    /// Poll the next completion of a multishot op, which is only submitted to
+    /// the uring driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn poll_multi_op(&self, index: usize, cx: &mut Context<'_>) -> Poll<CompletionMeta> {
+        match self {
+            Inner::Uring(this) => UringInner::poll_multi_op(this, index, cx),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => unreachable!("multishot op on legacy driver"),
+        }
+    }
+
+    /// Cancel an in-flight op. The legacy driver has nothing in flight.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn cancel_op(&self, index: usize) {
+        match self {
+            Inner::Uring(this) => UringInner::cancel_op(this, index),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => {}
+        }
+    }
+
+    /// Drop an in-flight op without canceling it. The legacy driver has
+    /// nothing in flight.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn detach_op<T: 'static>(&self, index: usize, data: &mut Option<T>) {
+        match self {
+            Inner::Uring(this) => UringInner::detach_op(this, index, data),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => {}
+        }
+    }
+
+    /// Wait until pending submissions are handed to the kernel.
+    #[allow(unused)]
+    pub(crate) fn poll_flush(&self, epoch: &mut Option<u64>, cx: &mut Context<'_>) -> Poll<()> {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::poll_flush(this, epoch, cx),
+            // Legacy driver does syscall directly, there is nothing to flush.
+            #[cfg(all(unix, feature = "legacy"))]
+            Inner::Legacy(_) => Poll::Ready(()),
+            #[cfg(all(
+                not(feature = "legacy"),
+                not(all(target_os = "linux", feature = "iouring"))
+            ))]
+            _ => {
+                util::feature_panic();
+            }
+        }
+    }
+
+    /// Return if there are tasks waiting for flush.
+    pub(crate) fn flush_requested(&self) -> bool {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::flush_requested(this),
+            #[cfg(all(unix, feature = "legacy"))]
+            Inner::Legacy(_) => false,
+            #[cfg(all(
+                not(feature = "legacy"),
+                not(all(target_os = "linux", feature = "iouring"))
+            ))]
+            _ => {
+                util::feature_panic();
+            }
+        }
+    }
+
+    pub(crate) fn stats(&self) -> crate::stats::DriverStats {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::stats(this),
+            #[cfg(all(unix, feature = "legacy"))]
+            Inner::Legacy(this) => LegacyInner::stats(this),
+            #[cfg(all(
+                not(feature = "legacy"),
+                not(all(target_os = "linux", feature = "iouring"))
+            ))]
+            _ => {
+                util::feature_panic();
+            }
+        }
+    }
+
+    pub(crate) fn capabilities(&self) -> crate::runtime::Capabilities {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::capabilities(this),
+            #[cfg(all(unix, feature = "legacy"))]
+            Inner::Legacy(_) => crate::runtime::Capabilities::legacy(),
+            #[cfg(all(
+                not(feature = "legacy"),
+                not(all(target_os = "linux", feature = "iouring"))
+            ))]
+            _ => {
+                util::feature_panic();
+            }
+        }
+    }
+
+    /// Put the fd into the fixed file table of the ring. `None` is returned if
+    /// the table is full or not set up, and on the legacy driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn register_file(
+        &self,
+        fd: std::os::unix::io::RawFd,
+    ) -> io::Result<Option<FixedFile>> {
+        match self {
+            Inner::Uring(this) => UringInner::register_file(this, fd),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => Ok(None),
+        }
+    }
+
+    /// Register buffers with the ring. The legacy driver has no registered
+    /// buffers, so `None` is returned.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn register_buffers(
+        &self,
+        bufs: &[libc::iovec],
+    ) -> io::Result<Option<BufferRegistration>> {
+        match self {
+            Inner::Uring(this) => UringInner::register_buffers(this, bufs).map(Some),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => Ok(None),
+        }
+    }
+
+    /// Register a ring of provided buffers. Multishot ops are not supported
+    /// by the legacy driver, so `None` is returned.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn register_buf_ring(
+        &self,
+        buf_size: usize,
+        count: u16,
+    ) -> io::Result<Option<BufRing>> {
+        match self {
+            Inner::Uring(this) => BufRing::new(this, buf_size, count).map(Some),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => Ok(None),
+        }
+    }
+
+    /// The ring of provided buffers set up for the driver, if any.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn provided_buffers(&self) -> Option<std::rc::Rc<BufRing>> {
+        match self {
+            Inner::Uring(this) => UringInner::provided_buffers(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => None,
+        }
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
     fn is_legacy(&self) -> bool {
         matches!(self, Inner::Legacy(..))
@@ -180,7 +387,9 @@ impl Inner {
 pub(crate) enum UnparkHandle {
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     Uring(self::uring::UnparkHandle),
+    #[cfg(all(unix, feature = "legacy"))]
     Legacy(self::legacy::UnparkHandle),
+    Custom(std::sync::Arc<dyn unpark::Unpark>),
 }
 
 #[cfg(feature = "sync")]
@@ -191,6 +400,7 @@ impl unpark::Unpark for UnparkHandle {
             UnparkHandle::Uring(inner) => inner.unpark(),
             #[cfg(all(unix, feature = "legacy"))]
             UnparkHandle::Legacy(inner) => inner.unpark(),
+            UnparkHandle::Custom(inner) => inner.unpark(),
             #[cfg(all(
                 not(feature = "legacy"),
                 not(all(target_os = "linux", feature = "iouring"))
@@ -3,19 +3,47 @@ use std::{
     io,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use crate::driver;
 
+/// Build the SQE of an op on a `SharedFd`, referring to the file by its slot
+/// in the fixed file table if it has one.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+macro_rules! uring_fd {
+    ($shared_fd:expr, |$fd:ident| $sqe:expr) => {
+        match $shared_fd.fixed_slot() {
+            Some(slot) => {
+                let $fd = io_uring::types::Fixed(slot);
+                $sqe
+            }
+            None => {
+                let $fd = io_uring::types::Fd($shared_fd.raw_fd());
+                $sqe
+            }
+        }
+    };
+}
+
 pub(crate) mod close;
 
 mod accept;
-mod connect;
+pub(crate) mod connect;
+mod fallocate;
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) mod fixed;
 mod fsync;
 mod open;
+#[cfg(unix)]
+pub(crate) mod poll;
 mod read;
-mod recv;
+pub(crate) mod recv;
 mod send;
+#[cfg(unix)]
+pub(crate) mod shutdown;
+#[cfg(unix)]
+pub(crate) mod statx;
 mod write;
 
 #[cfg(all(target_os = "linux", feature = "splice"))]
@@ -31,6 +59,75 @@ pub(crate) struct Op<T: 'static> {
 
     // Per-operation data
     pub(super) data: Option<T>,
+
+    // Timer giving up the op, for the legacy driver which can not link a
+    // timeout to it
+    #[cfg(all(unix, feature = "legacy"))]
+    pub(super) deadline: Option<Pin<Box<crate::time::Sleep>>>,
+
+    // Span of the op, set once it is submitted
+    #[cfg(feature = "tracing")]
+    pub(super) trace: Option<OpTrace>,
+}
+
+/// Span of an op with the time it is submitted, so its completion can be
+/// recorded with the latency.
+#[cfg(feature = "tracing")]
+pub(super) struct OpTrace {
+    span: tracing::Span,
+    submitted: std::time::Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl OpTrace {
+    pub(super) fn new<T: OpAble>(data: &T) -> Self {
+        // Name the op by its type, e.g. `Read` for `op::read::Read<Vec<u8>>`.
+        let name = std::any::type_name::<T>();
+        let name = name.split('<').next().unwrap_or(name);
+        let name = name.rsplit("::").next().unwrap_or(name);
+        let fd = data.fd().map(|fd| fd.raw_fd());
+        let span = tracing::trace_span!("op", op = name, fd);
+        tracing::trace!(parent: &span, "submitted");
+        Self {
+            span,
+            submitted: std::time::Instant::now(),
+        }
+    }
+
+    fn completed(&self, meta: &CompletionMeta) {
+        tracing::trace!(
+            parent: &self.span,
+            result = ?meta.result,
+            latency_us = self.submitted.elapsed().as_micros() as u64,
+            "completed"
+        );
+    }
+
+    fn dropped(&self) {
+        tracing::trace!(
+            parent: &self.span,
+            latency_us = self.submitted.elapsed().as_micros() as u64,
+            "dropped in flight"
+        );
+    }
+}
+
+/// Identifies an in-flight op, so it can be canceled while another task is
+/// waiting for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OpCanceller {
+    // Op index in the slab, or io index for the legacy driver
+    pub(super) index: usize,
+    // Readiness the op waits for, `None` for uring ops
+    #[cfg(all(unix, feature = "legacy"))]
+    pub(super) direction: Option<super::legacy::ready::Direction>,
+}
+
+impl OpCanceller {
+    /// Cancel the op. An op which has already completed is not affected.
+    pub(crate) fn cancel(&self) {
+        driver::CURRENT.with(|this| this.cancel(self));
+    }
 }
 
 /// Operation completion. Returns stored state with the result of the operation.
@@ -56,16 +153,30 @@ pub(crate) trait OpAble {
     fn legacy_interest(&self) -> Option<(super::legacy::ready::Direction, usize)>;
     #[cfg(all(unix, feature = "legacy"))]
     fn legacy_call(&mut self) -> io::Result<u32>;
+
+    /// Opcode of `uring_op` if the op can be done by `legacy_call` on the
+    /// uring driver when the kernel does not support the opcode. Only ops
+    /// which do not wait for readiness can fall back.
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    fn uring_fallback_opcode(&self) -> Option<u8> {
+        None
+    }
+
+    /// File the op works on, recorded in its span by the `tracing` feature.
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&super::shared_fd::SharedFd> {
+        None
+    }
 }
 
 /// If legacy is enabled and iouring is not, we can expose io interface in a poll-like way.
 /// This can provide better compatibility for crates programmed in poll-like way.
-#[cfg(all(unix, feature = "legacy"))]
+#[cfg(all(unix, feature = "legacy", feature = "tokio-compat"))]
 pub(crate) trait PollLegacy {
     fn poll_legacy(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<CompletionMeta>;
 }
 
-#[cfg(all(unix, feature = "legacy"))]
+#[cfg(all(unix, feature = "legacy", feature = "tokio-compat"))]
 impl<T> PollLegacy for T
 where
     T: OpAble,
@@ -95,7 +206,17 @@ impl<T> Op<T> {
     where
         T: OpAble,
     {
-        driver::CURRENT.with(|this| this.submit_with(data))
+        driver::CURRENT.with(|this| this.submit_with(data, None))
+    }
+
+    /// Submit an operation which fails with `ETIMEDOUT` if it is not done
+    /// within `timeout`. With the uring driver, a linked timeout cancels it in
+    /// the kernel.
+    pub(crate) fn submit_with_timeout(data: T, timeout: Option<Duration>) -> io::Result<Op<T>>
+    where
+        T: OpAble,
+    {
+        driver::CURRENT.with(|this| this.submit_with(data, timeout))
     }
 
     /// Try submitting an operation to uring
@@ -112,6 +233,79 @@ impl<T> Op<T> {
     }
 }
 
+impl<T: OpAble> Op<T> {
+    /// Get the canceller of the op. With the legacy driver, it cancels the op
+    /// waiting for the same readiness of the same fd.
+    pub(crate) fn op_canceller(&self) -> OpCanceller {
+        #[cfg(all(unix, feature = "legacy"))]
+        if self.driver.is_legacy() {
+            let interest = self.data.as_ref().and_then(|data| data.legacy_interest());
+            return OpCanceller {
+                index: interest.map_or(0, |(_, index)| index),
+                direction: interest.map(|(direction, _)| direction),
+            };
+        }
+        OpCanceller {
+            index: self.index,
+            #[cfg(all(unix, feature = "legacy"))]
+            direction: None,
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl<T> Op<T> {
+    /// Poll the next completion of a multishot op. The op is finished once a
+    /// completion without `IORING_CQE_F_MORE` is returned.
+    pub(crate) fn poll_multi(&mut self, cx: &mut Context<'_>) -> Poll<CompletionMeta> {
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
+        let meta = ready!(self.driver.poll_multi_op(self.index, cx));
+        coop.made_progress();
+        #[cfg(feature = "tracing")]
+        if let Some(trace) = self.trace.as_ref() {
+            trace.completed(&meta);
+        }
+        if !io_uring::cqueue::more(meta.flags) {
+            self.index = usize::MAX;
+        }
+        Poll::Ready(meta)
+    }
+
+    /// Cancel the op if it is in flight.
+    pub(crate) fn cancel(&self) {
+        self.driver.cancel_op(self.index);
+    }
+
+    /// Whether the op was turned down because as many ops as the slab limit
+    /// were in flight, so it never reaches the kernel.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn is_rejected(&self) -> bool {
+        !self.driver.is_legacy() && self.index == usize::MAX
+    }
+}
+
+impl<T> Op<T> {
+    /// Drop the op without canceling it, for ops which must take effect even
+    /// if nobody waits for them.
+    #[allow(unused)]
+    pub(crate) fn detach(mut self) {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        {
+            self.driver.detach_op(self.index, &mut self.data);
+            self.index = usize::MAX;
+        }
+    }
+
+    /// Whether the timer of a legacy op has fired.
+    #[cfg(all(unix, feature = "legacy"))]
+    fn poll_deadline(&mut self, cx: &mut Context<'_>) -> bool {
+        match self.deadline.as_mut() {
+            Some(deadline) => deadline.as_mut().poll(cx).is_ready(),
+            None => false,
+        }
+    }
+}
+
 impl<T> Future for Op<T>
 where
     T: Unpin + OpAble + 'static,
@@ -120,9 +314,23 @@ where
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let me = &mut *self;
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
         let data_mut = me.data.as_mut().expect("unexpected operation state");
-        let meta = ready!(me.driver.poll_op::<T>(data_mut, me.index, cx));
+        let meta = match me.driver.poll_op::<T>(data_mut, me.index, cx) {
+            Poll::Ready(meta) => meta,
+            #[cfg(all(unix, feature = "legacy"))]
+            Poll::Pending if me.poll_deadline(cx) => CompletionMeta {
+                result: Err(io::Error::from_raw_os_error(libc::ETIMEDOUT)),
+                flags: 0,
+            },
+            Poll::Pending => return Poll::Pending,
+        };
+        coop.made_progress();
 
+        #[cfg(feature = "tracing")]
+        if let Some(trace) = me.trace.as_ref() {
+            trace.completed(&meta);
+        }
         me.index = usize::MAX;
         let data = me.data.take().expect("unexpected operation state");
         Poll::Ready(Completion { data, meta })
@@ -131,6 +339,12 @@ where
 
 impl<T> Drop for Op<T> {
     fn drop(&mut self) {
+        #[cfg(feature = "tracing")]
+        if let Some(trace) = self.trace.as_ref() {
+            if self.index != usize::MAX {
+                trace.dropped();
+            }
+        }
         self.driver.drop_op(self.index, &mut self.data);
     }
 }
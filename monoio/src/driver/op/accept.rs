@@ -4,7 +4,7 @@ use std::{
 };
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
 #[cfg(all(unix, feature = "legacy"))]
 use {
     crate::{driver::legacy::ready::Direction, syscall_u32},
@@ -36,14 +36,18 @@ impl Op<Accept> {
 }
 
 impl OpAble for Accept {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Accept::new(
-            types::Fd(self.fd.raw_fd()),
+        let (addr, addrlen) = (
             self.addr.0.as_mut_ptr() as *mut _,
-            &mut self.addr.1,
-        )
-        .build()
+            &mut self.addr.1 as *mut _,
+        );
+        uring_fd!(self.fd, |fd| opcode::Accept::new(fd, addr, addrlen).build())
     }
 
     #[cfg(all(unix, feature = "legacy"))]
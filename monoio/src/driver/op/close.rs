@@ -8,6 +8,10 @@ use super::{Op, OpAble};
 #[cfg(all(unix, feature = "legacy"))]
 use crate::{driver::legacy::ready::Direction, syscall_u32};
 
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
 pub(crate) struct Close {
     #[cfg(unix)]
     fd: RawFd,
@@ -17,7 +21,14 @@ impl Op<Close> {
     #[allow(unused)]
     #[cfg(unix)]
     pub(crate) fn close(fd: RawFd) -> io::Result<Op<Close>> {
-        Op::try_submit_with(Close { fd })
+        let op = Op::try_submit_with(Close { fd })?;
+        // A close turned down by a full op slab would never run, so the
+        // caller has to close the fd itself.
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if op.is_rejected() {
+            return Err(io::Error::from_raw_os_error(libc::EBUSY));
+        }
+        Ok(op)
     }
 }
 
@@ -27,6 +38,11 @@ impl OpAble for Close {
         opcode::Close::new(types::Fd(self.fd)).build()
     }
 
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    fn uring_fallback_opcode(&self) -> Option<u8> {
+        Some(opcode::Close::CODE)
+    }
+
     #[cfg(all(unix, feature = "legacy"))]
     fn legacy_interest(&self) -> Option<(Direction, usize)> {
         None
@@ -7,6 +7,10 @@ use super::{super::shared_fd::SharedFd, Op, OpAble};
 #[cfg(all(unix, feature = "legacy"))]
 use crate::driver::legacy::ready::Direction;
 
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
 pub(crate) struct Connect {
     pub(crate) fd: SharedFd,
     socket_addr: Box<SocketAddrCRepr>,
@@ -14,8 +18,9 @@ pub(crate) struct Connect {
 }
 
 impl Op<Connect> {
-    /// Submit a request to connect.
-    pub(crate) fn connect(socket_type: libc::c_int, addr: SocketAddr) -> io::Result<Op<Connect>> {
+    /// Create a socket and a request to connect it, to be submitted with or
+    /// without a timeout.
+    pub(crate) fn connect_raw(socket_type: libc::c_int, addr: SocketAddr) -> io::Result<Connect> {
         #[cfg(unix)]
         {
             let domain = match addr {
@@ -23,20 +28,30 @@ impl Op<Connect> {
                 SocketAddr::V6(_) => libc::AF_INET6,
             };
             let socket = super::new_socket(domain, socket_type)?;
-            let (raw_addr, raw_addr_length) = socket_addr(&addr);
-
-            Op::submit_with(Connect {
-                fd: SharedFd::new(socket)?,
-                socket_addr: Box::new(raw_addr),
-                socket_addr_len: raw_addr_length,
-            })
+            Ok(Self::connect_fd_raw(SharedFd::new(socket)?, addr))
         }
         #[cfg(windows)]
         unimplemented!()
     }
+
+    /// A request to connect an existing socket, to be submitted.
+    #[cfg(unix)]
+    pub(crate) fn connect_fd_raw(fd: SharedFd, addr: SocketAddr) -> Connect {
+        let (raw_addr, raw_addr_length) = socket_addr(&addr);
+        Connect {
+            fd,
+            socket_addr: Box::new(raw_addr),
+            socket_addr_len: raw_addr_length,
+        }
+    }
 }
 
 impl OpAble for Connect {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Connect::new(
@@ -65,6 +80,10 @@ impl OpAble for Connect {
     }
 }
 
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
 pub(crate) struct ConnectUnix {
     /// Holds a strong ref to the FD, preventing the file from being closed
     /// while the operation is in-flight.
@@ -74,14 +93,14 @@ pub(crate) struct ConnectUnix {
 }
 
 impl Op<ConnectUnix> {
-    #[cfg(unix)]
-
     /// Submit a request to connect.
+    #[cfg(unix)]
     pub(crate) fn connect_unix(
+        socket_type: libc::c_int,
         socket_addr: libc::sockaddr_un,
         socket_len: libc::socklen_t,
     ) -> io::Result<Op<ConnectUnix>> {
-        let socket = super::new_socket(libc::AF_UNIX, libc::SOCK_STREAM)?;
+        let socket = super::new_socket(libc::AF_UNIX, socket_type)?;
 
         Op::submit_with(ConnectUnix {
             fd: SharedFd::new(socket)?,
@@ -91,6 +110,11 @@ impl Op<ConnectUnix> {
 }
 
 impl OpAble for ConnectUnix {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Connect::new(
@@ -131,6 +155,10 @@ pub(crate) union SocketAddrCRepr {
 }
 
 impl SocketAddrCRepr {
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(dead_code)
+    )]
     pub(crate) fn as_ptr(&self) -> *const libc::sockaddr {
         self as *const _ as *const libc::sockaddr
     }
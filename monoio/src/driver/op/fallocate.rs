@@ -0,0 +1,69 @@
+use std::io;
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use io_uring::opcode;
+
+use super::{super::shared_fd::SharedFd, Op, OpAble};
+#[cfg(all(unix, feature = "legacy"))]
+use crate::driver::legacy::ready::Direction;
+#[cfg(all(target_os = "linux", feature = "legacy"))]
+use crate::syscall_u32;
+
+/// Allocate disk space for a range of a file.
+pub(crate) struct Fallocate {
+    #[allow(unused)]
+    fd: SharedFd,
+    #[allow(unused)]
+    offset: u64,
+    #[allow(unused)]
+    len: u64,
+}
+
+impl Op<Fallocate> {
+    pub(crate) fn fallocate(fd: &SharedFd, offset: u64, len: u64) -> io::Result<Op<Fallocate>> {
+        Op::submit_with(Fallocate {
+            fd: fd.clone(),
+            offset,
+            len,
+        })
+    }
+}
+
+impl OpAble for Fallocate {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        uring_fd!(self.fd, |fd| opcode::Fallocate64::new(fd, self.len as _)
+            .offset(self.offset as _)
+            .build())
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    fn uring_fallback_opcode(&self) -> Option<u8> {
+        Some(opcode::Fallocate64::CODE)
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(all(target_os = "linux", feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        syscall_u32!(fallocate(
+            self.fd.raw_fd(),
+            0,
+            self.offset as _,
+            self.len as _
+        ))
+    }
+
+    #[cfg(all(unix, not(target_os = "linux"), feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
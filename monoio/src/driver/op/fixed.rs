@@ -0,0 +1,152 @@
+//! Fast path for reads and writes of fixed files with registered buffers.
+
+use std::{
+    cell::UnsafeCell,
+    future::Future,
+    io,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use io_uring::{opcode, squeue::Entry, types};
+
+use crate::{
+    buf::{FixedBuf, IoBuf, IoBufMut},
+    driver::{shared_fd::SharedFd, uring::UringInner, Inner, CURRENT},
+    BufResult,
+};
+
+/// An op on a fixed file with a registered buffer, which borrows the file
+/// while it is awaited.
+///
+/// Unlike [`Op`](super::Op), it neither holds the file nor the driver, so
+/// submitting and completing it does not allocate or touch a reference count.
+/// The file is only cloned if the op is dropped in flight, to keep it open
+/// until the kernel is done with it. Fast ops are not traced.
+pub(crate) struct FixedOp<'a> {
+    fd: &'a SharedFd,
+    // Driver the op is submitted to, only compared with the current one.
+    driver: *const UnsafeCell<UringInner>,
+    index: usize,
+    buf: Option<FixedBuf>,
+}
+
+impl<'a> FixedOp<'a> {
+    /// Read into `buf` at `offset`. The buffer is handed back if the fast
+    /// path does not apply: the driver is not io_uring, the file is not in
+    /// the fixed file table or the pool of the buffer is not registered.
+    pub(crate) fn read_at(
+        fd: &'a SharedFd,
+        mut buf: FixedBuf,
+        offset: u64,
+    ) -> Result<Self, FixedBuf> {
+        let (slot, index) = match (fd.fixed_slot(), buf.fixed_index()) {
+            (Some(slot), Some(index)) => (slot, index),
+            _ => return Err(buf),
+        };
+        let (ptr, len) = (buf.write_ptr(), buf.bytes_total());
+        let sqe = opcode::ReadFixed::new(types::Fixed(slot), ptr, len as _, index)
+            .offset(offset as _)
+            .build();
+        Self::submit(fd, buf, sqe)
+    }
+
+    /// Write `buf` at `offset`, see [`read_at`](FixedOp::read_at).
+    pub(crate) fn write_at(fd: &'a SharedFd, buf: FixedBuf, offset: u64) -> Result<Self, FixedBuf> {
+        let (slot, index) = match (fd.fixed_slot(), buf.fixed_index()) {
+            (Some(slot), Some(index)) => (slot, index),
+            _ => return Err(buf),
+        };
+        let (ptr, len) = (buf.read_ptr(), buf.bytes_init());
+        let sqe = opcode::WriteFixed::new(types::Fixed(slot), ptr, len as _, index)
+            .offset(offset as _)
+            .build();
+        Self::submit(fd, buf, sqe)
+    }
+
+    // The buffer lives in the arena of its pool, so the SQE stays valid when
+    // it is moved into the op.
+    fn submit(fd: &'a SharedFd, buf: FixedBuf, sqe: Entry) -> Result<Self, FixedBuf> {
+        CURRENT.with(|inner| match inner {
+            Inner::Uring(this) => match UringInner::push_entry(this, sqe) {
+                Ok(index) => Ok(FixedOp {
+                    fd,
+                    driver: Rc::as_ptr(this),
+                    index,
+                    buf: Some(buf),
+                }),
+                Err(_) => Err(buf),
+            },
+            #[allow(unreachable_patterns)]
+            _ => Err(buf),
+        })
+    }
+
+    pub(crate) async fn read(self) -> BufResult<usize, FixedBuf> {
+        let (res, mut buf) = self.await;
+        let res = res.map(|v| v as usize);
+        if let Ok(n) = res {
+            // Safety: the kernel wrote `n` bytes to the buffer.
+            unsafe { buf.set_init(n) };
+        }
+        (res, buf)
+    }
+
+    pub(crate) async fn write(self) -> BufResult<usize, FixedBuf> {
+        let (res, buf) = self.await;
+        (res.map(|v| v as _), buf)
+    }
+}
+
+impl Future for FixedOp<'_> {
+    type Output = (io::Result<u32>, FixedBuf);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = &mut *self;
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
+        let index = me.index;
+        let meta = with_driver(me.driver, |this| UringInner::poll_op(this, index, cx))
+            .expect("fixed op polled out of its runtime");
+        let meta = ready!(meta);
+        coop.made_progress();
+        me.index = usize::MAX;
+        let buf = me.buf.take().expect("unexpected operation state");
+        Poll::Ready((meta.result, buf))
+    }
+}
+
+impl Drop for FixedOp<'_> {
+    fn drop(&mut self) {
+        if self.index == usize::MAX {
+            return;
+        }
+        // The kernel may still use the file and the buffer.
+        let mut data = self.buf.take().map(|buf| (buf, self.fd.clone()));
+        let index = self.index;
+        if with_driver(self.driver, |this| {
+            UringInner::drop_op(this, index, &mut data)
+        })
+        .is_none()
+        {
+            // Out of its runtime the op can not be tracked, so the buffer is
+            // leaked rather than reused while the kernel may write to it.
+            std::mem::forget(data);
+        }
+    }
+}
+
+// Run `f` on the driver if it is the one of the current runtime, which also
+// proves it is alive.
+fn with_driver<R>(
+    driver: *const UnsafeCell<UringInner>,
+    f: impl FnOnce(&Rc<UnsafeCell<UringInner>>) -> R,
+) -> Option<R> {
+    if !CURRENT.is_set() {
+        return None;
+    }
+    CURRENT.with(|inner| match inner {
+        Inner::Uring(this) if Rc::as_ptr(this) == driver => Some(f(this)),
+        _ => None,
+    })
+}
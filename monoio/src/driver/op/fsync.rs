@@ -1,7 +1,7 @@
 use std::io;
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::{opcode, squeue, types};
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
 #[cfg(all(unix, feature = "legacy"))]
@@ -10,36 +10,61 @@ use crate::{driver::legacy::ready::Direction, syscall_u32};
 pub(crate) struct Fsync {
     #[allow(unused)]
     fd: SharedFd,
-    #[cfg(target_os = "linux")]
+    #[allow(unused)]
     data_sync: bool,
+    // Not started before all ops submitted earlier complete
+    #[allow(unused)]
+    drain: bool,
 }
 
 impl Op<Fsync> {
     pub(crate) fn fsync(fd: &SharedFd) -> io::Result<Op<Fsync>> {
-        Op::submit_with(Fsync {
-            fd: fd.clone(),
-            #[cfg(target_os = "linux")]
-            data_sync: false,
-        })
+        Op::sync(fd, false, false)
     }
 
     pub(crate) fn datasync(fd: &SharedFd) -> io::Result<Op<Fsync>> {
+        Op::sync(fd, true, false)
+    }
+
+    /// Submit a sync ordered after all ops submitted before it, with
+    /// `IOSQE_IO_DRAIN` on the uring driver.
+    pub(crate) fn sync_barrier(fd: &SharedFd, data_sync: bool) -> io::Result<Op<Fsync>> {
+        Op::sync(fd, data_sync, true)
+    }
+
+    fn sync(fd: &SharedFd, data_sync: bool, drain: bool) -> io::Result<Op<Fsync>> {
         Op::submit_with(Fsync {
             fd: fd.clone(),
-            #[cfg(target_os = "linux")]
-            data_sync: true,
+            data_sync,
+            drain,
         })
     }
 }
 
 impl OpAble for Fsync {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        let mut opc = opcode::Fsync::new(types::Fd(self.fd.raw_fd()));
-        if self.data_sync {
-            opc = opc.flags(types::FsyncFlags::DATASYNC)
+        let flags = if self.data_sync {
+            types::FsyncFlags::DATASYNC
+        } else {
+            types::FsyncFlags::empty()
+        };
+        let sqe = uring_fd!(self.fd, |fd| opcode::Fsync::new(fd).flags(flags).build());
+        if self.drain {
+            sqe.flags(squeue::Flags::IO_DRAIN)
+        } else {
+            sqe
         }
-        opc.build()
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    fn uring_fallback_opcode(&self) -> Option<u8> {
+        Some(opcode::Fsync::CODE)
     }
 
     #[cfg(all(unix, feature = "legacy"))]
@@ -9,6 +9,10 @@ use crate::{driver::legacy::ready::Direction, syscall_u32};
 use crate::{driver::util::cstr, fs::OpenOptions};
 
 /// Open a file
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
 pub(crate) struct Open {
     pub(crate) path: CString,
     flags: i32,
@@ -22,7 +26,16 @@ impl Op<Open> {
     pub(crate) fn open<P: AsRef<Path>>(path: P, options: &OpenOptions) -> io::Result<Op<Open>> {
         // Here the path will be copied, so its safe.
         let path = cstr(path.as_ref())?;
-        let flags = libc::O_CLOEXEC | options.access_mode()? | options.creation_mode()?;
+        let flags = libc::O_CLOEXEC
+            | options.access_mode()?
+            | options.creation_mode()?
+            | (options.custom_flags & !libc::O_ACCMODE);
+        #[cfg(target_os = "linux")]
+        let flags = if options.direct {
+            flags | libc::O_DIRECT
+        } else {
+            flags
+        };
         let mode = options.mode;
 
         Op::submit_with(Open { path, flags, mode })
@@ -38,6 +51,11 @@ impl OpAble for Open {
             .build()
     }
 
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    fn uring_fallback_opcode(&self) -> Option<u8> {
+        Some(opcode::OpenAt::CODE)
+    }
+
     #[cfg(all(unix, feature = "legacy"))]
     fn legacy_interest(&self) -> Option<(Direction, usize)> {
         None
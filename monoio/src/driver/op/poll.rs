@@ -0,0 +1,230 @@
+use std::io;
+#[cfg(feature = "poll-io")]
+use std::{
+    io::IoSlice,
+    task::{Context, Poll},
+};
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use io_uring::opcode;
+
+use super::{super::shared_fd::SharedFd, Op, OpAble};
+#[cfg(all(unix, feature = "legacy"))]
+use crate::driver::legacy::ready::Direction;
+
+/// Wait for the fd to be readable or writable.
+pub(crate) struct PollAdd {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+    #[allow(unused)]
+    is_read: bool,
+}
+
+impl Op<PollAdd> {
+    pub(crate) fn poll_read(fd: &SharedFd) -> io::Result<Self> {
+        Op::submit_with(PollAdd {
+            fd: fd.clone(),
+            is_read: true,
+        })
+    }
+
+    pub(crate) fn poll_write(fd: &SharedFd) -> io::Result<Self> {
+        Op::submit_with(PollAdd {
+            fd: fd.clone(),
+            is_read: false,
+        })
+    }
+}
+
+impl OpAble for PollAdd {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let flags = if self.is_read {
+            libc::POLLIN | libc::POLLPRI | libc::POLLRDHUP
+        } else {
+            libc::POLLOUT
+        };
+        uring_fd!(self.fd, |fd| opcode::PollAdd::new(fd, flags as _).build())
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        let direction = if self.is_read {
+            Direction::Read
+        } else {
+            Direction::Write
+        };
+        self.fd.registered_index().map(|idx| (direction, idx))
+    }
+
+    // The readiness of the driver may be left from an earlier event, so check
+    // it again. Not ready clears it to wait for the next event.
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        let mut pollfd = libc::pollfd {
+            fd: self.fd.raw_fd(),
+            events: if self.is_read {
+                libc::POLLIN | libc::POLLPRI
+            } else {
+                libc::POLLOUT
+            },
+            revents: 0,
+        };
+        match crate::syscall!(poll(&mut pollfd, 1, 0))? {
+            0 => Err(io::ErrorKind::WouldBlock.into()),
+            _ => Ok(pollfd.revents as u32),
+        }
+    }
+}
+
+/// Poll-style IO over borrowed buffers on a socket.
+///
+/// With the legacy driver, the syscall is done once the fd is ready, just like
+/// other ops. With the uring driver, the syscall is tried without blocking, and
+/// a `PollAdd` is submitted to wait for the readiness when it would block.
+#[cfg(feature = "poll-io")]
+pub(crate) struct PollIo {
+    fd: SharedFd,
+    #[allow(unused)]
+    read: Option<Op<PollAdd>>,
+    #[allow(unused)]
+    write: Option<Op<PollAdd>>,
+}
+
+#[cfg(feature = "poll-io")]
+impl PollIo {
+    pub(crate) fn new(fd: SharedFd) -> Self {
+        Self {
+            fd,
+            read: None,
+            write: None,
+        }
+    }
+
+    pub(crate) fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        #[cfg(all(unix, feature = "legacy"))]
+        if crate::driver::CURRENT.with(|inner| inner.is_legacy()) {
+            let raw_buf = unsafe { crate::buf::RawBuf::new(buf.as_ptr(), buf.len()) };
+            return Self::poll_legacy(Op::recv_raw(&self.fd, raw_buf), cx);
+        }
+        let fd = self.fd.raw_fd();
+        self.poll_uring(cx, true, || {
+            crate::syscall!(recv(
+                fd,
+                buf.as_mut_ptr() as _,
+                buf.len(),
+                libc::MSG_DONTWAIT
+            ))
+        })
+    }
+
+    pub(crate) fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        #[cfg(all(unix, feature = "legacy"))]
+        if crate::driver::CURRENT.with(|inner| inner.is_legacy()) {
+            let raw_buf = unsafe { crate::buf::RawBuf::new(buf.as_ptr(), buf.len()) };
+            return Self::poll_legacy(Op::send_raw(&self.fd, raw_buf), cx);
+        }
+        let fd = self.fd.raw_fd();
+        self.poll_uring(cx, false, || {
+            crate::syscall!(send(
+                fd,
+                buf.as_ptr() as _,
+                buf.len(),
+                libc::MSG_DONTWAIT | SEND_FLAGS
+            ))
+        })
+    }
+
+    pub(crate) fn poll_sendv(
+        &mut self,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        #[cfg(all(unix, feature = "legacy"))]
+        if crate::driver::CURRENT.with(|inner| inner.is_legacy()) {
+            let raw_buf = unsafe {
+                crate::buf::RawBufVectored::new(bufs.as_ptr() as *const libc::iovec, bufs.len())
+            };
+            return Self::poll_legacy(Op::writev_raw(&self.fd, raw_buf), cx);
+        }
+        let fd = self.fd.raw_fd();
+        self.poll_uring(cx, false, || {
+            let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+            msg.msg_iov = bufs.as_ptr() as *mut libc::iovec;
+            msg.msg_iovlen = bufs.len() as _;
+            crate::syscall!(sendmsg(fd, &msg, libc::MSG_DONTWAIT | SEND_FLAGS))
+        })
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn poll_legacy<T: OpAble>(mut op: T, cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        crate::driver::CURRENT
+            .with(|inner| inner.poll_op(&mut op, 0, cx))
+            .map(|meta| meta.result.map(|n| n as usize))
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn poll_uring(
+        &mut self,
+        cx: &mut Context<'_>,
+        is_read: bool,
+        mut f: impl FnMut() -> io::Result<isize>,
+    ) -> Poll<io::Result<usize>> {
+        use std::{future::Future, pin::Pin};
+
+        let fd = &self.fd;
+        let wait = if is_read {
+            &mut self.read
+        } else {
+            &mut self.write
+        };
+        loop {
+            if let Some(op) = wait.as_mut() {
+                let completion = ready!(Pin::new(op).poll(cx));
+                *wait = None;
+                completion.meta.result?;
+            }
+            match f() {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    *wait = Some(if is_read {
+                        Op::poll_read(fd)?
+                    } else {
+                        Op::poll_write(fd)?
+                    });
+                }
+                res => return Poll::Ready(res.map(|n| n as usize)),
+            }
+        }
+    }
+
+    // The legacy driver is the only one without uring.
+    #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+    fn poll_uring(
+        &mut self,
+        _cx: &mut Context<'_>,
+        _is_read: bool,
+        _f: impl FnMut() -> io::Result<isize>,
+    ) -> Poll<io::Result<usize>> {
+        unreachable!()
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "poll-io"))]
+const SEND_FLAGS: libc::c_int = libc::MSG_NOSIGNAL;
+#[cfg(all(not(target_os = "linux"), feature = "poll-io"))]
+const SEND_FLAGS: libc::c_int = 0;
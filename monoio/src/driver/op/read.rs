@@ -1,7 +1,7 @@
 use std::io;
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
 #[cfg(all(unix, feature = "legacy"))]
 use {
     crate::{driver::legacy::ready::Direction, syscall_u32},
@@ -9,11 +9,17 @@ use {
 };
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use crate::driver::BufRing;
 use crate::{
-    buf::{IoBufMut, IoVecBufMut},
+    buf::{FixedBuf, IoBufMut, IoVecBufMut, ProvidedBuf},
     BufResult,
 };
 
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
 pub(crate) struct Read<T> {
     /// Holds a strong ref to the FD, preventing the file from being closed
     /// while the operation is in-flight.
@@ -34,6 +40,19 @@ impl<T: IoBufMut> Op<Read<T>> {
         })
     }
 
+    /// Read at the file position, for streams such as pipes.
+    pub(crate) fn read_stream(fd: &SharedFd, buf: T) -> io::Result<Op<Read<T>>> {
+        Op::submit_with(Read {
+            fd: fd.clone(),
+            offset: -1,
+            buf,
+        })
+    }
+
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
     pub(crate) async fn read(self) -> BufResult<usize, T> {
         let complete = self.await;
 
@@ -54,16 +73,58 @@ impl<T: IoBufMut> Op<Read<T>> {
     }
 }
 
+impl Op<Read<Vec<u8>>> {
+    /// Read up to `len` bytes at `offset` into a buffer the kernel picks from
+    /// the provided buffers of the driver. Without provided buffers, or when
+    /// all of them are in use, a heap buffer is used instead.
+    pub(crate) async fn read_provided_at(
+        fd: &SharedFd,
+        len: usize,
+        offset: u64,
+    ) -> io::Result<ProvidedBuf> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(ring) = crate::driver::CURRENT.with(|inner| inner.provided_buffers()) {
+            let completion = Op::submit_with(ReadProvided {
+                fd: fd.clone(),
+                offset: offset as _,
+                ring: ring.clone(),
+                len: len.min(u32::MAX as usize) as u32,
+            })?
+            .await;
+            let flags = completion.meta.flags;
+            match completion.meta.result {
+                Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {}
+                res => return res.map(|n| ProvidedBuf::from_completion(&ring, flags, n)),
+            }
+        }
+        let (res, buf) = Op::read_at(fd, Vec::with_capacity(len), offset)?
+            .read()
+            .await;
+        res.map(|_| ProvidedBuf::heap(buf))
+    }
+}
+
 impl<T: IoBufMut> OpAble for Read<T> {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Read::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.write_ptr(),
-            self.buf.bytes_total() as _,
-        )
-        .offset(self.offset)
-        .build()
+        let (ptr, len) = (self.buf.write_ptr(), self.buf.bytes_total());
+        if let Some(index) = crate::buf::pool::registered_index(ptr, len) {
+            return uring_fd!(self.fd, |fd| {
+                opcode::ReadFixed::new(fd, ptr, len as _, index)
+                    .offset(self.offset)
+                    .build()
+            });
+        }
+        uring_fd!(self.fd, |fd| {
+            opcode::Read::new(fd, ptr, len as _)
+                .offset(self.offset)
+                .build()
+        })
     }
 
     #[cfg(all(unix, feature = "legacy"))]
@@ -74,18 +135,13 @@ impl<T: IoBufMut> OpAble for Read<T> {
     #[cfg(all(unix, feature = "legacy"))]
     fn legacy_call(&mut self) -> io::Result<u32> {
         let fd = self.fd.as_raw_fd();
-        if self.offset != 0 {
-            syscall_u32!(lseek(fd, self.offset, libc::SEEK_CUR))?;
-            syscall_u32!(read(
+        if self.offset >= 0 {
+            syscall_u32!(pread(
                 fd,
                 self.buf.write_ptr() as _,
-                self.buf.bytes_total().min(u32::MAX as usize)
+                self.buf.bytes_total().min(u32::MAX as usize),
+                self.offset
             ))
-            .map_err(|e| {
-                // seek back if read fail...
-                let _ = syscall_u32!(lseek(fd, -self.offset, libc::SEEK_CUR));
-                e
-            })
         } else {
             syscall_u32!(read(
                 fd,
@@ -96,6 +152,92 @@ impl<T: IoBufMut> OpAble for Read<T> {
     }
 }
 
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
+pub(crate) struct ReadFixed {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+    offset: libc::off_t,
+
+    /// Reference to the in-flight buffer.
+    pub(crate) buf: FixedBuf,
+}
+
+impl Op<ReadFixed> {
+    pub(crate) fn read_fixed_at(
+        fd: &SharedFd,
+        buf: FixedBuf,
+        offset: u64,
+    ) -> io::Result<Op<ReadFixed>> {
+        Op::submit_with(ReadFixed {
+            fd: fd.clone(),
+            offset: offset as _,
+            buf,
+        })
+    }
+
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
+    pub(crate) async fn read(self) -> BufResult<usize, FixedBuf> {
+        let complete = self.await;
+        let res = complete.meta.result.map(|v| v as usize);
+        let mut buf = complete.data.buf;
+
+        if let Ok(n) = res {
+            // Safety: the kernel wrote `n` bytes to the buffer.
+            unsafe {
+                buf.set_init(n);
+            }
+        }
+        (res, buf)
+    }
+}
+
+impl OpAble for ReadFixed {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let (ptr, len) = (self.buf.write_ptr(), self.buf.bytes_total());
+        match self.buf.fixed_index() {
+            Some(index) => uring_fd!(self.fd, |fd| {
+                opcode::ReadFixed::new(fd, ptr, len as _, index)
+                    .offset(self.offset)
+                    .build()
+            }),
+            None => uring_fd!(self.fd, |fd| {
+                opcode::Read::new(fd, ptr, len as _)
+                    .offset(self.offset)
+                    .build()
+            }),
+        }
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd.registered_index().map(|idx| (Direction::Read, idx))
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        syscall_u32!(pread(
+            self.fd.as_raw_fd(),
+            self.buf.write_ptr() as _,
+            self.buf.bytes_total(),
+            self.offset
+        ))
+    }
+}
+
 pub(crate) struct ReadVec<T> {
     /// Holds a strong ref to the FD, preventing the file from being closed
     /// while the operation is in-flight.
@@ -114,6 +256,17 @@ impl<T: IoVecBufMut> Op<ReadVec<T>> {
         })
     }
 
+    pub(crate) fn readv_raw(fd: &SharedFd, buf_vec: T) -> ReadVec<T> {
+        ReadVec {
+            fd: fd.clone(),
+            buf_vec,
+        }
+    }
+
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
     pub(crate) async fn read(self) -> BufResult<usize, T> {
         let complete = self.await;
         let res = complete.meta.result.map(|v| v as _);
@@ -130,11 +283,16 @@ impl<T: IoVecBufMut> Op<ReadVec<T>> {
 }
 
 impl<T: IoVecBufMut> OpAble for ReadVec<T> {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         let ptr = self.buf_vec.write_iovec_ptr() as _;
         let len = self.buf_vec.write_iovec_len() as _;
-        opcode::Readv::new(types::Fd(self.fd.raw_fd()), ptr, len).build()
+        uring_fd!(self.fd, |fd| opcode::Readv::new(fd, ptr, len).build())
     }
 
     #[cfg(all(unix, feature = "legacy"))]
@@ -151,3 +309,47 @@ impl<T: IoVecBufMut> OpAble for ReadVec<T> {
         ))
     }
 }
+
+/// Read into a buffer picked from a ring of provided buffers.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) struct ReadProvided {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+    offset: libc::off_t,
+
+    /// The kernel may write to any buffer of the ring while in-flight.
+    ring: std::rc::Rc<BufRing>,
+    len: u32,
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl OpAble for ReadProvided {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let (len, buf_group) = (self.len, self.ring.bgid());
+        uring_fd!(self.fd, |fd| {
+            opcode::Read::new(fd, std::ptr::null_mut(), len)
+                .offset(self.offset)
+                .buf_group(buf_group)
+                .build()
+                .flags(io_uring::squeue::Flags::BUFFER_SELECT)
+        })
+    }
+
+    // Only submitted to the uring driver.
+    #[cfg(feature = "legacy")]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(feature = "legacy")]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
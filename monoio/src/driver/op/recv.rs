@@ -1,15 +1,26 @@
 use std::io;
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
+#[cfg(unix)]
+use {
+    super::send::msghdr,
+    crate::buf::{CmsgBuf, IoVecBufMut},
+    socket2::SockAddr,
+};
 #[cfg(all(unix, feature = "legacy"))]
 use {
     crate::{driver::legacy::ready::Direction, syscall_u32},
     std::os::unix::prelude::AsRawFd,
 };
 
-use super::{super::shared_fd::SharedFd, Op, OpAble};
-use crate::{buf::IoBufMut, BufResult};
+use super::{super::shared_fd::SharedFd, Completion, Op, OpAble};
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use crate::driver::BufRing;
+use crate::{
+    buf::{IoBufMut, ProvidedBuf},
+    BufResult,
+};
 
 pub(crate) struct Recv<T> {
     /// Holds a strong ref to the FD, preventing the file from being closed
@@ -19,14 +30,15 @@ pub(crate) struct Recv<T> {
 
     /// Reference to the in-flight buffer.
     pub(crate) buf: T,
+
+    /// Flags like `MSG_PEEK`.
+    #[allow(unused)]
+    flags: libc::c_int,
 }
 
 impl<T: IoBufMut> Op<Recv<T>> {
     pub(crate) fn recv(fd: &SharedFd, buf: T) -> io::Result<Self> {
-        Op::submit_with(Recv {
-            fd: fd.clone(),
-            buf,
-        })
+        Op::submit_with(Self::recv_raw(fd, buf))
     }
 
     #[allow(unused)]
@@ -34,9 +46,30 @@ impl<T: IoBufMut> Op<Recv<T>> {
         Recv {
             fd: fd.clone(),
             buf,
+            flags: 0,
         }
     }
 
+    /// Receive without removing the data from the socket queue, so the next
+    /// receive gets it again.
+    #[cfg(unix)]
+    pub(crate) fn peek(fd: &SharedFd, buf: T) -> io::Result<Self> {
+        Op::submit_with(Self::peek_raw(fd, buf))
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn peek_raw(fd: &SharedFd, buf: T) -> Recv<T> {
+        Recv {
+            fd: fd.clone(),
+            buf,
+            flags: libc::MSG_PEEK,
+        }
+    }
+
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
     pub(crate) async fn read(self) -> BufResult<usize, T> {
         let complete = self.await;
         let res = complete.meta.result.map(|v| v as _);
@@ -52,15 +85,51 @@ impl<T: IoBufMut> Op<Recv<T>> {
     }
 }
 
+impl Op<Recv<Vec<u8>>> {
+    /// Receive up to `len` bytes into a buffer the kernel picks from the
+    /// provided buffers of the driver. Without provided buffers, or when all of
+    /// them are in use, a heap buffer is used instead.
+    pub(crate) async fn recv_provided(fd: &SharedFd, len: usize) -> io::Result<ProvidedBuf> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(ring) = crate::driver::CURRENT.with(|inner| inner.provided_buffers()) {
+            let completion = Op::submit_with(RecvProvided {
+                fd: fd.clone(),
+                ring: ring.clone(),
+                len: len.min(u32::MAX as usize) as u32,
+            })?
+            .await;
+            let flags = completion.meta.flags;
+            match completion.meta.result {
+                Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {}
+                res => return res.map(|n| ProvidedBuf::from_completion(&ring, flags, n)),
+            }
+        }
+        let (res, buf) = Op::recv(fd, Vec::with_capacity(len))?.read().await;
+        res.map(|_| ProvidedBuf::heap(buf))
+    }
+}
+
 impl<T: IoBufMut> OpAble for Recv<T> {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Recv::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.write_ptr(),
-            self.buf.bytes_total() as _,
-        )
-        .build()
+        let (ptr, len) = (self.buf.write_ptr(), self.buf.bytes_total());
+        // A read on a socket is a recv without flags.
+        if self.flags == 0 {
+            if let Some(index) = crate::buf::pool::registered_index(ptr, len) {
+                return uring_fd!(self.fd, |fd| {
+                    opcode::ReadFixed::new(fd, ptr, len as _, index).build()
+                });
+            }
+        }
+        let flags = self.flags;
+        uring_fd!(self.fd, |fd| {
+            opcode::Recv::new(fd, ptr, len as _).flags(flags).build()
+        })
     }
 
     #[cfg(all(unix, feature = "legacy"))]
@@ -75,7 +144,387 @@ impl<T: IoBufMut> OpAble for Recv<T> {
             fd,
             self.buf.write_ptr() as _,
             self.buf.bytes_total().min(u32::MAX as usize),
-            0
+            self.flags
         ))
     }
 }
+
+/// Receive into buffers picked from a provided buffer ring, posting a
+/// completion for every receive until the kernel ends it.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) struct RecvMulti {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+
+    /// The kernel may write to any buffer of the ring while in-flight.
+    ring: std::rc::Rc<BufRing>,
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl Op<RecvMulti> {
+    pub(crate) fn recv_multi(fd: &SharedFd, ring: std::rc::Rc<BufRing>) -> io::Result<Self> {
+        Op::submit_with(RecvMulti {
+            fd: fd.clone(),
+            ring,
+        })
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl OpAble for RecvMulti {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let buf_group = self.ring.bgid();
+        uring_fd!(self.fd, |fd| opcode::RecvMulti::new(fd, buf_group).build())
+    }
+
+    // Only submitted to the uring driver.
+    #[cfg(feature = "legacy")]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(feature = "legacy")]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Receive into a buffer picked from a ring of provided buffers.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) struct RecvProvided {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+
+    /// The kernel may write to any buffer of the ring while in-flight.
+    ring: std::rc::Rc<BufRing>,
+    len: u32,
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl OpAble for RecvProvided {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let (len, buf_group) = (self.len, self.ring.bgid());
+        uring_fd!(self.fd, |fd| {
+            opcode::Recv::new(fd, std::ptr::null_mut(), len)
+                .buf_group(buf_group)
+                .build()
+                .flags(io_uring::squeue::Flags::BUFFER_SELECT)
+        })
+    }
+
+    // Only submitted to the uring driver.
+    #[cfg(feature = "legacy")]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(feature = "legacy")]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Receive into iovecs, along with the source address and ancillary messages.
+#[cfg(unix)]
+pub(crate) struct RecvMsg<T> {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+
+    pub(crate) buf_vec: T,
+    pub(crate) control: Option<CmsgBuf>,
+    // Boxed, so the pointers in the header stay valid when the op moves.
+    info: Box<(libc::sockaddr_storage, libc::msghdr)>,
+    // Flags passed to `recvmsg`, like `MSG_CMSG_CLOEXEC`.
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(dead_code)
+    )]
+    flags: libc::c_int,
+}
+
+/// What a `recvmsg` returns besides the data.
+#[cfg(unix)]
+#[allow(unused)]
+#[derive(Debug)]
+pub(crate) struct RecvMsgMeta {
+    pub(crate) len: usize,
+    /// The source address, if the socket reports one.
+    pub(crate) addr: Option<SockAddr>,
+    /// Flags like `MSG_TRUNC` and `MSG_CTRUNC`.
+    pub(crate) flags: libc::c_int,
+}
+
+#[cfg(unix)]
+#[allow(unused)]
+impl<T: IoVecBufMut> Op<RecvMsg<T>> {
+    pub(crate) fn recv_msg(
+        fd: &SharedFd,
+        buf_vec: T,
+        control: Option<CmsgBuf>,
+    ) -> io::Result<Self> {
+        Self::recv_msg_with_flags(fd, buf_vec, control, 0)
+    }
+
+    pub(crate) fn recv_msg_with_flags(
+        fd: &SharedFd,
+        mut buf_vec: T,
+        mut control: Option<CmsgBuf>,
+        flags: libc::c_int,
+    ) -> io::Result<Self> {
+        // Safety: a zeroed `sockaddr_storage` is valid.
+        let mut info = Box::new((unsafe { std::mem::zeroed() }, msghdr()));
+        let (addr, header) = &mut *info;
+        header.msg_name = addr as *mut libc::sockaddr_storage as _;
+        header.msg_namelen = std::mem::size_of::<libc::sockaddr_storage>() as _;
+        header.msg_iov = buf_vec.write_iovec_ptr();
+        header.msg_iovlen = buf_vec.write_iovec_len() as _;
+        if let Some(control) = control.as_mut().filter(|c| c.capacity() > 0) {
+            header.msg_control = control.as_mut_ptr() as _;
+            header.msg_controllen = control.capacity() as _;
+        }
+        Op::submit_with(RecvMsg {
+            fd: fd.clone(),
+            buf_vec,
+            control,
+            info,
+            flags,
+        })
+    }
+
+    pub(crate) async fn read(self) -> BufResult<RecvMsgMeta, (T, Option<CmsgBuf>)> {
+        self.await.into_msg()
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoVecBufMut> Completion<RecvMsg<T>> {
+    /// Split the completion into the result and the buffers.
+    pub(crate) fn into_msg(self) -> BufResult<RecvMsgMeta, (T, Option<CmsgBuf>)> {
+        let RecvMsg {
+            mut buf_vec,
+            mut control,
+            info,
+            ..
+        } = self.data;
+        let (addr, header) = *info;
+        let res = self.meta.result.map(|n| {
+            // Safety: the kernel wrote `n` bytes to the buffers, and the
+            // messages, address and flags to the header.
+            unsafe {
+                buf_vec.set_init(n as usize);
+                if let Some(control) = control.as_mut() {
+                    control.set_len(header.msg_controllen as _);
+                }
+            }
+            let addr = match header.msg_namelen {
+                0 => None,
+                len => Some(unsafe { SockAddr::new(addr, len) }),
+            };
+            RecvMsgMeta {
+                len: n as usize,
+                addr,
+                flags: header.msg_flags,
+            }
+        });
+        (res, (buf_vec, control))
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoVecBufMut> OpAble for RecvMsg<T> {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let header = &mut self.info.1 as *mut _;
+        uring_fd!(self.fd, |fd| opcode::RecvMsg::new(fd, header)
+            .flags(self.flags as u32)
+            .build())
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd.registered_index().map(|idx| (Direction::Read, idx))
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        syscall_u32!(recvmsg(self.fd.as_raw_fd(), &mut self.info.1, self.flags))
+    }
+}
+
+/// Receive datagrams into buffers picked from a provided buffer ring, posting
+/// a completion for every datagram until the kernel ends it. Every buffer
+/// starts with an `io_uring_recvmsg_out` header and the source address.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) struct RecvMsgMulti {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+
+    /// The kernel may write to any buffer of the ring while in-flight.
+    ring: std::rc::Rc<BufRing>,
+    // Boxed, so the header stays valid when the op moves.
+    header: Box<libc::msghdr>,
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl Op<RecvMsgMulti> {
+    pub(crate) fn recv_msg_multi(fd: &SharedFd, ring: std::rc::Rc<BufRing>) -> io::Result<Self> {
+        Op::submit_with(RecvMsgMulti {
+            fd: fd.clone(),
+            ring,
+            header: Box::new(RecvMsgMulti::header()),
+        })
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl RecvMsgMulti {
+    /// Bytes a buffer takes besides the datagram: the 16 bytes
+    /// `io_uring_recvmsg_out` header and the source address.
+    pub(crate) const PREFIX_LEN: usize = 16 + std::mem::size_of::<libc::sockaddr_storage>();
+
+    /// The header submitted with the op, only reserving room for the source
+    /// address.
+    fn header() -> libc::msghdr {
+        let mut header = msghdr();
+        header.msg_namelen = std::mem::size_of::<libc::sockaddr_storage>() as _;
+        header
+    }
+
+    /// Split a buffer filled by the op into the datagram and its source
+    /// address.
+    pub(crate) fn parse(mut buf: ProvidedBuf) -> io::Result<(ProvidedBuf, SockAddr)> {
+        let (addr, offset, len) = {
+            let out = io_uring::types::RecvMsgOut::parse(&buf, &Self::header()).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid recvmsg buffer")
+            })?;
+            let name = out.name_data();
+            let mut storage: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+            // Safety: the name is no longer than the storage.
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    name.as_ptr(),
+                    &mut storage as *mut _ as *mut u8,
+                    name.len(),
+                );
+            }
+            let addr = unsafe { SockAddr::new(storage, name.len() as _) };
+            let payload = out.payload_data();
+            (
+                addr,
+                payload.as_ptr() as usize - buf.as_ptr() as usize,
+                payload.len(),
+            )
+        };
+        buf.narrow(offset, len);
+        Ok((buf, addr))
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl OpAble for RecvMsgMulti {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let (header, buf_group) = (&*self.header as *const _, self.ring.bgid());
+        uring_fd!(self.fd, |fd| {
+            opcode::RecvMsgMulti::new(fd, header, buf_group).build()
+        })
+    }
+
+    // Only submitted to the uring driver.
+    #[cfg(feature = "legacy")]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(feature = "legacy")]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::os::unix::{net::UnixDatagram, prelude::IntoRawFd};
+
+    use super::*;
+    use crate::buf::VecBuf;
+
+    async fn send_and_recv_msg() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sock");
+        let (tx, rx) = (
+            UnixDatagram::unbound().unwrap(),
+            UnixDatagram::bind(&path).unwrap(),
+        );
+        tx.set_nonblocking(true).unwrap();
+        rx.set_nonblocking(true).unwrap();
+        let tx = SharedFd::new(tx.into_raw_fd()).unwrap();
+        let rx = SharedFd::new(rx.into_raw_fd()).unwrap();
+
+        let mut control = CmsgBuf::default();
+        control.push_fds(&[tx.raw_fd()]);
+        let bufs = VecBuf::from(vec![b"hel".to_vec(), b"lo".to_vec()]);
+        let addr = SockAddr::unix(&path).unwrap();
+        let (res, _) = Op::send_msg(&tx, bufs, Some(addr), Some(control))
+            .unwrap()
+            .write()
+            .await;
+        assert_eq!(res.unwrap(), 5);
+
+        let bufs = VecBuf::from(vec![vec![0; 2], vec![0; 8]]);
+        let control = CmsgBuf::with_capacity(CmsgBuf::space(4));
+        let (res, (bufs, control)) = Op::recv_msg(&rx, bufs, Some(control)).unwrap().read().await;
+        let meta = res.unwrap();
+        assert_eq!(meta.len, 5);
+        // The sender is not bound.
+        assert!(meta.addr.is_none());
+        assert_eq!(meta.flags & libc::MSG_CTRUNC, 0);
+        assert_eq!(Vec::<Vec<u8>>::from(bufs), [&b"he"[..], &b"llo"[..]]);
+
+        let fds = control.unwrap().iter().next().unwrap().fds().unwrap();
+        assert_eq!(fds.len(), 1);
+        assert_ne!(fds[0], tx.raw_fd());
+        unsafe { libc::close(fds[0]) };
+    }
+
+    #[test]
+    fn send_and_recv_msg_all_drivers() {
+        #[cfg(feature = "iouring")]
+        crate::RuntimeBuilder::<crate::IoUringDriver>::new()
+            .build()
+            .unwrap()
+            .block_on(send_and_recv_msg());
+        #[cfg(feature = "legacy")]
+        crate::RuntimeBuilder::<crate::LegacyDriver>::new()
+            .build()
+            .unwrap()
+            .block_on(send_and_recv_msg());
+    }
+}
@@ -1,7 +1,9 @@
 use std::io;
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
+#[cfg(unix)]
+use {crate::buf::CmsgBuf, socket2::SockAddr};
 #[cfg(all(unix, feature = "legacy"))]
 use {
     crate::{driver::legacy::ready::Direction, syscall_u32},
@@ -9,7 +11,10 @@ use {
 };
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
-use crate::{buf::IoBuf, BufResult};
+use crate::{
+    buf::{IoBuf, IoVecBuf},
+    BufResult,
+};
 
 pub(crate) struct Send<T> {
     /// Holds a strong ref to the FD, preventing the file from being closed
@@ -36,6 +41,10 @@ impl<T: IoBuf> Op<Send<T>> {
         }
     }
 
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
     pub(crate) async fn write(self) -> BufResult<usize, T> {
         let complete = self.await;
         (complete.meta.result.map(|v| v as _), complete.data.buf)
@@ -43,6 +52,11 @@ impl<T: IoBuf> Op<Send<T>> {
 }
 
 impl<T: IoBuf> OpAble for Send<T> {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         #[cfg(feature = "zero-copy")]
@@ -63,19 +77,24 @@ impl<T: IoBuf> OpAble for Send<T> {
             }
         }
 
+        // A write on a socket is a send without flags, see `buf::pool` about
+        // `MSG_NOSIGNAL`.
+        let (ptr, len) = (self.buf.read_ptr(), self.buf.bytes_init());
+        if let Some(index) = crate::buf::pool::registered_index(ptr, len) {
+            return uring_fd!(self.fd, |fd| {
+                opcode::WriteFixed::new(fd, ptr, len as _, index).build()
+            });
+        }
+
         #[cfg(feature = "zero-copy")]
         let flags = zero_copy_flag_guard(&self.buf);
         #[cfg(not(feature = "zero-copy"))]
         #[allow(deprecated)]
         let flags = libc::MSG_NOSIGNAL as libc::c_int;
 
-        opcode::Send::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.read_ptr(),
-            self.buf.bytes_init() as _,
-        )
-        .flags(flags)
-        .build()
+        uring_fd!(self.fd, |fd| {
+            opcode::Send::new(fd, ptr, len as _).flags(flags).build()
+        })
     }
 
     #[cfg(all(unix, feature = "legacy"))]
@@ -106,3 +125,106 @@ impl<T: IoBuf> OpAble for Send<T> {
         ))
     }
 }
+
+/// Send the data of iovecs, with an optional destination address and
+/// ancillary messages.
+#[cfg(unix)]
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
+pub(crate) struct SendMsg<T> {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+
+    pub(crate) buf_vec: T,
+    pub(crate) control: Option<CmsgBuf>,
+    // Boxed, so the pointers in the header stay valid when the op moves.
+    info: Box<(Option<SockAddr>, libc::msghdr)>,
+}
+
+#[cfg(unix)]
+#[allow(unused)]
+impl<T: IoVecBuf> Op<SendMsg<T>> {
+    pub(crate) fn send_msg(
+        fd: &SharedFd,
+        buf_vec: T,
+        addr: Option<SockAddr>,
+        mut control: Option<CmsgBuf>,
+    ) -> io::Result<Self> {
+        let mut info = Box::new((addr, msghdr()));
+        let (addr, header) = &mut *info;
+        if let Some(addr) = addr {
+            header.msg_name = addr.as_ptr() as _;
+            header.msg_namelen = addr.len();
+        }
+        header.msg_iov = buf_vec.read_iovec_ptr() as _;
+        header.msg_iovlen = buf_vec.read_iovec_len() as _;
+        if let Some(control) = control.as_mut().filter(|c| !c.is_empty()) {
+            header.msg_control = control.as_mut_ptr() as _;
+            header.msg_controllen = control.len() as _;
+        }
+        Op::submit_with(SendMsg {
+            fd: fd.clone(),
+            buf_vec,
+            control,
+            info,
+        })
+    }
+
+    pub(crate) async fn write(self) -> BufResult<usize, (T, Option<CmsgBuf>)> {
+        let complete = self.await;
+        let data = complete.data;
+        (
+            complete.meta.result.map(|v| v as _),
+            (data.buf_vec, data.control),
+        )
+    }
+}
+
+#[cfg(unix)]
+impl<T: IoVecBuf> OpAble for SendMsg<T> {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let header = &self.info.1 as *const _;
+        #[allow(deprecated)]
+        let flags = libc::MSG_NOSIGNAL as u32;
+        uring_fd!(self.fd, |fd| {
+            opcode::SendMsg::new(fd, header).flags(flags).build()
+        })
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd
+            .registered_index()
+            .map(|idx| (Direction::Write, idx))
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        #[cfg(target_os = "linux")]
+        #[allow(deprecated)]
+        let flags = libc::MSG_NOSIGNAL as _;
+        #[cfg(not(target_os = "linux"))]
+        let flags = 0;
+
+        syscall_u32!(sendmsg(self.fd.as_raw_fd(), &self.info.1, flags))
+    }
+}
+
+/// An empty `msghdr`.
+#[cfg(unix)]
+pub(super) fn msghdr() -> libc::msghdr {
+    // Safety: all fields of `msghdr` are integers or pointers, which are
+    // valid when zeroed. Some platforms have private padding fields, so it
+    // can not be built with a struct literal.
+    unsafe { std::mem::zeroed() }
+}
@@ -0,0 +1,84 @@
+use std::{future::Future, io, net::Shutdown as How};
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use io_uring::opcode;
+
+use super::{super::shared_fd::SharedFd, Op, OpAble};
+#[cfg(all(unix, feature = "legacy"))]
+use crate::{driver::legacy::ready::Direction, syscall_u32};
+
+pub(crate) struct Shutdown {
+    #[allow(unused)]
+    fd: SharedFd,
+    #[allow(unused)]
+    how: libc::c_int,
+}
+
+/// Shut down the read half, the write half or both halves of a socket.
+///
+/// With the uring driver, the op is submitted before the future is polled,
+/// and with the legacy driver the syscall is done right away, so the socket
+/// is shut down even if the future is dropped, e.g. by the drop of an owned
+/// write half.
+pub(crate) fn shutdown(fd: &SharedFd, how: How) -> impl Future<Output = io::Result<()>> {
+    let how = match how {
+        How::Read => libc::SHUT_RD,
+        How::Write => libc::SHUT_WR,
+        How::Both => libc::SHUT_RDWR,
+    };
+    let op = if super::non_blocking() {
+        crate::syscall!(shutdown(fd.raw_fd(), how)).map(|_| DetachOnDrop(None))
+    } else {
+        Op::submit_with(Shutdown {
+            fd: fd.clone(),
+            how,
+        })
+        .map(|op| DetachOnDrop(Some(op)))
+    };
+    async move {
+        let mut op = op?;
+        if let Some(op) = op.0.as_mut() {
+            op.await.meta.result?;
+        }
+        Ok(())
+    }
+}
+
+// The shutdown op is punted to the io worker of the kernel, so canceling it on
+// drop would prevent it. Detach it instead.
+struct DetachOnDrop(Option<Op<Shutdown>>);
+
+impl Drop for DetachOnDrop {
+    fn drop(&mut self) {
+        if let Some(op) = self.0.take() {
+            op.detach();
+        }
+    }
+}
+
+impl OpAble for Shutdown {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        uring_fd!(self.fd, |fd| opcode::Shutdown::new(fd, self.how).build())
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    fn uring_fallback_opcode(&self) -> Option<u8> {
+        Some(opcode::Shutdown::CODE)
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        syscall_u32!(shutdown(self.fd.raw_fd(), self.how))
+    }
+}
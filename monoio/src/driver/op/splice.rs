@@ -57,7 +57,34 @@ impl Op<Splice> {
     }
 }
 
+/// Duplicate data of a pipe to another pipe, without consuming it.
+pub(crate) struct Tee {
+    fd_in: SharedFd,
+    fd_out: SharedFd,
+    len: u32,
+}
+
+impl Op<Tee> {
+    pub(crate) fn tee(fd_in: &SharedFd, fd_out: &SharedFd, len: u32) -> io::Result<Op<Tee>> {
+        Op::submit_with(Tee {
+            fd_in: fd_in.clone(),
+            fd_out: fd_out.clone(),
+            len,
+        })
+    }
+
+    pub(crate) async fn result(self) -> io::Result<u32> {
+        let complete = self.await;
+        complete.meta.result
+    }
+}
+
 impl OpAble for Splice {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd_in)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         const FLAG: u32 = libc::SPLICE_F_MOVE;
@@ -103,3 +130,36 @@ impl OpAble for Splice {
         ))
     }
 }
+
+impl OpAble for Tee {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd_in)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        opcode::Tee::new(
+            types::Fd(self.fd_in.raw_fd()),
+            types::Fd(self.fd_out.raw_fd()),
+            self.len,
+        )
+        .build()
+    }
+
+    // Pipes are not registered with the legacy driver.
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        syscall_u32!(tee(
+            self.fd_in.as_raw_fd(),
+            self.fd_out.as_raw_fd(),
+            self.len as usize,
+            libc::SPLICE_F_NONBLOCK
+        ))
+    }
+}
@@ -0,0 +1,150 @@
+use std::{ffi::CString, io, mem::MaybeUninit, path::Path};
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use io_uring::{opcode, types};
+
+use super::{super::shared_fd::SharedFd, Op, OpAble};
+use crate::driver::util::cstr;
+#[cfg(all(unix, feature = "legacy"))]
+use crate::{driver::legacy::ready::Direction, syscall_u32};
+
+/// Buffer filled by the op, `statx` on Linux and `stat` elsewhere.
+#[cfg(target_os = "linux")]
+pub(crate) type StatBuf = libc::statx;
+#[cfg(not(target_os = "linux"))]
+pub(crate) type StatBuf = libc::stat;
+
+/// Get the status of a file by path, or of an open file.
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
+pub(crate) struct Statx {
+    // Open file to stat, the path is empty if set
+    fd: Option<SharedFd>,
+    path: CString,
+    follow_symlinks: bool,
+    buf: Box<MaybeUninit<StatBuf>>,
+}
+
+impl Op<Statx> {
+    /// Submit a request to get the status of the file at `path`.
+    pub(crate) fn statx_path<P: AsRef<Path>>(
+        path: P,
+        follow_symlinks: bool,
+    ) -> io::Result<Op<Statx>> {
+        Op::submit_with(Statx {
+            fd: None,
+            path: cstr(path.as_ref())?,
+            follow_symlinks,
+            buf: Box::new(MaybeUninit::uninit()),
+        })
+    }
+
+    /// Submit a request to get the status of an open file.
+    pub(crate) fn statx_fd(fd: &SharedFd) -> io::Result<Op<Statx>> {
+        Op::submit_with(Statx {
+            fd: Some(fd.clone()),
+            path: CString::default(),
+            follow_symlinks: true,
+            buf: Box::new(MaybeUninit::uninit()),
+        })
+    }
+
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
+    pub(crate) async fn stat(self) -> io::Result<StatBuf> {
+        let complete = self.await;
+        complete.meta.result?;
+        // Safety: the buffer is filled once the op succeeds.
+        Ok(unsafe { complete.data.buf.assume_init_read() })
+    }
+}
+
+impl Statx {
+    #[cfg(all(target_os = "linux", any(feature = "iouring", feature = "legacy")))]
+    fn flags(&self) -> i32 {
+        let mut flags = libc::AT_STATX_SYNC_AS_STAT;
+        if self.fd.is_some() {
+            flags |= libc::AT_EMPTY_PATH;
+        }
+        if !self.follow_symlinks {
+            flags |= libc::AT_SYMLINK_NOFOLLOW;
+        }
+        flags
+    }
+
+    #[cfg(all(target_os = "linux", any(feature = "iouring", feature = "legacy")))]
+    fn dirfd(&self) -> libc::c_int {
+        match &self.fd {
+            // Fixed files are not accepted by statx, the raw fd is still open.
+            Some(fd) => fd.raw_fd(),
+            None => libc::AT_FDCWD,
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", any(feature = "iouring", feature = "legacy")))]
+const MASK: u32 = libc::STATX_BASIC_STATS | libc::STATX_BTIME;
+
+impl OpAble for Statx {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        self.fd.as_ref()
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        opcode::Statx::new(
+            types::Fd(self.dirfd()),
+            self.path.as_ptr(),
+            self.buf.as_mut_ptr().cast(),
+        )
+        .flags(self.flags())
+        .mask(MASK)
+        .build()
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    fn uring_fallback_opcode(&self) -> Option<u8> {
+        Some(opcode::Statx::CODE)
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(all(target_os = "linux", feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        syscall_u32!(statx(
+            self.dirfd(),
+            self.path.as_ptr(),
+            self.flags(),
+            MASK,
+            self.buf.as_mut_ptr()
+        ))
+    }
+
+    #[cfg(all(unix, not(target_os = "linux"), feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        match &self.fd {
+            Some(fd) => syscall_u32!(fstat(fd.raw_fd(), self.buf.as_mut_ptr())),
+            None => {
+                let flags = if self.follow_symlinks {
+                    0
+                } else {
+                    libc::AT_SYMLINK_NOFOLLOW
+                };
+                syscall_u32!(fstatat(
+                    libc::AT_FDCWD,
+                    self.path.as_ptr(),
+                    self.buf.as_mut_ptr(),
+                    flags
+                ))
+            }
+        }
+    }
+}
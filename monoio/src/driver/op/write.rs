@@ -1,7 +1,7 @@
 use std::io;
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
 #[cfg(all(unix, feature = "legacy"))]
 use {
     crate::{driver::legacy::ready::Direction, syscall_u32},
@@ -10,10 +10,14 @@ use {
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
 use crate::{
-    buf::{IoBuf, IoVecBuf},
+    buf::{FixedBuf, IoBuf, IoVecBuf},
     BufResult,
 };
 
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
 pub(crate) struct Write<T> {
     /// Holds a strong ref to the FD, preventing the file from being closed
     /// while the operation is in-flight.
@@ -33,6 +37,19 @@ impl<T: IoBuf> Op<Write<T>> {
         })
     }
 
+    /// Write at the file position, for streams such as pipes.
+    pub(crate) fn write_stream(fd: &SharedFd, buf: T) -> io::Result<Op<Write<T>>> {
+        Op::submit_with(Write {
+            fd: fd.clone(),
+            offset: -1,
+            buf,
+        })
+    }
+
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
     pub(crate) async fn write(self) -> BufResult<usize, T> {
         let complete = self.await;
         (complete.meta.result.map(|v| v as _), complete.data.buf)
@@ -40,15 +57,26 @@ impl<T: IoBuf> Op<Write<T>> {
 }
 
 impl<T: IoBuf> OpAble for Write<T> {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Write::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.read_ptr(),
-            self.buf.bytes_init() as _,
-        )
-        .offset(self.offset)
-        .build()
+        let (ptr, len) = (self.buf.read_ptr(), self.buf.bytes_init());
+        if let Some(index) = crate::buf::pool::registered_index(ptr, len) {
+            return uring_fd!(self.fd, |fd| {
+                opcode::WriteFixed::new(fd, ptr, len as _, index)
+                    .offset(self.offset)
+                    .build()
+            });
+        }
+        uring_fd!(self.fd, |fd| {
+            opcode::Write::new(fd, ptr, len as _)
+                .offset(self.offset)
+                .build()
+        })
     }
 
     #[cfg(all(unix, feature = "legacy"))]
@@ -61,18 +89,13 @@ impl<T: IoBuf> OpAble for Write<T> {
     #[cfg(all(unix, feature = "legacy"))]
     fn legacy_call(&mut self) -> io::Result<u32> {
         let fd = self.fd.as_raw_fd();
-        if self.offset != 0 {
-            syscall_u32!(lseek(fd, self.offset, libc::SEEK_CUR))?;
-            syscall_u32!(write(
+        if self.offset >= 0 {
+            syscall_u32!(pwrite(
                 fd,
                 self.buf.read_ptr() as _,
-                self.buf.bytes_init().min(u32::MAX as usize)
+                self.buf.bytes_init().min(u32::MAX as usize),
+                self.offset
             ))
-            .map_err(|e| {
-                // seek back if read fail...
-                let _ = syscall_u32!(lseek(fd, -self.offset, libc::SEEK_CUR));
-                e
-            })
         } else {
             syscall_u32!(write(
                 fd,
@@ -83,6 +106,84 @@ impl<T: IoBuf> OpAble for Write<T> {
     }
 }
 
+#[cfg_attr(
+    not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+    allow(dead_code)
+)]
+pub(crate) struct WriteFixed {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+    offset: libc::off_t,
+
+    pub(crate) buf: FixedBuf,
+}
+
+impl Op<WriteFixed> {
+    pub(crate) fn write_fixed_at(
+        fd: &SharedFd,
+        buf: FixedBuf,
+        offset: u64,
+    ) -> io::Result<Op<WriteFixed>> {
+        Op::submit_with(WriteFixed {
+            fd: fd.clone(),
+            offset: offset as _,
+            buf,
+        })
+    }
+
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
+    pub(crate) async fn write(self) -> BufResult<usize, FixedBuf> {
+        let complete = self.await;
+        (complete.meta.result.map(|v| v as _), complete.data.buf)
+    }
+}
+
+impl OpAble for WriteFixed {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let (ptr, len) = (self.buf.read_ptr(), self.buf.bytes_init());
+        match self.buf.fixed_index() {
+            Some(index) => uring_fd!(self.fd, |fd| {
+                opcode::WriteFixed::new(fd, ptr, len as _, index)
+                    .offset(self.offset)
+                    .build()
+            }),
+            None => uring_fd!(self.fd, |fd| {
+                opcode::Write::new(fd, ptr, len as _)
+                    .offset(self.offset)
+                    .build()
+            }),
+        }
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd
+            .registered_index()
+            .map(|idx| (Direction::Write, idx))
+    }
+
+    #[cfg(all(unix, feature = "legacy"))]
+    fn legacy_call(&mut self) -> io::Result<u32> {
+        syscall_u32!(pwrite(
+            self.fd.as_raw_fd(),
+            self.buf.read_ptr() as _,
+            self.buf.bytes_init(),
+            self.offset
+        ))
+    }
+}
+
 pub(crate) struct WriteVec<T> {
     /// Holds a strong ref to the FD, preventing the file from being closed
     /// while the operation is in-flight.
@@ -108,6 +209,10 @@ impl<T: IoVecBuf> Op<WriteVec<T>> {
         }
     }
 
+    #[cfg_attr(
+        not(any(feature = "legacy", all(target_os = "linux", feature = "iouring"))),
+        allow(unused_variables)
+    )]
     pub(crate) async fn write(self) -> BufResult<usize, T> {
         let complete = self.await;
         (complete.meta.result.map(|v| v as _), complete.data.buf_vec)
@@ -115,11 +220,16 @@ impl<T: IoVecBuf> Op<WriteVec<T>> {
 }
 
 impl<T: IoVecBuf> OpAble for WriteVec<T> {
+    #[cfg(feature = "tracing")]
+    fn fd(&self) -> Option<&SharedFd> {
+        Some(&self.fd)
+    }
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         let ptr = self.buf_vec.read_iovec_ptr() as *const _;
         let len = self.buf_vec.read_iovec_len() as _;
-        opcode::Writev::new(types::Fd(self.fd.raw_fd()), ptr, len).build()
+        uring_fd!(self.fd, |fd| opcode::Writev::new(fd, ptr, len).build())
     }
 
     #[cfg(all(unix, feature = "legacy"))]
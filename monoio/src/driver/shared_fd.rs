@@ -1,8 +1,18 @@
+#[cfg(all(
+    unix,
+    any(feature = "legacy", all(target_os = "linux", feature = "iouring"))
+))]
+use std::os::unix::io::FromRawFd;
 #[cfg(unix)]
-use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::io::{AsRawFd, RawFd};
 #[cfg(windows)]
 use std::os::windows::io::{AsRawHandle, FromRawHandle, RawHandle};
-use std::{cell::UnsafeCell, io, rc::Rc};
+use std::{
+    cell::UnsafeCell,
+    io,
+    rc::Rc,
+    task::{Poll, Waker},
+};
 
 use super::CURRENT;
 
@@ -21,8 +31,15 @@ struct Inner {
     #[cfg(windows)]
     fd: RawHandle,
 
-    // Waker to notify when the close operation completes.
+    // Registration with the legacy driver, or close state with the uring one
     state: UnsafeCell<State>,
+
+    // Waker of the task waiting for the other references to be dropped.
+    waiter: UnsafeCell<Option<Waker>>,
+
+    // Slot in the fixed file table of the ring
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fixed: UnsafeCell<Option<super::FixedFile>>,
 }
 
 enum State {
@@ -43,9 +60,6 @@ enum UringState {
     /// Initial state
     Init,
 
-    /// Waiting for all in-flight operation to complete.
-    Waiting(Option<std::task::Waker>),
-
     /// The FD is closing
     Closing(super::op::Op<super::op::close::Close>),
 
@@ -113,7 +127,7 @@ impl SharedFd {
             not(feature = "legacy"),
             not(all(target_os = "linux", feature = "iouring"))
         ))]
-        #[allow(unused)]
+        #[allow(clippy::diverging_sub_expression)]
         let state = super::util::feature_panic();
 
         #[allow(unreachable_code)]
@@ -121,6 +135,9 @@ impl SharedFd {
             inner: Rc::new(Inner {
                 fd,
                 state: UnsafeCell::new(state),
+                waiter: UnsafeCell::new(None),
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                fixed: UnsafeCell::new(None),
             }),
         })
     }
@@ -151,6 +168,9 @@ impl SharedFd {
             inner: Rc::new(Inner {
                 fd,
                 state: UnsafeCell::new(state),
+                waiter: UnsafeCell::new(None),
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                fixed: UnsafeCell::new(None),
             }),
         }
     }
@@ -178,7 +198,9 @@ impl SharedFd {
     /// Note: this action will consume self and return rawfd without closing it.
     pub(crate) fn try_unwrap(self) -> Result<RawFd, Self> {
         let fd = self.inner.fd;
-        match Rc::try_unwrap(self.inner) {
+        // Take the Rc out, the drop of self would only wake the waiter.
+        let this = std::mem::ManuallyDrop::new(self);
+        match Rc::try_unwrap(unsafe { std::ptr::read(&this.inner) }) {
             Ok(_inner) => {
                 #[cfg(all(unix, feature = "legacy"))]
                 let state = unsafe { &*_inner.state.get() };
@@ -208,6 +230,15 @@ impl SharedFd {
                         })
                     }
                 }
+                // Drop the state and the waiter only, the drop of Inner would
+                // close the fd.
+                let inner = std::mem::ManuallyDrop::new(_inner);
+                unsafe { std::ptr::drop_in_place(inner.state.get()) };
+                unsafe { std::ptr::drop_in_place(inner.waiter.get()) };
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                unsafe {
+                    std::ptr::drop_in_place(inner.fixed.get())
+                };
                 Ok(fd)
             }
             Err(inner) => Err(Self { inner }),
@@ -221,6 +252,29 @@ impl SharedFd {
         unimplemented!()
     }
 
+    /// Put the fd into the fixed file table of the ring, so ops refer to it
+    /// by slot. Returns whether it has a slot.
+    #[cfg(unix)]
+    pub(crate) fn register_fixed(&self) -> io::Result<bool> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        {
+            let fixed = unsafe { &mut *self.inner.fixed.get() };
+            if fixed.is_none() {
+                *fixed = CURRENT.with(|inner| inner.register_file(self.inner.fd))?;
+            }
+            Ok(fixed.is_some())
+        }
+        #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+        Ok(false)
+    }
+
+    /// Slot in the fixed file table, if the fd is registered.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn fixed_slot(&self) -> Option<u32> {
+        let fixed = unsafe { &*self.inner.fixed.get() };
+        fixed.as_ref().map(|fixed| fixed.slot())
+    }
+
     #[allow(unused)]
     pub(crate) fn registered_index(&self) -> Option<usize> {
         let state = unsafe { &*self.inner.state.get() };
@@ -241,28 +295,64 @@ impl SharedFd {
         }
     }
 
+    /// Wait until the in-flight ops on the fd complete, then deregister it and
+    /// return the raw fd without closing it.
+    #[cfg(unix)]
+    pub(crate) async fn drain_unwrap(mut self) -> RawFd {
+        self.wait_unique().await;
+        match self.try_unwrap() {
+            Ok(fd) => fd,
+            Err(_) => unreachable!("the fd is referenced after waiting"),
+        }
+    }
+
     /// An FD cannot be closed until all in-flight operation have completed.
     /// This prevents bugs where in-flight reads could operate on the incorrect
     /// file descriptor.
-    pub(crate) async fn close(self) {
+    pub(crate) async fn close(mut self) {
+        self.wait_unique().await;
         // Here we only submit close op for uring mode.
         // Fd will be closed when Inner drops for legacy mode.
         #[cfg(all(target_os = "linux", feature = "iouring"))]
         {
             let fd = self.inner.fd;
-            let mut this = self;
             #[allow(irrefutable_let_patterns)]
-            if let State::Uring(uring_state) = unsafe { &mut *this.inner.state.get() } {
-                if Rc::get_mut(&mut this.inner).is_some() {
-                    *uring_state = match super::op::Op::close(fd) {
-                        Ok(op) => UringState::Closing(op),
-                        Err(_) => {
-                            let _ = unsafe { std::fs::File::from_raw_fd(fd) };
-                            return;
-                        }
-                    };
-                }
-                this.inner.closed().await;
+            if let State::Uring(uring_state) = unsafe { &mut *self.inner.state.get() } {
+                *uring_state = match super::op::Op::close(fd) {
+                    Ok(op) => UringState::Closing(op),
+                    Err(_) => {
+                        let _ = unsafe { std::fs::File::from_raw_fd(fd) };
+                        UringState::Closed
+                    }
+                };
+                self.inner.closed().await;
+            }
+        }
+    }
+
+    /// Wait until the other references are dropped. They are held by the
+    /// in-flight ops on the fd, including the ones dropped before completion.
+    async fn wait_unique(&mut self) {
+        std::future::poll_fn(|cx| {
+            if Rc::strong_count(&self.inner) == 1 {
+                return Poll::Ready(());
+            }
+            let waiter = unsafe { &mut *self.inner.waiter.get() };
+            if !matches!(waiter, Some(waker) if waker.will_wake(cx.waker())) {
+                *waiter = Some(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl Drop for SharedFd {
+    fn drop(&mut self) {
+        // Only the waiter is left, see `wait_unique`.
+        if Rc::strong_count(&self.inner) == 2 {
+            if let Some(waker) = unsafe { &mut *self.inner.waiter.get() }.take() {
+                waker.wake();
             }
         }
     }
@@ -270,42 +360,21 @@ impl SharedFd {
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 impl Inner {
-    /// Completes when the FD has been closed.
+    /// Completes when the close op submitted by `SharedFd::close` completes.
     /// Should only be called for uring mode.
     async fn closed(&self) {
-        use std::task::Poll;
-
-        crate::macros::support::poll_fn(|cx| {
+        std::future::poll_fn(|cx| {
             let state = unsafe { &mut *self.state.get() };
 
             #[allow(irrefutable_let_patterns)]
             if let State::Uring(uring_state) = state {
                 use std::{future::Future, pin::Pin};
 
-                return match uring_state {
-                    UringState::Init => {
-                        *uring_state = UringState::Waiting(Some(cx.waker().clone()));
-                        Poll::Pending
-                    }
-                    UringState::Waiting(Some(waker)) => {
-                        if !waker.will_wake(cx.waker()) {
-                            *waker = cx.waker().clone();
-                        }
-
-                        Poll::Pending
-                    }
-                    UringState::Waiting(None) => {
-                        *uring_state = UringState::Waiting(Some(cx.waker().clone()));
-                        Poll::Pending
-                    }
-                    UringState::Closing(op) => {
-                        // Nothing to do if the close operation failed.
-                        let _ = ready!(Pin::new(op).poll(cx));
-                        *uring_state = UringState::Closed;
-                        Poll::Ready(())
-                    }
-                    UringState::Closed => Poll::Ready(()),
-                };
+                if let UringState::Closing(op) = uring_state {
+                    // Nothing to do if the close operation failed.
+                    let _ = ready!(Pin::new(op).poll(cx));
+                    *uring_state = UringState::Closed;
+                }
             }
             Poll::Ready(())
         })
@@ -315,15 +384,20 @@ impl Inner {
 
 impl Drop for Inner {
     fn drop(&mut self) {
-        let fd = self.fd;
         let state = unsafe { &mut *self.state.get() };
         #[allow(unreachable_patterns)]
         match state {
             #[cfg(all(target_os = "linux", feature = "iouring"))]
-            State::Uring(UringState::Init) | State::Uring(UringState::Waiting(..)) => {
-                if super::op::Op::close(fd).is_err() {
-                    let _ = unsafe { std::fs::File::from_raw_fd(fd) };
-                };
+            State::Uring(UringState::Init) => {
+                // Ops on the fd hold it until they complete, so the close is
+                // submitted after them. It is detached rather than canceled,
+                // as the kernel may still have it queued.
+                match super::op::Op::close(self.fd) {
+                    Ok(op) => op.detach(),
+                    Err(_) => {
+                        let _ = unsafe { std::fs::File::from_raw_fd(self.fd) };
+                    }
+                }
             }
             #[cfg(all(unix, feature = "legacy"))]
             State::Legacy(idx) => {
@@ -338,7 +412,7 @@ impl Drop for Inner {
                             super::Inner::Legacy(inner) => {
                                 // deregister it from driver(Poll and slab) and close fd
                                 if let Some(idx) = idx {
-                                    let mut source = mio::unix::SourceFd(&fd);
+                                    let mut source = mio::unix::SourceFd(&self.fd);
                                     let _ = super::legacy::LegacyDriver::deregister(
                                         inner,
                                         *idx,
@@ -349,7 +423,7 @@ impl Drop for Inner {
                         }
                     })
                 }
-                let _ = unsafe { std::fs::File::from_raw_fd(fd) };
+                let _ = unsafe { std::fs::File::from_raw_fd(self.fd) };
             }
             // TODO: windows
             _ => {}
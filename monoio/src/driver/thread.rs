@@ -44,3 +44,32 @@ pub(crate) fn unregister_waker_sender(id: usize) {
 pub(crate) fn get_waker_sender(id: usize) -> Option<Sender<Waker>> {
     lock!(WAKER_SENDER).get(&id).cloned()
 }
+
+/// Registration of a driver implemented outside of the crate. Wakers sent
+/// from other threads are received here and woken by the runtime after park.
+pub(crate) struct CustomRegistration {
+    id: usize,
+    receiver: flume::Receiver<Waker>,
+}
+
+impl CustomRegistration {
+    pub(crate) fn new(id: usize, unpark: UnparkHandle) -> Self {
+        let (sender, receiver) = flume::unbounded();
+        register_unpark_handle(id, unpark);
+        register_waker_sender(id, sender);
+        Self { id, receiver }
+    }
+
+    pub(crate) fn wake_received(&self) {
+        while let Ok(w) = self.receiver.try_recv() {
+            w.wake();
+        }
+    }
+}
+
+impl Drop for CustomRegistration {
+    fn drop(&mut self) {
+        unregister_unpark_handle(self.id);
+        unregister_waker_sender(self.id);
+    }
+}
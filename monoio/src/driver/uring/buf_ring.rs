@@ -0,0 +1,165 @@
+//! Rings of provided buffers, which multishot ops pick buffers from.
+
+use std::{
+    alloc::{alloc_zeroed, dealloc, Layout},
+    cell::{Cell, RefCell, UnsafeCell},
+    io,
+    rc::{Rc, Weak},
+    sync::atomic::{AtomicU16, Ordering},
+    task::Waker,
+};
+
+use io_uring::types::BufRingEntry;
+
+use super::UringInner;
+use crate::buf::pool::Arena;
+
+// The kernel requires the ring to be page aligned.
+const RING_ALIGN: usize = 4096;
+// Max number of entries of a ring.
+const MAX_ENTRIES: u16 = 1 << 15;
+
+/// Buffers provided to the kernel through a ring registered with a buffer
+/// group id. The ring is unregistered on drop. It does not keep the uring
+/// alive.
+pub(crate) struct BufRing {
+    bgid: u16,
+    ring: *mut BufRingEntry,
+    layout: Layout,
+    mask: u16,
+    // Tail of the ring, only written by us.
+    tail: Cell<u16>,
+    arena: Rc<Arena>,
+    // Number of buffers the kernel can pick.
+    available: Cell<usize>,
+    // Tasks waiting for a buffer to be given back.
+    waiters: RefCell<Vec<Waker>>,
+    uring: Weak<UnsafeCell<UringInner>>,
+}
+
+impl BufRing {
+    /// Register a ring of `count` buffers of `buf_size` bytes.
+    pub(crate) fn new(
+        this: &Rc<UnsafeCell<UringInner>>,
+        buf_size: usize,
+        count: u16,
+    ) -> io::Result<Self> {
+        if count == 0 || count > MAX_ENTRIES || buf_size > u32::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "buffer ring must have 1 to 32768 buffers of at most 4GiB",
+            ));
+        }
+        let arena = Arena::new(buf_size, count as usize)?;
+        let entries = count.next_power_of_two();
+        let layout = Layout::from_size_align(
+            entries as usize * std::mem::size_of::<BufRingEntry>(),
+            RING_ALIGN,
+        )
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let ring = unsafe { alloc_zeroed(layout) } as *mut BufRingEntry;
+        if ring.is_null() {
+            return Err(io::ErrorKind::OutOfMemory.into());
+        }
+
+        let inner = unsafe { &mut *this.get() };
+        let bgid = match inner.free_buf_groups.pop() {
+            Some(bgid) => bgid,
+            None => {
+                inner.next_buf_group = inner.next_buf_group.wrapping_add(1);
+                inner.next_buf_group
+            }
+        };
+        if let Err(e) = inner
+            .uring
+            .submitter()
+            .register_buf_ring(ring as u64, entries, bgid)
+        {
+            inner.free_buf_groups.push(bgid);
+            unsafe { dealloc(ring as *mut u8, layout) };
+            return Err(e);
+        }
+
+        let buf_ring = Self {
+            bgid,
+            ring,
+            layout,
+            mask: entries - 1,
+            tail: Cell::new(0),
+            arena,
+            available: Cell::new(0),
+            waiters: RefCell::new(Vec::new()),
+            uring: Rc::downgrade(this),
+        };
+        for bid in 0..count {
+            buf_ring.recycle(bid);
+        }
+        Ok(buf_ring)
+    }
+
+    pub(crate) fn bgid(&self) -> u16 {
+        self.bgid
+    }
+
+    pub(crate) fn buf_size(&self) -> usize {
+        self.arena.buf_size()
+    }
+
+    /// Pointer to the buffer with id `bid`.
+    pub(crate) fn buf_ptr(&self, bid: u16) -> *mut u8 {
+        self.arena.slot_ptr(bid as usize)
+    }
+
+    /// Mark a buffer as picked by the kernel.
+    pub(crate) fn take(&self) {
+        self.available.set(self.available.get() - 1);
+    }
+
+    /// Give the buffer with id `bid` back to the kernel.
+    pub(crate) fn recycle(&self, bid: u16) {
+        let tail = self.tail.get();
+        let entry = unsafe { &mut *self.ring.add((tail & self.mask) as usize) };
+        entry.set_addr(self.buf_ptr(bid) as u64);
+        entry.set_len(self.buf_size() as u32);
+        entry.set_bid(bid);
+        let tail = tail.wrapping_add(1);
+        self.tail.set(tail);
+        // Publish the entry to the kernel.
+        unsafe {
+            let shared_tail = BufRingEntry::tail(self.ring) as *const AtomicU16;
+            (*shared_tail).store(tail, Ordering::Release);
+        }
+
+        self.available.set(self.available.get() + 1);
+        for waker in self.waiters.borrow_mut().drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Whether the kernel has a buffer to pick. If not, the waker is woken
+    /// when a buffer is given back.
+    pub(crate) fn poll_available(&self, waker: &Waker) -> bool {
+        if self.available.get() != 0 {
+            return true;
+        }
+        self.waiters.borrow_mut().push(waker.clone());
+        false
+    }
+}
+
+impl Drop for BufRing {
+    fn drop(&mut self) {
+        if let Some(this) = self.uring.upgrade() {
+            let inner = unsafe { &mut *this.get() };
+            if inner
+                .uring
+                .submitter()
+                .unregister_buf_ring(self.bgid)
+                .is_ok()
+            {
+                inner.free_buf_groups.push(self.bgid);
+            }
+        }
+        unsafe { dealloc(self.ring as *mut u8, self.layout) };
+    }
+}
@@ -2,10 +2,13 @@
 //! Partly borrow from tokio-uring.
 
 use std::{
+    collections::VecDeque,
     io,
     task::{Context, Poll, Waker},
 };
 
+use io_uring::cqueue;
+
 use crate::{driver::op::CompletionMeta, utils::slab::Ref};
 
 pub(crate) enum Lifecycle {
@@ -17,30 +20,51 @@ pub(crate) enum Lifecycle {
 
     /// The submitter no longer has interest in the operation result. The state
     /// must be passed to the driver and held until the operation completes.
-    Ignored(Box<dyn std::any::Any>),
+    Ignored(#[allow(dead_code)] Box<dyn std::any::Any>),
 
     /// The operation has completed.
     Completed(io::Result<u32>, u32),
+
+    /// A multishot operation posted completions which are not taken yet, with
+    /// the waker of the submitter if it is waiting. The operation is finished
+    /// once a completion without `IORING_CQE_F_MORE` is queued.
+    Queued(VecDeque<CompletionMeta>, Option<Waker>),
 }
 
 impl<'a> Ref<'a, Lifecycle> {
-    pub(crate) fn complete(mut self, result: io::Result<u32>, flags: u32) {
+    /// Mark the operation completed. The waker to wake is returned instead of
+    /// being called, so the caller can defer it.
+    pub(crate) fn complete(mut self, result: io::Result<u32>, flags: u32) -> Option<Waker> {
         let ref_mut = &mut *self;
+        let more = cqueue::more(flags);
         match ref_mut {
+            Lifecycle::Submitted | Lifecycle::Waiting(_) if more => {
+                let queue = VecDeque::from([CompletionMeta { result, flags }]);
+                match std::mem::replace(ref_mut, Lifecycle::Queued(queue, None)) {
+                    Lifecycle::Waiting(waker) => Some(waker),
+                    _ => None,
+                }
+            }
+            Lifecycle::Queued(queue, waker) => {
+                queue.push_back(CompletionMeta { result, flags });
+                waker.take()
+            }
             Lifecycle::Submitted => {
                 *ref_mut = Lifecycle::Completed(result, flags);
+                None
             }
             Lifecycle::Waiting(_) => {
                 let old = std::mem::replace(ref_mut, Lifecycle::Completed(result, flags));
                 match old {
-                    Lifecycle::Waiting(waker) => {
-                        waker.wake();
-                    }
+                    Lifecycle::Waiting(waker) => Some(waker),
                     _ => unsafe { std::hint::unreachable_unchecked() },
                 }
             }
             Lifecycle::Ignored(..) => {
-                self.remove();
+                if !more {
+                    self.remove();
+                }
+                None
             }
             Lifecycle::Completed(..) => unsafe { std::hint::unreachable_unchecked() },
         }
@@ -68,11 +92,35 @@ impl<'a> Ref<'a, Lifecycle> {
         }
     }
 
+    /// Take the next completion of a multishot operation. The slot is removed
+    /// with the last one.
+    pub(crate) fn poll_multi(mut self, cx: &mut Context<'_>) -> Poll<CompletionMeta> {
+        let ref_mut = &mut *self;
+        match ref_mut {
+            Lifecycle::Queued(queue, waker) => match queue.pop_front() {
+                Some(meta) if cqueue::more(meta.flags) => Poll::Ready(meta),
+                Some(meta) => {
+                    self.remove();
+                    Poll::Ready(meta)
+                }
+                None => {
+                    *waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            },
+            // Nothing posted yet, or a single completion ended it.
+            _ => self.poll_op(cx),
+        }
+    }
+
     // return if the op must has been finished
     pub(crate) fn drop_op<T: 'static>(mut self, data: &mut Option<T>) -> bool {
         let ref_mut = &mut *self;
         match ref_mut {
-            Lifecycle::Submitted | Lifecycle::Waiting(_) => {
+            Lifecycle::Queued(queue, _) if ends_with_last(queue) => {
+                self.remove();
+            }
+            Lifecycle::Submitted | Lifecycle::Waiting(_) | Lifecycle::Queued(..) => {
                 if let Some(data) = data.take() {
                     *ref_mut = Lifecycle::Ignored(Box::new(data));
                 } else {
@@ -88,3 +136,8 @@ impl<'a> Ref<'a, Lifecycle> {
         true
     }
 }
+
+// Whether the last completion of a multishot operation is queued.
+fn ends_with_last(queue: &VecDeque<CompletionMeta>) -> bool {
+    matches!(queue.back(), Some(meta) if !cqueue::more(meta.flags))
+}
@@ -7,10 +7,11 @@ use std::{
     os::unix::prelude::{AsRawFd, RawFd},
     rc::Rc,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use io_uring::{cqueue, opcode, types::Timespec, IoUring};
+use fxhash::FxHashMap;
+use io_uring::{cqueue, opcode, squeue, types::Timespec, IoUring};
 use lifecycle::Lifecycle;
 
 use super::{
@@ -18,21 +19,23 @@ use super::{
     util::timespec,
     Driver, Inner, CURRENT,
 };
-use crate::utils::slab::Slab;
+use crate::{runtime::Capabilities, stats::DriverStats, utils::slab::Slab};
 
+mod buf_ring;
 mod lifecycle;
 #[cfg(feature = "sync")]
 mod waker;
 #[cfg(feature = "sync")]
 pub(crate) use waker::UnparkHandle;
 
-#[allow(unused)]
+pub(crate) use self::buf_ring::BufRing;
+
 pub(crate) const CANCEL_USERDATA: u64 = u64::MAX;
-pub(crate) const TIMEOUT_USERDATA: u64 = u64::MAX - 1;
 #[allow(unused)]
-pub(crate) const EVENTFD_USERDATA: u64 = u64::MAX - 2;
+pub(crate) const TIMEOUT_USERDATA: u64 = u64::MAX - 1;
+pub(crate) const LINK_TIMEOUT_USERDATA: u64 = u64::MAX - 3;
 
-pub(crate) const MIN_REVERSED_USERDATA: u64 = u64::MAX - 2;
+pub(crate) const MIN_REVERSED_USERDATA: u64 = u64::MAX - 3;
 
 /// Driver with uring.
 pub struct IoUringDriver {
@@ -41,13 +44,12 @@ pub struct IoUringDriver {
     // Used as timeout buffer
     timespec: *mut Timespec,
 
-    // Used as read eventfd buffer
-    #[cfg(feature = "sync")]
-    eventfd_read_dst: *mut u8,
-
     // Used for drop
     #[cfg(feature = "sync")]
     thread_id: usize,
+
+    // Registered buffer arena, dropped after the ring
+    buffer_pool: Option<Rc<crate::buf::pool::Arena>>,
 }
 
 pub(crate) struct UringInner {
@@ -57,67 +59,237 @@ pub(crate) struct UringInner {
     /// IoUring bindings
     uring: ManuallyDrop<IoUring>,
 
+    /// Tasks waiting for pending submissions to be flushed
+    flush_waiters: Vec<std::task::Waker>,
+
+    /// Incremented on every io_uring_enter
+    submit_epoch: u64,
+
+    /// Submission statistics
+    stats: DriverStats,
+
+    /// Wakers collected during tick, they are woken after the CQ pass
+    deferred_wakers: Vec<std::task::Waker>,
+
+    /// Free slots of the fixed file table
+    free_file_slots: Vec<u32>,
+
+    /// Last buffer group id handed out, and ids given back
+    next_buf_group: u16,
+    free_buf_groups: Vec<u16>,
+
+    /// Provided buffers shared by ops of this ring
+    provided_buffers: Option<Rc<BufRing>>,
+
+    /// Timeouts linked to in-flight ops, by op index. The kernel reads them
+    /// when the ops are submitted, so they are kept until the ops complete.
+    link_timeouts: FxHashMap<usize, LinkTimeout>,
+
+    /// Indexes of dropped ops whose cancel did not fit into the SQ, pushed
+    /// on the next submission
+    pending_cancels: Vec<usize>,
+
+    /// Opcodes the kernel does not support, indexed by opcode
+    unsupported_ops: [bool; 256],
+
+    /// Submit once this many entries are queued, if set
+    submit_watermark: Option<usize>,
+
     /// Shared waker
     #[cfg(feature = "sync")]
     shared_waker: std::sync::Arc<waker::EventWaker>,
 
-    // Mark if eventfd is in the ring
-    #[cfg(feature = "sync")]
-    eventfd_installed: bool,
-
     // Waker receiver
     #[cfg(feature = "sync")]
     waker_receiver: flume::Receiver<std::task::Waker>,
 }
 
+/// Opcodes an IOPOLL ring accepts.
+const IOPOLL_OPS: [u8; 6] = [
+    opcode::Read::CODE,
+    opcode::Write::CODE,
+    opcode::Readv::CODE,
+    opcode::Writev::CODE,
+    opcode::ReadFixed::CODE,
+    opcode::WriteFixed::CODE,
+];
+
+/// Find out opcodes not supported by the kernel. Probing is available since
+/// 5.6, and opcodes are numbered in the order they were added, so on failure
+/// everything added in 5.6 or later is treated as unsupported. On an IOPOLL
+/// ring only reads and writes are supported, so opening, syncing and closing
+/// files run as syscalls.
+fn probe_unsupported_ops(uring: &IoUring) -> [bool; 256] {
+    let mut probe = io_uring::Probe::new();
+    let probed = uring.submitter().register_probe(&mut probe).is_ok();
+    let iopoll = uring.params().is_setup_iopoll();
+    let mut unsupported = [false; 256];
+    for (code, unsupported) in unsupported.iter_mut().enumerate() {
+        let code = code as u8;
+        *unsupported = if iopoll && !IOPOLL_OPS.contains(&code) {
+            true
+        } else if probed {
+            !probe.is_supported(code)
+        } else {
+            code >= opcode::Fallocate64::CODE
+        };
+    }
+    unsupported
+}
+
+/// Buffers registered with a ring. It does not keep the ring alive.
+pub(crate) struct BufferRegistration(std::rc::Weak<UnsafeCell<UringInner>>);
+
+impl BufferRegistration {
+    pub(crate) fn is_alive(&self) -> bool {
+        self.0.strong_count() != 0
+    }
+
+    pub(crate) fn unregister(self) -> io::Result<()> {
+        let this = match self.0.upgrade() {
+            Some(this) => this,
+            None => return Ok(()),
+        };
+        let inner = unsafe { &mut *this.get() };
+        // Queued ops may refer to the buffers by index.
+        inner.submit()?;
+        inner.uring.submitter().unregister_buffers()
+    }
+}
+
+/// A file in a slot of the fixed file table of a ring. The slot is cleared on
+/// drop. It does not keep the ring alive.
+pub(crate) struct FixedFile {
+    slot: u32,
+    ring: std::rc::Weak<UnsafeCell<UringInner>>,
+}
+
+impl FixedFile {
+    pub(crate) fn slot(&self) -> u32 {
+        self.slot
+    }
+}
+
+impl Drop for FixedFile {
+    fn drop(&mut self) {
+        if let Some(this) = self.ring.upgrade() {
+            let inner = unsafe { &mut *this.get() };
+            // The file is released once ops using the slot complete.
+            if inner
+                .uring
+                .submitter()
+                .register_files_update(self.slot, &[-1])
+                .is_ok()
+            {
+                inner.free_file_slots.push(self.slot);
+            }
+        }
+    }
+}
+
 // When dropping the driver, all in-flight operations must have completed. This
 // type wraps the slab and ensures that, on drop, the slab is empty.
 struct Ops {
     slab: Slab<Lifecycle>,
 }
 
+// Timeout linked to an in-flight op.
+struct LinkTimeout {
+    // Read by the kernel when the op is submitted.
+    _timespec: Box<Timespec>,
+    // Whether the op is canceled by an async cancel, which fails it with
+    // ECANCELED like the timeout does.
+    canceled: bool,
+}
+
 impl IoUringDriver {
     const DEFAULT_ENTRIES: u32 = 1024;
+    const DEFAULT_WAKE_LIST_CAPACITY: usize = 64;
 
-    pub(crate) fn new() -> io::Result<IoUringDriver> {
-        Self::new_with_entries(Self::DEFAULT_ENTRIES)
+    pub(crate) fn new(b: &io_uring::Builder) -> io::Result<IoUringDriver> {
+        Self::new_with_entries(b, Self::DEFAULT_ENTRIES)
     }
 
     #[cfg(not(feature = "sync"))]
-    pub(crate) fn new_with_entries(entries: u32) -> io::Result<IoUringDriver> {
-        let uring = ManuallyDrop::new(IoUring::new(entries)?);
+    pub(crate) fn new_with_entries(
+        urb: &io_uring::Builder,
+        entries: u32,
+    ) -> io::Result<IoUringDriver> {
+        let uring = ManuallyDrop::new(urb.build(entries)?);
+        let unsupported_ops = probe_unsupported_ops(&uring);
 
         let inner = Rc::new(UnsafeCell::new(UringInner {
             ops: Ops::new(),
             uring,
+            flush_waiters: Vec::new(),
+            submit_epoch: 0,
+            stats: DriverStats::default(),
+            deferred_wakers: Vec::with_capacity(Self::DEFAULT_WAKE_LIST_CAPACITY),
+            free_file_slots: Vec::new(),
+            next_buf_group: 0,
+            free_buf_groups: Vec::new(),
+            provided_buffers: None,
+            link_timeouts: FxHashMap::default(),
+            pending_cancels: Vec::new(),
+            unsupported_ops,
+            submit_watermark: None,
         }));
 
         Ok(IoUringDriver {
             inner,
             timespec: Box::leak(Box::new(Timespec::new())) as *mut Timespec,
+            buffer_pool: None,
         })
     }
 
     #[cfg(feature = "sync")]
-    pub(crate) fn new_with_entries(entries: u32) -> io::Result<IoUringDriver> {
-        let uring = ManuallyDrop::new(IoUring::new(entries)?);
+    pub(crate) fn new_with_entries(
+        urb: &io_uring::Builder,
+        entries: u32,
+    ) -> io::Result<IoUringDriver> {
+        let uring = urb.build(entries)?;
+        // With IORING_SETUP_DEFER_TASKRUN, completions are only posted while
+        // waiting in io_uring_enter, so sleeping on the eventfd would hang. It
+        // can not be told apart from IORING_SETUP_SINGLE_ISSUER, which it
+        // requires.
+        if uring.params().is_setup_single_issuer() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "single issuer and defer taskrun rings are not supported with the sync feature",
+            ));
+        }
+        let uring = ManuallyDrop::new(uring);
+        let unsupported_ops = probe_unsupported_ops(&uring);
 
-        // Create eventfd and register it to the ring.
+        // Create eventfd and register it to the ring, so completions signal it
+        // as well as unparking from other threads.
         let waker = {
-            let fd = crate::syscall!(eventfd(0, libc::EFD_CLOEXEC))?;
+            let fd = crate::syscall!(eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK))?;
             unsafe {
                 use std::os::unix::io::FromRawFd;
                 std::fs::File::from_raw_fd(fd)
             }
         };
+        uring.submitter().register_eventfd(waker.as_raw_fd())?;
 
         let (waker_sender, waker_receiver) = flume::unbounded::<std::task::Waker>();
 
         let inner = Rc::new(UnsafeCell::new(UringInner {
             ops: Ops::new(),
             uring,
+            flush_waiters: Vec::new(),
+            submit_epoch: 0,
+            stats: DriverStats::default(),
+            deferred_wakers: Vec::with_capacity(Self::DEFAULT_WAKE_LIST_CAPACITY),
+            free_file_slots: Vec::new(),
+            next_buf_group: 0,
+            free_buf_groups: Vec::new(),
+            provided_buffers: None,
+            link_timeouts: FxHashMap::default(),
+            pending_cancels: Vec::new(),
+            unsupported_ops,
+            submit_watermark: None,
             shared_waker: std::sync::Arc::new(waker::EventWaker::new(waker)),
-            eventfd_installed: false,
             waker_receiver,
         }));
 
@@ -125,8 +297,8 @@ impl IoUringDriver {
         let driver = IoUringDriver {
             inner,
             timespec: Box::leak(Box::new(Timespec::new())) as *mut Timespec,
-            eventfd_read_dst: Box::leak(Box::new([0_u8; 8])) as *mut u8,
             thread_id,
+            buffer_pool: None,
         };
 
         // Register unpark handle
@@ -135,6 +307,57 @@ impl IoUringDriver {
         Ok(driver)
     }
 
+    /// Register an arena of `count` buffers of `buf_size` bytes with the ring
+    /// and serve `buf::pool` from it on this thread.
+    pub(crate) fn register_buffer_pool(&mut self, buf_size: usize, count: usize) -> io::Result<()> {
+        let arena = crate::buf::pool::Arena::new(buf_size, count)?;
+        let inner = unsafe { &*self.inner.get() };
+        // The arena is kept alive until the ring is dropped.
+        inner.uring.submitter().register_buffers(&[arena.iovec()])?;
+        crate::buf::pool::install(arena.clone());
+        self.buffer_pool = Some(arena);
+        Ok(())
+    }
+
+    /// Register a ring of `count` provided buffers of `buf_size` bytes, which
+    /// ops reading into provided buffers pick from.
+    pub(crate) fn register_provided_buffers(
+        &mut self,
+        buf_size: usize,
+        count: u16,
+    ) -> io::Result<()> {
+        let ring = BufRing::new(&self.inner, buf_size, count)?;
+        let inner = unsafe { &mut *self.inner.get() };
+        inner.provided_buffers = Some(Rc::new(ring));
+        Ok(())
+    }
+
+    /// Submit once `watermark` entries are queued instead of waiting for park.
+    pub(crate) fn set_submit_watermark(&mut self, watermark: u32) {
+        let inner = unsafe { &mut *self.inner.get() };
+        inner.submit_watermark = Some(watermark as usize);
+    }
+
+    /// Allocate slots for `capacity` in-flight ops up front, and fail ops
+    /// submitted while `limit` ops are in flight.
+    pub(crate) fn set_op_slab(&mut self, capacity: usize, limit: Option<usize>) {
+        let inner = unsafe { &mut *self.inner.get() };
+        inner.ops.slab.reserve(capacity);
+        if let Some(limit) = limit {
+            inner.ops.slab.set_limit(limit);
+        }
+    }
+
+    /// Register a sparse table of `slots` fixed files with the ring.
+    pub(crate) fn register_fixed_files(&mut self, slots: u32) -> io::Result<()> {
+        let inner = unsafe { &mut *self.inner.get() };
+        // -1 marks an empty slot.
+        let fds = vec![-1; slots as usize];
+        inner.uring.submitter().register_files(&fds)?;
+        inner.free_file_slots = (0..slots).rev().collect();
+        Ok(())
+    }
+
     #[allow(unused)]
     fn num_operations(&self) -> usize {
         let inner = self.inner.get();
@@ -142,6 +365,7 @@ impl IoUringDriver {
     }
 
     // Flush to make enough space
+    #[cfg(not(feature = "sync"))]
     fn flush_space(inner: &mut UringInner, need: usize) -> io::Result<()> {
         let sq = inner.uring.submission();
         debug_assert!(sq.capacity() >= need);
@@ -152,17 +376,7 @@ impl IoUringDriver {
         Ok(())
     }
 
-    #[cfg(feature = "sync")]
-    fn install_eventfd(&self, inner: &mut UringInner, fd: RawFd) {
-        let entry = opcode::Read::new(io_uring::types::Fd(fd), self.eventfd_read_dst, 8)
-            .build()
-            .user_data(EVENTFD_USERDATA);
-
-        let mut sq = inner.uring.submission();
-        let _ = unsafe { sq.push(&entry) };
-        inner.eventfd_installed = true;
-    }
-
+    #[cfg(not(feature = "sync"))]
     fn install_timeout(&self, inner: &mut UringInner, duration: Duration) {
         let timespec = timespec(duration);
         unsafe {
@@ -205,36 +419,36 @@ impl IoUringDriver {
             }
         }
 
-        if need_wait {
-            // Install timeout and eventfd for unpark if sync is enabled
-
-            // 1. alloc spaces
-            let mut space = 0;
+        if inner.uring.params().is_setup_iopoll() {
+            let timeout = if need_wait {
+                timeout
+            } else {
+                Some(Duration::ZERO)
+            };
+            Self::iopoll_wait(inner, timeout)?;
+        } else if need_wait {
+            // The registered eventfd is signaled by completions and by unpark,
+            // so submit and wait on it.
             #[cfg(feature = "sync")]
-            if !inner.eventfd_installed {
-                space += 1;
-            }
-            if timeout.is_some() {
-                space += 1;
-            }
-            if space != 0 {
-                Self::flush_space(inner, space)?;
+            {
+                inner.enter(0)?;
+                if inner.uring.completion().is_empty() {
+                    inner.sleep(timeout)?;
+                }
             }
 
-            // 2. install eventfd and timeout
-            #[cfg(feature = "sync")]
-            if !inner.eventfd_installed {
-                self.install_eventfd(inner, inner.shared_waker.as_raw_fd());
-            }
-            if let Some(duration) = timeout {
-                self.install_timeout(inner, duration);
+            // Install timeout, then submit and wait
+            #[cfg(not(feature = "sync"))]
+            {
+                if let Some(duration) = timeout {
+                    Self::flush_space(inner, 1)?;
+                    self.install_timeout(inner, duration);
+                }
+                inner.enter(1)?;
             }
-
-            // Submit and Wait
-            inner.uring.submit_and_wait(1)?;
         } else {
             // Submit only
-            inner.uring.submit()?;
+            inner.enter(0)?;
         }
 
         // Set status as awake
@@ -249,6 +463,30 @@ impl IoUringDriver {
 
         Ok(())
     }
+
+    // An IOPOLL ring can not hold the timeout op, so poll for completions
+    // until one arrives or the timeout expires, and sleep in ppoll(2) when
+    // nothing is in flight.
+    fn iopoll_wait(inner: &mut UringInner, timeout: Option<Duration>) -> io::Result<()> {
+        let deadline = timeout.map(|d| Instant::now() + d);
+        loop {
+            inner.enter(0)?;
+            if !inner.uring.completion().is_empty() {
+                return Ok(());
+            }
+            let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            if remaining == Some(Duration::ZERO) {
+                return Ok(());
+            }
+            #[cfg(feature = "sync")]
+            if !inner.waker_receiver.is_empty() {
+                return Ok(());
+            }
+            if inner.ops.slab.len() == 0 {
+                return inner.sleep(remaining);
+            }
+        }
+    }
 }
 
 impl Driver for IoUringDriver {
@@ -286,25 +524,57 @@ impl Driver for IoUringDriver {
 
 impl UringInner {
     fn tick(&mut self) {
+        // Completions of an IOPOLL ring are only posted when polled for.
+        if self.uring.params().is_setup_iopoll() && self.uring.completion().is_empty() {
+            let _ = self.enter(0);
+        }
+
         let mut cq = self.uring.completion();
         cq.sync();
 
         for cqe in cq {
             if cqe.user_data() >= MIN_REVERSED_USERDATA {
-                #[cfg(feature = "sync")]
-                if cqe.user_data() == EVENTFD_USERDATA {
-                    self.eventfd_installed = false;
+                // ENOENT means the op completed before the cancel got to it.
+                if cqe.user_data() == CANCEL_USERDATA
+                    && (cqe.result() == 0 || cqe.result() == -libc::EALREADY)
+                {
+                    self.stats.ops_canceled += 1;
                 }
                 continue;
             }
             let index = cqe.user_data() as _;
-            self.ops.complete(index, resultify(&cqe), cqe.flags());
+            let mut result = resultify(&cqe);
+            // The slot may be reused once the op is done, so its cancel must
+            // not be pushed anymore.
+            if !self.pending_cancels.is_empty() && !cqueue::more(cqe.flags()) {
+                self.pending_cancels.retain(|i| *i != index);
+            }
+            if let Some(link_timeout) = self.link_timeouts.remove(&index) {
+                // Unless canceled by an async cancel, the op is canceled by
+                // its linked timeout.
+                if !link_timeout.canceled
+                    && matches!(&result, Err(e) if e.raw_os_error() == Some(libc::ECANCELED))
+                {
+                    result = Err(io::Error::from_raw_os_error(libc::ETIMEDOUT));
+                }
+            }
+            if let Some(waker) = self.ops.complete(index, result, cqe.flags()) {
+                self.deferred_wakers.push(waker);
+            }
+        }
+
+        // Wake tasks after the whole CQ pass, so no task is woken while we are
+        // still walking the ring.
+        let mut wakers = std::mem::take(&mut self.deferred_wakers);
+        for waker in wakers.drain(..) {
+            waker.wake();
         }
+        self.deferred_wakers = wakers;
     }
 
     fn submit(&mut self) -> io::Result<()> {
         loop {
-            match self.uring.submit() {
+            match self.enter(0) {
                 Ok(_) => {
                     self.uring.submission().sync();
                     return Ok(());
@@ -322,40 +592,216 @@ impl UringInner {
         }
     }
 
-    fn new_op<T>(data: T, inner: &mut UringInner, driver: Inner) -> Op<T> {
+    // Submit and wait for `want` completions, then record stats and wake flush
+    // waiters.
+    fn enter(&mut self, want: usize) -> io::Result<usize> {
+        // Submitting will wake up the SQPOLL thread if it is sleeping.
+        if self.uring.params().is_setup_sqpoll() && self.uring.submission().need_wakeup() {
+            self.stats.sqpoll_wakeups += 1;
+        }
+        self.push_pending_cancels();
+        let n = self.uring.submitter().submit_and_wait(want)?;
+        self.after_submit(n);
+        Ok(n)
+    }
+
+    // Sleep until the timeout or, with sync enabled, until a completion is
+    // posted or unparked.
+    fn sleep(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        let timeout = timeout.map(|d| libc::timespec {
+            tv_sec: d.as_secs().min(libc::time_t::MAX as u64) as libc::time_t,
+            tv_nsec: d.subsec_nanos() as _,
+        });
+        let timeout_ptr = timeout
+            .as_ref()
+            .map_or(std::ptr::null(), |t| t as *const libc::timespec);
+        #[cfg(feature = "sync")]
+        let mut fds = [libc::pollfd {
+            fd: self.shared_waker.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        #[cfg(not(feature = "sync"))]
+        let mut fds: [libc::pollfd; 0] = [];
+
+        match crate::syscall!(ppoll(
+            fds.as_mut_ptr(),
+            fds.len() as _,
+            timeout_ptr,
+            std::ptr::null()
+        )) {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+        // Reset the eventfd, it does not block.
+        #[cfg(feature = "sync")]
+        if fds[0].revents & libc::POLLIN != 0 {
+            let mut buf = [0_u8; 8];
+            let _ = crate::syscall!(read(fds[0].fd, buf.as_mut_ptr().cast(), buf.len()));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn after_submit(&mut self, n: usize) {
+        self.submit_epoch = self.submit_epoch.wrapping_add(1);
+        if n != 0 {
+            self.stats.sqes_submitted += n as u64;
+            self.stats.submit_calls += 1;
+        }
+        for waker in self.flush_waiters.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Put the fd into a free slot of the fixed file table, `None` is
+    /// returned if there is no free slot.
+    pub(crate) fn register_file(
+        this: &Rc<UnsafeCell<UringInner>>,
+        fd: RawFd,
+    ) -> io::Result<Option<FixedFile>> {
+        let inner = unsafe { &mut *this.get() };
+        let slot = match inner.free_file_slots.pop() {
+            Some(slot) => slot,
+            None => return Ok(None),
+        };
+        if let Err(e) = inner.uring.submitter().register_files_update(slot, &[fd]) {
+            inner.free_file_slots.push(slot);
+            return Err(e);
+        }
+        Ok(Some(FixedFile {
+            slot,
+            ring: Rc::downgrade(this),
+        }))
+    }
+
+    pub(crate) fn provided_buffers(this: &Rc<UnsafeCell<UringInner>>) -> Option<Rc<BufRing>> {
+        let inner = unsafe { &*this.get() };
+        inner.provided_buffers.clone()
+    }
+
+    pub(crate) fn register_buffers(
+        this: &Rc<UnsafeCell<UringInner>>,
+        bufs: &[libc::iovec],
+    ) -> io::Result<BufferRegistration> {
+        let inner = unsafe { &*this.get() };
+        inner.uring.submitter().register_buffers(bufs)?;
+        Ok(BufferRegistration(Rc::downgrade(this)))
+    }
+
+    pub(crate) fn poll_flush(
+        this: &Rc<UnsafeCell<UringInner>>,
+        epoch: &mut Option<u64>,
+        cx: &mut Context<'_>,
+    ) -> Poll<()> {
+        let inner = unsafe { &mut *this.get() };
+        match *epoch {
+            Some(e) if e != inner.submit_epoch => return Poll::Ready(()),
+            Some(_) => {}
+            None => {
+                if inner.uring.submission().is_empty() {
+                    return Poll::Ready(());
+                }
+                *epoch = Some(inner.submit_epoch);
+            }
+        }
+        inner.flush_waiters.push(cx.waker().clone());
+        Poll::Pending
+    }
+
+    pub(crate) fn flush_requested(this: &Rc<UnsafeCell<UringInner>>) -> bool {
+        let inner = unsafe { &*this.get() };
+        !inner.flush_waiters.is_empty()
+    }
+
+    pub(crate) fn stats(this: &Rc<UnsafeCell<UringInner>>) -> DriverStats {
+        let inner = unsafe { &*this.get() };
+        DriverStats {
+            slab_used: inner.ops.slab.len(),
+            slab_capacity: inner.ops.slab.capacity(),
+            ..inner.stats
+        }
+    }
+
+    pub(crate) fn capabilities(this: &Rc<UnsafeCell<UringInner>>) -> Capabilities {
+        let inner = unsafe { &*this.get() };
+        Capabilities::uring(&inner.unsupported_ops)
+    }
+
+    fn new_op<T>(data: T, index: usize, driver: Inner) -> Op<T> {
         Op {
             driver,
-            index: inner.ops.insert(),
+            index,
             data: Some(data),
+            #[cfg(feature = "legacy")]
+            deadline: None,
+            #[cfg(feature = "tracing")]
+            trace: None,
         }
     }
 
     pub(crate) fn submit_with_data<T>(
         this: &Rc<UnsafeCell<UringInner>>,
         data: T,
+        timeout: Option<Duration>,
     ) -> io::Result<Op<T>>
     where
         T: OpAble,
     {
         let inner = unsafe { &mut *this.get() };
-        // If the submission queue is full, flush it to the kernel
-        if inner.uring.submission().is_full() {
-            inner.submit()?;
-        }
+        inner.reserve_sqes(if timeout.is_some() { 2 } else { 1 })?;
 
         // Create the operation
-        let mut op = Self::new_op(data, inner, Inner::Uring(this.clone()));
+        let index = match inner.ops.insert() {
+            Some(index) => index,
+            // Too many ops are in flight. The op fails once polled, so the
+            // caller gets its buffers back.
+            None => return Ok(Self::new_op(data, usize::MAX, Inner::Uring(this.clone()))),
+        };
+        let mut op = Self::new_op(data, index, Inner::Uring(this.clone()));
 
         // Configure the SQE
         let data_mut = unsafe { op.data.as_mut().unwrap_unchecked() };
+
+        // Run it as a syscall if the kernel does not support the opcode.
+        #[cfg(feature = "legacy")]
+        if let Some(code) = OpAble::uring_fallback_opcode(data_mut) {
+            if inner.unsupported_ops[code as usize] {
+                let result = OpAble::legacy_call(data_mut);
+                // Nobody is waiting yet, so there is no waker.
+                let _ = inner.ops.complete(op.index, result, 0);
+                inner.stats.syscall_fallbacks += 1;
+                return Ok(op);
+            }
+        }
         let sqe = OpAble::uring_op(data_mut).user_data(op.index as _);
 
-        {
-            let mut sq = inner.uring.submission();
+        match timeout {
+            Some(timeout) => {
+                // The timeout cancels the op if it fires first.
+                let timespec = Box::new(timespec(timeout));
+                let timeout_sqe = opcode::LinkTimeout::new(&*timespec)
+                    .build()
+                    .user_data(LINK_TIMEOUT_USERDATA);
+                let entries = [sqe.flags(squeue::Flags::IO_LINK), timeout_sqe];
+                let mut sq = inner.uring.submission();
+                if unsafe { sq.push_multiple(&entries).is_err() } {
+                    unimplemented!("when is this hit?");
+                }
+                let link_timeout = LinkTimeout {
+                    _timespec: timespec,
+                    canceled: false,
+                };
+                inner.link_timeouts.insert(op.index, link_timeout);
+            }
+            None => {
+                let mut sq = inner.uring.submission();
 
-            // Push the new operation
-            if unsafe { sq.push(&sqe).is_err() } {
-                unimplemented!("when is this hit?");
+                // Push the new operation
+                if unsafe { sq.push(&sqe).is_err() } {
+                    unimplemented!("when is this hit?");
+                }
             }
         }
 
@@ -368,19 +814,78 @@ impl UringInner {
         // CHIHAI: We are not going to do syscall now. If we are waiting
         // for IO, we will submit on `park`.
         // let _ = inner.submit();
+
+        inner.submit_above_watermark();
         Ok(op)
     }
 
+    /// Push an SQE built outside of an `Op`, returning the index of the op,
+    /// or `usize::MAX` if too many ops are in flight. Unlike
+    /// `submit_with_data`, it does not clone the driver handle.
+    pub(crate) fn push_entry(
+        this: &Rc<UnsafeCell<UringInner>>,
+        sqe: squeue::Entry,
+    ) -> io::Result<usize> {
+        let inner = unsafe { &mut *this.get() };
+        inner.reserve_sqes(1)?;
+        let index = match inner.ops.insert() {
+            Some(index) => index,
+            None => return Ok(usize::MAX),
+        };
+        if unsafe { inner.uring.submission().push(&sqe.user_data(index as _)) }.is_err() {
+            unimplemented!("when is this hit?");
+        }
+        inner.submit_above_watermark();
+        Ok(index)
+    }
+
+    // If the submission queue has no room for `entries`, flush it to the
+    // kernel.
+    fn reserve_sqes(&mut self, entries: usize) -> io::Result<()> {
+        let sq = self.uring.submission();
+        if sq.capacity() - sq.len() < entries {
+            drop(sq);
+            self.submit()?;
+        }
+        Ok(())
+    }
+
+    // Ops are submitted on park, unless asked to submit once enough entries
+    // are queued.
+    fn submit_above_watermark(&mut self) {
+        if let Some(watermark) = self.submit_watermark {
+            if self.uring.submission().len() >= watermark {
+                let _ = self.submit();
+            }
+        }
+    }
+
     pub(crate) fn poll_op(
         this: &Rc<UnsafeCell<UringInner>>,
         index: usize,
         cx: &mut Context<'_>,
     ) -> Poll<CompletionMeta> {
+        if index == usize::MAX {
+            return Poll::Ready(rejected());
+        }
         let inner = unsafe { &mut *this.get() };
         let lifecycle = unsafe { inner.ops.slab.get(index).unwrap_unchecked() };
         lifecycle.poll_op(cx)
     }
 
+    pub(crate) fn poll_multi_op(
+        this: &Rc<UnsafeCell<UringInner>>,
+        index: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<CompletionMeta> {
+        if index == usize::MAX {
+            return Poll::Ready(rejected());
+        }
+        let inner = unsafe { &mut *this.get() };
+        let lifecycle = unsafe { inner.ops.slab.get(index).unwrap_unchecked() };
+        lifecycle.poll_multi(cx)
+    }
+
     pub(crate) fn drop_op<T: 'static>(
         this: &Rc<UnsafeCell<UringInner>>,
         index: usize,
@@ -393,22 +898,86 @@ impl UringInner {
         }
         if let Some(lifecycle) = inner.ops.slab.get(index) {
             let _must_finished = lifecycle.drop_op(data);
-            #[cfg(features = "async-cancel")]
+            #[cfg(feature = "async-cancel")]
             if !_must_finished {
-                unsafe {
-                    let cancel = io_uring::opcode::AsyncCancel::new(index as u64).build();
-
-                    // Try push cancel, if failed, will submit and re-push.
-                    if inner.uring.submission().push(&cancel).is_err() {
-                        let _ = inner.submit();
-                        let _ = inner.uring.submission().push(&cancel);
-                    }
-                }
+                inner.cancel(index);
+            }
+        }
+    }
+
+    /// Drop an in-flight operation without canceling it, so it still runs to
+    /// completion.
+    pub(crate) fn detach_op<T: 'static>(
+        this: &Rc<UnsafeCell<UringInner>>,
+        index: usize,
+        data: &mut Option<T>,
+    ) {
+        let inner = unsafe { &mut *this.get() };
+        if index == usize::MAX {
+            return;
+        }
+        if let Some(lifecycle) = inner.ops.slab.get(index) {
+            lifecycle.drop_op(data);
+        }
+    }
+
+    /// Cancel an in-flight operation. Multishot operations are always
+    /// canceled when dropped, as they may never finish otherwise.
+    pub(crate) fn cancel_op(this: &Rc<UnsafeCell<UringInner>>, index: usize) {
+        let inner = unsafe { &mut *this.get() };
+        if index != usize::MAX {
+            inner.cancel(index);
+        }
+    }
+
+    fn cancel(&mut self, index: usize) {
+        if let Some(link_timeout) = self.link_timeouts.get_mut(&index) {
+            link_timeout.canceled = true;
+        }
+        let cancel = cancel_entry(index);
+        // Try push cancel, if failed, submit without reaping completions and
+        // re-push. If the SQ is still full, it is pushed on the next
+        // submission.
+        if unsafe { self.uring.submission().push(&cancel) }.is_ok() {
+            return;
+        }
+        if self.enter(0).is_ok() {
+            self.uring.submission().sync();
+            if unsafe { self.uring.submission().push(&cancel) }.is_ok() {
+                return;
             }
         }
+        self.pending_cancels.push(index);
+    }
+
+    fn push_pending_cancels(&mut self) {
+        if self.pending_cancels.is_empty() {
+            return;
+        }
+        let mut sq = self.uring.submission();
+        let room = sq.capacity() - sq.len();
+        let n = room.min(self.pending_cancels.len());
+        for index in self.pending_cancels.drain(..n) {
+            let _ = unsafe { sq.push(&cancel_entry(index)) };
+        }
+    }
+}
+
+// Completion of an op submitted while as many ops as the limit were in
+// flight.
+fn rejected() -> CompletionMeta {
+    CompletionMeta {
+        result: Err(io::Error::from_raw_os_error(libc::EBUSY)),
+        flags: 0,
     }
 }
 
+fn cancel_entry(index: usize) -> squeue::Entry {
+    io_uring::opcode::AsyncCancel::new(index as u64)
+        .build()
+        .user_data(CANCEL_USERDATA)
+}
+
 impl AsRawFd for IoUringDriver {
     fn as_raw_fd(&self) -> RawFd {
         unsafe { (*self.inner.get()).uring.as_raw_fd() }
@@ -422,10 +991,9 @@ impl Drop for IoUringDriver {
         // Dealloc leaked memory
         unsafe { std::ptr::drop_in_place(self.timespec) };
 
-        #[cfg(feature = "sync")]
-        unsafe {
-            std::ptr::drop_in_place(self.eventfd_read_dst)
-        };
+        if self.buffer_pool.is_some() {
+            crate::buf::pool::uninstall();
+        }
 
         // Deregister thread id
         #[cfg(feature = "sync")]
@@ -450,14 +1018,20 @@ impl Ops {
         Ops { slab: Slab::new() }
     }
 
-    // Insert a new operation
-    pub(crate) fn insert(&mut self) -> usize {
-        self.slab.insert(Lifecycle::Submitted)
+    // Insert a new operation, or return None if as many ops as the limit are
+    // in flight
+    pub(crate) fn insert(&mut self) -> Option<usize> {
+        self.slab.try_insert(Lifecycle::Submitted).ok()
     }
 
-    fn complete(&mut self, index: usize, result: io::Result<u32>, flags: u32) {
+    fn complete(
+        &mut self,
+        index: usize,
+        result: io::Result<u32>,
+        flags: u32,
+    ) -> Option<std::task::Waker> {
         let lifecycle = unsafe { self.slab.get(index).unwrap_unchecked() };
-        lifecycle.complete(result, flags);
+        lifecycle.complete(result, flags)
     }
 }
 
@@ -471,3 +1045,28 @@ fn resultify(cqe: &cqueue::Entry) -> io::Result<u32> {
         Err(io::Error::from_raw_os_error(-res))
     }
 }
+
+#[cfg(all(test, feature = "legacy"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback_unsupported_opcode() {
+        let mut rt = crate::RuntimeBuilder::<IoUringDriver>::new()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            CURRENT.with(|inner| match inner {
+                Inner::Uring(this) => {
+                    let inner = unsafe { &mut *this.get() };
+                    inner.unsupported_ops[opcode::OpenAt::CODE as usize] = true;
+                }
+                _ => unreachable!(),
+            });
+            let tmp = tempfile::NamedTempFile::new().unwrap();
+            let file = crate::fs::File::open(tmp.path()).await.unwrap();
+            assert_eq!(crate::stats::driver_stats().syscall_fallbacks, 1);
+            drop(file);
+        });
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    cell::RefCell,
+    future::{poll_fn, Future},
+    io,
+    pin::Pin,
+    rc::Rc,
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+use crate::{
+    fs::File,
+    time::{sleep_until, Instant, Sleep},
+};
+
+const DEFAULT_QUEUE_BYTES: usize = 1024 * 1024;
+
+/// Options to build an [`AppendWriter`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use monoio::fs::{AppendWriterBuilder, OpenOptions};
+///
+/// #[monoio::main(timer_enabled = true)]
+/// async fn main() -> std::io::Result<()> {
+///     let file = OpenOptions::new()
+///         .write(true)
+///         .create(true)
+///         .open("access.log")
+///         .await?;
+///     let offset = std::fs::metadata("access.log")?.len();
+///     let log = AppendWriterBuilder::new()
+///         .sync_interval(Duration::from_secs(1))
+///         .build(file, offset);
+///     log.append(b"GET / 200\n").await?;
+///     log.close().await
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AppendWriterBuilder {
+    queue_bytes: usize,
+    sync_interval: Option<Duration>,
+}
+
+impl Default for AppendWriterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppendWriterBuilder {
+    /// Create options with a 1MiB queue and no periodic fsync.
+    pub fn new() -> Self {
+        Self {
+            queue_bytes: DEFAULT_QUEUE_BYTES,
+            sync_interval: None,
+        }
+    }
+
+    /// Set the max bytes queued and not yet submitted. Appends wait while the
+    /// queue is full. A single append larger than it is accepted when the
+    /// queue is empty.
+    pub fn queue_bytes(&mut self, queue_bytes: usize) -> &mut Self {
+        self.queue_bytes = queue_bytes;
+        self
+    }
+
+    /// Sync written data to disk with `fdatasync` at most `interval` after it
+    /// is written. The timer of the runtime must be enabled.
+    pub fn sync_interval(&mut self, interval: Duration) -> &mut Self {
+        self.sync_interval = Some(interval);
+        self
+    }
+
+    /// Start writing to `file` from `offset`, which is usually the current
+    /// length of the file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a monoio runtime.
+    pub fn build(&self, file: File, offset: u64) -> AppendWriter {
+        let shared = Rc::new(Shared {
+            file,
+            start: offset,
+            queue_bytes: self.queue_bytes,
+            state: RefCell::new(State {
+                handles: 1,
+                ..Default::default()
+            }),
+        });
+        crate::spawn(run(shared.clone(), self.sync_interval));
+        AppendWriter { shared }
+    }
+}
+
+/// A file writer shared by many tasks which appends data in large batches.
+///
+/// Data given to [`append`](AppendWriter::append) is copied to a queue. A
+/// background task writes out everything queued with one write while the
+/// previous write is in flight, so concurrent appends are coalesced. It fits
+/// write-ahead logs and access logs.
+///
+/// Appends are not durable until [`sync`](AppendWriter::sync) returns, or the
+/// periodic fsync set by [`AppendWriterBuilder::sync_interval`] is done. After
+/// a write or sync fails, every call returns the error.
+///
+/// The writer can be cloned. The background task writes out the queue and
+/// exits after all clones are dropped.
+pub struct AppendWriter {
+    shared: Rc<Shared>,
+}
+
+struct Shared {
+    file: File,
+    start: u64,
+    queue_bytes: usize,
+    state: RefCell<State>,
+}
+
+#[derive(Default)]
+struct State {
+    // Data appended but not yet submitted.
+    queue: Vec<u8>,
+    // Bytes appended, written and synced since start.
+    appended: u64,
+    written: u64,
+    synced: u64,
+    // Appended bytes which `sync` waits to be synced.
+    sync_target: u64,
+    error: Option<io::Error>,
+    // Alive writers.
+    handles: usize,
+    task_waker: Option<Waker>,
+    waiters: Vec<Waker>,
+}
+
+impl State {
+    fn check(&self) -> io::Result<()> {
+        match &self.error {
+            Some(e) => Err(io::Error::new(e.kind(), e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    fn wake_task(&mut self) {
+        if let Some(waker) = self.task_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_waiters(&mut self) {
+        for waker in self.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl AppendWriter {
+    /// Create a writer with default options. See [`AppendWriterBuilder`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a monoio runtime.
+    pub fn new(file: File, offset: u64) -> Self {
+        AppendWriterBuilder::new().build(file, offset)
+    }
+
+    /// Queue `data` to be appended, waiting while the queue is full. Returns
+    /// the file offset the data will be written at.
+    pub async fn append(&self, data: &[u8]) -> io::Result<u64> {
+        let shared = &self.shared;
+        poll_fn(|cx| {
+            let mut state = shared.state.borrow_mut();
+            state.check()?;
+            if !state.queue.is_empty() && state.queue.len() + data.len() > shared.queue_bytes {
+                state.waiters.push(cx.waker().clone());
+                return Poll::Pending;
+            }
+            let offset = shared.start + state.appended;
+            state.queue.extend_from_slice(data);
+            state.appended += data.len() as u64;
+            state.wake_task();
+            Poll::Ready(Ok(offset))
+        })
+        .await
+    }
+
+    /// Wait until all data appended before is written to the file.
+    pub async fn flush(&self) -> io::Result<()> {
+        let target = self.shared.state.borrow().appended;
+        self.wait(|state| state.written >= target).await
+    }
+
+    /// Wait until all data appended before is written and synced to disk.
+    pub async fn sync(&self) -> io::Result<()> {
+        let target = {
+            let mut state = self.shared.state.borrow_mut();
+            state.sync_target = state.sync_target.max(state.appended);
+            state.wake_task();
+            state.appended
+        };
+        self.wait(|state| state.synced >= target).await
+    }
+
+    /// Flush the appended data and drop the writer.
+    pub async fn close(self) -> io::Result<()> {
+        self.flush().await
+    }
+
+    async fn wait(&self, done: impl Fn(&State) -> bool) -> io::Result<()> {
+        poll_fn(|cx| {
+            let mut state = self.shared.state.borrow_mut();
+            if done(&state) {
+                return Poll::Ready(Ok(()));
+            }
+            state.check()?;
+            state.waiters.push(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl Clone for AppendWriter {
+    fn clone(&self) -> Self {
+        self.shared.state.borrow_mut().handles += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for AppendWriter {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.borrow_mut();
+        state.handles -= 1;
+        if state.handles == 0 {
+            state.wake_task();
+        }
+    }
+}
+
+enum Work {
+    Write,
+    Sync,
+    Exit,
+}
+
+async fn run(shared: Rc<Shared>, sync_interval: Option<Duration>) {
+    // Swapped with the queue, so no allocation is needed after warming up.
+    let mut spare = Vec::new();
+    let mut last_sync = Instant::now();
+    loop {
+        let dirty = {
+            let state = shared.state.borrow();
+            state.written > state.synced
+        };
+        let deadline = sync_interval.filter(|_| dirty).map(|i| last_sync + i);
+        match next_work(&shared, deadline).await {
+            Work::Write => {
+                let (buf, offset) = {
+                    let mut state = shared.state.borrow_mut();
+                    let buf = std::mem::replace(&mut state.queue, spare);
+                    let offset = shared.start + state.written;
+                    // Room is made in the queue.
+                    state.wake_waiters();
+                    (buf, offset)
+                };
+                let len = buf.len() as u64;
+                let (res, mut buf) = shared.file.write_all_at(buf, offset).await;
+                buf.clear();
+                spare = buf;
+
+                let mut state = shared.state.borrow_mut();
+                match res {
+                    Ok(()) => state.written += len,
+                    Err(e) => state.error = Some(e),
+                }
+                state.wake_waiters();
+            }
+            Work::Sync => {
+                let target = shared.state.borrow().written;
+                let res = shared.file.sync_data().await;
+                last_sync = Instant::now();
+
+                let mut state = shared.state.borrow_mut();
+                match res {
+                    Ok(()) => state.synced = target,
+                    Err(e) => state.error = Some(e),
+                }
+                state.wake_waiters();
+            }
+            Work::Exit => return,
+        }
+    }
+}
+
+async fn next_work(shared: &Shared, deadline: Option<Instant>) -> Work {
+    let mut sleep: Option<Pin<Box<Sleep>>> = deadline.map(|d| Box::pin(sleep_until(d)));
+    poll_fn(|cx| {
+        let mut state = shared.state.borrow_mut();
+        if state.error.is_some() {
+            return Poll::Ready(Work::Exit);
+        }
+        if let Some(sleep) = sleep.as_mut() {
+            if sleep.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Work::Sync);
+            }
+        }
+        if !state.queue.is_empty() {
+            return Poll::Ready(Work::Write);
+        }
+        if state.sync_target > state.synced && state.written >= state.sync_target {
+            return Poll::Ready(Work::Sync);
+        }
+        if state.handles == 0 {
+            // All writers are dropped, sync once more if periodic fsync is on.
+            if sleep.is_some() {
+                return Poll::Ready(Work::Sync);
+            }
+            return Poll::Ready(Work::Exit);
+        }
+        state.task_waker = Some(cx.waker().clone());
+        Poll::Pending
+    })
+    .await
+}
@@ -0,0 +1,133 @@
+use std::{
+    io,
+    os::unix::{fs::MetadataExt, io::AsRawFd},
+    path::Path,
+};
+
+use super::{File, OpenOptions};
+
+// Size of the buffer when the data is copied through userspace.
+const BUF_SIZE: usize = 64 * 1024;
+
+/// Copies the contents of one file to another, along with its permission
+/// bits. The destination is created if it does not exist and truncated if
+/// it does. Returns the number of bytes copied.
+///
+/// On Linux, if a thread pool is attached to the runtime with
+/// `RuntimeBuilder::attach_thread_pool`, the data does not go through
+/// userspace: the file is cloned with the `FICLONE` ioctl when the filesystem
+/// can share extents, or copied by `copy_file_range(2)` in the kernel. Both
+/// block until the copy is done and io_uring has no op for them, so they
+/// run on the pool. Without a pool, or if neither works for the files (e.g.
+/// across filesystems on old kernels), the data is copied through a buffer
+/// with async reads and writes.
+///
+/// # Errors
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] if `from` is not a regular
+/// file, or with the error of opening either file or of the copy.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let copied = monoio::fs::copy("foo.txt", "bar.txt").await?;
+///     println!("copied {copied} bytes");
+///     Ok(())
+/// }
+/// ```
+pub async fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
+    let src = File::open(from).await?;
+    let metadata = src.metadata().await?;
+    if !metadata.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the source path is not a regular file",
+        ));
+    }
+    let dst = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(to)
+        .await?;
+    // Opening only sets the mode of a new file, masked by the umask.
+    crate::syscall!(fchmod(dst.as_raw_fd(), metadata.mode() as _))?;
+
+    #[cfg(all(target_os = "linux", feature = "sync"))]
+    if crate::blocking::pool_attached() {
+        if let Some(copied) = copy_in_kernel(&src, &dst, metadata.len()).await? {
+            return Ok(copied);
+        }
+    }
+    copy_by_buffer(&src, &dst).await
+}
+
+/// Clone or copy the file in the kernel, or `None` if it is not supported
+/// for the files.
+#[cfg(all(target_os = "linux", feature = "sync"))]
+async fn copy_in_kernel(src: &File, dst: &File, len: u64) -> io::Result<Option<u64>> {
+    use std::os::unix::io::BorrowedFd;
+
+    // The pool gets its own fds, as it may still use them after this future
+    // is dropped and the files are closed. They share the file offsets, which
+    // the positional ops of `File` do not move.
+    // Safety: the fds are open as long as the files are.
+    let src = unsafe { BorrowedFd::borrow_raw(src.as_raw_fd()) }.try_clone_to_owned()?;
+    let dst = unsafe { BorrowedFd::borrow_raw(dst.as_raw_fd()) }.try_clone_to_owned()?;
+    super::run_blocking(move || {
+        let (src, dst) = (src.as_raw_fd(), dst.as_raw_fd());
+        if unsafe { libc::ioctl(dst, libc::FICLONE, src) } == 0 {
+            return Ok(Some(len));
+        }
+        let mut copied = 0;
+        loop {
+            let res = unsafe {
+                libc::copy_file_range(
+                    src,
+                    std::ptr::null_mut(),
+                    dst,
+                    std::ptr::null_mut(),
+                    1 << 30,
+                    0,
+                )
+            };
+            match res {
+                0 => return Ok(Some(copied)),
+                n if n > 0 => copied += n as u64,
+                _ => {
+                    let err = io::Error::last_os_error();
+                    match err.raw_os_error() {
+                        Some(libc::EINTR) => {}
+                        Some(
+                            libc::ENOSYS
+                            | libc::EXDEV
+                            | libc::EOPNOTSUPP
+                            | libc::EINVAL
+                            | libc::EPERM,
+                        ) if copied == 0 => return Ok(None),
+                        _ => return Err(err),
+                    }
+                }
+            }
+        }
+    })
+    .await
+}
+
+async fn copy_by_buffer(src: &File, dst: &File) -> io::Result<u64> {
+    let mut buf = Vec::with_capacity(BUF_SIZE);
+    let mut pos = 0;
+    loop {
+        let (res, read) = src.read_at(buf, pos).await;
+        let n = res?;
+        if n == 0 {
+            return Ok(pos);
+        }
+        let (res, written) = dst.write_all_at(read, pos).await;
+        res?;
+        pos += n as u64;
+        buf = written;
+    }
+}
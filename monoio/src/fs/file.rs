@@ -1,13 +1,19 @@
 #[cfg(unix)]
-use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(windows)]
 use std::os::windows::io::{AsRawHandle, RawHandle};
-use std::{io, path::Path};
+use std::{future::Future, io, path::Path};
 
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use crate::driver::op::fixed::FixedOp;
 use crate::{
-    buf::{IoBuf, IoBufMut},
+    buf::{FixedBuf, IoBuf, IoBufMut, ProvidedBuf},
     driver::{op::Op, shared_fd::SharedFd},
     fs::OpenOptions,
+    io::{
+        as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
+        AsyncReadRentAt, AsyncWriteRentAt,
+    },
 };
 
 /// A reference to an open file on the filesystem.
@@ -254,6 +260,79 @@ impl File {
         (Ok(()), buf)
     }
 
+    /// Read some bytes at the specified offset from the file into a buffer of
+    /// a [`FixedBufPool`](crate::buf::FixedBufPool), filling it from the
+    /// start.
+    ///
+    /// It behaves like [`read_at`](File::read_at), and uses the fixed-buffer
+    /// opcode when the pool is registered with the ring.
+    ///
+    /// # Fast path
+    ///
+    /// If the file is also in the fixed file table, see
+    /// [`register_fixed`](File::register_fixed), the read is submitted and
+    /// completed without allocating and without cloning the file or the
+    /// driver handle. It is meant for the highest-throughput users, who keep
+    /// a few files and buffers registered for the lifetime of the runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::{buf::FixedBufPool, fs::File};
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let pool = FixedBufPool::new(4096, 4)?;
+    ///     pool.register()?;
+    ///
+    ///     let f = File::open("foo.txt").await?;
+    ///     let (res, buffer) = f.read_fixed_at(pool.get().await, 0).await;
+    ///     let n = res?;
+    ///
+    ///     println!("The bytes: {:?}", &buffer[..n]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn read_fixed_at(
+        &self,
+        buf: FixedBuf,
+        pos: u64,
+    ) -> crate::BufResult<usize, FixedBuf> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        let buf = match FixedOp::read_at(&self.fd, buf, pos) {
+            Ok(op) => return op.read().await,
+            Err(buf) => buf,
+        };
+        let op = Op::read_fixed_at(&self.fd, buf, pos).unwrap();
+        op.read().await
+    }
+
+    /// Read up to `len` bytes at the specified offset from the file into a
+    /// buffer the kernel picks from the provided buffers set up by
+    /// [`RuntimeBuilder::with_provided_buffers`](crate::RuntimeBuilder::with_provided_buffers).
+    /// An empty buffer is returned at the end of the file.
+    ///
+    /// A heap buffer is used instead without provided buffers, when all of
+    /// them are in use, or with the legacy driver.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::File;
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.txt").await?;
+    ///     let buffer = f.read_provided_at(4096, 0).await?;
+    ///
+    ///     println!("The bytes: {:?}", &buffer[..]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn read_provided_at(&self, len: usize, pos: u64) -> io::Result<ProvidedBuf> {
+        Op::read_provided_at(&self.fd, len, pos).await
+    }
+
     /// Write a buffer into this file at the specified offset, returning how
     /// many bytes were written.
     ///
@@ -373,6 +452,47 @@ impl File {
         (Ok(()), buf)
     }
 
+    /// Write the data of a buffer of a [`FixedBufPool`](crate::buf::FixedBufPool)
+    /// into this file at the specified offset, returning how many bytes were
+    /// written.
+    ///
+    /// It behaves like [`write_at`](File::write_at), and uses the fixed-buffer
+    /// opcode when the pool is registered with the ring. Like
+    /// [`read_fixed_at`](File::read_fixed_at), it takes the allocation-free
+    /// fast path if the file is in the fixed file table.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::{buf::FixedBufPool, fs::File};
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let pool = FixedBufPool::new(4096, 4)?;
+    ///     pool.register()?;
+    ///
+    ///     let file = File::create("foo.txt").await?;
+    ///     let mut buf = pool.get().await;
+    ///     buf.extend_from_slice(b"some bytes");
+    ///     let (res, _) = file.write_fixed_at(buf, 0).await;
+    ///     println!("wrote {} bytes", res?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn write_fixed_at(
+        &self,
+        buf: FixedBuf,
+        pos: u64,
+    ) -> crate::BufResult<usize, FixedBuf> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        let buf = match FixedOp::write_at(&self.fd, buf, pos) {
+            Ok(op) => return op.write().await,
+            Err(buf) => buf,
+        };
+        let op = Op::write_fixed_at(&self.fd, buf, pos).unwrap();
+        op.write().await
+    }
+
     /// Attempts to sync all OS-internal metadata to disk.
     ///
     /// This function will attempt to ensure that all in-memory data reaches the
@@ -448,6 +568,131 @@ impl File {
         Ok(())
     }
 
+    /// Like [`sync_all`], but also a write barrier: the sync does not start
+    /// before every op submitted earlier has completed, so writes issued by
+    /// other tasks (or futures still pending in a `join`) are covered without
+    /// waiting for them first. It is done by `IOSQE_IO_DRAIN` with the
+    /// io_uring driver.
+    ///
+    /// The barrier orders against all ops of the ring, not only the ones on
+    /// this file. A pending read on a socket of the same thread holds it back
+    /// until the read completes, so keep the file io of a write-ahead log on
+    /// its own thread.
+    ///
+    /// With the legacy driver, file ops are done when they are first polled,
+    /// so the ops submitted earlier are already finished.
+    ///
+    /// [`sync_all`]: File::sync_all
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::File;
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let f = File::create("wal.log").await?;
+    ///     let write = f.write_at(&b"record"[..], 0);
+    ///     let commit = f.sync_all_barrier();
+    ///     // The write is submitted first, the sync runs after it.
+    ///     let ((res, _), commit) = monoio::join!(write, commit);
+    ///     res?;
+    ///     commit?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sync_all_barrier(&self) -> io::Result<()> {
+        let op = Op::sync_barrier(&self.fd, false)?;
+        op.await.meta.result?;
+        Ok(())
+    }
+
+    /// Like [`sync_data`], but also a write barrier, see
+    /// [`sync_all_barrier`].
+    ///
+    /// [`sync_data`]: File::sync_data
+    /// [`sync_all_barrier`]: File::sync_all_barrier
+    pub async fn sync_data_barrier(&self) -> io::Result<()> {
+        let op = Op::sync_barrier(&self.fd, true)?;
+        op.await.meta.result?;
+        Ok(())
+    }
+
+    /// Allocate disk space for `len` bytes from `offset`, so later writes to
+    /// the range do not fail for lack of space. The file grows if the range
+    /// goes past its end. It is done by `IORING_OP_FALLOCATE` with the
+    /// io_uring driver.
+    ///
+    /// Only supported on Linux.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::File;
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let f = File::create("segment.log").await?;
+    ///     // Reserve a 64MiB segment.
+    ///     f.allocate(0, 64 << 20).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        let op = Op::fallocate(&self.fd, offset, len)?;
+        op.await.meta.result?;
+        Ok(())
+    }
+
+    /// Truncate or extend the file to `size` bytes, like
+    /// [`std::fs::File::set_len`]. Extended bytes read as zeros and take no
+    /// disk space until written, use [`allocate`](Self::allocate) to reserve
+    /// it.
+    ///
+    /// io_uring has no truncate op before Linux 6.9, so `ftruncate(2)` is
+    /// called in place.
+    #[cfg(unix)]
+    pub async fn set_len(&self, size: u64) -> io::Result<()> {
+        let size = libc::off_t::try_from(size)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "size is too large"))?;
+        crate::syscall!(ftruncate(self.fd.raw_fd(), size))?;
+        Ok(())
+    }
+
+    /// Queries metadata about the underlying file.
+    ///
+    /// See [`fs::metadata`](crate::fs::metadata) for how it is queried.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::File;
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.txt").await?;
+    ///     let metadata = f.metadata().await?;
+    ///     println!("{} bytes", metadata.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub async fn metadata(&self) -> io::Result<super::Metadata> {
+        let stat = Op::statx_fd(&self.fd)?.stat().await?;
+        Ok(super::Metadata::from_stat(&stat))
+    }
+
+    /// Put the file into the fixed file table set up by
+    /// [`RuntimeBuilder::with_fixed_files`](crate::RuntimeBuilder::with_fixed_files),
+    /// so its ops refer to it by slot and the kernel does not look up the fd
+    /// for every op. The slot is freed when the file is closed.
+    ///
+    /// Returns `false` if the table is full or not set up, or with the legacy
+    /// driver.
+    pub fn register_fixed(&self) -> io::Result<bool> {
+        self.fd.register_fixed()
+    }
+
     /// Closes the file.
     ///
     /// The method completes once the close operation has completed,
@@ -476,6 +721,53 @@ impl File {
         self.fd.close().await;
         Ok(())
     }
+
+    /// Convert to a `std::fs::File` once the in-flight ops on the file
+    /// complete, e.g. to pass it to another library or process.
+    ///
+    /// Unlike [`IntoRawFd::into_raw_fd`], which panics if ops dropped before
+    /// completion still use the file, it waits for them.
+    #[cfg(unix)]
+    pub async fn into_std(self) -> std::fs::File {
+        let fd = self.fd.drain_unwrap().await;
+        unsafe { std::fs::File::from_raw_fd(fd) }
+    }
+}
+
+impl AsyncReadRentAt for File {
+    #[inline]
+    fn read_at<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        pos: usize,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        File::read_at(self, buf, pos as u64)
+    }
+}
+
+impl AsyncWriteRentAt for File {
+    #[inline]
+    fn write_at<T: IoBuf>(
+        &self,
+        buf: T,
+        pos: usize,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        File::write_at(self, buf, pos as u64)
+    }
+}
+
+impl AsReadFd for File {
+    #[inline]
+    fn as_reader_fd(&mut self) -> &SharedFdWrapper {
+        SharedFdWrapper::new(&self.fd)
+    }
+}
+
+impl AsWriteFd for File {
+    #[inline]
+    fn as_writer_fd(&mut self) -> &SharedFdWrapper {
+        SharedFdWrapper::new(&self.fd)
+    }
 }
 
 #[cfg(unix)]
@@ -485,6 +777,23 @@ impl AsRawFd for File {
     }
 }
 
+#[cfg(unix)]
+impl FromRawFd for File {
+    /// Must be called inside a monoio runtime.
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self::from_shared_fd(SharedFd::new_without_register(fd))
+    }
+}
+
+#[cfg(unix)]
+impl IntoRawFd for File {
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
 #[cfg(windows)]
 impl AsRawHandle for File {
     fn as_raw_handle(&self) -> RawHandle {
@@ -0,0 +1,321 @@
+use std::{
+    fmt,
+    fs::Permissions,
+    io,
+    os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::driver::op::{statx::StatBuf, Op};
+
+/// Given a path, query the file system to get information about a file,
+/// directory, etc. Symbolic links are followed.
+///
+/// With the io_uring driver the file is queried by `IORING_OP_STATX`, so a
+/// slow filesystem does not block the thread. The legacy driver, and the
+/// io_uring driver on kernels before 5.6, call `statx(2)` (`stat(2)` on
+/// other platforms) in place.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let metadata = monoio::fs::metadata("foo.txt").await?;
+///     println!("{} bytes", metadata.len());
+///     Ok(())
+/// }
+/// ```
+pub async fn metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    let stat = Op::statx_path(path, true)?.stat().await?;
+    Ok(Metadata::from_stat(&stat))
+}
+
+/// Same as [`metadata`], but does not follow a symbolic link at `path`.
+pub async fn symlink_metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    let stat = Op::statx_path(path, false)?.stat().await?;
+    Ok(Metadata::from_stat(&stat))
+}
+
+/// Metadata information about a file, returned by [`metadata`],
+/// [`symlink_metadata`] and [`File::metadata`](super::File::metadata).
+///
+/// Unix specific fields like the inode number are read through
+/// [`MetadataExt`].
+#[derive(Clone)]
+pub struct Metadata {
+    dev: u64,
+    ino: u64,
+    mode: u32,
+    nlink: u64,
+    uid: u32,
+    gid: u32,
+    rdev: u64,
+    size: u64,
+    blksize: u64,
+    blocks: u64,
+    atime: (i64, i64),
+    mtime: (i64, i64),
+    ctime: (i64, i64),
+    // Not every filesystem records the creation time.
+    btime: Option<(i64, i64)>,
+}
+
+impl Metadata {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn from_stat(stat: &StatBuf) -> Self {
+        let time = |t: libc::statx_timestamp| (t.tv_sec, t.tv_nsec as i64);
+        Self {
+            dev: libc::makedev(stat.stx_dev_major, stat.stx_dev_minor),
+            ino: stat.stx_ino,
+            mode: stat.stx_mode as u32,
+            nlink: stat.stx_nlink as u64,
+            uid: stat.stx_uid,
+            gid: stat.stx_gid,
+            rdev: libc::makedev(stat.stx_rdev_major, stat.stx_rdev_minor),
+            size: stat.stx_size,
+            blksize: stat.stx_blksize as u64,
+            blocks: stat.stx_blocks,
+            atime: time(stat.stx_atime),
+            mtime: time(stat.stx_mtime),
+            ctime: time(stat.stx_ctime),
+            btime: (stat.stx_mask & libc::STATX_BTIME != 0).then(|| time(stat.stx_btime)),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn from_stat(stat: &StatBuf) -> Self {
+        #[cfg(target_vendor = "apple")]
+        let btime = Some((stat.st_birthtime as i64, stat.st_birthtime_nsec as i64));
+        #[cfg(not(target_vendor = "apple"))]
+        let btime = None;
+        Self {
+            dev: stat.st_dev as u64,
+            ino: stat.st_ino as u64,
+            mode: stat.st_mode as u32,
+            nlink: stat.st_nlink as u64,
+            uid: stat.st_uid,
+            gid: stat.st_gid,
+            rdev: stat.st_rdev as u64,
+            size: stat.st_size as u64,
+            blksize: stat.st_blksize as u64,
+            blocks: stat.st_blocks as u64,
+            atime: (stat.st_atime as i64, stat.st_atime_nsec as i64),
+            mtime: (stat.st_mtime as i64, stat.st_mtime_nsec as i64),
+            ctime: (stat.st_ctime as i64, stat.st_ctime_nsec as i64),
+            btime,
+        }
+    }
+
+    /// The type of the file.
+    pub fn file_type(&self) -> FileType {
+        FileType { mode: self.mode }
+    }
+
+    /// Whether it is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.file_type().is_dir()
+    }
+
+    /// Whether it is a regular file.
+    pub fn is_file(&self) -> bool {
+        self.file_type().is_file()
+    }
+
+    /// Whether it is a symbolic link, only possible with
+    /// [`symlink_metadata`].
+    pub fn is_symlink(&self) -> bool {
+        self.file_type().is_symlink()
+    }
+
+    /// Size of the file in bytes.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.size
+    }
+
+    /// Permissions of the file.
+    pub fn permissions(&self) -> Permissions {
+        Permissions::from_mode(self.mode)
+    }
+
+    /// Last modification time.
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        Ok(system_time(self.mtime))
+    }
+
+    /// Last access time.
+    pub fn accessed(&self) -> io::Result<SystemTime> {
+        Ok(system_time(self.atime))
+    }
+
+    /// Creation time. Fails with [`io::ErrorKind::Unsupported`] if the
+    /// platform or filesystem does not record it.
+    pub fn created(&self) -> io::Result<SystemTime> {
+        match self.btime {
+            Some(btime) => Ok(system_time(btime)),
+            None => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "creation time is not available",
+            )),
+        }
+    }
+}
+
+impl MetadataExt for Metadata {
+    fn dev(&self) -> u64 {
+        self.dev
+    }
+
+    fn ino(&self) -> u64 {
+        self.ino
+    }
+
+    fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    fn nlink(&self) -> u64 {
+        self.nlink
+    }
+
+    fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    fn gid(&self) -> u32 {
+        self.gid
+    }
+
+    fn rdev(&self) -> u64 {
+        self.rdev
+    }
+
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn atime(&self) -> i64 {
+        self.atime.0
+    }
+
+    fn atime_nsec(&self) -> i64 {
+        self.atime.1
+    }
+
+    fn mtime(&self) -> i64 {
+        self.mtime.0
+    }
+
+    fn mtime_nsec(&self) -> i64 {
+        self.mtime.1
+    }
+
+    fn ctime(&self) -> i64 {
+        self.ctime.0
+    }
+
+    fn ctime_nsec(&self) -> i64 {
+        self.ctime.1
+    }
+
+    fn blksize(&self) -> u64 {
+        self.blksize
+    }
+
+    fn blocks(&self) -> u64 {
+        self.blocks
+    }
+}
+
+impl fmt::Debug for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metadata")
+            .field("file_type", &self.file_type())
+            .field("len", &self.len())
+            .field("permissions", &self.permissions())
+            .field("modified", &self.modified())
+            .field("accessed", &self.accessed())
+            .field("created", &self.created())
+            .finish()
+    }
+}
+
+/// The type of a file, returned by [`Metadata::file_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileType {
+    mode: u32,
+}
+
+impl FileType {
+    #[allow(clippy::unnecessary_cast)]
+    pub(crate) fn from_std(ty: std::fs::FileType) -> Self {
+        let mode = if ty.is_dir() {
+            libc::S_IFDIR
+        } else if ty.is_file() {
+            libc::S_IFREG
+        } else if ty.is_symlink() {
+            libc::S_IFLNK
+        } else if ty.is_block_device() {
+            libc::S_IFBLK
+        } else if ty.is_char_device() {
+            libc::S_IFCHR
+        } else if ty.is_fifo() {
+            libc::S_IFIFO
+        } else if ty.is_socket() {
+            libc::S_IFSOCK
+        } else {
+            0
+        };
+        Self { mode: mode as u32 }
+    }
+
+    /// Whether it is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.is(libc::S_IFDIR)
+    }
+
+    /// Whether it is a regular file.
+    pub fn is_file(&self) -> bool {
+        self.is(libc::S_IFREG)
+    }
+
+    /// Whether it is a symbolic link.
+    pub fn is_symlink(&self) -> bool {
+        self.is(libc::S_IFLNK)
+    }
+
+    // `mode_t` is not `u32` on every platform.
+    #[allow(clippy::unnecessary_cast)]
+    fn is(&self, ty: libc::mode_t) -> bool {
+        self.mode & libc::S_IFMT as u32 == ty as u32
+    }
+}
+
+impl FileTypeExt for FileType {
+    fn is_block_device(&self) -> bool {
+        self.is(libc::S_IFBLK)
+    }
+
+    fn is_char_device(&self) -> bool {
+        self.is(libc::S_IFCHR)
+    }
+
+    fn is_fifo(&self) -> bool {
+        self.is(libc::S_IFIFO)
+    }
+
+    fn is_socket(&self) -> bool {
+        self.is(libc::S_IFSOCK)
+    }
+}
+
+fn system_time((sec, nsec): (i64, i64)) -> SystemTime {
+    let nsec = Duration::from_nanos(nsec as u64);
+    if sec >= 0 {
+        UNIX_EPOCH + Duration::from_secs(sec as u64) + nsec
+    } else {
+        UNIX_EPOCH - Duration::from_secs(sec.unsigned_abs()) + nsec
+    }
+}
@@ -0,0 +1,388 @@
+use std::{
+    fmt, io, mem,
+    ops::{Deref, DerefMut},
+    os::unix::io::AsRawFd,
+    ptr::{self, NonNull},
+    sync::Arc,
+};
+
+use super::File;
+use crate::buf::{IoBuf, IoBufMut};
+
+/// A read-only shared memory map of a file.
+///
+/// It implements [`IoBuf`], so a mapped file, or a [`slice`](IoBuf::slice) of
+/// it, can be written to a socket without copying it into a buffer first.
+/// Cloning it is cheap and shares the mapping, e.g. to serve the same file to
+/// several connections.
+///
+/// Reading a page which is not in memory blocks the thread on a page fault.
+/// [`advise`](Mmap::advise) the kernel to read ahead to avoid it.
+///
+/// Creating a map is unsafe, as the file may be changed from outside of the
+/// program while it is mapped, see [`Mmap::map`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{
+///     fs::{Advice, File, Mmap},
+///     io::AsyncWriteRentExt,
+///     net::TcpStream,
+/// };
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let file = File::open("index.html").await?;
+///     // Safety: the file is not modified or truncated while it is served.
+///     let map = unsafe { Mmap::map(&file)? };
+///     map.advise(Advice::WillNeed)?;
+///
+///     let mut stream = TcpStream::connect("127.0.0.1:8080").await?;
+///     stream.write_all(map).await.0?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Mmap {
+    inner: Arc<Mapping>,
+}
+
+impl Mmap {
+    /// Map the whole file, which must be opened for reading.
+    ///
+    /// # Safety
+    ///
+    /// The mapped bytes are shared with the file, which the caller must make
+    /// sure is neither modified nor truncated while it is mapped, in this
+    /// process or in another one. A write breaks the immutability of the
+    /// `&[u8]` the map derefs to, and accessing a page past the end of a
+    /// truncated file raises `SIGBUS`.
+    pub unsafe fn map(file: &File) -> io::Result<Self> {
+        let len = file_len(file)?;
+        Self::map_range(file, 0, len)
+    }
+
+    /// Map `len` bytes of the file from `offset`. The offset does not have to
+    /// be aligned to pages.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the range is past the
+    /// end of the file.
+    ///
+    /// # Safety
+    ///
+    /// See [`map`](Mmap::map).
+    pub unsafe fn map_range(file: &File, offset: u64, len: usize) -> io::Result<Self> {
+        Ok(Self {
+            inner: Arc::new(Mapping::new(file, offset, len, libc::PROT_READ)?),
+        })
+    }
+
+    /// Advise the kernel how the mapping is going to be accessed.
+    pub fn advise(&self, advice: Advice) -> io::Result<()> {
+        self.inner.advise(advice)
+    }
+}
+
+impl Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+}
+
+impl fmt::Debug for Mmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mmap").field("len", &self.len()).finish()
+    }
+}
+
+unsafe impl IoBuf for Mmap {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.inner.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.inner.len
+    }
+}
+
+/// A writable shared memory map of a file. Changes are written back to the
+/// file by the kernel, or by [`flush`](MmapMut::flush).
+///
+/// It implements [`IoBufMut`], so data can be read from a socket into the
+/// file directly.
+///
+/// Creating a map is unsafe, as the file may be changed from outside of the
+/// program while it is mapped, see [`MmapMut::map`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::fs::{MmapMut, OpenOptions};
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let file = OpenOptions::new()
+///         .read(true)
+///         .write(true)
+///         .open("foo.txt")
+///         .await?;
+///     // Safety: nothing else accesses the file while it is mapped.
+///     let mut map = unsafe { MmapMut::map(&file)? };
+///     map[..5].copy_from_slice(b"hello");
+///     map.flush().await?;
+///     Ok(())
+/// }
+/// ```
+pub struct MmapMut {
+    inner: Arc<Mapping>,
+}
+
+impl MmapMut {
+    /// Map the whole file, which must be opened for reading and writing.
+    ///
+    /// # Safety
+    ///
+    /// The mapped bytes are shared with the file, which the caller must make
+    /// sure is neither accessed nor truncated while it is mapped, in this
+    /// process or in another one. Another access aliases the `&mut [u8]` the
+    /// map derefs to, and accessing a page past the end of a truncated file
+    /// raises `SIGBUS`.
+    pub unsafe fn map(file: &File) -> io::Result<Self> {
+        let len = file_len(file)?;
+        Self::map_range(file, 0, len)
+    }
+
+    /// Map `len` bytes of the file from `offset`. The offset does not have to
+    /// be aligned to pages.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the range is past the
+    /// end of the file.
+    ///
+    /// # Safety
+    ///
+    /// See [`map`](MmapMut::map).
+    pub unsafe fn map_range(file: &File, offset: u64, len: usize) -> io::Result<Self> {
+        Ok(Self {
+            inner: Arc::new(Mapping::new(
+                file,
+                offset,
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+            )?),
+        })
+    }
+
+    /// Advise the kernel how the mapping is going to be accessed.
+    pub fn advise(&self, advice: Advice) -> io::Result<()> {
+        self.inner.advise(advice)
+    }
+
+    /// Write the changes back to the file and wait for it with `msync(2)`,
+    /// which runs on the thread pool attached to the runtime, or in place if
+    /// there is none.
+    pub async fn flush(&self) -> io::Result<()> {
+        // The pool keeps the mapping alive if this future is dropped.
+        let mapping = self.inner.clone();
+        super::run_blocking(move || mapping.sync()).await
+    }
+
+    /// Make the mapping read-only.
+    pub fn make_read_only(self) -> io::Result<Mmap> {
+        self.inner.protect(libc::PROT_READ)?;
+        Ok(Mmap { inner: self.inner })
+    }
+}
+
+impl Deref for MmapMut {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+}
+
+impl DerefMut for MmapMut {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // Safety: the mapping is writable and only shared with a pending
+        // `msync`, which does not access the memory.
+        unsafe { std::slice::from_raw_parts_mut(self.inner.ptr.as_ptr(), self.inner.len) }
+    }
+}
+
+impl fmt::Debug for MmapMut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MmapMut").field("len", &self.len()).finish()
+    }
+}
+
+unsafe impl IoBuf for MmapMut {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.inner.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.inner.len
+    }
+}
+
+unsafe impl IoBufMut for MmapMut {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.inner.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.inner.len
+    }
+
+    // The mapped bytes are always initialized.
+    #[inline]
+    unsafe fn set_init(&mut self, _pos: usize) {}
+}
+
+/// How a mapping is going to be accessed, see `madvise(2)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Advice {
+    /// No special treatment, the default.
+    Normal,
+    /// Pages are accessed in random order, read ahead less.
+    Random,
+    /// Pages are accessed in order, read ahead more.
+    Sequential,
+    /// The pages will be accessed soon, read them ahead.
+    WillNeed,
+    /// The pages will not be accessed soon, they may be dropped from memory.
+    DontNeed,
+}
+
+impl Advice {
+    fn as_raw(self) -> libc::c_int {
+        match self {
+            Advice::Normal => libc::MADV_NORMAL,
+            Advice::Random => libc::MADV_RANDOM,
+            Advice::Sequential => libc::MADV_SEQUENTIAL,
+            Advice::WillNeed => libc::MADV_WILLNEED,
+            Advice::DontNeed => libc::MADV_DONTNEED,
+        }
+    }
+}
+
+struct Mapping {
+    // The mapped pages, from the page the data starts in. Empty for an empty
+    // mapping, which `mmap` does not support.
+    base: *mut libc::c_void,
+    base_len: usize,
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// Safety: the memory is not tied to a thread.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn new(file: &File, offset: u64, len: usize, prot: libc::c_int) -> io::Result<Self> {
+        let file_len = file_len(file)? as u64;
+        if !matches!(offset.checked_add(len as u64), Some(end) if end <= file_len) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the mapped range is past the end of the file",
+            ));
+        }
+        if len == 0 {
+            return Ok(Self {
+                base: ptr::null_mut(),
+                base_len: 0,
+                ptr: NonNull::dangling(),
+                len: 0,
+            });
+        }
+
+        let page = page_size() as u64;
+        let delta = (offset % page) as usize;
+        let base_len = len + delta;
+        let base = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                base_len,
+                prot,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                (offset - delta as u64) as libc::off_t,
+            )
+        };
+        if base == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        // Safety: mmap does not return null on success.
+        let ptr = unsafe { NonNull::new_unchecked((base as *mut u8).add(delta)) };
+        Ok(Self {
+            base,
+            base_len,
+            ptr,
+            len,
+        })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    fn advise(&self, advice: Advice) -> io::Result<()> {
+        if self.base_len == 0 {
+            return Ok(());
+        }
+        crate::syscall!(madvise(self.base, self.base_len, advice.as_raw())).map(|_| ())
+    }
+
+    fn sync(&self) -> io::Result<()> {
+        if self.base_len == 0 {
+            return Ok(());
+        }
+        crate::syscall!(msync(self.base, self.base_len, libc::MS_SYNC)).map(|_| ())
+    }
+
+    fn protect(&self, prot: libc::c_int) -> io::Result<()> {
+        if self.base_len == 0 {
+            return Ok(());
+        }
+        crate::syscall!(mprotect(self.base, self.base_len, prot)).map(|_| ())
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.base_len != 0 {
+            unsafe { libc::munmap(self.base, self.base_len) };
+        }
+    }
+}
+
+fn file_len(file: &File) -> io::Result<usize> {
+    let mut stat = mem::MaybeUninit::<libc::stat>::uninit();
+    crate::syscall!(fstat(file.as_raw_fd(), stat.as_mut_ptr()))?;
+    // Safety: fstat succeeded.
+    let size = unsafe { stat.assume_init() }.st_size;
+    usize::try_from(size).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the file is too large to be mapped",
+        )
+    })
+}
+
+fn page_size() -> usize {
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
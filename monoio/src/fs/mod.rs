@@ -1,7 +1,54 @@
 //! Filesystem manipulation operations.
 
+use std::io;
+
+mod append_writer;
+pub use append_writer::{AppendWriter, AppendWriterBuilder};
+
 mod file;
 pub use file::File;
 
+#[cfg(unix)]
+mod copy;
+#[cfg(unix)]
+pub use copy::copy;
+
+#[cfg(unix)]
+mod metadata;
+#[cfg(unix)]
+pub use metadata::{metadata, symlink_metadata, FileType, Metadata};
+
 mod open_options;
 pub use open_options::OpenOptions;
+
+#[cfg(unix)]
+mod mmap;
+#[cfg(unix)]
+pub use mmap::{Advice, Mmap, MmapMut};
+
+#[cfg(unix)]
+mod read_dir;
+#[cfg(unix)]
+pub use read_dir::{read_dir, DirEntry, ReadDir};
+
+#[cfg(target_os = "linux")]
+mod watch;
+#[cfg(target_os = "linux")]
+pub use watch::{watch, Event, EventMask, WatchDescriptor, Watcher};
+
+/// Run blocking io on the thread pool attached to the runtime, or in place
+/// if there is none.
+#[allow(unused)]
+async fn run_blocking<F, R>(f: F) -> io::Result<R>
+where
+    F: FnOnce() -> io::Result<R> + Send + 'static,
+    R: Send + 'static,
+{
+    #[cfg(feature = "sync")]
+    if crate::blocking::pool_attached() {
+        return crate::spawn_blocking(f)
+            .await
+            .unwrap_or_else(|e| Err(io::Error::other(format!("blocking task failed: {e:?}"))));
+    }
+    f()
+}
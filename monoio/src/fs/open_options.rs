@@ -59,6 +59,10 @@ pub struct OpenOptions {
     create_new: bool,
     #[cfg(unix)]
     pub(crate) mode: libc::mode_t,
+    #[cfg(unix)]
+    pub(crate) custom_flags: libc::c_int,
+    #[cfg(target_os = "linux")]
+    pub(crate) direct: bool,
 }
 
 impl OpenOptions {
@@ -89,6 +93,10 @@ impl OpenOptions {
             create_new: false,
             #[cfg(unix)]
             mode: 0o666,
+            #[cfg(unix)]
+            custom_flags: 0,
+            #[cfg(target_os = "linux")]
+            direct: false,
         }
     }
 
@@ -260,9 +268,71 @@ impl OpenOptions {
         self
     }
 
+    /// Pass custom flags to the `flags` argument of `open`, such as
+    /// `libc::O_DIRECT`. The bits of the access mode are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::OpenOptions;
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let file = OpenOptions::new()
+    ///         .read(true)
+    ///         .custom_flags(libc::O_NOFOLLOW)
+    ///         .open("foo.txt")
+    ///         .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn custom_flags(&mut self, flags: i32) -> &mut OpenOptions {
+        self.custom_flags = flags as libc::c_int;
+        self
+    }
+
+    /// Sets the option to open the file with `O_DIRECT`, bypassing the page
+    /// cache.
+    ///
+    /// The buffers, lengths and offsets of reads and writes must then be
+    /// aligned to the logical block size of the filesystem, usually 512 or
+    /// 4096 bytes, or the ops fail with `EINVAL`. [`DmaBuf`] allocates such
+    /// buffers.
+    ///
+    /// [`DmaBuf`]: crate::buf::DmaBuf
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::{buf::DmaBuf, fs::OpenOptions};
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let file = OpenOptions::new()
+    ///         .read(true)
+    ///         .direct(true)
+    ///         .open("foo.txt")
+    ///         .await?;
+    ///     let (res, buf) = file.read_at(DmaBuf::new(4096), 0).await;
+    ///     println!("The bytes: {:?}", &buf[..res?]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(target_os = "linux")]
+    pub fn direct(&mut self, direct: bool) -> &mut OpenOptions {
+        self.direct = direct;
+        self
+    }
+
     #[cfg(unix)]
     /// Opens a file at `path` with the options specified by `self`.
     ///
+    /// With the io_uring driver the file is opened by `IORING_OP_OPENAT`, so a
+    /// slow filesystem like NFS or FUSE does not block the thread. The legacy
+    /// driver, and the io_uring driver on kernels before 5.6, call `open(2)`
+    /// in place.
+    ///
     /// # Errors
     ///
     /// This function will return an error under a number of different
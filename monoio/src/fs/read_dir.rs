@@ -0,0 +1,129 @@
+use std::{
+    collections::VecDeque,
+    ffi::OsString,
+    fs,
+    future::Future,
+    io,
+    os::unix::fs::DirEntryExt,
+    path::{Path, PathBuf},
+};
+
+use super::{run_blocking, symlink_metadata, FileType, Metadata};
+use crate::io::stream::Stream;
+
+// Entries read from the directory at a time.
+const BATCH: usize = 64;
+
+/// Returns a stream over the entries within a directory.
+///
+/// io_uring has no op to read a directory, so the entries are read with
+/// `getdents(2)` in batches. If a thread pool is attached to the runtime
+/// with `RuntimeBuilder::attach_thread_pool`, the reads are done on it.
+/// Otherwise they are done in place, which only takes long on a slow
+/// filesystem.
+///
+/// The entries are not yielded in any particular order, and `.` and `..` are
+/// skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::io::stream::Stream;
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let mut entries = monoio::fs::read_dir(".").await?;
+///     while let Some(entry) = entries.next().await {
+///         println!("{}", entry?.path().display());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
+    let path = path.as_ref().to_owned();
+    let dir = run_blocking(move || fs::read_dir(path)).await?;
+    Ok(ReadDir {
+        dir: Some(dir),
+        entries: VecDeque::new(),
+    })
+}
+
+/// Stream of the entries in a directory, returned by [`read_dir`].
+///
+/// Dropping a pending `next` future ends the stream, as the batch being read
+/// is lost with it.
+#[derive(Debug)]
+pub struct ReadDir {
+    // `None` once all entries have been read
+    dir: Option<fs::ReadDir>,
+    entries: VecDeque<io::Result<fs::DirEntry>>,
+}
+
+impl Stream for ReadDir {
+    type Item = io::Result<DirEntry>;
+
+    type NextFuture<'a> = impl Future<Output = Option<Self::Item>> + 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move {
+            if self.entries.is_empty() {
+                let dir = self.dir.take()?;
+                match run_blocking(move || Ok(read_batch(dir))).await {
+                    Ok((dir, entries)) => {
+                        self.dir = dir;
+                        self.entries = entries;
+                    }
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+            let entry = self.entries.pop_front()?;
+            Some(entry.map(|inner| DirEntry { inner }))
+        }
+    }
+}
+
+type Batch = (Option<fs::ReadDir>, VecDeque<io::Result<fs::DirEntry>>);
+
+fn read_batch(mut dir: fs::ReadDir) -> Batch {
+    let entries: VecDeque<_> = dir.by_ref().take(BATCH).collect();
+    let dir = (entries.len() == BATCH).then_some(dir);
+    (dir, entries)
+}
+
+/// An entry in a directory, yielded by [`ReadDir`].
+#[derive(Debug)]
+pub struct DirEntry {
+    inner: fs::DirEntry,
+}
+
+impl DirEntry {
+    /// The full path of the entry, the directory path joined with its file
+    /// name.
+    pub fn path(&self) -> PathBuf {
+        self.inner.path()
+    }
+
+    /// The file name of the entry, without the directory.
+    pub fn file_name(&self) -> OsString {
+        self.inner.file_name()
+    }
+
+    /// Metadata of the entry, symbolic links are not followed like
+    /// [`symlink_metadata`].
+    pub async fn metadata(&self) -> io::Result<Metadata> {
+        symlink_metadata(self.path()).await
+    }
+
+    /// The type of the entry, symbolic links are not followed. It is read
+    /// along with the entry on most filesystems, others need an `lstat(2)`
+    /// which is done in place.
+    pub fn file_type(&self) -> io::Result<FileType> {
+        self.inner.file_type().map(FileType::from_std)
+    }
+}
+
+impl DirEntryExt for DirEntry {
+    fn ino(&self) -> u64 {
+        self.inner.ino()
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    ffi::{CString, OsString},
+    future::Future,
+    io, mem,
+    ops::{BitOr, BitOrAssign},
+    os::unix::{
+        ffi::{OsStrExt, OsStringExt},
+        io::{AsRawFd, RawFd},
+    },
+    path::Path,
+};
+
+use crate::{
+    driver::{op::Op, shared_fd::SharedFd},
+    io::stream::Stream,
+};
+
+// Enough for a few events, and at least one with the longest name.
+const BUF_SIZE: usize = 4096;
+const HEADER_SIZE: usize = mem::size_of::<libc::inotify_event>();
+
+/// Watch `path` for changes, returning a stream of the events.
+///
+/// The changes watched for are [`EventMask::ALL_CHANGES`]. Use
+/// [`Watcher::add`] to watch more paths or other events.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::io::stream::Stream;
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let mut watcher = monoio::fs::watch("config")?;
+///     while let Some(event) = watcher.next().await {
+///         println!("{:?}", event?);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn watch<P: AsRef<Path>>(path: P) -> io::Result<Watcher> {
+    let watcher = Watcher::new()?;
+    watcher.add(path, EventMask::ALL_CHANGES)?;
+    Ok(watcher)
+}
+
+/// Stream of filesystem events from an inotify instance, driven by the
+/// runtime.
+///
+/// Dropping a pending `next` future loses the events being read with it.
+#[derive(Debug)]
+pub struct Watcher {
+    fd: SharedFd,
+    // Events read and not yielded yet, from `pos`.
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl Watcher {
+    /// Create an inotify instance watching nothing yet. Must be called inside
+    /// a monoio runtime.
+    pub fn new() -> io::Result<Self> {
+        let flags = if crate::driver::op::non_blocking() {
+            libc::IN_CLOEXEC | libc::IN_NONBLOCK
+        } else {
+            libc::IN_CLOEXEC
+        };
+        let fd = crate::syscall!(inotify_init1(flags))?;
+        let fd = match SharedFd::new(fd) {
+            Ok(fd) => fd,
+            Err(e) => {
+                let _ = crate::syscall!(close(fd));
+                return Err(e);
+            }
+        };
+        Ok(Self {
+            fd,
+            buf: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    /// Watch `path` for the events in `mask`. Watching a path again replaces
+    /// its mask and returns the same descriptor.
+    pub fn add<P: AsRef<Path>>(&self, path: P, mask: EventMask) -> io::Result<WatchDescriptor> {
+        let path = CString::new(path.as_ref().as_os_str().as_bytes())?;
+        crate::syscall!(inotify_add_watch(self.fd.raw_fd(), path.as_ptr(), mask.0))
+            .map(WatchDescriptor)
+    }
+
+    /// Stop watching. An event with [`EventMask::IGNORED`] is yielded for it.
+    pub fn remove(&self, wd: WatchDescriptor) -> io::Result<()> {
+        crate::syscall!(inotify_rm_watch(self.fd.raw_fd(), wd.0)).map(|_| ())
+    }
+
+    /// Wait for the next event.
+    pub async fn next_event(&mut self) -> io::Result<Event> {
+        if self.pos >= self.buf.len() {
+            let mut buf = mem::take(&mut self.buf);
+            buf.clear();
+            buf.reserve(BUF_SIZE);
+            let (res, buf) = Op::read_stream(&self.fd, buf)?.read().await;
+            self.buf = buf;
+            self.pos = 0;
+            // The buffer is left empty on failure.
+            if res? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+
+        let rest = &self.buf[self.pos..];
+        // Safety: the kernel only returns whole events.
+        let header: libc::inotify_event =
+            unsafe { std::ptr::read_unaligned(rest.as_ptr() as *const _) };
+        let name = &rest[HEADER_SIZE..HEADER_SIZE + header.len as usize];
+        self.pos += HEADER_SIZE + header.len as usize;
+        // The name is padded with nul bytes.
+        let name = match name.iter().position(|b| *b == 0).unwrap_or(name.len()) {
+            0 => None,
+            len => Some(OsString::from_vec(name[..len].to_vec())),
+        };
+        Ok(Event {
+            wd: WatchDescriptor(header.wd),
+            mask: EventMask(header.mask),
+            cookie: header.cookie,
+            name,
+        })
+    }
+}
+
+impl Stream for Watcher {
+    type Item = io::Result<Event>;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move { Some(self.next_event().await) }
+    }
+}
+
+impl AsRawFd for Watcher {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+/// Identifies a path watched by a [`Watcher`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WatchDescriptor(libc::c_int);
+
+/// A filesystem event yielded by a [`Watcher`].
+#[derive(Clone, Debug)]
+pub struct Event {
+    /// The watched path the event is about.
+    pub wd: WatchDescriptor,
+    /// What happened.
+    pub mask: EventMask,
+    /// Relates the [`EventMask::MOVED_FROM`] and [`EventMask::MOVED_TO`]
+    /// events of a rename, 0 for other events.
+    pub cookie: u32,
+    /// The name of the file within a watched directory the event is about,
+    /// `None` if it is about the watched path itself.
+    pub name: Option<OsString>,
+}
+
+/// The kinds of events to watch for, or that happened, see `inotify(7)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EventMask(u32);
+
+impl EventMask {
+    /// A file was read.
+    pub const ACCESS: Self = Self(libc::IN_ACCESS);
+    /// A file was written.
+    pub const MODIFY: Self = Self(libc::IN_MODIFY);
+    /// The metadata changed.
+    pub const ATTRIB: Self = Self(libc::IN_ATTRIB);
+    /// A file opened for writing was closed.
+    pub const CLOSE_WRITE: Self = Self(libc::IN_CLOSE_WRITE);
+    /// A file not opened for writing was closed.
+    pub const CLOSE_NOWRITE: Self = Self(libc::IN_CLOSE_NOWRITE);
+    /// A file was opened.
+    pub const OPEN: Self = Self(libc::IN_OPEN);
+    /// A file was renamed out of a watched directory.
+    pub const MOVED_FROM: Self = Self(libc::IN_MOVED_FROM);
+    /// A file was renamed into a watched directory.
+    pub const MOVED_TO: Self = Self(libc::IN_MOVED_TO);
+    /// A file was created in a watched directory.
+    pub const CREATE: Self = Self(libc::IN_CREATE);
+    /// A file was deleted from a watched directory.
+    pub const DELETE: Self = Self(libc::IN_DELETE);
+    /// The watched path itself was deleted.
+    pub const DELETE_SELF: Self = Self(libc::IN_DELETE_SELF);
+    /// The watched path itself was moved.
+    pub const MOVE_SELF: Self = Self(libc::IN_MOVE_SELF);
+    /// All of the events above.
+    pub const ALL_EVENTS: Self = Self(libc::IN_ALL_EVENTS);
+    /// The events changing a file or a directory, that is all events but
+    /// [`ACCESS`](Self::ACCESS), [`OPEN`](Self::OPEN) and
+    /// [`CLOSE_NOWRITE`](Self::CLOSE_NOWRITE).
+    pub const ALL_CHANGES: Self =
+        Self(libc::IN_ALL_EVENTS & !(libc::IN_ACCESS | libc::IN_OPEN | libc::IN_CLOSE_NOWRITE));
+
+    /// Only set in events, the watch was removed.
+    pub const IGNORED: Self = Self(libc::IN_IGNORED);
+    /// Only set in events, the file the event is about is a directory.
+    pub const ISDIR: Self = Self(libc::IN_ISDIR);
+    /// Only set in events, events were dropped since the queue was full.
+    pub const Q_OVERFLOW: Self = Self(libc::IN_Q_OVERFLOW);
+    /// Only set in events, the filesystem of the watched path was unmounted.
+    pub const UNMOUNT: Self = Self(libc::IN_UNMOUNT);
+
+    /// Create a mask from raw `IN_*` bits, e.g. `IN_ONLYDIR`.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw `IN_*` bits.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns true if all bits of `other` are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for EventMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for EventMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
@@ -1,11 +1,30 @@
 //! We impl AsReadFd and AsWriteFd for some structs.
 
-use crate::driver::shared_fd::SharedFd;
+use std::{
+    io,
+    task::{Context, Poll},
+};
+
+use crate::{buf::ProvidedBuf, driver::shared_fd::SharedFd};
 
 /// Get a readable shared fd from self.
 pub trait AsReadFd {
     /// Get fd.
     fn as_reader_fd(&mut self) -> &SharedFdWrapper;
+
+    /// Take up to `len` bytes already received from the fd, like the ones held
+    /// by the read buffer of a [`TcpStream`](crate::net::TcpStream), which
+    /// come before the data still in the fd. Ready with `None` once there are
+    /// none left, and nothing is received from the fd in the background
+    /// anymore, so it can be read by other means, e.g. spliced.
+    #[inline]
+    fn poll_take_received(
+        &mut self,
+        _cx: &mut Context<'_>,
+        _len: usize,
+    ) -> Poll<Option<io::Result<ProvidedBuf>>> {
+        Poll::Ready(None)
+    }
 }
 
 /// Get a writable shared fd from self.
@@ -0,0 +1,218 @@
+use std::{future::Future, io};
+
+use crate::{
+    io::{stream::Stream, AsyncBufRead},
+    BufResult,
+};
+
+/// AsyncBufReadExt
+///
+/// The buffers are passed by ownership and returned with the result. Reads
+/// with a limit fail with [`io::ErrorKind::InvalidData`] once more than
+/// `limit` bytes are read without seeing the delimiter, so a peer can not make
+/// the buffer grow without bound.
+pub trait AsyncBufReadExt {
+    /// The future of Result<size, buffer>
+    type ReadUntilFuture<'a>: Future<Output = BufResult<usize, Vec<u8>>>
+    where
+        Self: 'a;
+
+    /// Read and append to `buf` until the delimiter `byte` or EOF is reached.
+    /// The delimiter is appended too. Returns how many bytes were read, 0 on
+    /// EOF.
+    fn read_until(&mut self, byte: u8, buf: Vec<u8>) -> Self::ReadUntilFuture<'_> {
+        self.read_until_limited(byte, buf, usize::MAX)
+    }
+
+    /// Like [`read_until`](Self::read_until), but fail after `limit` bytes,
+    /// including the delimiter, are read. The bytes read before the failure
+    /// are appended to `buf`, and the rest of the line is left in the reader.
+    fn read_until_limited(
+        &mut self,
+        byte: u8,
+        buf: Vec<u8>,
+        limit: usize,
+    ) -> Self::ReadUntilFuture<'_>;
+
+    /// The future of Result<size, String>
+    type ReadLineFuture<'a>: Future<Output = BufResult<usize, String>>
+    where
+        Self: 'a;
+
+    /// Read and append to `buf` until a newline(the `0xA` byte) or EOF is
+    /// reached. The newline is appended too. Returns how many bytes were read,
+    /// 0 on EOF.
+    ///
+    /// If the data read is not valid UTF-8, [`io::ErrorKind::InvalidData`] is
+    /// returned and `buf` is left unchanged.
+    fn read_line(&mut self, buf: String) -> Self::ReadLineFuture<'_> {
+        self.read_line_limited(buf, usize::MAX)
+    }
+
+    /// Like [`read_line`](Self::read_line), but fail after `limit` bytes,
+    /// including the newline, are read. `buf` is left unchanged on failure, so
+    /// the bytes read are dropped, and the rest of the line is left in the
+    /// reader.
+    fn read_line_limited(&mut self, buf: String, limit: usize) -> Self::ReadLineFuture<'_>;
+
+    /// Convert to a stream over the lines of the reader, see [`Lines`].
+    fn lines(self) -> Lines<Self>
+    where
+        Self: Sized,
+    {
+        Lines {
+            reader: self,
+            limit: usize::MAX,
+        }
+    }
+}
+
+impl<A> AsyncBufReadExt for A
+where
+    A: AsyncBufRead + ?Sized,
+{
+    type ReadUntilFuture<'a>
+        = impl Future<Output = BufResult<usize, Vec<u8>>> + 'a
+    where
+        A: 'a;
+
+    fn read_until_limited(
+        &mut self,
+        byte: u8,
+        mut buf: Vec<u8>,
+        limit: usize,
+    ) -> Self::ReadUntilFuture<'_> {
+        async move {
+            let res = read_until_internal(self, byte, &mut buf, limit).await;
+            (res, buf)
+        }
+    }
+
+    type ReadLineFuture<'a>
+        = impl Future<Output = BufResult<usize, String>> + 'a
+    where
+        A: 'a;
+
+    fn read_line_limited(&mut self, buf: String, limit: usize) -> Self::ReadLineFuture<'_> {
+        async move {
+            let mut bytes = buf.into_bytes();
+            let len = bytes.len();
+            let res = read_until_internal(self, b'\n', &mut bytes, limit).await;
+            let res = match res {
+                Ok(n) if std::str::from_utf8(&bytes[len..]).is_ok() => Ok(n),
+                Ok(_) => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream did not contain valid UTF-8",
+                )),
+                Err(e) => Err(e),
+            };
+            if res.is_err() {
+                bytes.truncate(len);
+            }
+            // # Safety
+            // The bytes appended are checked, or truncated on failure.
+            (res, unsafe { String::from_utf8_unchecked(bytes) })
+        }
+    }
+}
+
+async fn read_until_internal<A: AsyncBufRead + ?Sized>(
+    reader: &mut A,
+    byte: u8,
+    buf: &mut Vec<u8>,
+    limit: usize,
+) -> io::Result<usize> {
+    let mut read = 0;
+    loop {
+        let available = reader.fill_buf().await?;
+        let (done, used) = match available.iter().position(|b| *b == byte) {
+            Some(i) => (true, i + 1),
+            None => (false, available.len()),
+        };
+        if used > limit - read {
+            let used = limit - read;
+            buf.extend_from_slice(&available[..used]);
+            reader.consume(used);
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "delimiter not found within the limit",
+            ));
+        }
+        buf.extend_from_slice(&available[..used]);
+        reader.consume(used);
+        read += used;
+        if done || used == 0 {
+            return Ok(read);
+        }
+    }
+}
+
+/// Stream over the lines of a reader, created by
+/// [`AsyncBufReadExt::lines`].
+///
+/// Each line is yielded without the trailing `\n` or `\r\n`. A line longer
+/// than the limit yields an [`io::ErrorKind::InvalidData`] error, and the
+/// stream should not be used after it.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Lines<R> {
+    reader: R,
+    limit: usize,
+}
+
+impl<R> Lines<R> {
+    /// Set the maximum length of a line in bytes, including the newline.
+    /// There is no limit by default.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes this `Lines`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncBufRead> Lines<R> {
+    /// Read the next line, `None` on EOF.
+    pub async fn next_line(&mut self) -> io::Result<Option<String>> {
+        let (res, mut line) = self
+            .reader
+            .read_line_limited(String::new(), self.limit)
+            .await;
+        if res? == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+}
+
+impl<R: AsyncBufRead> Stream for Lines<R> {
+    type Item = io::Result<String>;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move { self.next_line().await.transpose() }
+    }
+}
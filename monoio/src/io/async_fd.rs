@@ -0,0 +1,99 @@
+use std::{
+    io,
+    os::unix::io::{AsRawFd, RawFd},
+};
+
+use crate::driver::{op::Op, shared_fd::SharedFd};
+
+/// Readiness of an fd not created by monoio, e.g. the socket of a database
+/// client, a netlink socket or a GPIO device, so it can be driven by the
+/// runtime.
+///
+/// Wait with [`readable`](Self::readable) or [`writable`](Self::writable),
+/// then do the non-blocking IO on [`get_ref`](Self::get_ref), and wait again
+/// if it fails with [`io::ErrorKind::WouldBlock`]. The fd should be in
+/// non-blocking mode.
+///
+/// With the io_uring driver, the readiness is waited for by a poll op. With the
+/// legacy driver, the fd is registered to the driver. A duplicate of the fd is
+/// used for it, so the inner value keeps its fd and closes it as usual.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{io::Read, os::unix::net::UnixStream};
+///
+/// use monoio::io::AsyncFd;
+///
+/// async fn read(fd: &AsyncFd<UnixStream>, buf: &mut [u8]) -> std::io::Result<usize> {
+///     loop {
+///         fd.readable().await?;
+///         match fd.get_ref().read(buf) {
+///             Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+///             res => return res,
+///         }
+///     }
+/// }
+/// ```
+pub struct AsyncFd<T: AsRawFd> {
+    inner: T,
+    fd: SharedFd,
+}
+
+impl<T: AsRawFd> AsyncFd<T> {
+    /// Wrap `inner` to wait for the readiness of its fd. Must be called inside
+    /// a monoio runtime.
+    pub fn new(inner: T) -> io::Result<Self> {
+        let fd = crate::syscall!(fcntl(inner.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0))?;
+        let fd = match SharedFd::new(fd) {
+            Ok(fd) => fd,
+            Err(e) => {
+                let _ = crate::syscall!(close(fd));
+                return Err(e);
+            }
+        };
+        Ok(Self { inner, fd })
+    }
+
+    /// Wait until the fd is readable, or is closed or fails, so a read does
+    /// not block.
+    pub async fn readable(&self) -> io::Result<()> {
+        Op::poll_read(&self.fd)?.await.meta.result.map(|_| ())
+    }
+
+    /// Wait until the fd is writable, or is closed or fails, so a write does
+    /// not block.
+    pub async fn writable(&self) -> io::Result<()> {
+        Op::poll_write(&self.fd)?.await.meta.result.map(|_| ())
+    }
+
+    /// Gets a reference to the inner value.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the inner value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Stop waiting for the readiness of the fd and return the inner value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: AsRawFd> AsRawFd for AsyncFd<T> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl<T: AsRawFd + std::fmt::Debug> std::fmt::Debug for AsyncFd<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncFd")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
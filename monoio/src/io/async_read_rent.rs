@@ -1,70 +1,72 @@
 use std::future::Future;
 
+use super::CancelHandle;
 use crate::{
     buf::{IoBufMut, IoVecBufMut, RawBuf},
     BufResult,
 };
 
 /// AsyncReadRent: async read with a ownership of a buffer
+// Futures of io types are local to the thread, so no `Send` bound is needed.
+#[allow(async_fn_in_trait)]
 pub trait AsyncReadRent {
-    /// The future of read Result<size, buffer>
-    type ReadFuture<'a, T>: Future<Output = BufResult<usize, T>>
-    where
-        Self: 'a,
-        T: IoBufMut + 'a;
-    /// The future of readv Result<size, buffer>
-    type ReadvFuture<'a, T>: Future<Output = BufResult<usize, T>>
-    where
-        Self: 'a,
-        T: IoVecBufMut + 'a;
-
     /// Same as read(2)
-    fn read<T: IoBufMut>(&mut self, buf: T) -> Self::ReadFuture<'_, T>;
+    async fn read<T: IoBufMut>(&mut self, buf: T) -> BufResult<usize, T>;
     /// Same as readv(2)
-    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> Self::ReadvFuture<'_, T>;
+    async fn readv<T: IoVecBufMut>(&mut self, buf: T) -> BufResult<usize, T>;
 }
 
 /// AsyncReadRentAt: async read with a ownership of a buffer and a position
+#[allow(async_fn_in_trait)]
 pub trait AsyncReadRentAt {
-    /// The future of Result<size, buffer>
-    type Future<'a, T>: Future<Output = BufResult<usize, T>>
-    where
-        Self: 'a,
-        T: 'a;
-
     /// Same as Read(2)
-    fn read_at<T: IoBufMut>(&mut self, buf: T, pos: usize) -> Self::Future<'_, T>;
+    async fn read_at<T: IoBufMut>(&mut self, buf: T, pos: usize) -> BufResult<usize, T>;
+}
+
+/// CancelableAsyncReadRent: async read which can be canceled by the
+/// [`Canceller`](super::Canceller) of the given handle
+#[allow(async_fn_in_trait)]
+pub trait CancelableAsyncReadRent: AsyncReadRent {
+    /// Same as read(2), fails with `ECANCELED` if canceled
+    async fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> BufResult<usize, T>;
+    /// Same as readv(2), fails with `ECANCELED` if canceled
+    async fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> BufResult<usize, T>;
 }
 
 impl<A: ?Sized + AsyncReadRent> AsyncReadRent for &mut A {
-    type ReadFuture<'a, T> = A::ReadFuture<'a, T>
-    where
-        Self: 'a,
-        T: IoBufMut + 'a;
+    #[inline]
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).read(buf)
+    }
 
-    type ReadvFuture<'a, T> = A::ReadvFuture<'a, T>
-    where
-        Self: 'a,
-        T: IoVecBufMut + 'a;
+    #[inline]
+    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).readv(buf)
+    }
+}
 
+impl<A: ?Sized + AsyncReadRent> AsyncReadRent for Box<A> {
     #[inline]
-    fn read<T: IoBufMut>(&mut self, buf: T) -> Self::ReadFuture<'_, T> {
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
         (**self).read(buf)
     }
 
     #[inline]
-    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> Self::ReadvFuture<'_, T> {
+    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
         (**self).readv(buf)
     }
 }
 
 impl AsyncReadRent for &[u8] {
-    type ReadFuture<'a, B> = impl std::future::Future<Output = crate::BufResult<usize, B>> where
-        B: IoBufMut + 'a, Self: 'a;
-    type ReadvFuture<'a, B> = impl std::future::Future<Output = crate::BufResult<usize, B>> where
-        B: IoVecBufMut + 'a, Self: 'a;
-
-    fn read<T: IoBufMut>(&mut self, mut buf: T) -> Self::ReadFuture<'_, T> {
+    fn read<T: IoBufMut>(&mut self, mut buf: T) -> impl Future<Output = BufResult<usize, T>> {
         let amt = std::cmp::min(self.len(), buf.bytes_total());
         let (a, b) = self.split_at(amt);
         unsafe {
@@ -75,7 +77,7 @@ impl AsyncReadRent for &[u8] {
         async move { (Ok(amt), buf) }
     }
 
-    fn readv<T: IoVecBufMut>(&mut self, mut buf: T) -> Self::ReadvFuture<'_, T> {
+    fn readv<T: IoVecBufMut>(&mut self, mut buf: T) -> impl Future<Output = BufResult<usize, T>> {
         // # Safety
         // We do it in pure sync way.
         let n = match unsafe { RawBuf::new_from_iovec_mut(&mut buf) } {
@@ -98,3 +100,43 @@ impl AsyncReadRent for &[u8] {
         async move { (Ok(n), buf) }
     }
 }
+
+impl<A: ?Sized + CancelableAsyncReadRent> CancelableAsyncReadRent for &mut A {
+    #[inline]
+    fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).cancelable_read(buf, c)
+    }
+
+    #[inline]
+    fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).cancelable_readv(buf, c)
+    }
+}
+
+impl<A: ?Sized + CancelableAsyncReadRent> CancelableAsyncReadRent for Box<A> {
+    #[inline]
+    fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).cancelable_read(buf, c)
+    }
+
+    #[inline]
+    fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).cancelable_readv(buf, c)
+    }
+}
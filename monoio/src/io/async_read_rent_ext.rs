@@ -20,7 +20,10 @@ macro_rules! reader_trait {
 
 macro_rules! reader_be_impl {
     ($future: ident, $n_ty: ty, $f: ident) => {
-        type $future<'a> = impl Future<Output = std::io::Result<$n_ty>> + 'a where A: 'a;
+        type $future<'a>
+            = impl Future<Output = std::io::Result<$n_ty>> + 'a
+        where
+            A: 'a;
 
         fn $f(&mut self) -> Self::$future<'_> {
             async {
@@ -36,7 +39,10 @@ macro_rules! reader_be_impl {
 
 macro_rules! reader_le_impl {
     ($future: ident, $n_ty: ty, $f: ident) => {
-        type $future<'a> = impl Future<Output = std::io::Result<$n_ty>> + 'a where A: 'a;
+        type $future<'a>
+            = impl Future<Output = std::io::Result<$n_ty>> + 'a
+        where
+            A: 'a;
 
         fn $f(&mut self) -> Self::$future<'_> {
             async {
@@ -59,7 +65,7 @@ pub trait AsyncReadRentExt {
         T: IoBufMut + 'a;
 
     /// Read until buf capacity is fulfilled
-    fn read_exact<T: 'static>(&mut self, buf: T) -> Self::ReadExactFuture<'_, T>
+    fn read_exact<T>(&mut self, buf: T) -> Self::ReadExactFuture<'_, T>
     where
         T: 'static + IoBufMut;
 
@@ -70,7 +76,7 @@ pub trait AsyncReadRentExt {
         T: IoVecBufMut + 'a;
 
     /// Readv until buf capacity is fulfilled
-    fn read_vectored_exact<T: 'static>(&mut self, buf: T) -> Self::ReadVectoredExactFuture<'_, T>
+    fn read_vectored_exact<T>(&mut self, buf: T) -> Self::ReadVectoredExactFuture<'_, T>
     where
         T: 'static + IoVecBufMut;
 
@@ -105,7 +111,11 @@ impl<A> AsyncReadRentExt for A
 where
     A: AsyncReadRent + ?Sized,
 {
-    type ReadExactFuture<'a, T> = impl Future<Output = BufResult<usize, T>> + 'a where A: 'a, T: IoBufMut + 'a;
+    type ReadExactFuture<'a, T>
+        = impl Future<Output = BufResult<usize, T>> + 'a
+    where
+        A: 'a,
+        T: IoBufMut + 'a;
 
     fn read_exact<T>(&mut self, mut buf: T) -> Self::ReadExactFuture<'_, T>
     where
@@ -140,12 +150,13 @@ where
         }
     }
 
-    type ReadVectoredExactFuture<'a, T> = impl Future<Output = BufResult<usize, T>> + 'a where A: 'a, T: IoVecBufMut + 'a;
+    type ReadVectoredExactFuture<'a, T>
+        = impl Future<Output = BufResult<usize, T>> + 'a
+    where
+        A: 'a,
+        T: IoVecBufMut + 'a;
 
-    fn read_vectored_exact<T: 'static>(
-        &mut self,
-        mut buf: T,
-    ) -> Self::ReadVectoredExactFuture<'_, T>
+    fn read_vectored_exact<T>(&mut self, mut buf: T) -> Self::ReadVectoredExactFuture<'_, T>
     where
         T: 'static + IoVecBufMut,
     {
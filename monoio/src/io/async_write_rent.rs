@@ -1,94 +1,130 @@
 use std::future::Future;
 
+use super::CancelHandle;
 use crate::{
-    buf::{IoBuf, IoBufMut, IoVecBuf},
+    buf::{IoBuf, IoVecBuf},
     BufResult,
 };
 
 /// AsyncWriteRent: async write with a ownership of a buffer
+// Futures of io types are local to the thread, so no `Send` bound is needed.
+#[allow(async_fn_in_trait)]
 pub trait AsyncWriteRent {
-    /// The future of write Result<size, buffer>
-    type WriteFuture<'a, T>: Future<Output = BufResult<usize, T>>
-    where
-        Self: 'a,
-        T: IoBuf + 'a;
-    /// The future of writev Result<size, buffer>
-    type WritevFuture<'a, T>: Future<Output = BufResult<usize, T>>
-    where
-        Self: 'a,
-        T: IoVecBuf + 'a;
-
-    /// The future of flush
-    type FlushFuture<'a>: Future<Output = std::io::Result<()>>
-    where
-        Self: 'a;
-
-    /// The future of shutdown
-    type ShutdownFuture<'a>: Future<Output = std::io::Result<()>>
-    where
-        Self: 'a;
-
     /// Same as write(2)
-    fn write<T: IoBuf>(&mut self, buf: T) -> Self::WriteFuture<'_, T>;
+    async fn write<T: IoBuf>(&mut self, buf: T) -> BufResult<usize, T>;
 
     /// Same as writev(2)
-    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> Self::WritevFuture<'_, T>;
+    async fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> BufResult<usize, T>;
 
     /// Flush buffered data if needed
-    fn flush(&mut self) -> Self::FlushFuture<'_>;
+    async fn flush(&mut self) -> std::io::Result<()>;
 
     /// Same as shutdown
-    fn shutdown(&mut self) -> Self::ShutdownFuture<'_>;
+    async fn shutdown(&mut self) -> std::io::Result<()>;
 }
 
 /// AsyncWriteRentAt: async write with a ownership of a buffer and a position
+#[allow(async_fn_in_trait)]
 pub trait AsyncWriteRentAt {
-    /// The future of Result<size, buffer>
-    type Future<'a, T>: Future<Output = BufResult<usize, T>>
-    where
-        Self: 'a,
-        T: 'a;
-
     /// Write buf at given offset
-    fn write_at<T: IoBufMut>(&self, buf: T, pos: usize) -> Self::Future<'_, T>;
+    async fn write_at<T: IoBuf>(&self, buf: T, pos: usize) -> BufResult<usize, T>;
+}
+
+/// CancelableAsyncWriteRent: async write which can be canceled by the
+/// [`Canceller`](super::Canceller) of the given handle
+#[allow(async_fn_in_trait)]
+pub trait CancelableAsyncWriteRent: AsyncWriteRent {
+    /// Same as write(2), fails with `ECANCELED` if canceled
+    async fn cancelable_write<T: IoBuf>(&mut self, buf: T, c: CancelHandle) -> BufResult<usize, T>;
+
+    /// Same as writev(2), fails with `ECANCELED` if canceled
+    async fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> BufResult<usize, T>;
 }
 
 impl<A: ?Sized + AsyncWriteRent> AsyncWriteRent for &mut A {
-    type WriteFuture<'a, T> = A::WriteFuture<'a, T>
-    where
-        Self: 'a,
-        T: IoBuf + 'a;
+    #[inline]
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).write(buf)
+    }
 
-    type WritevFuture<'a, T> = A::WritevFuture<'a, T>
-    where
-        Self: 'a,
-        T: IoVecBuf + 'a;
+    #[inline]
+    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).writev(buf_vec)
+    }
 
-    type FlushFuture<'a> = A::FlushFuture<'a>
-    where
-        Self: 'a;
+    #[inline]
+    fn flush(&mut self) -> impl Future<Output = std::io::Result<()>> {
+        (**self).flush()
+    }
 
-    type ShutdownFuture<'a> = A::ShutdownFuture<'a>
-    where
-        Self: 'a;
+    #[inline]
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
+        (**self).shutdown()
+    }
+}
 
+impl<A: ?Sized + AsyncWriteRent> AsyncWriteRent for Box<A> {
     #[inline]
-    fn write<T: IoBuf>(&mut self, buf: T) -> Self::WriteFuture<'_, T> {
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
         (**self).write(buf)
     }
 
     #[inline]
-    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> Self::WritevFuture<'_, T> {
+    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> impl Future<Output = BufResult<usize, T>> {
         (**self).writev(buf_vec)
     }
 
     #[inline]
-    fn flush(&mut self) -> Self::FlushFuture<'_> {
+    fn flush(&mut self) -> impl Future<Output = std::io::Result<()>> {
         (**self).flush()
     }
 
     #[inline]
-    fn shutdown(&mut self) -> Self::ShutdownFuture<'_> {
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
         (**self).shutdown()
     }
 }
+
+impl<A: ?Sized + CancelableAsyncWriteRent> CancelableAsyncWriteRent for &mut A {
+    #[inline]
+    fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).cancelable_write(buf, c)
+    }
+
+    #[inline]
+    fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).cancelable_writev(buf_vec, c)
+    }
+}
+
+impl<A: ?Sized + CancelableAsyncWriteRent> CancelableAsyncWriteRent for Box<A> {
+    #[inline]
+    fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).cancelable_write(buf, c)
+    }
+
+    #[inline]
+    fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = BufResult<usize, T>> {
+        (**self).cancelable_writev(buf_vec, c)
+    }
+}
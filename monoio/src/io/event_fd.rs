@@ -0,0 +1,113 @@
+use std::{
+    io,
+    os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    sync::Arc,
+};
+
+use crate::driver::{op::Op, shared_fd::SharedFd};
+
+/// An eventfd counter, to signal a runtime from other threads.
+///
+/// [`read`](Self::read) waits until the counter is not zero, then returns it
+/// and resets it to zero. A write adds to the counter, either with
+/// [`write`](Self::write) inside the runtime, or with an [`EventFdWriter`]
+/// from any thread.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::io::EventFd;
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let event = EventFd::new(0)?;
+///     let writer = event.writer();
+///     std::thread::spawn(move || writer.write(1).unwrap());
+///     assert_eq!(event.read().await?, 1);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EventFd {
+    fd: SharedFd,
+    writer: EventFdWriter,
+}
+
+/// A handle to add to the counter of an [`EventFd`] from any thread.
+#[derive(Clone, Debug)]
+pub struct EventFdWriter {
+    fd: Arc<OwnedFd>,
+}
+
+impl EventFd {
+    /// Create an eventfd with the counter set to `initval`. Must be called
+    /// inside a monoio runtime.
+    pub fn new(initval: u32) -> io::Result<Self> {
+        let flags = if crate::driver::op::non_blocking() {
+            libc::EFD_CLOEXEC | libc::EFD_NONBLOCK
+        } else {
+            libc::EFD_CLOEXEC
+        };
+        let fd = crate::syscall!(eventfd(initval, flags))?;
+        // Safety: the fd is just created and owned by nothing else.
+        let owned = unsafe { OwnedFd::from_raw_fd(fd) };
+        // The writer holds a duplicate, so it outlives the runtime side.
+        let fd = crate::syscall!(fcntl(fd, libc::F_DUPFD_CLOEXEC, 0))?;
+        let fd = match SharedFd::new(fd) {
+            Ok(fd) => fd,
+            Err(e) => {
+                let _ = crate::syscall!(close(fd));
+                return Err(e);
+            }
+        };
+        Ok(Self {
+            fd,
+            writer: EventFdWriter {
+                fd: Arc::new(owned),
+            },
+        })
+    }
+
+    /// Wait until the counter is not zero, then return it and reset it to
+    /// zero.
+    pub async fn read(&self) -> io::Result<u64> {
+        let (res, buf) = Op::read_stream(&self.fd, Vec::with_capacity(8))?
+            .read()
+            .await;
+        res?;
+        Ok(u64::from_ne_bytes(buf[..8].try_into().unwrap()))
+    }
+
+    /// Add `val` to the counter.
+    pub async fn write(&self, val: u64) -> io::Result<()> {
+        let (res, _) = Op::write_stream(&self.fd, val.to_ne_bytes().to_vec())?
+            .write()
+            .await;
+        res.map(|_| ())
+    }
+
+    /// Returns a handle to write to the counter from other threads.
+    pub fn writer(&self) -> EventFdWriter {
+        self.writer.clone()
+    }
+}
+
+impl EventFdWriter {
+    /// Add `val` to the counter, waking the runtime waiting in
+    /// [`EventFd::read`].
+    ///
+    /// The counter can not exceed `u64::MAX - 1`. A write past it fails with
+    /// [`io::ErrorKind::WouldBlock`] on the legacy driver, and blocks until
+    /// the counter is read on the io_uring driver.
+    pub fn write(&self, val: u64) -> io::Result<()> {
+        let buf = val.to_ne_bytes();
+        crate::syscall!(write(self.fd.as_raw_fd(), buf.as_ptr().cast(), buf.len())).map(|_| ())
+    }
+}
+
+impl AsRawFd for EventFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
@@ -1,10 +1,15 @@
 //! IO traits
 
 mod async_buf_read;
+mod async_buf_read_ext;
+#[cfg(unix)]
+mod async_fd;
 mod async_read_rent;
 mod async_read_rent_ext;
 mod async_write_rent;
 mod async_write_rent_ext;
+#[cfg(target_os = "linux")]
+mod event_fd;
 
 pub mod sink;
 pub mod stream;
@@ -14,15 +19,23 @@ pub mod as_fd;
 pub mod splice;
 
 pub use async_buf_read::AsyncBufRead;
-pub use async_read_rent::{AsyncReadRent, AsyncReadRentAt};
+pub use async_buf_read_ext::{AsyncBufReadExt, Lines};
+#[cfg(unix)]
+pub use async_fd::AsyncFd;
+pub use async_read_rent::{AsyncReadRent, AsyncReadRentAt, CancelableAsyncReadRent};
 pub use async_read_rent_ext::AsyncReadRentExt;
-pub use async_write_rent::{AsyncWriteRent, AsyncWriteRentAt};
+pub use async_write_rent::{AsyncWriteRent, AsyncWriteRentAt, CancelableAsyncWriteRent};
 pub use async_write_rent_ext::AsyncWriteRentExt;
+#[cfg(target_os = "linux")]
+pub use event_fd::{EventFd, EventFdWriter};
 
 mod util;
 #[cfg(all(target_os = "linux", feature = "splice"))]
 pub use util::zero_copy;
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) use util::AssociateGuard;
 pub use util::{
-    copy, BufReader, BufWriter, OwnedReadHalf, OwnedWriteHalf, PrefixedReadIo, ReadHalf, Split,
+    copy, zero_copy_bidirectional, zero_copy_bidirectional_with_idle_timeout, BufReader, BufWriter,
+    CancelHandle, Canceller, OwnedReadHalf, OwnedWriteHalf, PrefixedReadIo, ReadHalf, Split,
     Splitable, WriteHalf,
 };
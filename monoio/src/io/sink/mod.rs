@@ -37,15 +37,18 @@ pub trait Sink<Item> {
 impl<T, S: ?Sized + Sink<T>> Sink<T> for &mut S {
     type Error = S::Error;
 
-    type SendFuture<'a> = S::SendFuture<'a>
+    type SendFuture<'a>
+        = S::SendFuture<'a>
     where
         Self: 'a;
 
-    type FlushFuture<'a> = S::FlushFuture<'a>
+    type FlushFuture<'a>
+        = S::FlushFuture<'a>
     where
         Self: 'a;
 
-    type CloseFuture<'a> = S::CloseFuture<'a>
+    type CloseFuture<'a>
+        = S::CloseFuture<'a>
     where
         Self: 'a;
 
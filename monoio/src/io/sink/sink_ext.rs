@@ -1,6 +1,8 @@
 use super::Sink;
+use crate::io::stream::Stream;
 
 /// Sink extensions.
+#[allow(async_fn_in_trait)]
 pub trait SinkExt<T>: Sink<T> {
     /// SendFlushFuture.
     type SendFlushFuture<'a>: std::future::Future<Output = Result<(), Self::Error>> + 'a
@@ -9,6 +11,17 @@ pub trait SinkExt<T>: Sink<T> {
 
     /// Send and flush.
     fn send_and_flush(&mut self, item: T) -> Self::SendFlushFuture<'_>;
+
+    /// Send all items of the stream, then flush.
+    async fn send_all<St>(&mut self, mut stream: St) -> Result<(), Self::Error>
+    where
+        St: Stream<Item = T>,
+    {
+        while let Some(item) = stream.next().await {
+            self.send(item).await?;
+        }
+        self.flush().await
+    }
 }
 
 impl<T, A> SinkExt<T> for A
@@ -16,7 +29,9 @@ where
     A: Sink<T>,
     T: 'static,
 {
-    type SendFlushFuture<'a> = impl std::future::Future<Output = Result<(), Self::Error>> + 'a where
+    type SendFlushFuture<'a>
+        = impl std::future::Future<Output = Result<(), Self::Error>> + 'a
+    where
         A: 'a;
 
     fn send_and_flush(&mut self, item: T) -> Self::SendFlushFuture<'_> {
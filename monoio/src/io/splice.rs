@@ -1,9 +1,18 @@
 //! Splice related trait and default impl.
 
-use std::future::Future;
+use std::{cell::RefCell, future::Future, io, os::unix::io::RawFd};
 
 use super::as_fd::{AsReadFd, AsWriteFd};
-use crate::{driver::op::Op, net::Pipe};
+use crate::{
+    buf::IoBuf,
+    driver::{op::Op, shared_fd::SharedFd},
+    net::{unix::new_pipe, Pipe},
+};
+
+// Default capacity of a linux pipe.
+pub(crate) const PIPE_SIZE: u32 = 64 * 1024;
+// Idle pipes kept per thread, for each kind of driver.
+const POOL_SIZE: usize = 16;
 
 /// Splice data from self to pipe.
 pub trait SpliceSource {
@@ -12,7 +21,7 @@ pub trait SpliceSource {
     where
         Self: 'a;
     /// Splice data from self to pipe.
-    fn splice_to_pipe<'a>(&'a mut self, pipe: &'a mut Pipe, len: u32) -> Self::SpliceFuture<'_>;
+    fn splice_to_pipe<'a>(&'a mut self, pipe: &'a mut Pipe, len: u32) -> Self::SpliceFuture<'a>;
 }
 
 /// Splice data from self from pipe.
@@ -22,31 +31,282 @@ pub trait SpliceDestination {
     where
         Self: 'a;
     /// Splice data from self from pipe.
-    fn splice_from_pipe<'a>(&'a mut self, pipe: &'a mut Pipe, len: u32) -> Self::SpliceFuture<'_>;
+    fn splice_from_pipe<'a>(&'a mut self, pipe: &'a mut Pipe, len: u32) -> Self::SpliceFuture<'a>;
 }
 
 impl<T: AsReadFd> SpliceSource for T {
-    type SpliceFuture<'a> = impl Future<Output = std::io::Result<u32>> + 'a where Self: 'a;
+    type SpliceFuture<'a>
+        = impl Future<Output = std::io::Result<u32>> + 'a
+    where
+        Self: 'a;
 
     #[inline]
-    fn splice_to_pipe<'a>(&'a mut self, pipe: &'a mut Pipe, len: u32) -> Self::SpliceFuture<'_> {
-        async move {
-            Op::splice_to_pipe(self.as_reader_fd().as_ref(), &pipe.fd, len)?
-                .splice()
-                .await
-        }
+    fn splice_to_pipe<'a>(&'a mut self, pipe: &'a mut Pipe, len: u32) -> Self::SpliceFuture<'a> {
+        async move { splice_ready(self.as_reader_fd().as_ref(), &pipe.fd, len, true).await }
     }
 }
 
 impl<T: AsWriteFd> SpliceDestination for T {
-    type SpliceFuture<'a> = impl Future<Output = std::io::Result<u32>> + 'a where Self: 'a;
+    type SpliceFuture<'a>
+        = impl Future<Output = std::io::Result<u32>> + 'a
+    where
+        Self: 'a;
 
     #[inline]
-    fn splice_from_pipe<'a>(&'a mut self, pipe: &'a mut Pipe, len: u32) -> Self::SpliceFuture<'_> {
-        async move {
-            Op::splice_from_pipe(&pipe.fd, self.as_writer_fd().as_ref(), len)?
-                .splice()
-                .await
+    fn splice_from_pipe<'a>(&'a mut self, pipe: &'a mut Pipe, len: u32) -> Self::SpliceFuture<'a> {
+        async move { splice_ready(&pipe.fd, self.as_writer_fd().as_ref(), len, false).await }
+    }
+}
+
+// Sockets created non-blocking, like the ones of `UnixStream::pair`, make the
+// uring splice fail with EAGAIN instead of waiting for them, so wait for the
+// socket to be ready and try again.
+async fn splice_ready(
+    fd_in: &SharedFd,
+    fd_out: &SharedFd,
+    len: u32,
+    to_pipe: bool,
+) -> io::Result<u32> {
+    loop {
+        let op = if to_pipe {
+            Op::splice_to_pipe(fd_in, fd_out, len)?
+        } else {
+            Op::splice_from_pipe(fd_in, fd_out, len)?
+        };
+        match op.splice().await {
+            Err(e)
+                if e.kind() == io::ErrorKind::WouldBlock && !crate::driver::op::non_blocking() =>
+            {
+                let ready = if to_pipe {
+                    Op::poll_read(fd_in)?
+                } else {
+                    Op::poll_write(fd_out)?
+                };
+                ready.await.meta.result?;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Move up to `len` bytes from `src` to `dst` without copying them to user
+/// space, and return the number of bytes moved. It is less than `len` only
+/// if `src` reaches eof.
+///
+/// Any fds supporting splice work, e.g. a file to a socket, a socket to a
+/// file, or pipes. The data goes through an intermediate pipe, which is
+/// taken from a pool of the thread and given back once drained, so a proxy
+/// does not create pipes for every connection.
+///
+/// Bytes `src` already received from its fd, like the ones held by the read
+/// buffer of a [`TcpStream`](crate::net::TcpStream), are moved first.
+///
+/// Files are read and written at their file offset. It starts at 0 (at the
+/// end with `append`) and is not moved by the positional ops of
+/// [`File`](crate::fs::File), so it is only moved by splicing.
+///
+/// With the legacy driver, pipes passed as `src` or `dst` are not
+/// registered for readiness, so they fail with
+/// [`io::ErrorKind::WouldBlock`] if they are not ready.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{fs::File, io::splice::splice, net::TcpStream};
+///
+/// async fn send_file(stream: &mut TcpStream) -> std::io::Result<u64> {
+///     let mut file = File::open("index.html").await?;
+///     let len = file.metadata().await?.len();
+///     splice(&mut file, stream, len).await
+/// }
+/// ```
+pub async fn splice<SRC: AsReadFd, DST: AsWriteFd>(
+    src: &mut SRC,
+    dst: &mut DST,
+    len: u64,
+) -> io::Result<u64> {
+    let mut pipe = PooledPipe::take()?;
+    let mut moved = forward_received(src, dst, &mut pipe, len).await?;
+    while moved < len {
+        let chunk = (len - moved).min(PIPE_SIZE as u64) as u32;
+        let mut to_write = src.splice_to_pipe(&mut pipe.write, chunk).await?;
+        if to_write == 0 {
+            break;
+        }
+        while to_write > 0 {
+            let written = dst.splice_from_pipe(&mut pipe.read, to_write).await?;
+            to_write -= written;
+            moved += written as u64;
+        }
+    }
+    pipe.give_back();
+    Ok(moved)
+}
+
+/// Move up to `len` bytes `src` already received from its fd to `dst` through
+/// the empty `pipe`, so the data spliced from the fd afterwards stays in
+/// order. Returns the number of bytes moved, and leaves the pipe empty.
+pub(crate) async fn forward_received<SRC: AsReadFd, DST: AsWriteFd>(
+    src: &mut SRC,
+    dst: &mut DST,
+    pipe: &mut PooledPipe,
+    len: u64,
+) -> io::Result<u64> {
+    let mut moved = 0;
+    while moved < len {
+        let chunk = (len - moved).min(PIPE_SIZE as u64) as usize;
+        let mut buf = match std::future::poll_fn(|cx| src.poll_take_received(cx, chunk)).await {
+            Some(buf) => buf?,
+            None => break,
+        };
+        let mut offset = 0;
+        while offset < buf.len() {
+            let (res, slice) = Op::write_stream(&pipe.write.fd, buf.slice(offset..))?
+                .write()
+                .await;
+            buf = slice.into_inner();
+            let mut to_write = res? as u32;
+            offset += to_write as usize;
+            while to_write > 0 {
+                let written = dst.splice_from_pipe(&mut pipe.read, to_write).await?;
+                to_write -= written;
+                moved += written as u64;
+            }
+        }
+    }
+    Ok(moved)
+}
+
+/// Duplicate up to `len` bytes of data in the pipe `src` to the pipe `dst`,
+/// without consuming them from `src` or copying them to user space. Returns
+/// the number of bytes duplicated, 0 if `src` is empty and its write end is
+/// closed. As `src` is not consumed, calling it again duplicates the same
+/// data.
+///
+/// Together with [`splice`], it mirrors traffic: splice from a socket into
+/// a pipe, tee the pipe into a second one, then splice each pipe to its
+/// destination.
+///
+/// With the legacy driver, pipes are not registered for readiness, so it
+/// fails with [`io::ErrorKind::WouldBlock`] if `src` is empty or `dst` is
+/// full.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{
+///     io::splice::{tee, SpliceDestination, SpliceSource},
+///     net::{unix::new_pipe, Pipe, TcpStream},
+/// };
+///
+/// async fn drain(pipe: &mut Pipe, to: &mut TcpStream, mut len: u32) -> std::io::Result<()> {
+///     while len > 0 {
+///         len -= to.splice_from_pipe(pipe, len).await?;
+///     }
+///     Ok(())
+/// }
+///
+/// async fn mirror(
+///     client: &mut TcpStream,
+///     upstream: &mut TcpStream,
+///     mirror: &mut TcpStream,
+/// ) -> std::io::Result<()> {
+///     let (mut pr, mut pw) = new_pipe()?;
+///     let (mut mirror_pr, mut mirror_pw) = new_pipe()?;
+///     let n = client.splice_to_pipe(&mut pw, 64 * 1024).await?;
+///     // The mirror pipe is empty, so it takes all the data.
+///     let mirrored = tee(&mut pr, &mut mirror_pw, n).await?;
+///     drain(&mut pr, upstream, n).await?;
+///     drain(&mut mirror_pr, mirror, mirrored).await
+/// }
+/// ```
+pub async fn tee(src: &mut Pipe, dst: &mut Pipe, len: u32) -> io::Result<u32> {
+    Op::tee(&src.fd, &dst.fd, len)?.result().await
+}
+
+thread_local! {
+    static PIPES: RefCell<PipePool> = RefCell::new(PipePool::default());
+}
+
+// Raw fds of idle pipes, so they do not need the driver to be closed.
+#[derive(Default)]
+struct PipePool {
+    // Pipes created for the legacy driver are non-blocking.
+    blocking: Vec<(RawFd, RawFd)>,
+    non_blocking: Vec<(RawFd, RawFd)>,
+}
+
+impl PipePool {
+    fn pipes(&mut self, non_blocking: bool) -> &mut Vec<(RawFd, RawFd)> {
+        if non_blocking {
+            &mut self.non_blocking
+        } else {
+            &mut self.blocking
+        }
+    }
+}
+
+impl Drop for PipePool {
+    fn drop(&mut self) {
+        for (read, write) in self.blocking.drain(..).chain(self.non_blocking.drain(..)) {
+            unsafe {
+                libc::close(read);
+                libc::close(write);
+            }
+        }
+    }
+}
+
+/// A pipe from the pool of the thread. It is only given back once empty;
+/// dropping it closes it, e.g. if an error left data in it.
+pub(crate) struct PooledPipe {
+    pub(crate) read: Pipe,
+    pub(crate) write: Pipe,
+    non_blocking: bool,
+}
+
+impl PooledPipe {
+    pub(crate) fn take() -> io::Result<Self> {
+        let non_blocking = crate::driver::op::non_blocking();
+        let pooled = PIPES.with(|pool| pool.borrow_mut().pipes(non_blocking).pop());
+        let (read, write) = match pooled {
+            Some((read, write)) => (
+                Pipe::from_shared_fd(SharedFd::new_without_register(read)),
+                Pipe::from_shared_fd(SharedFd::new_without_register(write)),
+            ),
+            None => new_pipe()?,
+        };
+        Ok(Self {
+            read,
+            write,
+            non_blocking,
+        })
+    }
+
+    /// Give the pipe back to the pool, it must be empty.
+    pub(crate) fn give_back(self) {
+        let Ok(read) = self.read.fd.try_unwrap() else {
+            return;
+        };
+        let Ok(write) = self.write.fd.try_unwrap() else {
+            unsafe { libc::close(read) };
+            return;
+        };
+        let rejected = PIPES.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            let pipes = pool.pipes(self.non_blocking);
+            if pipes.len() < POOL_SIZE {
+                pipes.push((read, write));
+                false
+            } else {
+                true
+            }
+        });
+        if rejected {
+            unsafe {
+                libc::close(read);
+                libc::close(write);
+            }
         }
     }
 }
@@ -58,7 +58,8 @@ pub trait Stream {
 impl<S: ?Sized + Stream> Stream for &mut S {
     type Item = S::Item;
 
-    type NextFuture<'a> = S::NextFuture<'a>
+    type NextFuture<'a>
+        = S::NextFuture<'a>
     where
         Self: 'a;
 
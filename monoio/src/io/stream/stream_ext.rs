@@ -1,8 +1,15 @@
-use std::future::Future;
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 use super::{assert_stream, Stream};
+use crate::io::sink::Sink;
 
 /// Stream extensions.
+#[allow(async_fn_in_trait)]
 pub trait StreamExt: Stream {
     /// Maps a stream to a stream of its items.
     fn map<T, F>(self, f: F) -> Map<Self, F>
@@ -24,8 +31,59 @@ pub trait StreamExt: Stream {
         assert_stream::<Fut::Output, _>(Then::new(self, f))
     }
 
+    /// Filters the items of this stream with a predicate.
+    fn filter<F>(self, f: F) -> Filter<Self, F>
+    where
+        F: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Filter::new(self, f))
+    }
+
+    /// Runs up to `n` futures yielded by this stream at the same time, and
+    /// yields their outputs in the order of the futures.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    fn buffered(self, n: usize) -> Buffered<Self>
+    where
+        Self::Item: Future,
+        Self: Sized,
+    {
+        assert_stream::<<Self::Item as Future>::Output, _>(Buffered::new(self, n))
+    }
+
+    /// Groups the items of this stream into vectors of `n` items. The last
+    /// vector may be shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    fn chunks(self, n: usize) -> Chunks<Self>
+    where
+        Self: Sized,
+    {
+        assert_stream::<Vec<Self::Item>, _>(Chunks::new(self, n))
+    }
+
+    /// Sends all items of this stream to the sink, then closes the sink.
+    async fn forward<S>(mut self, mut sink: S) -> Result<(), S::Error>
+    where
+        S: Sink<Self::Item>,
+        Self: Sized,
+    {
+        while let Some(item) = self.next().await {
+            sink.send(item).await?;
+        }
+        sink.close().await
+    }
+
     /// Runs this stream to completion, executing the provided asynchronous
     /// closure for each element on the stream.
+    // The future is an opaque type, which can not be made public.
+    #[allow(private_interfaces)]
+    #[define_opaque(ForEachFut)]
     fn for_each<Fut, F>(mut self, mut f: F) -> ForEachFut<Self, Fut, F>
     where
         F: FnMut(Self::Item) -> Fut,
@@ -64,8 +122,11 @@ where
 {
     type Item = Item;
 
-    type NextFuture<'a> = impl Future<Output = Option<Self::Item>> + 'a where
-        F: 'a, St: 'a;
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        F: 'a,
+        St: 'a;
 
     fn next(&mut self) -> Self::NextFuture<'_> {
         async move { self.stream.next().await.map(&mut self.f) }
@@ -95,8 +156,11 @@ where
 {
     type Item = Fut::Output;
 
-    type NextFuture<'a> = impl Future<Output = Option<Self::Item>> + 'a where
-        F: 'a, St: 'a,;
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        F: 'a,
+        St: 'a;
 
     fn next(&mut self) -> Self::NextFuture<'_> {
         async move {
@@ -105,3 +169,174 @@ where
         }
     }
 }
+
+#[must_use = "streams do nothing unless polled"]
+pub struct Filter<St, F> {
+    stream: St,
+    f: F,
+}
+
+impl<St, F> Filter<St, F> {
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self { stream, f }
+    }
+}
+
+impl<St, F> Stream for Filter<St, F>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> bool,
+{
+    type Item = St::Item;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        F: 'a,
+        St: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move {
+            loop {
+                let item = self.stream.next().await?;
+                if (self.f)(&item) {
+                    return Some(item);
+                }
+            }
+        }
+    }
+}
+
+enum Slot<Fut: Future> {
+    Pending(Pin<Box<Fut>>),
+    Done(Fut::Output),
+}
+
+#[must_use = "streams do nothing unless polled"]
+pub struct Buffered<St>
+where
+    St: Stream,
+    St::Item: Future,
+{
+    stream: St,
+    // Set when the stream returned None.
+    done: bool,
+    queue: VecDeque<Slot<St::Item>>,
+    max: usize,
+}
+
+impl<St> Buffered<St>
+where
+    St: Stream,
+    St::Item: Future,
+{
+    pub(super) fn new(stream: St, max: usize) -> Self {
+        assert!(max > 0, "buffered requires at least 1 future");
+        Self {
+            stream,
+            done: false,
+            queue: VecDeque::with_capacity(max),
+            max,
+        }
+    }
+
+    // Poll all pending futures and return the first output if it is ready.
+    fn poll_front(&mut self, cx: &mut Context<'_>) -> Poll<Option<<St::Item as Future>::Output>> {
+        for slot in self.queue.iter_mut() {
+            if let Slot::Pending(fut) = slot {
+                if let Poll::Ready(output) = fut.as_mut().poll(cx) {
+                    *slot = Slot::Done(output);
+                }
+            }
+        }
+        match self.queue.front() {
+            None => Poll::Ready(None),
+            Some(Slot::Pending(_)) => Poll::Pending,
+            Some(Slot::Done(_)) => match self.queue.pop_front() {
+                Some(Slot::Done(output)) => Poll::Ready(Some(output)),
+                _ => unreachable!(),
+            },
+        }
+    }
+}
+
+impl<St> Stream for Buffered<St>
+where
+    St: Stream,
+    St::Item: Future,
+{
+    type Item = <St::Item as Future>::Output;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        St: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move {
+            while !self.done && self.queue.len() < self.max {
+                match self.stream.next().await {
+                    Some(fut) => self.queue.push_back(Slot::Pending(Box::pin(fut))),
+                    None => self.done = true,
+                }
+            }
+            std::future::poll_fn(|cx| self.poll_front(cx)).await
+        }
+    }
+}
+
+#[must_use = "streams do nothing unless polled"]
+pub struct Chunks<St: Stream> {
+    stream: St,
+    // Set when the stream returned None.
+    done: bool,
+    items: Vec<St::Item>,
+    cap: usize,
+}
+
+impl<St: Stream> Chunks<St> {
+    pub(super) fn new(stream: St, cap: usize) -> Self {
+        assert!(cap > 0, "chunks requires at least 1 item");
+        Self {
+            stream,
+            done: false,
+            items: Vec::with_capacity(cap),
+            cap,
+        }
+    }
+
+    fn take(&mut self) -> Vec<St::Item> {
+        std::mem::replace(&mut self.items, Vec::with_capacity(self.cap))
+    }
+}
+
+impl<St: Stream> Stream for Chunks<St> {
+    type Item = Vec<St::Item>;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        St: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move {
+            // Items are kept in self, so nothing is lost if this is canceled.
+            while !self.done {
+                match self.stream.next().await {
+                    Some(item) => {
+                        self.items.push(item);
+                        if self.items.len() >= self.cap {
+                            return Some(self.take());
+                        }
+                    }
+                    None => self.done = true,
+                }
+            }
+            if self.items.is_empty() {
+                None
+            } else {
+                Some(self.take())
+            }
+        }
+    }
+}
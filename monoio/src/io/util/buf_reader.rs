@@ -70,58 +70,51 @@ impl<R> BufReader<R> {
 }
 
 impl<R: AsyncReadRent> AsyncReadRent for BufReader<R> {
-    type ReadFuture<'a, T>  = impl Future<Output = crate::BufResult<usize, T>> + 'a where
-        T: IoBufMut + 'a, R: 'a;
-
-    type ReadvFuture<'a, T> = impl Future<Output = crate::BufResult<usize, T>> + 'a where
-        T: IoVecBufMut + 'a, R: 'a;
-
-    fn read<T: IoBufMut>(&mut self, mut buf: T) -> Self::ReadFuture<'_, T> {
-        async move {
-            // If we don't have any buffered data and we're doing a massive read
-            // (larger than our internal buffer), bypass our internal buffer
-            // entirely.
-            let owned_buf = self.buf.as_ref().unwrap();
-            if self.pos == self.cap && buf.bytes_total() >= owned_buf.len() {
-                self.discard_buffer();
-                return self.inner.read(buf).await;
-            }
+    async fn read<T: IoBufMut>(&mut self, mut buf: T) -> crate::BufResult<usize, T> {
+        // If we don't have any buffered data and we're doing a massive read
+        // (larger than our internal buffer), bypass our internal buffer
+        // entirely.
+        let owned_buf = self.buf.as_ref().unwrap();
+        if self.pos == self.cap && buf.bytes_total() >= owned_buf.len() {
+            self.discard_buffer();
+            return self.inner.read(buf).await;
+        }
 
-            let rem = match self.fill_buf().await {
-                Ok(slice) => slice,
-                Err(e) => {
-                    return (Err(e), buf);
-                }
-            };
-            let amt = std::cmp::min(rem.len(), buf.bytes_total());
-            unsafe {
-                buf.write_ptr().copy_from_nonoverlapping(rem.as_ptr(), amt);
-                buf.set_init(amt);
+        let rem = match self.fill_buf().await {
+            Ok(slice) => slice,
+            Err(e) => {
+                return (Err(e), buf);
             }
-            self.consume(amt);
-            (Ok(amt), buf)
+        };
+        let amt = std::cmp::min(rem.len(), buf.bytes_total());
+        unsafe {
+            buf.write_ptr().copy_from_nonoverlapping(rem.as_ptr(), amt);
+            buf.set_init(amt);
         }
+        self.consume(amt);
+        (Ok(amt), buf)
     }
 
-    fn readv<T: IoVecBufMut>(&mut self, mut buf: T) -> Self::ReadvFuture<'_, T> {
-        async move {
-            let slice = match IoVecWrapperMut::new(buf) {
-                Ok(slice) => slice,
-                Err(buf) => return (Ok(0), buf),
-            };
-
-            let (result, slice) = self.read(slice).await;
-            buf = slice.into_inner();
-            if let Ok(n) = result {
-                unsafe { buf.set_init(n) };
-            }
-            (result, buf)
+    async fn readv<T: IoVecBufMut>(&mut self, mut buf: T) -> crate::BufResult<usize, T> {
+        let slice = match IoVecWrapperMut::new(buf) {
+            Ok(slice) => slice,
+            Err(buf) => return (Ok(0), buf),
+        };
+
+        let (result, slice) = self.read(slice).await;
+        buf = slice.into_inner();
+        if let Ok(n) = result {
+            unsafe { buf.set_init(n) };
         }
+        (result, buf)
     }
 }
 
 impl<R: AsyncReadRent> AsyncBufRead for BufReader<R> {
-    type FillBufFuture<'a> = impl Future<Output = std::io::Result<&'a [u8]>> where Self: 'a;
+    type FillBufFuture<'a>
+        = impl Future<Output = std::io::Result<&'a [u8]>>
+    where
+        Self: 'a;
 
     fn fill_buf(&mut self) -> Self::FillBufFuture<'_> {
         async {
@@ -161,33 +154,26 @@ impl<R: AsyncReadRent> AsyncBufRead for BufReader<R> {
 }
 
 impl<R: AsyncReadRent + AsyncWriteRent> AsyncWriteRent for BufReader<R> {
-    type WriteFuture<'a, T> = R::WriteFuture<'a, T> where
-    T: IoBuf + 'a, R: 'a;
-
-    type WritevFuture<'a, T>= R::WritevFuture<'a, T> where
-    T: IoVecBuf + 'a, R: 'a;
-
-    type FlushFuture<'a> = R::FlushFuture<'a> where R: 'a;
-
-    type ShutdownFuture<'a> = R::ShutdownFuture<'a> where R: 'a;
-
     #[inline]
-    fn write<T: IoBuf>(&mut self, buf: T) -> Self::WriteFuture<'_, T> {
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = crate::BufResult<usize, T>> {
         self.inner.write(buf)
     }
 
     #[inline]
-    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> Self::WritevFuture<'_, T> {
+    fn writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         self.inner.writev(buf_vec)
     }
 
     #[inline]
-    fn flush(&mut self) -> Self::FlushFuture<'_> {
+    fn flush(&mut self) -> impl Future<Output = std::io::Result<()>> {
         self.inner.flush()
     }
 
     #[inline]
-    fn shutdown(&mut self) -> Self::ShutdownFuture<'_> {
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
         self.inner.shutdown()
     }
 }
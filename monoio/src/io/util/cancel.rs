@@ -0,0 +1,132 @@
+use std::{cell::RefCell, fmt, io, rc::Rc};
+
+use crate::driver::op::OpCanceller;
+
+/// Cancels io ops started with its [`CancelHandle`]s, e.g. to tear down a
+/// connection while another task is waiting to read from it.
+///
+/// On the io_uring driver pending ops are canceled with `AsyncCancel`, on the
+/// legacy driver the tasks waiting for readiness are woken up. A canceled op
+/// fails with the raw os error `ECANCELED`, and ops started after the cancel
+/// fail right away. An op which completes before its cancel gets to it keeps
+/// its result.
+///
+/// ```rust,no_run
+/// use monoio::{
+///     io::{CancelableAsyncReadRent, Canceller},
+///     net::TcpStream,
+/// };
+///
+/// async fn read_until_canceled(mut stream: TcpStream, canceller: &Canceller) {
+///     let handle = canceller.handle();
+///     let (res, _buf) = stream.cancelable_read(vec![0; 1024], handle).await;
+///     if let Err(e) = res {
+///         assert_eq!(e.raw_os_error(), Some(libc::ECANCELED));
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct Canceller {
+    shared: Rc<RefCell<Shared>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    canceled: bool,
+    ops: Vec<OpCanceller>,
+}
+
+impl Canceller {
+    /// Create a canceller.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a handle to pass to cancelable ops.
+    pub fn handle(&self) -> CancelHandle {
+        CancelHandle {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Cancel the pending ops of all handles. Once canceled, the canceller
+    /// stays canceled.
+    pub fn cancel(&self) {
+        let ops = {
+            let mut shared = self.shared.borrow_mut();
+            shared.canceled = true;
+            std::mem::take(&mut shared.ops)
+        };
+        for op in ops {
+            op.cancel();
+        }
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called.
+    pub fn is_canceled(&self) -> bool {
+        self.shared.borrow().canceled
+    }
+}
+
+impl fmt::Debug for Canceller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Canceller")
+            .field("canceled", &self.is_canceled())
+            .finish()
+    }
+}
+
+/// Handle of a [`Canceller`], passed to cancelable ops.
+#[derive(Clone)]
+pub struct CancelHandle {
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl CancelHandle {
+    /// Whether the canceller has been canceled.
+    pub fn is_canceled(&self) -> bool {
+        self.shared.borrow().canceled
+    }
+
+    /// Fail with `ECANCELED` if canceled, for ops to check before they start.
+    pub(crate) fn check(&self) -> io::Result<()> {
+        if self.is_canceled() {
+            return Err(io::Error::from_raw_os_error(libc::ECANCELED));
+        }
+        Ok(())
+    }
+
+    /// Let the canceller cancel the op until the returned guard is dropped.
+    /// The guard must not outlive the op, or the cancel may hit another op
+    /// reusing its slot.
+    pub(crate) fn associate_op(&self, op: OpCanceller) -> AssociateGuard {
+        self.shared.borrow_mut().ops.push(op);
+        AssociateGuard {
+            op,
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for CancelHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelHandle")
+            .field("canceled", &self.is_canceled())
+            .finish()
+    }
+}
+
+/// Keeps an op cancelable by a [`CancelHandle`].
+pub(crate) struct AssociateGuard {
+    op: OpCanceller,
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl Drop for AssociateGuard {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        if let Some(pos) = shared.ops.iter().position(|op| *op == self.op) {
+            shared.ops.swap_remove(pos);
+        }
+    }
+}
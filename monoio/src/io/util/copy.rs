@@ -1,13 +1,13 @@
 #![allow(unused)]
 
-use std::io;
+use std::{cell::Cell, future::Future, io, task::Poll, time::Duration};
 
 use crate::{
-    io::{AsyncReadRent, AsyncWriteRent, AsyncWriteRentExt},
-    net::{
-        tcp::{TcpReadHalf, TcpWriteHalf},
-        unix::new_pipe,
+    io::{
+        as_fd::{AsReadFd, AsWriteFd},
+        AsyncReadRent, AsyncWriteRent, AsyncWriteRentExt, Split, Splitable,
     },
+    time::Instant,
 };
 
 const BUF_SIZE: usize = 4 * 1024;
@@ -24,7 +24,7 @@ where
     'r: loop {
         let (read_res, mut buf_read) = reader.read(buf).await;
         match read_res {
-            Ok(n) if n == 0 => {
+            Ok(0) => {
                 // read closed
                 break;
             }
@@ -45,7 +45,7 @@ where
         'w: loop {
             let (write_res, buf_) = writer.write_all(buf_read).await;
             match write_res {
-                Ok(n) if n == 0 => {
+                Ok(0) => {
                     // write closed
                     return Err(io::Error::new(
                         io::ErrorKind::WriteZero,
@@ -74,29 +74,193 @@ where
     Ok(transfered)
 }
 
-/// Copy with splice.
+/// Copy with splice until `reader` reaches eof, see
+/// [`splice`](crate::io::splice::splice).
 #[cfg(all(target_os = "linux", feature = "splice"))]
 pub async fn zero_copy<SRC: crate::io::as_fd::AsReadFd, DST: crate::io::as_fd::AsWriteFd>(
     reader: &mut SRC,
     writer: &mut DST,
 ) -> io::Result<u64> {
-    use crate::{
-        driver::op::Op,
-        io::splice::{SpliceDestination, SpliceSource},
+    crate::io::splice::splice(reader, writer, u64::MAX).await
+}
+
+/// Copy data in both directions between two streams, like the client and the
+/// backend connections of a proxy. The streams may be of different types, e.g.
+/// a `TcpStream` and a `UnixStream`.
+///
+/// Data is moved through a pipe per direction with splice, so it is not copied
+/// to the user space. If splice is not available, because the kernel or the
+/// fds do not support it or the `splice` feature is disabled, a buffered copy
+/// is used instead.
+///
+/// When one stream reaches eof, the write side of the other one is shut down,
+/// while the other direction goes on until it reaches eof too. It returns the
+/// bytes copied from `a` to `b` and from `b` to `a` after both directions are
+/// finished.
+pub async fn zero_copy_bidirectional<A, B>(a: &mut A, b: &mut B) -> io::Result<(u64, u64)>
+where
+    A: Split + AsyncReadRent + AsyncWriteRent + AsReadFd + AsWriteFd,
+    B: Split + AsyncReadRent + AsyncWriteRent + AsReadFd + AsWriteFd,
+{
+    copy_bidirectional(a, b, None).await
+}
+
+/// Like [`zero_copy_bidirectional`], but fails with [`io::ErrorKind::TimedOut`]
+/// if no data is copied in either direction for `idle_timeout`.
+///
+/// The timer of the runtime must be enabled.
+pub async fn zero_copy_bidirectional_with_idle_timeout<A, B>(
+    a: &mut A,
+    b: &mut B,
+    idle_timeout: Duration,
+) -> io::Result<(u64, u64)>
+where
+    A: Split + AsyncReadRent + AsyncWriteRent + AsReadFd + AsWriteFd,
+    B: Split + AsyncReadRent + AsyncWriteRent + AsReadFd + AsWriteFd,
+{
+    copy_bidirectional(a, b, Some(idle_timeout)).await
+}
+
+async fn copy_bidirectional<A, B>(
+    a: &mut A,
+    b: &mut B,
+    idle_timeout: Option<Duration>,
+) -> io::Result<(u64, u64)>
+where
+    A: Split + AsyncReadRent + AsyncWriteRent + AsReadFd + AsWriteFd,
+    B: Split + AsyncReadRent + AsyncWriteRent + AsReadFd + AsWriteFd,
+{
+    let activity = Cell::new(Instant::now());
+    let (mut a_read, mut a_write) = a.split();
+    let (mut b_read, mut b_write) = b.split();
+
+    let mut transfer = std::pin::pin!(async {
+        crate::try_join!(
+            copy_one_way(&mut a_read, &mut b_write, &activity),
+            copy_one_way(&mut b_read, &mut a_write, &activity),
+        )
+    });
+    let mut watchdog = std::pin::pin!(idle_watchdog(&activity, idle_timeout));
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(res) = transfer.as_mut().poll(cx) {
+            return Poll::Ready(res);
+        }
+        watchdog.as_mut().poll(cx).map(Err)
+    })
+    .await
+}
+
+// Resolves to an error once no data is copied for `idle_timeout`.
+async fn idle_watchdog(activity: &Cell<Instant>, idle_timeout: Option<Duration>) -> io::Error {
+    let Some(idle_timeout) = idle_timeout else {
+        return std::future::pending().await;
     };
+    loop {
+        let deadline = activity.get() + idle_timeout;
+        if deadline <= Instant::now() {
+            return io::Error::new(io::ErrorKind::TimedOut, "copy idle timeout");
+        }
+        crate::time::sleep_until(deadline).await;
+    }
+}
 
-    let (mut pr, mut pw) = new_pipe()?;
+async fn copy_one_way<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    activity: &Cell<Instant>,
+) -> io::Result<u64>
+where
+    R: AsReadFd + AsyncReadRent,
+    W: AsWriteFd + AsyncWriteRent,
+{
     let mut transfered: u64 = 0;
+    #[cfg(all(target_os = "linux", feature = "splice"))]
+    let done = splice_one_way(reader, writer, activity, &mut transfered).await?;
+    #[cfg(not(all(target_os = "linux", feature = "splice")))]
+    let done = false;
+    if !done {
+        buffered_one_way(reader, writer, activity, &mut transfered).await?;
+    }
+    writer.shutdown().await?;
+    Ok(transfered)
+}
+
+// Returns false if splice is not supported. The pipe is always drained then,
+// so the caller can go on with a buffered copy.
+#[cfg(all(target_os = "linux", feature = "splice"))]
+async fn splice_one_way<R: AsReadFd, W: AsWriteFd>(
+    reader: &mut R,
+    writer: &mut W,
+    activity: &Cell<Instant>,
+    transfered: &mut u64,
+) -> io::Result<bool> {
+    use crate::io::splice::{
+        forward_received, PooledPipe, SpliceDestination, SpliceSource, PIPE_SIZE,
+    };
+
+    let Ok(mut pipe) = PooledPipe::take() else {
+        return Ok(false);
+    };
+    // Bytes the reader already received, e.g. in its read buffer, come first.
+    let forwarded = forward_received(reader, writer, &mut pipe, u64::MAX).await?;
+    if forwarded > 0 {
+        *transfered += forwarded;
+        activity.set(Instant::now());
+    }
     loop {
-        let mut to_write = reader.splice_to_pipe(&mut pw, BUF_SIZE as u32).await?;
-        if to_write == 0 {
-            break;
-        }
-        transfered += to_write as u64;
+        let mut to_write = match reader.splice_to_pipe(&mut pipe.write, PIPE_SIZE).await {
+            Ok(0) => {
+                pipe.give_back();
+                return Ok(true);
+            }
+            Ok(n) => n,
+            Err(e) if splice_unsupported(&e) => {
+                pipe.give_back();
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+        activity.set(Instant::now());
         while to_write > 0 {
-            let written = writer.splice_from_pipe(&mut pr, to_write).await?;
+            let written = writer.splice_from_pipe(&mut pipe.read, to_write).await?;
             to_write -= written;
+            *transfered += written as u64;
+            activity.set(Instant::now());
         }
     }
-    Ok(transfered)
+}
+
+#[cfg(all(target_os = "linux", feature = "splice"))]
+fn splice_unsupported(e: &io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::EINVAL | libc::ENOSYS | libc::EOPNOTSUPP)
+    )
+}
+
+async fn buffered_one_way<R: AsyncReadRent, W: AsyncWriteRent>(
+    reader: &mut R,
+    writer: &mut W,
+    activity: &Cell<Instant>,
+    transfered: &mut u64,
+) -> io::Result<()> {
+    let mut buf: Vec<u8> = Vec::with_capacity(BUF_SIZE);
+    loop {
+        let (read_res, buf_read) = reader.read(buf).await;
+        match read_res {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
+                buf = buf_read;
+                continue;
+            }
+            Err(e) => return Err(e),
+        }
+        activity.set(Instant::now());
+
+        let (write_res, buf_) = writer.write_all(buf_read).await;
+        *transfered += write_res? as u64;
+        activity.set(Instant::now());
+        buf = buf_;
+    }
 }
@@ -2,14 +2,18 @@
 
 mod buf_reader;
 mod buf_writer;
+mod cancel;
 mod copy;
 mod prefixed_io;
 mod split;
 
 pub use buf_reader::BufReader;
 pub use buf_writer::BufWriter;
-pub use copy::copy;
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) use cancel::AssociateGuard;
+pub use cancel::{CancelHandle, Canceller};
 #[cfg(all(target_os = "linux", feature = "splice"))]
 pub use copy::zero_copy;
+pub use copy::{copy, zero_copy_bidirectional, zero_copy_bidirectional_with_idle_timeout};
 pub use prefixed_io::PrefixedReadIo;
 pub use split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, Split, Splitable, WriteHalf};
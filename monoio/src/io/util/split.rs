@@ -5,17 +5,31 @@ use std::{
     future::Future,
     io,
     rc::Rc,
+    task::{Context, Poll},
 };
 
 use crate::{
-    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
-    io::{AsyncReadRent, AsyncWriteRent},
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut, ProvidedBuf},
+    io::{
+        as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
+        AsyncReadRent, AsyncWriteRent, CancelHandle, CancelableAsyncReadRent,
+        CancelableAsyncWriteRent,
+    },
 };
 
 /// Owned Read Half Part
 #[derive(Debug)]
 pub struct OwnedReadHalf<T>(pub Rc<UnsafeCell<T>>);
 /// Owned Write Half Part
+///
+/// Dropping it calls [`AsyncWriteRent::shutdown`] on the stream without
+/// awaiting the returned future. This only shuts down streams whose
+/// `shutdown` starts before the future is polled, like
+/// [`TcpStream`](crate::net::TcpStream) and
+/// [`UnixStream`](crate::net::UnixStream). For other streams, e.g. an `async
+/// fn shutdown` which flushes first like a TLS stream or a
+/// [`BufWriter`](crate::io::BufWriter), nothing happens, so the half should be
+/// shut down explicitly before it is dropped.
 #[derive(Debug)]
 pub struct OwnedWriteHalf<T>(pub Rc<UnsafeCell<T>>)
 where
@@ -61,84 +75,162 @@ pub trait Splitable {
     fn split(&mut self) -> (Self::Read<'_>, Self::Write<'_>);
 }
 
-#[allow(clippy::cast_ref_to_mut)]
+#[allow(invalid_reference_casting)]
 impl<'t, Inner> AsyncReadRent for ReadHalf<'t, Inner>
 where
     Inner: AsyncReadRent,
 {
-    type ReadFuture<'a, B> = impl Future<Output = crate::BufResult<usize, B>> + 'a where
-        't: 'a, B: IoBufMut + 'a, Inner: 'a;
-    type ReadvFuture<'a, B> = impl Future<Output = crate::BufResult<usize, B>> + 'a where
-        't: 'a, B: IoVecBufMut + 'a, Inner: 'a;
-
     #[inline]
-    fn read<T: IoBufMut>(&mut self, buf: T) -> Self::ReadFuture<'_, T>
-    where
-        T: IoBufMut,
-    {
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = crate::BufResult<usize, T>> {
         // Submit the read operation
         let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
         raw_stream.read(buf)
     }
 
     #[inline]
-    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> Self::ReadvFuture<'_, T> {
+    fn readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         // Submit the read operation
         let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
         raw_stream.readv(buf)
     }
 }
 
-#[allow(clippy::cast_ref_to_mut)]
+#[allow(invalid_reference_casting)]
 impl<'t, Inner> AsyncWriteRent for WriteHalf<'t, Inner>
 where
     Inner: AsyncWriteRent,
 {
-    type WriteFuture<'a, B> = impl Future<Output = crate::BufResult<usize, B>> + 'a where
-        't: 'a, B: IoBuf + 'a, Inner: 'a;
-    type WritevFuture<'a, B> = impl Future<Output = crate::BufResult<usize, B>> + 'a where
-        't: 'a, B: IoVecBuf + 'a, Inner: 'a;
-    type FlushFuture<'a> = impl Future<Output = io::Result<()>> + 'a where
-        't: 'a, Inner: 'a;
-    type ShutdownFuture<'a> = impl Future<Output = io::Result<()>> + 'a where
-        't: 'a, Inner: 'a;
-
     #[inline]
-    fn write<T: IoBuf>(&mut self, buf: T) -> Self::WriteFuture<'_, T>
-    where
-        T: IoBuf,
-    {
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = crate::BufResult<usize, T>> {
         // Submit the write operation
         let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
         raw_stream.write(buf)
     }
 
     #[inline]
-    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> Self::WritevFuture<'_, T> {
+    fn writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
         raw_stream.writev(buf_vec)
     }
 
     #[inline]
-    fn flush(&mut self) -> Self::FlushFuture<'_> {
+    fn flush(&mut self) -> impl Future<Output = std::io::Result<()>> {
         let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
         raw_stream.flush()
     }
 
     #[inline]
-    fn shutdown(&mut self) -> Self::ShutdownFuture<'_> {
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
         let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
         raw_stream.shutdown()
     }
 }
 
+#[allow(invalid_reference_casting)]
+impl<'t, Inner> CancelableAsyncReadRent for ReadHalf<'t, Inner>
+where
+    Inner: CancelableAsyncReadRent,
+{
+    #[inline]
+    fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
+        raw_stream.cancelable_read(buf, c)
+    }
+
+    #[inline]
+    fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
+        raw_stream.cancelable_readv(buf, c)
+    }
+}
+
+#[allow(invalid_reference_casting)]
+impl<'t, Inner> CancelableAsyncWriteRent for WriteHalf<'t, Inner>
+where
+    Inner: CancelableAsyncWriteRent,
+{
+    #[inline]
+    fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
+        raw_stream.cancelable_write(buf, c)
+    }
+
+    #[inline]
+    fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
+        raw_stream.cancelable_writev(buf_vec, c)
+    }
+}
+
+#[allow(invalid_reference_casting)]
+impl<'t, Inner> AsReadFd for ReadHalf<'t, Inner>
+where
+    Inner: AsReadFd,
+{
+    #[inline]
+    fn as_reader_fd(&mut self) -> &SharedFdWrapper {
+        let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
+        raw_stream.as_reader_fd()
+    }
+
+    #[inline]
+    fn poll_take_received(
+        &mut self,
+        cx: &mut Context<'_>,
+        len: usize,
+    ) -> Poll<Option<io::Result<ProvidedBuf>>> {
+        let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
+        raw_stream.poll_take_received(cx, len)
+    }
+}
+
+#[allow(invalid_reference_casting)]
+impl<'t, Inner> AsWriteFd for WriteHalf<'t, Inner>
+where
+    Inner: AsWriteFd,
+{
+    #[inline]
+    fn as_writer_fd(&mut self) -> &SharedFdWrapper {
+        let raw_stream = unsafe { &mut *(self.0 as *const Inner as *mut Inner) };
+        raw_stream.as_writer_fd()
+    }
+}
+
 impl<T> Splitable for T
 where
     T: Split + AsyncReadRent + AsyncWriteRent,
 {
-    type Read<'cx> = ReadHalf<'cx, T> where Self: 'cx;
+    type Read<'cx>
+        = ReadHalf<'cx, T>
+    where
+        Self: 'cx;
 
-    type Write<'cx> = WriteHalf<'cx, T> where Self: 'cx;
+    type Write<'cx>
+        = WriteHalf<'cx, T>
+    where
+        Self: 'cx;
 
     type OwnedRead = OwnedReadHalf<T>;
 
@@ -159,24 +251,20 @@ impl<Inner> AsyncReadRent for OwnedReadHalf<Inner>
 where
     Inner: AsyncReadRent,
 {
-    type ReadFuture<'a, T> = impl std::future::Future<Output = crate::BufResult<usize, T>> + 'a
-    where
-        Self: 'a,
-        T: crate::buf::IoBufMut + 'a;
-
-    type ReadvFuture<'a, T> = impl std::future::Future<Output = crate::BufResult<usize, T>> + 'a
-    where
-        Self: 'a,
-        T: crate::buf::IoVecBufMut + 'a;
-
     #[inline]
-    fn read<T: crate::buf::IoBufMut>(&mut self, buf: T) -> Self::ReadFuture<'_, T> {
+    fn read<T: crate::buf::IoBufMut>(
+        &mut self,
+        buf: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         let stream = unsafe { &mut *self.0.get() };
         stream.read(buf)
     }
 
     #[inline]
-    fn readv<T: crate::buf::IoVecBufMut>(&mut self, buf: T) -> Self::ReadvFuture<'_, T> {
+    fn readv<T: crate::buf::IoVecBufMut>(
+        &mut self,
+        buf: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         let stream = unsafe { &mut *self.0.get() };
         stream.readv(buf)
     }
@@ -186,49 +274,87 @@ impl<Inner> AsyncWriteRent for OwnedWriteHalf<Inner>
 where
     Inner: AsyncWriteRent,
 {
-    type WriteFuture<'a, T> =  impl Future<Output = crate::BufResult<usize, T>> + 'a
-    where
-        Self: 'a,
-        T: crate::buf::IoBuf + 'a;
-
-    type WritevFuture<'a, T> =  impl Future<Output = crate::BufResult<usize, T>> + 'a
-    where
-        Self: 'a,
-        T: crate::buf::IoVecBuf + 'a;
-
-    type FlushFuture<'a> = impl Future<Output = std::io::Result<()>> + 'a
-    where
-        Self: 'a;
-
-    type ShutdownFuture<'a> = impl Future<Output = std::io::Result<()>> + 'a
-    where
-        Self: 'a;
-
     #[inline]
-    fn write<T: crate::buf::IoBuf>(&mut self, buf: T) -> Self::WriteFuture<'_, T> {
+    fn write<T: crate::buf::IoBuf>(
+        &mut self,
+        buf: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         let stream = unsafe { &mut *self.0.get() };
         stream.write(buf)
     }
 
     #[inline]
-    fn writev<T: crate::buf::IoVecBuf>(&mut self, buf_vec: T) -> Self::WritevFuture<'_, T> {
+    fn writev<T: crate::buf::IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         let stream = unsafe { &mut *self.0.get() };
         stream.writev(buf_vec)
     }
 
     #[inline]
-    fn flush(&mut self) -> Self::FlushFuture<'_> {
+    fn flush(&mut self) -> impl Future<Output = std::io::Result<()>> {
         let stream = unsafe { &mut *self.0.get() };
         stream.flush()
     }
 
     #[inline]
-    fn shutdown(&mut self) -> Self::ShutdownFuture<'_> {
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
         let stream = unsafe { &mut *self.0.get() };
         stream.shutdown()
     }
 }
 
+impl<Inner> CancelableAsyncReadRent for OwnedReadHalf<Inner>
+where
+    Inner: CancelableAsyncReadRent,
+{
+    #[inline]
+    fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let stream = unsafe { &mut *self.0.get() };
+        stream.cancelable_read(buf, c)
+    }
+
+    #[inline]
+    fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let stream = unsafe { &mut *self.0.get() };
+        stream.cancelable_readv(buf, c)
+    }
+}
+
+impl<Inner> CancelableAsyncWriteRent for OwnedWriteHalf<Inner>
+where
+    Inner: CancelableAsyncWriteRent,
+{
+    #[inline]
+    fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let stream = unsafe { &mut *self.0.get() };
+        stream.cancelable_write(buf, c)
+    }
+
+    #[inline]
+    fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let stream = unsafe { &mut *self.0.get() };
+        stream.cancelable_writev(buf_vec, c)
+    }
+}
+
 impl<T> OwnedReadHalf<T>
 where
     T: AsyncWriteRent,
@@ -258,9 +384,9 @@ where
     #[inline]
     fn drop(&mut self) {
         let write = unsafe { &mut *self.0.get() };
-        // Notes:: shutdown is an async function but rust currently does not support async drop
-        // this drop will only execute sync part of `shutdown` function.
-        write.shutdown();
+        // Rust does not support async drop, so only the part of `shutdown`
+        // done before the future is polled runs, see the struct docs.
+        drop(write.shutdown());
     }
 }
 
@@ -269,7 +395,9 @@ pub(crate) fn reunite<T: AsyncWriteRent>(
     write: OwnedWriteHalf<T>,
 ) -> Result<T, ReuniteError<T>> {
     if Rc::ptr_eq(&read.0, &write.0) {
-        drop(write);
+        // Drop the write half without shutting down the stream.
+        let write = std::mem::ManuallyDrop::new(write);
+        drop(unsafe { std::ptr::read(&write.0) });
         // This unwrap cannot fail as the api does not allow creating more than two
         // Arcs, and we just dropped the other half.
         Ok(Rc::try_unwrap(read.0)
@@ -11,9 +11,9 @@
 #![allow(stable_features)]
 #![feature(generic_associated_types)]
 #![feature(type_alias_impl_trait)]
+#![feature(impl_trait_in_assoc_type)]
 #![feature(box_into_inner)]
 #![feature(new_uninit)]
-#![feature(io_error_more)]
 #![feature(stmt_expr_attributes)]
 #![feature(unboxed_closures)]
 #![feature(once_cell)]
@@ -26,7 +26,7 @@ pub use monoio_macros::select_priv_declare_output_enum;
 #[macro_use]
 mod driver;
 pub(crate) mod builder;
-pub(crate) mod runtime;
+pub mod runtime;
 mod scheduler;
 pub mod time;
 
@@ -36,9 +36,19 @@ extern crate alloc;
 pub mod blocking;
 
 pub mod buf;
+#[cfg(feature = "bytes")]
+pub mod codec;
 pub mod fs;
 pub mod io;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod net;
+#[cfg(feature = "sync")]
+pub mod pool;
+#[cfg(target_os = "linux")]
+pub mod process;
+pub mod stats;
+pub mod sync;
 pub mod task;
 pub mod utils;
 
@@ -46,7 +56,9 @@ use std::future::Future;
 
 #[cfg(feature = "sync")]
 pub use blocking::spawn_blocking;
-pub use builder::{Buildable, RuntimeBuilder};
+pub use builder::{Buildable, Profile, RuntimeBuilder};
+#[cfg(feature = "sync")]
+pub use driver::unpark::Unpark;
 pub use driver::Driver;
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 pub use driver::IoUringDriver;
@@ -54,7 +66,9 @@ pub use driver::IoUringDriver;
 pub use driver::LegacyDriver;
 #[cfg(feature = "macros")]
 pub use monoio_macros::{main, test, test_all};
-pub use runtime::{spawn, Runtime};
+#[cfg(feature = "sync")]
+pub use runtime::RuntimeHandle;
+pub use runtime::{flush, spawn, spawn_with_priority, Runtime};
 #[cfg(all(
     unix,
     any(all(target_os = "linux", feature = "iouring"), feature = "legacy")
@@ -100,6 +114,95 @@ where
     rt.block_on(future)
 }
 
+/// Start `threads` threads with a monoio runtime on each, run the future made
+/// by `f` with the index of the thread, and wait for all of them.
+///
+/// The outputs are returned in the order of the index. A panic on any thread
+/// is resumed after all threads are finished.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{time::TimeDriver, LegacyDriver};
+///
+/// fn main() {
+///     let outputs =
+///         monoio::start_threads::<TimeDriver<LegacyDriver>, _, _>(4, |idx| async move {
+///             // Each thread may run its own accept loop on a `SO_REUSEPORT` listener.
+///             idx * 2
+///         });
+///     assert_eq!(outputs, vec![0, 2, 4, 6]);
+/// }
+/// ```
+pub fn start_threads<D, F, Fut>(threads: usize, f: F) -> Vec<Fut::Output>
+where
+    F: Fn(usize) -> Fut + Sync,
+    Fut: Future,
+    Fut::Output: Send + 'static,
+    D: Buildable + Driver,
+{
+    launch::<D, _, _>(threads, None, f)
+}
+
+/// Like [`start_threads`], but thread `i` is bound to the `i`-th cpu listed by
+/// [`utils::available_cpus`], wrapping around when there are more threads than
+/// cpus.
+#[cfg(feature = "utils")]
+pub fn start_threads_pinned<D, F, Fut>(threads: usize, f: F) -> Vec<Fut::Output>
+where
+    F: Fn(usize) -> Fut + Sync,
+    Fut: Future,
+    Fut::Output: Send + 'static,
+    D: Buildable + Driver,
+{
+    let cpus = utils::available_cpus().expect("Unable to list cpus.");
+    launch::<D, _, _>(threads, Some(&cpus), f)
+}
+
+fn launch<D, F, Fut>(threads: usize, cpus: Option<&[usize]>, f: F) -> Vec<Fut::Output>
+where
+    F: Fn(usize) -> Fut + Sync,
+    Fut: Future,
+    Fut::Output: Send + 'static,
+    D: Buildable + Driver,
+{
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|idx| {
+                let cpu = cpus
+                    .filter(|cpus| !cpus.is_empty())
+                    .map(|cpus| cpus[idx % cpus.len()]);
+                std::thread::Builder::new()
+                    .name(format!("monoio-worker-{idx}"))
+                    .spawn_scoped(scope, move || {
+                        #[allow(unused_mut)]
+                        let mut builder = builder::RuntimeBuilder::<D>::new();
+                        #[cfg(feature = "utils")]
+                        if let Some(cpu) = cpu {
+                            builder = builder.bind_to_cpu(cpu);
+                        }
+                        #[cfg(not(feature = "utils"))]
+                        let _ = cpu;
+                        let mut rt =
+                            builder::Buildable::build(&builder).expect("Unable to build runtime.");
+                        rt.block_on(f(idx))
+                    })
+                    .expect("Unable to spawn thread.")
+            })
+            .collect();
+        // The scope waits for the other threads before the panic is resumed.
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect()
+    })
+}
+
 /// A specialized `Result` type for `io-uring` operations with buffers.
 ///
 /// This type is used as a return value for asynchronous `io-uring` methods that
@@ -30,20 +30,19 @@
 ///
 /// The complete lifecycle of a `select!` expression is as follows:
 ///
-/// 1. Evaluate all provided `<precondition>` expressions. If the precondition
-///    returns `false`, disable the branch for the remainder of the current call
-///    to `select!`. Re-entering `select!` due to a loop clears the "disabled"
-///    state.
-/// 2. Aggregate the `<async expression>`s from each branch, including the
-///    disabled ones. If the branch is disabled, `<async expression>` is still
-///    evaluated, but the resulting future is not polled.
-/// 3. Concurrently await on the results for all remaining `<async
-/// expression>`s. 4. Once an `<async expression>` returns a value, attempt to
-/// apply the value    to the provided `<pattern>`, if the pattern matches,
-/// evaluate `<handler>`    and return. If the pattern **does not** match,
-/// disable the current branch    and for the remainder of the current call to
-/// `select!`. Continue from step 3. 5. If **all** branches are disabled,
-/// evaluate the `else` expression. If no    else branch is provided, panic.
+/// 1. Evaluate all provided `<precondition>` expressions. If the precondition returns `false`,
+///    disable the branch for the remainder of the current call to `select!`. Re-entering `select!`
+///    due to a loop clears the "disabled" state.
+/// 2. Aggregate the `<async expression>`s from each branch, including the disabled ones. If the
+///    branch is disabled, `<async expression>` is still evaluated, but the resulting future is not
+///    polled.
+/// 3. Concurrently await on the results for all remaining `<async expression>`s.
+/// 4. Once an `<async expression>` returns a value, attempt to apply the value to the provided
+///    `<pattern>`, if the pattern matches, evaluate `<handler>` and return. If the pattern **does
+///    not** match, disable the current branch and for the remainder of the current call to
+///    `select!`. Continue from step 3.
+/// 5. If **all** branches are disabled, evaluate the `else` expression. If no else branch is
+///    provided, panic.
 ///
 /// # Runtime characteristics
 ///
@@ -104,7 +104,7 @@ macro_rules! try_join {
 
                     // Safety: future is stored on the stack above
                     // and never moved.
-                    let mut fut = unsafe { Pin::new_unchecked(fut) };
+                    let fut = unsafe { Pin::new_unchecked(fut) };
 
                     fut
                         .take_output()
@@ -0,0 +1,129 @@
+//! Report runtime internals through the `metrics` facade.
+//!
+//! Counters are collected per thread and pushed to the installed recorder
+//! when the runtime is about to park, at most once per [`REPORT_INTERVAL`].
+//! Values of all runtime threads are added up under the same metric names, so
+//! no labels are attached.
+
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use crate::stats::DriverStats;
+
+/// Minimal interval between two automatic reports of the same thread.
+pub const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Counter of submission queue entries handed to the kernel.
+pub const DRIVER_SQES_SUBMITTED: &str = "monoio_driver_sqes_submitted_total";
+/// Counter of `io_uring_enter` calls which submitted entries.
+pub const DRIVER_SUBMIT_CALLS: &str = "monoio_driver_submit_calls_total";
+/// Counter of SQPOLL thread wakeups.
+pub const DRIVER_SQPOLL_WAKEUPS: &str = "monoio_driver_sqpoll_wakeups_total";
+/// Counter of ops done by syscalls because the kernel lacks their opcodes.
+pub const DRIVER_SYSCALL_FALLBACKS: &str = "monoio_driver_syscall_fallbacks_total";
+/// Counter of in-flight ops canceled after their futures were dropped.
+pub const DRIVER_OPS_CANCELED: &str = "monoio_driver_ops_canceled_total";
+/// Gauge of in-use driver slab slots.
+pub const DRIVER_SLAB_USED: &str = "monoio_driver_slab_used";
+/// Gauge of allocated driver slab slots.
+pub const DRIVER_SLAB_CAPACITY: &str = "monoio_driver_slab_capacity";
+/// Counter of spawned tasks.
+pub const RUNTIME_TASKS_SPAWNED: &str = "monoio_runtime_tasks_spawned_total";
+/// Counter of driver parks.
+pub const RUNTIME_PARKS: &str = "monoio_runtime_parks_total";
+/// Counter of accepted tcp connections.
+pub const NET_TCP_ACCEPTED: &str = "monoio_net_tcp_accepted_total";
+/// Counter of established outgoing tcp connections.
+pub const NET_TCP_CONNECTED: &str = "monoio_net_tcp_connected_total";
+
+#[derive(Default, Clone, Copy)]
+struct LocalCounters {
+    tasks_spawned: u64,
+    parks: u64,
+    tcp_accepted: u64,
+    tcp_connected: u64,
+}
+
+thread_local! {
+    static COUNTERS: Cell<LocalCounters> = Cell::new(LocalCounters::default());
+    // Values reported last time, to push deltas only.
+    static REPORTED: Cell<(DriverStats, LocalCounters)> = Cell::new(Default::default());
+    static LAST_REPORT: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+#[inline]
+fn incr(f: impl FnOnce(&mut LocalCounters)) {
+    COUNTERS.with(|c| {
+        let mut v = c.get();
+        f(&mut v);
+        c.set(v);
+    });
+}
+
+#[inline]
+pub(crate) fn task_spawned() {
+    incr(|c| c.tasks_spawned += 1);
+}
+
+#[inline]
+pub(crate) fn tcp_accepted() {
+    incr(|c| c.tcp_accepted += 1);
+}
+
+#[inline]
+pub(crate) fn tcp_connected() {
+    incr(|c| c.tcp_connected += 1);
+}
+
+/// Called by the runtime before park.
+pub(crate) fn on_park() {
+    incr(|c| c.parks += 1);
+    let due = LAST_REPORT.with(|last| match last.get() {
+        Some(t) => t.elapsed() >= REPORT_INTERVAL,
+        None => true,
+    });
+    if due {
+        report();
+    }
+}
+
+/// Push metrics of the current thread to the recorder now. The next
+/// automatic report is delayed for [`REPORT_INTERVAL`].
+///
+/// # Panics
+///
+/// Panics if called outside of a monoio runtime.
+pub fn report() {
+    LAST_REPORT.with(|last| last.set(Some(Instant::now())));
+    let stats = crate::stats::driver_stats();
+    let counters = COUNTERS.with(|c| c.get());
+    let (last_stats, last_counters) = REPORTED.with(|r| r.replace((stats, counters)));
+
+    // A new runtime on the same thread starts from 0, so deltas saturate.
+    let delta = |now: u64, last: u64| now.saturating_sub(last);
+
+    metrics::counter!(DRIVER_SQES_SUBMITTED)
+        .increment(delta(stats.sqes_submitted, last_stats.sqes_submitted));
+    metrics::counter!(DRIVER_SUBMIT_CALLS)
+        .increment(delta(stats.submit_calls, last_stats.submit_calls));
+    metrics::counter!(DRIVER_SQPOLL_WAKEUPS)
+        .increment(delta(stats.sqpoll_wakeups, last_stats.sqpoll_wakeups));
+    metrics::counter!(DRIVER_SYSCALL_FALLBACKS)
+        .increment(delta(stats.syscall_fallbacks, last_stats.syscall_fallbacks));
+    metrics::counter!(DRIVER_OPS_CANCELED)
+        .increment(delta(stats.ops_canceled, last_stats.ops_canceled));
+    // Gauges are adjusted by delta so values of all threads add up.
+    metrics::gauge!(DRIVER_SLAB_USED)
+        .increment(stats.slab_used as f64 - last_stats.slab_used as f64);
+    metrics::gauge!(DRIVER_SLAB_CAPACITY)
+        .increment(stats.slab_capacity as f64 - last_stats.slab_capacity as f64);
+    metrics::counter!(RUNTIME_TASKS_SPAWNED)
+        .increment(delta(counters.tasks_spawned, last_counters.tasks_spawned));
+    metrics::counter!(RUNTIME_PARKS).increment(delta(counters.parks, last_counters.parks));
+    metrics::counter!(NET_TCP_ACCEPTED)
+        .increment(delta(counters.tcp_accepted, last_counters.tcp_accepted));
+    metrics::counter!(NET_TCP_CONNECTED)
+        .increment(delta(counters.tcp_connected, last_counters.tcp_connected));
+}
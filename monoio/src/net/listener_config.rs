@@ -1,3 +1,5 @@
+use super::tcp::TcpKeepalive;
+
 /// Custom listener config
 #[derive(Debug, Clone, Copy)]
 pub struct ListenerConfig {
@@ -11,6 +13,8 @@ pub struct ListenerConfig {
     pub send_buf_size: Option<usize>,
     /// Recv buffer size or None to use default.
     pub recv_buf_size: Option<usize>,
+    /// Keepalive set on every accepted TCP stream, or None to leave it off.
+    pub tcp_keepalive: Option<TcpKeepalive>,
 }
 
 impl Default for ListenerConfig {
@@ -21,6 +25,7 @@ impl Default for ListenerConfig {
             backlog: 1024,
             send_buf_size: None,
             recv_buf_size: None,
+            tcp_keepalive: None,
         }
     }
 }
@@ -65,4 +70,12 @@ impl ListenerConfig {
         self.recv_buf_size = Some(recv_buf_size);
         self
     }
+
+    /// Enable keepalive on accepted TCP streams
+    #[must_use]
+    #[inline]
+    pub fn tcp_keepalive(mut self, tcp_keepalive: TcpKeepalive) -> Self {
+        self.tcp_keepalive = Some(tcp_keepalive);
+        self
+    }
 }
@@ -1,12 +1,26 @@
 //! Network related
-//! Currently, TCP/UnixStream/UnixDatagram are implemented.
+//! Currently, TCP/UDP/UnixStream/UnixDatagram are implemented. TLS is available
+//! with the `rustls` feature. A hyper server is provided by the `hyper` module
+//! of `monoio-compat`.
 
 mod listener_config;
+#[cfg(unix)]
+mod recv_from_stream;
 pub mod tcp;
+#[cfg(feature = "rustls")]
+pub mod tls;
+#[cfg(unix)]
+pub mod udp;
 #[cfg(unix)]
 pub mod unix;
 
 pub use listener_config::ListenerConfig;
+#[cfg(unix)]
+pub use recv_from_stream::RecvFromStream;
+#[cfg(unix)]
+pub use tcp::TcpSocket;
 pub use tcp::{TcpListener, TcpStream};
 #[cfg(unix)]
+pub use udp::UdpSocket;
+#[cfg(unix)]
 pub use unix::{Pipe, UnixDatagram, UnixListener, UnixStream};
@@ -0,0 +1,221 @@
+use std::{
+    fmt,
+    future::{poll_fn, Future},
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use socket2::SockAddr;
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use {crate::driver::op::recv::RecvMsgMulti, crate::driver::BufRing, std::rc::Rc};
+
+use crate::{
+    buf::{ProvidedBuf, VecBuf},
+    driver::{
+        op::{recv::RecvMsg, Op},
+        shared_fd::SharedFd,
+    },
+    io::stream::Stream,
+};
+
+/// A stream of datagrams and their source addresses received from a
+/// datagram socket, created by e.g.
+/// [`UnixDatagram::recv_from_stream`](super::UnixDatagram::recv_from_stream).
+///
+/// With the io_uring driver, one multishot recvmsg keeps receiving into
+/// buffers the kernel picks from a ring owned by the stream, and is submitted
+/// again when the kernel ends it, e.g. when all buffers are held by the
+/// application. Buffers go back to the ring when the yielded [`ProvidedBuf`]
+/// is dropped. With the legacy driver, or if the kernel does not support
+/// multishot recvmsg (before 6.0), every item is received by a regular
+/// recvmsg.
+///
+/// Datagrams longer than the buffer size are truncated. The stream ends after
+/// yielding an error.
+pub struct RecvFromStream<'a, A> {
+    fd: &'a SharedFd,
+    buf_size: usize,
+    to_addr: fn(&SockAddr) -> A,
+    state: State,
+}
+
+enum State {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    Multi {
+        ring: Rc<BufRing>,
+        op: Option<Op<RecvMsgMulti>>,
+        // Whether anything has been received yet.
+        received: bool,
+    },
+    Single(Option<Op<RecvMsg<VecBuf>>>),
+    Done,
+}
+
+impl<'a, A> RecvFromStream<'a, A> {
+    pub(crate) fn new(
+        fd: &'a SharedFd,
+        buf_size: usize,
+        count: u16,
+        to_addr: fn(&SockAddr) -> A,
+    ) -> io::Result<Self> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        let state = match crate::driver::CURRENT
+            .with(|inner| inner.register_buf_ring(buf_size + RecvMsgMulti::PREFIX_LEN, count))
+        {
+            Ok(Some(ring)) => State::Multi {
+                ring: Rc::new(ring),
+                op: None,
+                received: false,
+            },
+            // The kernel does not support buffer rings (before 5.19).
+            Ok(None) => State::Single(None),
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => State::Single(None),
+            Err(e) => return Err(e),
+        };
+        #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+        let state = {
+            let _ = count;
+            State::Single(None)
+        };
+        Ok(Self {
+            fd,
+            buf_size,
+            to_addr,
+            state,
+        })
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<io::Result<(ProvidedBuf, A)>>> {
+        // Only the multishot state loops, which needs io_uring.
+        #[cfg_attr(
+            not(all(target_os = "linux", feature = "iouring")),
+            allow(clippy::never_loop)
+        )]
+        loop {
+            match &mut self.state {
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                State::Multi { ring, op, received } => {
+                    let in_flight = match op {
+                        Some(op) => op,
+                        None => {
+                            // Wait for a buffer to arm it again.
+                            if !ring.poll_available(cx.waker()) {
+                                return Poll::Pending;
+                            }
+                            match Op::recv_msg_multi(self.fd, ring.clone()) {
+                                Ok(new_op) => op.insert(new_op),
+                                Err(e) => {
+                                    self.state = State::Done;
+                                    return Poll::Ready(Some(Err(e)));
+                                }
+                            }
+                        }
+                    };
+                    let meta = ready!(in_flight.poll_multi(cx));
+                    if !io_uring::cqueue::more(meta.flags) {
+                        *op = None;
+                    }
+                    match meta.result {
+                        Ok(n) => {
+                            let buf = ProvidedBuf::from_completion(ring, meta.flags, n);
+                            *received = true;
+                            return match RecvMsgMulti::parse(buf) {
+                                Ok((buf, addr)) => {
+                                    Poll::Ready(Some(Ok((buf, (self.to_addr)(&addr)))))
+                                }
+                                Err(e) => {
+                                    self.state = State::Done;
+                                    Poll::Ready(Some(Err(e)))
+                                }
+                            };
+                        }
+                        // All buffers are held by the application.
+                        Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {}
+                        // The kernel does not support multishot recvmsg.
+                        Err(e) if e.raw_os_error() == Some(libc::EINVAL) && !*received => {
+                            self.state = State::Single(None);
+                        }
+                        Err(e) => {
+                            self.state = State::Done;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    }
+                }
+                State::Single(op) => {
+                    let in_flight = match op {
+                        Some(op) => op,
+                        None => {
+                            let buf = VecBuf::from(vec![vec![0; self.buf_size]]);
+                            match Op::recv_msg(self.fd, buf, None) {
+                                Ok(new_op) => op.insert(new_op),
+                                Err(e) => {
+                                    self.state = State::Done;
+                                    return Poll::Ready(Some(Err(e)));
+                                }
+                            }
+                        }
+                    };
+                    let completion = ready!(Pin::new(in_flight).poll(cx));
+                    *op = None;
+                    let (res, (buf, _)) = completion.into_msg();
+                    return match res {
+                        Ok(meta) => {
+                            let mut buf = Vec::<Vec<u8>>::from(buf).swap_remove(0);
+                            buf.truncate(meta.len);
+                            let addr = match meta.addr {
+                                Some(addr) => addr,
+                                // Safety: an empty address is valid.
+                                None => unsafe { SockAddr::new(std::mem::zeroed(), 0) },
+                            };
+                            Poll::Ready(Some(Ok((ProvidedBuf::heap(buf), (self.to_addr)(&addr)))))
+                        }
+                        Err(e) => {
+                            self.state = State::Done;
+                            Poll::Ready(Some(Err(e)))
+                        }
+                    };
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl<A> Stream for RecvFromStream<'_, A> {
+    type Item = io::Result<(ProvidedBuf, A)>;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move { poll_fn(|cx| self.poll_next(cx)).await }
+    }
+}
+
+impl<A> Drop for RecvFromStream<'_, A> {
+    fn drop(&mut self) {
+        // A multishot recvmsg may never finish on its own.
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let State::Multi { op: Some(op), .. } = &self.state {
+            op.cancel();
+        }
+    }
+}
+
+impl<A> fmt::Debug for RecvFromStream<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.state {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            State::Multi { .. } => "multishot",
+            State::Single(_) => "single",
+            State::Done => "done",
+        };
+        f.debug_struct("RecvFromStream")
+            .field("fd", self.fd)
+            .field("mode", &mode)
+            .finish()
+    }
+}
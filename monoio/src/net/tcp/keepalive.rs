@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+/// TCP keepalive parameters, set on a [`TcpStream`](super::TcpStream) by
+/// [`set_keepalive`](super::TcpStream::set_keepalive) or on every accepted
+/// stream by
+/// [`ListenerConfig::tcp_keepalive`](crate::net::ListenerConfig::tcp_keepalive).
+///
+/// Once the connection is idle for `time`, up to `retries` probes are sent
+/// `interval` apart, and the connection is reset if none is answered. A
+/// parameter left unset keeps the system default, e.g. `net.ipv4.tcp_keepalive_*`
+/// on Linux.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use monoio::net::tcp::TcpKeepalive;
+///
+/// let keepalive = TcpKeepalive::new()
+///     .with_time(Duration::from_secs(60))
+///     .with_interval(Duration::from_secs(10))
+///     .with_retries(3);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TcpKeepalive {
+    time: Option<Duration>,
+    interval: Option<Duration>,
+    retries: Option<u32>,
+}
+
+impl TcpKeepalive {
+    /// Keepalive with the system default parameters.
+    pub const fn new() -> Self {
+        Self {
+            time: None,
+            interval: None,
+            retries: None,
+        }
+    }
+
+    /// Set the idle time before the first probe, `TCP_KEEPIDLE`
+    /// (`TCP_KEEPALIVE` on Apple platforms).
+    #[must_use]
+    pub const fn with_time(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// Set the time between probes, `TCP_KEEPINTVL`.
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Set the number of unanswered probes before the connection is reset,
+    /// `TCP_KEEPCNT`. Ignored on Windows.
+    #[must_use]
+    pub const fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    /// The idle time before the first probe.
+    pub const fn time(&self) -> Option<Duration> {
+        self.time
+    }
+
+    /// The time between probes.
+    pub const fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// The number of unanswered probes before the connection is reset.
+    pub const fn retries(&self) -> Option<u32> {
+        self.retries
+    }
+
+    pub(crate) fn set_on(&self, socket: &socket2::Socket) -> std::io::Result<()> {
+        let mut t = socket2::TcpKeepalive::new();
+        if let Some(time) = self.time {
+            t = t.with_time(time)
+        }
+        if let Some(interval) = self.interval {
+            t = t.with_interval(interval)
+        }
+        #[cfg(unix)]
+        if let Some(retries) = self.retries {
+            t = t.with_retries(retries)
+        }
+        socket.set_tcp_keepalive(&t)
+    }
+}
@@ -9,10 +9,10 @@ use std::{
     net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs},
 };
 
-use super::stream::TcpStream;
+use super::{stream::TcpStream, TcpKeepalive};
 use crate::{
     driver::{op::Op, shared_fd::SharedFd},
-    io::stream::Stream,
+    io::{stream::Stream, CancelHandle},
     net::ListenerConfig,
 };
 
@@ -21,6 +21,8 @@ pub struct TcpListener {
     fd: SharedFd,
     sys_listener: Option<std::net::TcpListener>,
     meta: UnsafeCell<ListenerMeta>,
+    // Set on every accepted stream.
+    keepalive: Option<TcpKeepalive>,
 }
 
 impl TcpListener {
@@ -33,10 +35,44 @@ impl TcpListener {
             fd,
             sys_listener: Some(sys_listener),
             meta: UnsafeCell::new(ListenerMeta::default()),
+            keepalive: None,
         }
     }
 
     /// Bind to address with config
+    ///
+    /// With [`ListenerConfig::reuse_port`], which is on by default, every
+    /// thread can bind its own listener to the same address and the kernel
+    /// balances incoming connections between them, so each per-core runtime
+    /// accepts on its own.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::net::{ListenerConfig, TcpListener};
+    ///
+    /// async fn serve() -> std::io::Result<()> {
+    ///     let config = ListenerConfig::default().reuse_port(true).backlog(4096);
+    ///     let listener = TcpListener::bind_with_config("0.0.0.0:8080", &config)?;
+    ///     loop {
+    ///         let (stream, _) = listener.accept().await?;
+    ///         // Serve the connection on this thread.
+    ///         monoio::spawn(async move { drop(stream) });
+    ///     }
+    /// }
+    ///
+    /// let threads: Vec<_> = (0..4)
+    ///     .map(|_| {
+    ///         std::thread::spawn(|| {
+    ///             let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new().build()?;
+    ///             rt.block_on(serve())
+    ///         })
+    ///     })
+    ///     .collect();
+    /// for thread in threads {
+    ///     thread.join().unwrap().unwrap();
+    /// }
+    /// ```
     pub fn bind_with_config<A: ToSocketAddrs>(
         addr: A,
         config: &ListenerConfig,
@@ -44,7 +80,7 @@ impl TcpListener {
         let addr = addr
             .to_socket_addrs()?
             .next()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "empty address"))?;
+            .ok_or_else(|| io::Error::other("empty address"))?;
 
         let domain = if addr.is_ipv6() {
             socket2::Domain::IPV6
@@ -80,10 +116,13 @@ impl TcpListener {
         #[cfg(windows)]
         let fd = unimplemented!();
 
-        Ok(Self::from_shared_fd(fd))
+        let mut listener = Self::from_shared_fd(fd);
+        listener.keepalive = config.tcp_keepalive;
+        Ok(listener)
     }
 
-    /// Bind to address
+    /// Bind to address with the default [`ListenerConfig`], see
+    /// [`bind_with_config`](Self::bind_with_config).
     pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
         let cfg = ListenerConfig::default();
         Self::bind_with_config(addr, &cfg)
@@ -92,7 +131,21 @@ impl TcpListener {
     #[cfg(unix)]
     /// Accept
     pub async fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
+        self.accept_with(None).await
+    }
+
+    #[cfg(unix)]
+    /// Accept, or fail with `ECANCELED` once the
+    /// [`Canceller`](crate::io::Canceller) of `c` is canceled.
+    pub async fn cancelable_accept(&self, c: CancelHandle) -> io::Result<(TcpStream, SocketAddr)> {
+        c.check()?;
+        self.accept_with(Some(&c)).await
+    }
+
+    #[cfg(unix)]
+    async fn accept_with(&self, c: Option<&CancelHandle>) -> io::Result<(TcpStream, SocketAddr)> {
         let op = Op::accept(&self.fd)?;
+        let _guard = c.map(|c| c.associate_op(op.op_canceller()));
 
         // Await the completion of the event
         let completion = op.await;
@@ -102,6 +155,11 @@ impl TcpListener {
 
         // Construct stream
         let stream = TcpStream::from_shared_fd(SharedFd::new(fd as _)?);
+        if let Some(keepalive) = self.keepalive {
+            stream.set_keepalive(Some(keepalive))?;
+        }
+        #[cfg(feature = "metrics")]
+        crate::metrics::tcp_accepted();
 
         // Construct SocketAddr
         let storage = completion.data.addr.0.as_ptr() as *const _ as *const libc::sockaddr_storage;
@@ -142,6 +200,19 @@ impl TcpListener {
         unimplemented!()
     }
 
+    /// Convert to a `std::net::TcpListener` once the in-flight ops on the
+    /// socket complete, see [`TcpStream::into_std`].
+    #[cfg(unix)]
+    pub async fn into_std(self) -> io::Result<std::net::TcpListener> {
+        // The listener only releases its std listener on drop.
+        let fd = self.fd.clone();
+        drop(self);
+        let fd = fd.drain_unwrap().await;
+        let listener = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+        listener.set_nonblocking(false)?;
+        Ok(listener)
+    }
+
     /// Returns the local address that this listener is bound to.
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
         let meta = self.meta.get();
@@ -152,10 +223,23 @@ impl TcpListener {
             .as_ref()
             .unwrap()
             .local_addr()
-            .map(|addr| {
-                unsafe { &mut *meta }.local_addr = Some(addr);
-                addr
-            })
+            .inspect(|&addr| unsafe { &mut *meta }.local_addr = Some(addr))
+    }
+
+    /// Get the value of the `IP_TTL` option on this socket.
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.sys_listener.as_ref().unwrap().ttl()
+    }
+
+    /// Set the value of the `IP_TTL` option on this socket, which accepted
+    /// streams inherit.
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.sys_listener.as_ref().unwrap().set_ttl(ttl)
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket, clearing it.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.sys_listener.as_ref().unwrap().take_error()
     }
 
     #[cfg(all(unix, feature = "legacy"))]
@@ -205,7 +289,7 @@ impl Drop for TcpListener {
     #[inline]
     fn drop(&mut self) {
         #[cfg(unix)]
-        self.sys_listener.take().unwrap().into_raw_fd();
+        let _ = self.sys_listener.take().unwrap().into_raw_fd();
         #[cfg(windows)]
         unimplemented!()
     }
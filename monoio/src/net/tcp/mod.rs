@@ -1,10 +1,23 @@
 #![allow(unreachable_pub)]
 //! TCP related.
 
+mod keepalive;
 mod listener;
+mod read_buf;
+mod recv_stream;
+#[cfg(unix)]
+mod socket;
 mod split;
 mod stream;
+#[cfg(all(unix, feature = "poll-io"))]
+mod stream_poll;
 
+pub use keepalive::TcpKeepalive;
 pub use listener::TcpListener;
+pub use recv_stream::RecvStream;
+#[cfg(unix)]
+pub use socket::TcpSocket;
 pub use split::{TcpOwnedReadHalf, TcpOwnedWriteHalf, TcpReadHalf, TcpWriteHalf};
 pub use stream::TcpStream;
+#[cfg(all(unix, feature = "poll-io"))]
+pub use stream_poll::TcpStreamPoll;
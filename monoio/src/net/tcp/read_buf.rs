@@ -0,0 +1,391 @@
+use std::{
+    cell::RefCell,
+    io,
+    task::{Context, Poll},
+    time::Duration,
+};
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use std::{collections::VecDeque, future::poll_fn, rc::Rc};
+
+use crate::{
+    buf::ProvidedBuf,
+    driver::{op::Op, shared_fd::SharedFd},
+    io::CancelHandle,
+};
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use crate::{
+    driver::{
+        op::{recv::RecvMulti, CompletionMeta},
+        BufRing,
+    },
+    io::AssociateGuard,
+};
+
+// Number of buffers in the ring of a read buffer.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+const RING_BUFFERS: u16 = 4;
+
+/// Internal read buffer for small reads of a [`TcpStream`](super::TcpStream).
+///
+/// With the io_uring driver, it is filled by a multishot recv into a ring of
+/// buffers owned by the stream. The recv stays armed between reads, so reads
+/// served while data keeps arriving need no submission. Paths which receive
+/// from the socket by other means stop it first, keeping the data in order.
+/// With the legacy driver, if the kernel does not support it, or with a read
+/// timeout, the buffer is refilled by one recv when empty.
+///
+/// The state is only borrowed within a poll, so a peek through a shared
+/// reference can fill it too.
+pub(super) struct ReadBuffer {
+    pub(super) capacity: usize,
+    state: RefCell<State>,
+}
+
+struct State {
+    // Data received by a single recv, served from `pos`.
+    buf: Vec<u8>,
+    // Offset in `buf`, or in the first chunk of the multishot recv. Only one of
+    // them holds data at a time.
+    pos: usize,
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    multi: Option<Multi>,
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+struct Multi {
+    ring: Rc<BufRing>,
+    op: Option<Op<RecvMulti>>,
+    // Whether anything has been received yet.
+    received: bool,
+    // Whether the in-flight recv is being canceled.
+    canceled: bool,
+    // Received chunks, an empty one on EOF.
+    queue: VecDeque<ProvidedBuf>,
+    // Error received after the queued chunks.
+    error: Option<io::Error>,
+}
+
+impl ReadBuffer {
+    pub(super) fn new(capacity: usize) -> Self {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        let multi = crate::driver::CURRENT
+            .is_set()
+            .then(|| {
+                crate::driver::CURRENT.with(|inner| inner.register_buf_ring(capacity, RING_BUFFERS))
+            })
+            // The kernel does not support buffer rings (before 5.19), or the
+            // driver is legacy.
+            .and_then(|ring| ring.ok().flatten())
+            .map(|ring| Multi {
+                ring: Rc::new(ring),
+                op: None,
+                received: false,
+                canceled: false,
+                queue: VecDeque::new(),
+                error: None,
+            });
+        Self {
+            capacity,
+            state: RefCell::new(State {
+                buf: Vec::with_capacity(capacity),
+                pos: 0,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                multi,
+            }),
+        }
+    }
+
+    /// Number of buffered bytes.
+    pub(super) fn len(&self) -> usize {
+        let state = self.state.borrow();
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(multi) = state.multi.as_ref().filter(|m| !m.queue.is_empty()) {
+            return multi.queue.iter().map(|buf| buf.len()).sum::<usize>() - state.pos;
+        }
+        state.buf.len() - state.pos
+    }
+
+    /// Whether nothing is buffered and no recv is in flight, so the socket can
+    /// be read directly.
+    pub(super) fn is_idle(&self) -> bool {
+        let state = self.state.borrow();
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(multi) = &state.multi {
+            if multi.op.is_some() || !multi.queue.is_empty() || multi.error.is_some() {
+                return false;
+            }
+        }
+        state.pos == state.buf.len()
+    }
+
+    /// Wait until data, EOF or an error is buffered, see
+    /// [`take_ready`](ReadBuffer::take_ready).
+    pub(super) async fn fill(
+        &self,
+        fd: &SharedFd,
+        timeout: Option<Duration>,
+        c: Option<&CancelHandle>,
+    ) -> io::Result<()> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        {
+            // The multishot recv can not be linked to a timeout.
+            if timeout.is_some() {
+                poll_fn(|cx| self.poll_stop(cx)).await;
+            }
+            let mut guard = None;
+            let filled = poll_fn(|cx| self.poll_fill(fd, timeout.is_none(), c, &mut guard, cx));
+            if filled.await? {
+                return Ok(());
+            }
+        }
+        self.fill_single(fd, timeout, c).await
+    }
+
+    // Refill the buffer with one recv.
+    async fn fill_single(
+        &self,
+        fd: &SharedFd,
+        timeout: Option<Duration>,
+        c: Option<&CancelHandle>,
+    ) -> io::Result<()> {
+        let mut buf = {
+            let mut state = self.state.borrow_mut();
+            if !state.buf_is_empty() {
+                return Ok(());
+            }
+            state.pos = 0;
+            // The buffer may be lost if the previous fill was canceled.
+            std::mem::take(&mut state.buf)
+        };
+        buf.clear();
+        if buf.capacity() < self.capacity {
+            buf = Vec::with_capacity(self.capacity);
+        }
+        let op = Op::submit_with_timeout(Op::recv_raw(fd, buf), timeout).unwrap();
+        let _guard = c.map(|c| c.associate_op(op.op_canceller()));
+        let (res, buf) = op.read().await;
+        let mut state = self.state.borrow_mut();
+        state.buf = buf;
+        state.pos = 0;
+        res.map(|_| ())
+    }
+
+    // Serve the multishot recv, arming it if `arm`. Ready with whether
+    // something is buffered, false if a single recv must be used instead.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn poll_fill(
+        &self,
+        fd: &SharedFd,
+        arm: bool,
+        c: Option<&CancelHandle>,
+        guard: &mut Option<AssociateGuard>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<bool>> {
+        let mut state = self.state.borrow_mut();
+        if !state.buf_is_empty() {
+            return Poll::Ready(Ok(true));
+        }
+        loop {
+            let multi = match state.multi.as_mut() {
+                Some(multi) => multi,
+                None => return Poll::Ready(Ok(false)),
+            };
+            if !multi.queue.is_empty() || multi.error.is_some() {
+                return Poll::Ready(Ok(true));
+            }
+            if !arm {
+                return Poll::Ready(Ok(false));
+            }
+            let op = match &mut multi.op {
+                Some(op) => op,
+                None => {
+                    // Canceled by the handle, so it is not armed again.
+                    if let Some(Err(e)) = c.map(CancelHandle::check) {
+                        return Poll::Ready(Err(e));
+                    }
+                    // Wait for a buffer to arm it again.
+                    if !multi.ring.poll_available(cx.waker()) {
+                        return Poll::Pending;
+                    }
+                    match Op::recv_multi(fd, multi.ring.clone()) {
+                        Ok(op) => multi.op.insert(op),
+                        Err(e) => return Poll::Ready(Err(e)),
+                    }
+                }
+            };
+            if let Some(c) = c {
+                *guard = Some(c.associate_op(op.op_canceller()));
+            }
+            let meta = ready!(op.poll_multi(cx));
+            state.complete(meta);
+        }
+    }
+
+    /// Cancel the multishot recv if it is in flight, and wait for the data it
+    /// received in the meantime to be buffered.
+    pub(super) fn poll_stop(&self, _cx: &mut Context<'_>) -> Poll<()> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        {
+            let mut state = self.state.borrow_mut();
+            while let Some(multi) = state.multi.as_mut() {
+                let op = match &mut multi.op {
+                    Some(op) => op,
+                    None => break,
+                };
+                if !multi.canceled {
+                    op.cancel();
+                    multi.canceled = true;
+                }
+                let meta = ready!(op.poll_multi(_cx));
+                state.complete(meta);
+            }
+        }
+        Poll::Ready(())
+    }
+
+    /// Copy the buffered data to `dst`, consuming it if `consume`. Returns
+    /// `None` if nothing is buffered, or 0 on EOF.
+    pub(super) fn take_ready(
+        &self,
+        dst: *mut u8,
+        len: usize,
+        consume: bool,
+    ) -> Option<io::Result<usize>> {
+        let mut state = self.state.borrow_mut();
+        let chunk = match state.chunk() {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => return None,
+            Err(e) => return Some(Err(e)),
+        };
+        let n = len.min(chunk.len());
+        unsafe { dst.copy_from_nonoverlapping(chunk.as_ptr(), n) };
+        if consume {
+            state.consume(n);
+        }
+        Some(Ok(n))
+    }
+
+    /// Take up to `len` buffered bytes, like
+    /// [`take_ready`](ReadBuffer::take_ready). A chunk received by the
+    /// multishot recv is handed over without copying if it fits.
+    pub(super) fn take_buf(&self, len: usize) -> Option<io::Result<ProvidedBuf>> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        {
+            let mut state = self.state.borrow_mut();
+            if state.pos == 0 {
+                if let Some(multi) = &mut state.multi {
+                    if multi.queue.front().is_some_and(|buf| buf.len() <= len) {
+                        return multi.queue.pop_front().map(Ok);
+                    }
+                }
+            }
+        }
+        let mut buf = Vec::with_capacity(len.min(self.len()));
+        let res = self.take_ready(buf.as_mut_ptr(), buf.capacity(), true)?;
+        Some(res.map(|n| {
+            unsafe { buf.set_len(n) };
+            ProvidedBuf::heap(buf)
+        }))
+    }
+
+    /// Stop the multishot recv and take the buffered data by chunks of up to
+    /// `len` bytes, so the socket can be read by other means once it returns
+    /// `None`.
+    pub(super) fn poll_take(
+        &self,
+        cx: &mut Context<'_>,
+        len: usize,
+    ) -> Poll<Option<io::Result<ProvidedBuf>>> {
+        ready!(self.poll_stop(cx));
+        loop {
+            match self.take_buf(len) {
+                // Skip EOF, which the socket returns again.
+                Some(Ok(buf)) if buf.is_empty() => continue,
+                res => return Poll::Ready(res),
+            }
+        }
+    }
+}
+
+impl State {
+    fn buf_is_empty(&self) -> bool {
+        self.pos == self.buf.len()
+    }
+
+    // The chunk being served, or the received error once there is none.
+    fn chunk(&mut self) -> io::Result<Option<&[u8]>> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        {
+            if let Some(multi) = self.multi.as_mut().filter(|m| m.queue.is_empty()) {
+                if let Some(e) = multi.error.take() {
+                    return Err(e);
+                }
+            }
+            if let Some(buf) = self.multi.as_ref().and_then(|m| m.queue.front()) {
+                return Ok(Some(&buf[self.pos..]));
+            }
+        }
+        if self.buf_is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(&self.buf[self.pos..]))
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.pos += n;
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(multi) = &mut self.multi {
+            if let Some(buf) = multi.queue.front() {
+                if self.pos == buf.len() {
+                    // Gives the buffer back to the ring.
+                    multi.queue.pop_front();
+                    self.pos = 0;
+                }
+                return;
+            }
+        }
+        if self.buf_is_empty() {
+            self.buf.clear();
+            self.pos = 0;
+        }
+    }
+
+    // Handle a completion of the multishot recv.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn complete(&mut self, meta: CompletionMeta) {
+        let multi = match self.multi.as_mut() {
+            Some(multi) => multi,
+            None => return,
+        };
+        if !io_uring::cqueue::more(meta.flags) {
+            multi.op = None;
+            multi.canceled = false;
+        }
+        match meta.result {
+            Ok(n) => {
+                let buf = ProvidedBuf::from_completion(&multi.ring, meta.flags, n);
+                multi.queue.push_back(buf);
+                multi.received = true;
+            }
+            // All buffers are held by the read buffer.
+            Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {}
+            // Stopped, or canceled by a handle, which the next fill reports.
+            Err(e) if e.raw_os_error() == Some(libc::ECANCELED) => {}
+            // The kernel does not support multishot recv.
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) && !multi.received => {
+                self.multi = None;
+            }
+            Err(e) => multi.error = Some(e),
+        }
+    }
+}
+
+impl Drop for ReadBuffer {
+    fn drop(&mut self) {
+        // A multishot recv may never finish on its own.
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(Multi { op: Some(op), .. }) = &self.state.get_mut().multi {
+            op.cancel();
+        }
+    }
+}
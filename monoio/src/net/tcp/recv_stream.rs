@@ -0,0 +1,216 @@
+use std::{
+    fmt,
+    future::{poll_fn, Future},
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use {crate::driver::op::recv::RecvMulti, crate::driver::BufRing, std::rc::Rc};
+
+use super::read_buf::ReadBuffer;
+use crate::{
+    buf::{IoBufMut, ProvidedBuf},
+    driver::{
+        op::{recv::Recv, Op},
+        shared_fd::SharedFd,
+    },
+    io::stream::Stream,
+};
+
+/// A stream of buffers received from a [`TcpStream`](super::TcpStream),
+/// created by [`TcpStream::recv_stream`](super::TcpStream::recv_stream).
+///
+/// With the io_uring driver, one multishot recv keeps receiving into buffers
+/// the kernel picks from a ring owned by the stream, and is submitted again
+/// when the kernel ends it, e.g. when all buffers are held by the
+/// application. Buffers go back to the ring when the yielded [`ProvidedBuf`]
+/// is dropped. With the legacy driver, or if the kernel does not support
+/// multishot recv (before 6.0), every item is received by a regular recv.
+///
+/// The stream ends on EOF or after yielding an error.
+pub struct RecvStream<'a> {
+    fd: &'a SharedFd,
+    buf_size: usize,
+    // Read buffer of the stream, whose bytes are yielded first.
+    read_buf: Option<&'a ReadBuffer>,
+    state: State,
+}
+
+enum State {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    Multi {
+        ring: Rc<BufRing>,
+        op: Option<Op<RecvMulti>>,
+        // Whether anything has been received yet.
+        received: bool,
+    },
+    Single(Option<Op<Recv<Vec<u8>>>>),
+    Done,
+}
+
+impl<'a> RecvStream<'a> {
+    pub(super) fn new(
+        fd: &'a SharedFd,
+        read_buf: Option<&'a ReadBuffer>,
+        buf_size: usize,
+        count: u16,
+    ) -> io::Result<Self> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        let state =
+            match crate::driver::CURRENT.with(|inner| inner.register_buf_ring(buf_size, count)) {
+                Ok(Some(ring)) => State::Multi {
+                    ring: Rc::new(ring),
+                    op: None,
+                    received: false,
+                },
+                // The kernel does not support buffer rings (before 5.19).
+                Ok(None) => State::Single(None),
+                Err(e) if e.raw_os_error() == Some(libc::EINVAL) => State::Single(None),
+                Err(e) => return Err(e),
+            };
+        #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+        let state = {
+            let _ = count;
+            State::Single(None)
+        };
+        Ok(Self {
+            fd,
+            buf_size,
+            read_buf,
+            state,
+        })
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<io::Result<ProvidedBuf>>> {
+        if let Some(read_buf) = self.read_buf {
+            match ready!(read_buf.poll_take(cx, usize::MAX)) {
+                Some(res) => return Poll::Ready(Some(res)),
+                None => self.read_buf = None,
+            }
+        }
+        // Only the multishot state loops, which needs io_uring.
+        #[cfg_attr(
+            not(all(target_os = "linux", feature = "iouring")),
+            allow(clippy::never_loop)
+        )]
+        loop {
+            match &mut self.state {
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                State::Multi { ring, op, received } => {
+                    let in_flight = match op {
+                        Some(op) => op,
+                        None => {
+                            // Wait for a buffer to arm it again.
+                            if !ring.poll_available(cx.waker()) {
+                                return Poll::Pending;
+                            }
+                            match Op::recv_multi(self.fd, ring.clone()) {
+                                Ok(new_op) => op.insert(new_op),
+                                Err(e) => {
+                                    self.state = State::Done;
+                                    return Poll::Ready(Some(Err(e)));
+                                }
+                            }
+                        }
+                    };
+                    let meta = ready!(in_flight.poll_multi(cx));
+                    if !io_uring::cqueue::more(meta.flags) {
+                        *op = None;
+                    }
+                    match meta.result {
+                        Ok(n) => {
+                            let buf = ProvidedBuf::from_completion(ring, meta.flags, n);
+                            if buf.is_empty() {
+                                self.state = State::Done;
+                                return Poll::Ready(None);
+                            }
+                            *received = true;
+                            return Poll::Ready(Some(Ok(buf)));
+                        }
+                        // All buffers are held by the application.
+                        Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {}
+                        // The kernel does not support multishot recv.
+                        Err(e) if e.raw_os_error() == Some(libc::EINVAL) && !*received => {
+                            self.state = State::Single(None);
+                        }
+                        Err(e) => {
+                            self.state = State::Done;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    }
+                }
+                State::Single(op) => {
+                    let in_flight = match op {
+                        Some(op) => op,
+                        None => match Op::recv(self.fd, Vec::with_capacity(self.buf_size)) {
+                            Ok(new_op) => op.insert(new_op),
+                            Err(e) => {
+                                self.state = State::Done;
+                                return Poll::Ready(Some(Err(e)));
+                            }
+                        },
+                    };
+                    let completion = ready!(Pin::new(in_flight).poll(cx));
+                    *op = None;
+                    return match completion.meta.result {
+                        Ok(0) => {
+                            self.state = State::Done;
+                            Poll::Ready(None)
+                        }
+                        Ok(n) => {
+                            let mut buf = completion.data.buf;
+                            // Safety: the kernel wrote `n` bytes to the buffer.
+                            unsafe { buf.set_init(n as usize) };
+                            Poll::Ready(Some(Ok(ProvidedBuf::heap(buf))))
+                        }
+                        Err(e) => {
+                            self.state = State::Done;
+                            Poll::Ready(Some(Err(e)))
+                        }
+                    };
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl Stream for RecvStream<'_> {
+    type Item = io::Result<ProvidedBuf>;
+
+    type NextFuture<'a>
+        = impl Future<Output = Option<Self::Item>> + 'a
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move { poll_fn(|cx| self.poll_next(cx)).await }
+    }
+}
+
+impl Drop for RecvStream<'_> {
+    fn drop(&mut self) {
+        // A multishot recv may never finish on its own.
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let State::Multi { op: Some(op), .. } = &self.state {
+            op.cancel();
+        }
+    }
+}
+
+impl fmt::Debug for RecvStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.state {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            State::Multi { .. } => "multishot",
+            State::Single(_) => "single",
+            State::Done => "done",
+        };
+        f.debug_struct("RecvStream")
+            .field("fd", self.fd)
+            .field("mode", &mode)
+            .finish()
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    io,
+    net::SocketAddr,
+    os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+    time::Duration,
+};
+
+use super::{TcpKeepalive, TcpListener, TcpStream};
+use crate::driver::{op::Op, shared_fd::SharedFd};
+
+/// A TCP socket which is neither connected nor listening yet, so options can
+/// be set on it before [`connect`](Self::connect) or [`listen`](Self::listen)
+/// turns it into a [`TcpStream`] or [`TcpListener`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::net::TcpSocket;
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let socket = TcpSocket::new_v4()?;
+///     socket.set_reuseport(true)?;
+///     socket.set_recv_buffer_size(1 << 20)?;
+///     socket.bind("0.0.0.0:8080".parse().unwrap())?;
+///     let listener = socket.listen(1024)?;
+///     let (stream, addr) = listener.accept().await?;
+///     Ok(())
+/// }
+/// ```
+pub struct TcpSocket {
+    inner: socket2::Socket,
+}
+
+impl TcpSocket {
+    /// Create a socket for IPv4.
+    pub fn new_v4() -> io::Result<Self> {
+        Self::new(socket2::Domain::IPV4)
+    }
+
+    /// Create a socket for IPv6.
+    pub fn new_v6() -> io::Result<Self> {
+        Self::new(socket2::Domain::IPV6)
+    }
+
+    fn new(domain: socket2::Domain) -> io::Result<Self> {
+        let inner =
+            socket2::Socket::new(domain, socket2::Type::STREAM, Some(socket2::Protocol::TCP))?;
+        if crate::driver::op::non_blocking() {
+            inner.set_nonblocking(true)?;
+        }
+        Ok(Self { inner })
+    }
+
+    /// Set the value of the `SO_REUSEADDR` option on this socket.
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        self.inner.set_reuse_address(reuseaddr)
+    }
+
+    /// Get the value of the `SO_REUSEADDR` option on this socket.
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        self.inner.reuse_address()
+    }
+
+    /// Set the value of the `SO_REUSEPORT` option on this socket.
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        self.inner.set_reuse_port(reuseport)
+    }
+
+    /// Get the value of the `SO_REUSEPORT` option on this socket.
+    pub fn reuseport(&self) -> io::Result<bool> {
+        self.inner.reuse_port()
+    }
+
+    /// Set the value of the `SO_SNDBUF` option on this socket.
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.inner.set_send_buffer_size(size)
+    }
+
+    /// Get the value of the `SO_SNDBUF` option on this socket.
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        self.inner.send_buffer_size()
+    }
+
+    /// Set the value of the `SO_RCVBUF` option on this socket.
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.inner.set_recv_buffer_size(size)
+    }
+
+    /// Get the value of the `SO_RCVBUF` option on this socket.
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        self.inner.recv_buffer_size()
+    }
+
+    /// Set the value of the `TCP_NODELAY` option on this socket.
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.inner.set_nodelay(nodelay)
+    }
+
+    /// Get the value of the `TCP_NODELAY` option on this socket.
+    pub fn nodelay(&self) -> io::Result<bool> {
+        self.inner.nodelay()
+    }
+
+    /// Set the value of the `SO_LINGER` option on this socket, see
+    /// [`TcpStream::set_linger`].
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.inner.set_linger(linger)
+    }
+
+    /// Get the value of the `SO_LINGER` option on this socket.
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.inner.linger()
+    }
+
+    /// Enable keepalive on this socket with the given parameters, or disable
+    /// it with `None`.
+    pub fn set_keepalive(&self, keepalive: Option<TcpKeepalive>) -> io::Result<()> {
+        match keepalive {
+            Some(keepalive) => keepalive.set_on(&self.inner),
+            None => self.inner.set_keepalive(false),
+        }
+    }
+
+    /// Bind this socket to a network interface by name, e.g. `eth0`, or
+    /// remove the binding with `None`, the `SO_BINDTODEVICE` option.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn bind_device(&self, interface: Option<&[u8]>) -> io::Result<()> {
+        self.inner.bind_device(interface)
+    }
+
+    /// Get the name of the interface this socket is bound to.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn device(&self) -> io::Result<Option<Vec<u8>>> {
+        self.inner.device()
+    }
+
+    /// Bind this socket to a local address.
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        self.inner.bind(&addr.into())
+    }
+
+    /// Returns the local address that this socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner
+            .local_addr()
+            .map(|addr| addr.as_socket().expect("tcp socket is expected"))
+    }
+
+    /// Connect this socket to `addr`, turning it into a [`TcpStream`].
+    pub async fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        let fd = SharedFd::new(self.inner.into_raw_fd())?;
+        TcpStream::connect_with(Op::connect_fd_raw(fd, addr), None).await
+    }
+
+    /// Listen on this socket with at most `backlog` pending connections,
+    /// turning it into a [`TcpListener`].
+    pub fn listen(self, backlog: i32) -> io::Result<TcpListener> {
+        self.inner.listen(backlog)?;
+        let fd = SharedFd::new(self.inner.into_raw_fd())?;
+        Ok(TcpListener::from_shared_fd(fd))
+    }
+}
+
+impl AsRawFd for TcpSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for TcpSocket {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl FromRawFd for TcpSocket {
+    /// The socket must be a TCP socket, non-blocking with the legacy driver.
+    #[inline]
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self {
+            inner: socket2::Socket::from_raw_fd(fd),
+        }
+    }
+}
+
+impl std::fmt::Debug for TcpSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TcpSocket")
+            .field("fd", &self.as_raw_fd())
+            .finish()
+    }
+}
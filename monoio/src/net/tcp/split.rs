@@ -1,9 +1,16 @@
-use std::{io, net::SocketAddr};
+use std::{
+    io,
+    net::SocketAddr,
+    task::{Context, Poll},
+};
 
 use super::TcpStream;
-use crate::io::{
-    as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
-    OwnedReadHalf, OwnedWriteHalf, ReadHalf, WriteHalf,
+use crate::{
+    buf::ProvidedBuf,
+    io::{
+        as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
+        OwnedReadHalf, OwnedWriteHalf, ReadHalf, WriteHalf,
+    },
 };
 
 /// ReadHalf.
@@ -11,24 +18,6 @@ pub type TcpReadHalf<'a> = ReadHalf<'a, TcpStream>;
 /// WriteHalf
 pub type TcpWriteHalf<'a> = WriteHalf<'a, TcpStream>;
 
-#[allow(clippy::cast_ref_to_mut)]
-impl<'t> AsReadFd for TcpReadHalf<'t> {
-    #[inline]
-    fn as_reader_fd(&mut self) -> &SharedFdWrapper {
-        let raw_stream = unsafe { &mut *(self.0 as *const TcpStream as *mut TcpStream) };
-        raw_stream.as_reader_fd()
-    }
-}
-
-#[allow(clippy::cast_ref_to_mut)]
-impl<'t> AsWriteFd for TcpWriteHalf<'t> {
-    #[inline]
-    fn as_writer_fd(&mut self) -> &SharedFdWrapper {
-        let raw_stream = unsafe { &mut *(self.0 as *const TcpStream as *mut TcpStream) };
-        raw_stream.as_writer_fd()
-    }
-}
-
 /// OwnedReadHalf.
 pub type TcpOwnedReadHalf = OwnedReadHalf<TcpStream>;
 /// OwnedWriteHalf
@@ -56,6 +45,16 @@ impl AsReadFd for TcpOwnedReadHalf {
         let raw_stream = unsafe { &mut *self.0.get() };
         raw_stream.as_reader_fd()
     }
+
+    #[inline]
+    fn poll_take_received(
+        &mut self,
+        cx: &mut Context<'_>,
+        len: usize,
+    ) -> Poll<Option<io::Result<ProvidedBuf>>> {
+        let raw_stream = unsafe { &mut *self.0.get() };
+        raw_stream.poll_take_received(cx, len)
+    }
 }
 
 impl TcpOwnedWriteHalf {
@@ -7,15 +7,24 @@ use std::{
     future::Future,
     io,
     net::{SocketAddr, ToSocketAddrs},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+#[cfg(all(unix, feature = "poll-io"))]
+use super::TcpStreamPoll;
+use super::{read_buf::ReadBuffer, RecvStream, TcpKeepalive};
+#[cfg(unix)]
+use crate::driver::op::shutdown::shutdown;
 use crate::{
-    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
-    driver::{op::Op, shared_fd::SharedFd},
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut, ProvidedBuf},
+    driver::{
+        op::{connect::Connect, Op},
+        shared_fd::SharedFd,
+    },
     io::{
         as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
-        AsyncReadRent, AsyncWriteRent, Split,
+        AsyncReadRent, AsyncWriteRent, CancelHandle, CancelableAsyncReadRent,
+        CancelableAsyncWriteRent, Split,
     },
 };
 
@@ -25,6 +34,9 @@ const EMPTY_SLICE: [u8; 0] = [];
 pub struct TcpStream {
     fd: SharedFd,
     meta: StreamMeta,
+    read_buf: Option<ReadBuffer>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
 }
 
 /// TcpStream is safe to split to two parts
@@ -40,7 +52,13 @@ impl TcpStream {
         // enable SOCK_ZEROCOPY
         meta.set_zero_copy();
 
-        Self { fd, meta }
+        Self {
+            fd,
+            meta,
+            read_buf: None,
+            read_timeout: None,
+            write_timeout: None,
+        }
     }
 
     /// Open a TCP connection to a remote host.
@@ -52,7 +70,7 @@ impl TcpStream {
         let addr = addr
             .to_socket_addrs()?
             .next()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "empty address"))?;
+            .ok_or_else(|| io::Error::other("empty address"))?;
 
         Self::connect_addr(addr).await
     }
@@ -60,14 +78,49 @@ impl TcpStream {
     #[cfg(unix)]
     /// Establishe a connection to the specified `addr`.
     pub async fn connect_addr(addr: SocketAddr) -> io::Result<Self> {
-        let op = Op::connect(libc::SOCK_STREAM, addr)?;
-        let completion = op.await;
+        Self::connect_with(Op::connect_raw(libc::SOCK_STREAM, addr)?, None).await
+    }
+
+    #[cfg(unix)]
+    /// Establish a connection to the specified `addr`, or fail with
+    /// [`io::ErrorKind::TimedOut`] if it is not established within `timeout`.
+    ///
+    /// With the io_uring driver, a timeout linked to the connect cancels it in
+    /// the kernel. With the legacy driver, the connect is given up by a timer,
+    /// so the timer must be enabled. The socket is closed on timeout.
+    ///
+    /// An error is returned if the zero [`Duration`] is passed.
+    pub async fn connect_timeout(addr: SocketAddr, timeout: Duration) -> io::Result<Self> {
+        let timeout = check_timeout(Some(timeout))?;
+        Self::connect_with(Op::connect_raw(libc::SOCK_STREAM, addr)?, timeout).await
+    }
+
+    #[cfg(unix)]
+    pub(crate) async fn connect_with(
+        connect: Connect,
+        timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let completion = Op::submit_with_timeout(connect, timeout)?.await;
         completion.meta.result?;
 
-        let mut stream = TcpStream::from_shared_fd(completion.data.fd);
-        // wait write ready
+        let stream = TcpStream::from_shared_fd(completion.data.fd);
+        // wait write ready, which the legacy driver does not wait for in the
+        // connect op, in the time left
         // TODO: not use write to detect writable
-        let _ = stream.write(&EMPTY_SLICE).await;
+        let timeout = match deadline {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(left) if !left.is_zero() => Some(left),
+                _ => return Err(io::Error::from_raw_os_error(libc::ETIMEDOUT)),
+            },
+            None => None,
+        };
+        let op = Op::submit_with_timeout(Op::send_raw(&stream.fd, &EMPTY_SLICE), timeout)?;
+        if let (Err(e), _) = op.write().await {
+            if deadline.is_some() && e.kind() == io::ErrorKind::TimedOut {
+                return Err(e);
+            }
+        }
         // getsockopt
         let sys_socket = unsafe { std::net::TcpStream::from_raw_fd(stream.fd.raw_fd()) };
         let err = sys_socket.take_error();
@@ -75,6 +128,8 @@ impl TcpStream {
         if let Some(e) = err? {
             return Err(e);
         }
+        #[cfg(feature = "metrics")]
+        crate::metrics::tcp_connected();
         Ok(stream)
     }
 
@@ -116,7 +171,272 @@ impl TcpStream {
         interval: Option<Duration>,
         retries: Option<u32>,
     ) -> io::Result<()> {
-        self.meta.set_tcp_keepalive(time, interval, retries)
+        let mut keepalive = TcpKeepalive::new();
+        if let Some(time) = time {
+            keepalive = keepalive.with_time(time);
+        }
+        if let Some(interval) = interval {
+            keepalive = keepalive.with_interval(interval);
+        }
+        if let Some(retries) = retries {
+            keepalive = keepalive.with_retries(retries);
+        }
+        self.set_keepalive(Some(keepalive))
+    }
+
+    /// Get the value of the `SO_KEEPALIVE` option on this socket.
+    #[inline]
+    pub fn keepalive(&self) -> io::Result<bool> {
+        self.meta.keepalive()
+    }
+
+    /// Enable keepalive on this socket with the given parameters, or disable
+    /// it with `None`.
+    #[inline]
+    pub fn set_keepalive(&self, keepalive: Option<TcpKeepalive>) -> io::Result<()> {
+        self.meta.set_keepalive(keepalive)
+    }
+
+    /// Get the value of the `IP_TTL` option on this socket.
+    #[inline]
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.meta.ttl()
+    }
+
+    /// Set the value of the `IP_TTL` option on this socket, the time-to-live
+    /// of every packet sent from it.
+    #[inline]
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.meta.set_ttl(ttl)
+    }
+
+    /// Get the value of the `SO_LINGER` option on this socket.
+    #[inline]
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.meta.linger()
+    }
+
+    /// Set the value of the `SO_LINGER` option on this socket.
+    ///
+    /// With a timeout, closing the socket waits until the unsent data is sent
+    /// or the timeout expires, which blocks the thread if the socket is closed
+    /// in place, e.g. with the legacy driver. A zero timeout resets the
+    /// connection on close instead.
+    #[inline]
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.meta.set_linger(linger)
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket, clearing it.
+    #[inline]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.meta.take_error()
+    }
+
+    /// Shut down the read half, the write half or both halves of the stream.
+    ///
+    /// Shutting down the write half sends a FIN, so the peer reads eof while
+    /// it can still send data back. It is what
+    /// [`AsyncWriteRent::shutdown`](crate::io::AsyncWriteRent::shutdown) does.
+    ///
+    /// With the io_uring driver, it is done by the shutdown op, which falls
+    /// back to the syscall on kernels before 5.11 if the `legacy` feature is
+    /// enabled.
+    #[cfg(unix)]
+    pub async fn shutdown_with(&self, how: std::net::Shutdown) -> io::Result<()> {
+        shutdown(&self.fd, how).await
+    }
+
+    /// Get the timeout of reads on this stream.
+    #[inline]
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    /// Set the timeout of reads on this stream, `None` for no timeout. A read
+    /// not done in time fails with [`io::ErrorKind::TimedOut`].
+    ///
+    /// With the io_uring driver, a timeout linked to every recv cancels it in
+    /// the kernel. With the legacy driver, the read is given up by a timer, so
+    /// the timer must be enabled.
+    ///
+    /// An error is returned if the zero [`Duration`] is passed.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.read_timeout = check_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// Get the timeout of writes on this stream.
+    #[inline]
+    pub fn write_timeout(&self) -> Option<Duration> {
+        self.write_timeout
+    }
+
+    /// Set the timeout of writes on this stream, `None` for no timeout. A
+    /// write not done in time fails with [`io::ErrorKind::TimedOut`].
+    ///
+    /// See [`set_read_timeout`](Self::set_read_timeout) about how the timeout
+    /// works.
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.write_timeout = check_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// Put the socket into the fixed file table set up by
+    /// [`RuntimeBuilder::with_fixed_files`](crate::RuntimeBuilder::with_fixed_files),
+    /// so its ops refer to it by slot and the kernel does not look up the fd
+    /// for every op. The slot is freed when the socket is closed.
+    ///
+    /// Returns `false` if the table is full or not set up, or with the legacy
+    /// driver.
+    pub fn register_fixed(&self) -> io::Result<bool> {
+        self.fd.register_fixed()
+    }
+
+    /// Enable internal read buffering with given capacity.
+    ///
+    /// With read buffering enabled, reads smaller than the capacity are served
+    /// from a stream owned buffer which is refilled by one large recv. This
+    /// collapses many tiny reads into few large ones, which is useful for chatty
+    /// protocols doing many small reads(e.g. reading a fixed size header first).
+    /// Reads not smaller than the capacity bypass the buffer when it is empty.
+    ///
+    /// With the io_uring driver, the buffer is filled by a multishot recv into
+    /// a ring of 4 buffers of `capacity` bytes, which stays armed between
+    /// reads, so no recv is submitted while data keeps arriving. Reads of any
+    /// size are served from the buffer while it is armed. It is stopped by
+    /// [`recv_stream`](TcpStream::recv_stream), [`into_std`](TcpStream::into_std),
+    /// splicing and poll-style IO. With a read timeout, or if the kernel does not
+    /// support it (before 6.0), one recv refills the buffer like with the
+    /// legacy driver.
+    ///
+    /// Calling it again only changes the capacity used for the next refill by
+    /// one recv, the buffers of the ring keep their size.
+    pub fn enable_read_buffer(&mut self, capacity: usize) {
+        match self.read_buf.as_mut() {
+            Some(read_buf) => read_buf.capacity = capacity,
+            None => self.read_buf = Some(ReadBuffer::new(capacity)),
+        }
+    }
+
+    /// Return the number of bytes buffered by internal read buffering.
+    #[inline]
+    pub fn read_buffered(&self) -> usize {
+        self.read_buf
+            .as_ref()
+            .map(ReadBuffer::len)
+            .unwrap_or_default()
+    }
+
+    async fn buffered_read<T: IoBufMut>(
+        &mut self,
+        mut buf: T,
+        c: Option<&CancelHandle>,
+    ) -> crate::BufResult<usize, T> {
+        let read_buf = self.read_buf.as_ref().expect("read buffer not enabled");
+
+        // Large reads go to the user buffer directly.
+        if read_buf.is_idle() && buf.bytes_total() >= read_buf.capacity {
+            let op = Op::recv_raw(&self.fd, buf);
+            let op = Op::submit_with_timeout(op, self.read_timeout).unwrap();
+            let _guard = c.map(|c| c.associate_op(op.op_canceller()));
+            return op.read().await;
+        }
+        read_from(read_buf, &self.fd, self.read_timeout, c, buf, true).await
+    }
+
+    // Serve a vectored read from the read buffer, into the first buffer.
+    async fn buffered_readv<T: IoVecBufMut>(
+        &mut self,
+        mut buf: T,
+        c: Option<&CancelHandle>,
+    ) -> crate::BufResult<usize, T> {
+        let read_buf = self.read_buf.as_ref().expect("read buffer not enabled");
+        // # Safety
+        // The raw buffer is only used before the future returns the iovec.
+        let raw_buf = match unsafe { crate::buf::RawBuf::new_from_iovec_mut(&mut buf) } {
+            Some(raw_buf) => raw_buf,
+            None => return (Ok(0), buf),
+        };
+        let (res, _) = read_from(read_buf, &self.fd, self.read_timeout, c, raw_buf, true).await;
+        if let Ok(n) = res {
+            unsafe { buf.set_init(n) };
+        }
+        (res, buf)
+    }
+
+    /// Receive into `buf` without removing the data from the socket, so the
+    /// next read returns it again, e.g. to sniff the protocol before handing
+    /// the stream to a parser. Returns how many bytes were peeked, 0 on EOF.
+    ///
+    /// Waits until data is available, and fails like a read after the read
+    /// timeout. Bytes held by the read buffer are peeked first, without
+    /// waiting for more.
+    pub async fn peek<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
+        if let Some(read_buf) = self.read_buf.as_ref().filter(|b| !b.is_idle()) {
+            return read_from(read_buf, &self.fd, self.read_timeout, None, buf, false).await;
+        }
+        let op = Op::peek_raw(&self.fd, buf);
+        Op::submit_with_timeout(op, self.read_timeout)
+            .unwrap()
+            .read()
+            .await
+    }
+
+    /// Receive up to `len` bytes into a buffer the kernel picks from the
+    /// provided buffers set up by
+    /// [`RuntimeBuilder::with_provided_buffers`](crate::RuntimeBuilder::with_provided_buffers),
+    /// so no buffer is held while waiting for data. An empty buffer is
+    /// returned on EOF.
+    ///
+    /// A heap buffer is used instead without provided buffers, when all of
+    /// them are in use, or with the legacy driver. Bytes held by the read
+    /// buffer are returned first.
+    pub async fn recv_provided(&mut self, len: usize) -> io::Result<ProvidedBuf> {
+        if let Some(read_buf) = self.read_buf.as_ref().filter(|b| !b.is_idle()) {
+            read_buf.fill(&self.fd, self.read_timeout, None).await?;
+            return read_buf
+                .take_buf(len)
+                .unwrap_or_else(|| Ok(ProvidedBuf::heap(Vec::new())));
+        }
+        Op::recv_provided(&self.fd, len).await
+    }
+
+    /// Receive into buffers of `buf_size` bytes as a stream, for protocols
+    /// which handle data as it arrives.
+    ///
+    /// With the io_uring driver, a ring of `count` buffers is registered for
+    /// the stream and a single multishot recv fills them, see [`RecvStream`].
+    /// Bytes held by the read buffer are yielded first, once the recv filling
+    /// it is stopped.
+    ///
+    /// `count` must be in `1..=32768`.
+    pub fn recv_stream(&mut self, buf_size: usize, count: u16) -> io::Result<RecvStream<'_>> {
+        RecvStream::new(&self.fd, self.read_buf.as_ref(), buf_size, count)
+    }
+
+    /// Convert to a [`TcpStreamPoll`] which does poll-style IO over borrowed
+    /// buffers, for crates built on tokio `AsyncRead` and `AsyncWrite`. Bytes
+    /// held by the read buffer are read first.
+    #[cfg(all(unix, feature = "poll-io"))]
+    pub fn into_poll_io(self) -> TcpStreamPoll {
+        TcpStreamPoll::new(self)
+    }
+
+    // Copy bytes held by the read buffer to `buf`, if there are any, once the
+    // recv filling it is stopped.
+    #[cfg(all(unix, feature = "poll-io"))]
+    pub(crate) fn poll_take_buffered(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<Option<io::Result<usize>>> {
+        let read_buf = match self.read_buf.as_ref() {
+            Some(read_buf) => read_buf,
+            None => return std::task::Poll::Ready(None),
+        };
+        ready!(read_buf.poll_stop(cx));
+        std::task::Poll::Ready(read_buf.take_ready(buf.as_mut_ptr(), buf.len(), true))
     }
 
     /// Creates new `TcpStream` from a `std::net::TcpStream`.
@@ -124,6 +444,25 @@ impl TcpStream {
         let fd = stream.into_raw_fd();
         Ok(Self::from_shared_fd(SharedFd::new(fd)?))
     }
+
+    /// Convert to a `std::net::TcpStream` once the in-flight ops on the socket
+    /// complete, e.g. to pass it to another library or process.
+    ///
+    /// Unlike [`IntoRawFd::into_raw_fd`], which panics if ops dropped before
+    /// completion still use the socket, it waits for them. Without the
+    /// `async-cancel` feature, such ops are not canceled, so it waits until
+    /// they are done. The socket is deregistered from the driver and put back
+    /// into blocking mode. Bytes held by the read buffer are lost.
+    #[cfg(unix)]
+    pub async fn into_std(self) -> io::Result<std::net::TcpStream> {
+        if let Some(read_buf) = &self.read_buf {
+            std::future::poll_fn(|cx| read_buf.poll_stop(cx)).await;
+        }
+        let fd = self.fd.drain_unwrap().await;
+        let stream = unsafe { std::net::TcpStream::from_raw_fd(fd) };
+        stream.set_nonblocking(false)?;
+        Ok(stream)
+    }
 }
 
 impl AsReadFd for TcpStream {
@@ -131,6 +470,17 @@ impl AsReadFd for TcpStream {
     fn as_reader_fd(&mut self) -> &SharedFdWrapper {
         SharedFdWrapper::new(&self.fd)
     }
+
+    fn poll_take_received(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        len: usize,
+    ) -> std::task::Poll<Option<io::Result<ProvidedBuf>>> {
+        match self.read_buf.as_ref() {
+            Some(read_buf) => read_buf.poll_take(cx, len),
+            None => std::task::Poll::Ready(None),
+        }
+    }
 }
 
 impl AsWriteFd for TcpStream {
@@ -181,69 +531,182 @@ impl std::fmt::Debug for TcpStream {
 }
 
 impl AsyncWriteRent for TcpStream {
-    type WriteFuture<'a, B> = impl Future<Output = crate::BufResult<usize, B>> where
-        B: IoBuf + 'a;
-    type WritevFuture<'a, B> = impl Future<Output = crate::BufResult<usize, B>> where
-        B: IoVecBuf + 'a;
-    type FlushFuture<'a> = impl Future<Output = io::Result<()>>;
-    type ShutdownFuture<'a> = impl Future<Output = io::Result<()>>;
-
     #[inline]
-    fn write<T: IoBuf>(&mut self, buf: T) -> Self::WriteFuture<'_, T> {
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = crate::BufResult<usize, T>> {
         // Submit the write operation
-        let op = Op::send(&self.fd, buf).unwrap();
-        op.write()
+        let op = Op::send_raw(&self.fd, buf);
+        Op::submit_with_timeout(op, self.write_timeout)
+            .unwrap()
+            .write()
     }
 
     #[inline]
-    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> Self::WritevFuture<'_, T> {
-        let op = Op::writev(&self.fd, buf_vec).unwrap();
-        op.write()
+    fn writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        let op = Op::writev_raw(&self.fd, buf_vec);
+        Op::submit_with_timeout(op, self.write_timeout)
+            .unwrap()
+            .write()
     }
 
     #[inline]
-    fn flush(&mut self) -> Self::FlushFuture<'_> {
+    async fn flush(&mut self) -> std::io::Result<()> {
         // Tcp stream does not need flush.
-        async move { Ok(()) }
+        Ok(())
     }
 
     #[cfg(unix)]
-    fn shutdown(&mut self) -> Self::ShutdownFuture<'_> {
-        // We could use shutdown op here, which requires kernel 5.11+.
-        // However, for simplicity, we just close the socket using direct syscall.
-        let fd = self.as_raw_fd();
-        let res = match unsafe { libc::shutdown(fd, libc::SHUT_WR) } {
-            -1 => Err(io::Error::last_os_error()),
-            _ => Ok(()),
-        };
-        async move { res }
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
+        shutdown(&self.fd, std::net::Shutdown::Write)
     }
 
     #[cfg(windows)]
-    fn shutdown(&mut self) -> Self::ShutdownFuture<'_> {
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
         async { unimplemented!() }
     }
 }
 
 impl AsyncReadRent for TcpStream {
-    type ReadFuture<'a, B> = impl std::future::Future<Output = crate::BufResult<usize, B>> where
-        B: IoBufMut + 'a;
-    type ReadvFuture<'a, B> = impl std::future::Future<Output = crate::BufResult<usize, B>> where
-        B: IoVecBufMut + 'a;
-
     #[inline]
-    fn read<T: IoBufMut>(&mut self, buf: T) -> Self::ReadFuture<'_, T> {
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = crate::BufResult<usize, T>> {
         // Submit the read operation
-        let op = Op::recv(&self.fd, buf).unwrap();
-        op.read()
+        let op = match self.read_buf {
+            None => {
+                let op = Op::recv_raw(&self.fd, buf);
+                Ok(Op::submit_with_timeout(op, self.read_timeout).unwrap())
+            }
+            Some(_) => Err(buf),
+        };
+        async move {
+            match op {
+                Ok(op) => op.read().await,
+                Err(buf) => self.buffered_read(buf, None).await,
+            }
+        }
     }
 
     #[inline]
-    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> Self::ReadvFuture<'_, T> {
-        // Submit the read operation
-        let op = Op::readv(&self.fd, buf).unwrap();
-        op.read()
+    fn readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
+        // Serve from the internal buffer if there is data buffered or a recv
+        // filling it is in flight
+        let op = match self.read_buf.as_ref() {
+            Some(read_buf) if !read_buf.is_idle() => Err(buf),
+            // Submit the read operation
+            _ => {
+                let op = Op::readv_raw(&self.fd, buf);
+                Ok(Op::submit_with_timeout(op, self.read_timeout).unwrap())
+            }
+        };
+        async move {
+            match op {
+                Ok(op) => op.read().await,
+                Err(buf) => self.buffered_readv(buf, None).await,
+            }
+        }
+    }
+}
+
+impl CancelableAsyncReadRent for TcpStream {
+    async fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if let Err(e) = c.check() {
+            return (Err(e), buf);
+        }
+        if self.read_buf.is_some() {
+            return self.buffered_read(buf, Some(&c)).await;
+        }
+        let op = Op::recv_raw(&self.fd, buf);
+        let op = Op::submit_with_timeout(op, self.read_timeout).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.read().await
+    }
+
+    async fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if let Err(e) = c.check() {
+            return (Err(e), buf);
+        }
+        if matches!(&self.read_buf, Some(read_buf) if !read_buf.is_idle()) {
+            return self.buffered_readv(buf, Some(&c)).await;
+        }
+        let op = Op::readv_raw(&self.fd, buf);
+        let op = Op::submit_with_timeout(op, self.read_timeout).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.read().await
+    }
+}
+
+impl CancelableAsyncWriteRent for TcpStream {
+    async fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if let Err(e) = c.check() {
+            return (Err(e), buf);
+        }
+        let op = Op::send_raw(&self.fd, buf);
+        let op = Op::submit_with_timeout(op, self.write_timeout).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.write().await
+    }
+
+    async fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if let Err(e) = c.check() {
+            return (Err(e), buf_vec);
+        }
+        let op = Op::writev_raw(&self.fd, buf_vec);
+        let op = Op::submit_with_timeout(op, self.write_timeout).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.write().await
+    }
+}
+
+fn check_timeout(timeout: Option<Duration>) -> io::Result<Option<Duration>> {
+    if timeout == Some(Duration::ZERO) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot set a 0 duration timeout",
+        ));
     }
+    Ok(timeout)
+}
+
+// Copy data from the read buffer to `buf` once it is filled, consuming it if
+// `consume`.
+async fn read_from<T: IoBufMut>(
+    read_buf: &ReadBuffer,
+    fd: &SharedFd,
+    timeout: Option<Duration>,
+    c: Option<&CancelHandle>,
+    mut buf: T,
+    consume: bool,
+) -> crate::BufResult<usize, T> {
+    if let Err(e) = read_buf.fill(fd, timeout, c).await {
+        return (Err(e), buf);
+    }
+    let res = read_buf
+        .take_ready(buf.write_ptr(), buf.bytes_total(), consume)
+        .unwrap_or(Ok(0));
+    if let Ok(n) = res {
+        unsafe { buf.set_init(n) };
+    }
+    (res, buf)
 }
 
 #[cfg(all(unix, feature = "legacy", feature = "tokio-compat"))]
@@ -253,10 +716,21 @@ impl tokio::io::AsyncRead for TcpStream {
         cx: &mut std::task::Context<'_>,
         buf: &mut tokio::io::ReadBuf<'_>,
     ) -> std::task::Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if let Some(read_buf) = this.read_buf.as_ref() {
+            let slice = unsafe { buf.unfilled_mut() };
+            if let Some(res) = read_buf.take_ready(slice.as_mut_ptr() as *mut u8, slice.len(), true)
+            {
+                let n = res?;
+                unsafe { buf.assume_init(n) };
+                buf.advance(n);
+                return std::task::Poll::Ready(Ok(()));
+            }
+        }
         unsafe {
             let slice = buf.unfilled_mut();
             let raw_buf = crate::buf::RawBuf::new(slice.as_ptr() as *const u8, slice.len());
-            let mut recv = Op::recv_raw(&self.fd, raw_buf);
+            let mut recv = Op::recv_raw(&this.fd, raw_buf);
             let ret = ready!(crate::driver::op::PollLegacy::poll_legacy(&mut recv, cx));
 
             std::task::Poll::Ready(ret.result.map(|n| {
@@ -275,7 +749,7 @@ impl tokio::io::AsyncWrite for TcpStream {
         buf: &[u8],
     ) -> std::task::Poll<Result<usize, io::Error>> {
         unsafe {
-            let raw_buf = crate::buf::RawBuf::new(buf.as_ptr() as *const u8, buf.len());
+            let raw_buf = crate::buf::RawBuf::new(buf.as_ptr(), buf.len());
             let mut send = Op::send_raw(&self.fd, raw_buf);
             let ret = ready!(crate::driver::op::PollLegacy::poll_legacy(&mut send, cx));
 
@@ -390,24 +864,36 @@ impl StreamMeta {
         self.socket.as_ref().unwrap().set_nodelay(no_delay)
     }
 
-    fn set_tcp_keepalive(
-        &self,
-        time: Option<Duration>,
-        interval: Option<Duration>,
-        retries: Option<u32>,
-    ) -> io::Result<()> {
-        let mut t = socket2::TcpKeepalive::new();
-        if let Some(time) = time {
-            t = t.with_time(time)
-        }
-        if let Some(interval) = interval {
-            t = t.with_interval(interval)
-        }
-        #[cfg(unix)]
-        if let Some(retries) = retries {
-            t = t.with_retries(retries)
+    fn keepalive(&self) -> io::Result<bool> {
+        self.socket.as_ref().unwrap().keepalive()
+    }
+
+    fn set_keepalive(&self, keepalive: Option<TcpKeepalive>) -> io::Result<()> {
+        let socket = self.socket.as_ref().unwrap();
+        match keepalive {
+            Some(keepalive) => keepalive.set_on(socket),
+            None => socket.set_keepalive(false),
         }
-        self.socket.as_ref().unwrap().set_tcp_keepalive(&t)
+    }
+
+    fn ttl(&self) -> io::Result<u32> {
+        self.socket.as_ref().unwrap().ttl()
+    }
+
+    fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.socket.as_ref().unwrap().set_ttl(ttl)
+    }
+
+    fn linger(&self) -> io::Result<Option<Duration>> {
+        self.socket.as_ref().unwrap().linger()
+    }
+
+    fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.socket.as_ref().unwrap().set_linger(linger)
+    }
+
+    fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.socket.as_ref().unwrap().take_error()
     }
 
     #[cfg(feature = "zero-copy")]
@@ -430,7 +916,7 @@ impl StreamMeta {
 impl Drop for StreamMeta {
     fn drop(&mut self) {
         #[cfg(unix)]
-        self.socket.take().unwrap().into_raw_fd();
+        let _ = self.socket.take().unwrap().into_raw_fd();
         #[cfg(windows)]
         unimplemented!()
     }
@@ -0,0 +1,91 @@
+//! TLS support based on rustls.
+//!
+//! The handshake and record layer are driven through the sans-io API of
+//! rustls, and ciphertext is moved with owned buffers like any other monoio
+//! IO, so there is no poll-based compat layer in between.
+//!
+//! Session resumption and ALPN are configured on the rustls `ClientConfig` and
+//! `ServerConfig`. Resumption works as long as connections are made with the
+//! same [`TlsConnector`] or [`TlsAcceptor`], which share the config.
+
+mod stream;
+
+use std::{io, sync::Arc};
+
+pub use rustls;
+use rustls::{ClientConfig, ClientConnection, ServerConfig, ServerConnection, ServerName};
+pub use stream::TlsStream;
+
+use crate::io::{AsyncReadRent, AsyncWriteRent};
+
+/// TLS stream of the client side.
+pub type ClientTlsStream<IO> = TlsStream<IO, ClientConnection>;
+/// TLS stream of the server side.
+pub type ServerTlsStream<IO> = TlsStream<IO, ServerConnection>;
+
+/// Make TLS connections over an established stream.
+#[derive(Clone)]
+pub struct TlsConnector {
+    config: Arc<ClientConfig>,
+}
+
+impl From<Arc<ClientConfig>> for TlsConnector {
+    fn from(config: Arc<ClientConfig>) -> Self {
+        Self { config }
+    }
+}
+
+impl From<ClientConfig> for TlsConnector {
+    fn from(config: ClientConfig) -> Self {
+        Self::from(Arc::new(config))
+    }
+}
+
+impl TlsConnector {
+    /// Do client handshake over the stream.
+    pub async fn connect<IO>(
+        &self,
+        domain: ServerName,
+        stream: IO,
+    ) -> io::Result<ClientTlsStream<IO>>
+    where
+        IO: AsyncReadRent + AsyncWriteRent,
+    {
+        let session =
+            ClientConnection::new(self.config.clone(), domain).map_err(io::Error::other)?;
+        let mut stream = TlsStream::new(stream, session);
+        stream.handshake().await?;
+        Ok(stream)
+    }
+}
+
+/// Accept TLS connections over an established stream.
+#[derive(Clone)]
+pub struct TlsAcceptor {
+    config: Arc<ServerConfig>,
+}
+
+impl From<Arc<ServerConfig>> for TlsAcceptor {
+    fn from(config: Arc<ServerConfig>) -> Self {
+        Self { config }
+    }
+}
+
+impl From<ServerConfig> for TlsAcceptor {
+    fn from(config: ServerConfig) -> Self {
+        Self::from(Arc::new(config))
+    }
+}
+
+impl TlsAcceptor {
+    /// Do server handshake over the stream.
+    pub async fn accept<IO>(&self, stream: IO) -> io::Result<ServerTlsStream<IO>>
+    where
+        IO: AsyncReadRent + AsyncWriteRent,
+    {
+        let session = ServerConnection::new(self.config.clone()).map_err(io::Error::other)?;
+        let mut stream = TlsStream::new(stream, session);
+        stream.handshake().await?;
+        Ok(stream)
+    }
+}
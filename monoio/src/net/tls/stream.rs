@@ -0,0 +1,236 @@
+use std::{
+    io::{self, Read, Write},
+    ops::{Deref, DerefMut},
+};
+
+use rustls::{ConnectionCommon, SideData};
+
+use crate::{
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut, RawBuf},
+    io::{AsyncReadRent, AsyncWriteRent, AsyncWriteRentExt},
+};
+
+const DEFAULT_BUFFER_SIZE: usize = 16 * 1024;
+
+/// A TLS stream over an underlying stream.
+///
+/// Plaintext is encrypted and decrypted by the rustls session in place, and
+/// ciphertext is read and written with two internal owned buffers.
+pub struct TlsStream<IO, C> {
+    io: IO,
+    session: C,
+    // Ciphertext read from io but not yet fed to the session.
+    read_buf: Vec<u8>,
+    read_pos: usize,
+    write_buf: Vec<u8>,
+}
+
+impl<IO, C> TlsStream<IO, C> {
+    pub(crate) fn new(io: IO, session: C) -> Self {
+        Self {
+            io,
+            session,
+            read_buf: Vec::with_capacity(DEFAULT_BUFFER_SIZE),
+            read_pos: 0,
+            write_buf: Vec::with_capacity(DEFAULT_BUFFER_SIZE),
+        }
+    }
+
+    /// Get a reference to the underlying stream and the session.
+    pub fn get_ref(&self) -> (&IO, &C) {
+        (&self.io, &self.session)
+    }
+
+    /// Get a mutable reference to the underlying stream and the session.
+    pub fn get_mut(&mut self) -> (&mut IO, &mut C) {
+        (&mut self.io, &mut self.session)
+    }
+
+    /// Consume self and return the underlying stream and the session.
+    pub fn into_inner(self) -> (IO, C) {
+        (self.io, self.session)
+    }
+}
+
+impl<IO, C, SD> TlsStream<IO, C>
+where
+    IO: AsyncReadRent + AsyncWriteRent,
+    C: DerefMut + Deref<Target = ConnectionCommon<SD>>,
+    SD: SideData + 'static,
+{
+    /// Get the negotiated ALPN protocol.
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.session.alpn_protocol()
+    }
+
+    pub(crate) async fn handshake(&mut self) -> io::Result<()> {
+        while self.session.is_handshaking() {
+            if self.session.wants_write() {
+                self.write_io().await?;
+                continue;
+            }
+            if self.read_io().await? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+        // Send the remaining handshake messages, like the client Finished.
+        while self.session.wants_write() {
+            self.write_io().await?;
+        }
+        Ok(())
+    }
+
+    // Feed ciphertext to the session, reading from io if there is nothing
+    // buffered. Returns 0 on eof.
+    async fn read_io(&mut self) -> io::Result<usize> {
+        if self.read_pos == self.read_buf.len() {
+            // The buffer may be lost if the previous read was canceled.
+            let mut buf = std::mem::take(&mut self.read_buf);
+            buf.clear();
+            if buf.capacity() == 0 {
+                buf.reserve(DEFAULT_BUFFER_SIZE);
+            }
+            self.read_pos = 0;
+            let (res, buf) = self.io.read(buf).await;
+            self.read_buf = buf;
+            if res? == 0 {
+                // Let the session know the eof.
+                self.session.read_tls(&mut io::empty())?;
+                return Ok(0);
+            }
+        }
+
+        let n = self
+            .session
+            .read_tls(&mut &self.read_buf[self.read_pos..])?;
+        self.read_pos += n;
+        if let Err(e) = self.session.process_new_packets() {
+            // Try to send the alert to the peer.
+            let _ = self.write_io().await;
+            return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+        }
+        Ok(n)
+    }
+
+    // Write out ciphertext produced by the session.
+    async fn write_io(&mut self) -> io::Result<usize> {
+        let mut buf = std::mem::take(&mut self.write_buf);
+        buf.clear();
+        self.session.write_tls(&mut buf)?;
+        let (res, buf) = self.io.write_all(buf).await;
+        self.write_buf = buf;
+        res
+    }
+
+    // Read plaintext into the given raw buffer.
+    async fn read_plain(&mut self, dst: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.session.reader().read(dst) {
+                Ok(n) => return Ok(n),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+            // Send pending messages like key update before waiting for the peer.
+            while self.session.wants_write() {
+                self.write_io().await?;
+            }
+            self.read_io().await?;
+        }
+    }
+
+    // Write plaintext from the given raw buffer.
+    async fn write_plain(&mut self, src: &[u8]) -> io::Result<usize> {
+        loop {
+            let n = self.session.writer().write(src)?;
+            while self.session.wants_write() {
+                self.write_io().await?;
+            }
+            if n != 0 || src.is_empty() {
+                return Ok(n);
+            }
+        }
+    }
+}
+
+// The session reads plaintext into an initialized slice, so the possibly
+// uninitialized memory of a buffer is zeroed before the slice is created.
+unsafe fn zeroed_slice<'a>(ptr: *mut u8, len: usize) -> &'a mut [u8] {
+    std::ptr::write_bytes(ptr, 0, len);
+    std::slice::from_raw_parts_mut(ptr, len)
+}
+
+impl<IO, C, SD> AsyncReadRent for TlsStream<IO, C>
+where
+    IO: AsyncReadRent + AsyncWriteRent,
+    C: DerefMut + Deref<Target = ConnectionCommon<SD>>,
+    SD: SideData + 'static,
+{
+    async fn read<T: IoBufMut>(&mut self, mut buf: T) -> crate::BufResult<usize, T> {
+        // Safety: the buffer is owned by the future until it returns.
+        let dst = unsafe { zeroed_slice(buf.write_ptr(), buf.bytes_total()) };
+        let res = self.read_plain(dst).await;
+        if let Ok(n) = res {
+            unsafe { buf.set_init(n) };
+        }
+        (res, buf)
+    }
+
+    async fn readv<T: IoVecBufMut>(&mut self, mut buf: T) -> crate::BufResult<usize, T> {
+        // # Safety
+        // The buffer is owned by the future until it returns.
+        let res = match unsafe { RawBuf::new_from_iovec_mut(&mut buf) } {
+            Some(mut raw_buf) => {
+                let dst = unsafe { zeroed_slice(raw_buf.write_ptr(), raw_buf.bytes_total()) };
+                self.read_plain(dst).await
+            }
+            None => Ok(0),
+        };
+        if let Ok(n) = res {
+            unsafe { buf.set_init(n) };
+        }
+        (res, buf)
+    }
+}
+
+impl<IO, C, SD> AsyncWriteRent for TlsStream<IO, C>
+where
+    IO: AsyncReadRent + AsyncWriteRent,
+    C: DerefMut + Deref<Target = ConnectionCommon<SD>>,
+    SD: SideData + 'static,
+{
+    async fn write<T: IoBuf>(&mut self, buf: T) -> crate::BufResult<usize, T> {
+        let src = unsafe { std::slice::from_raw_parts(buf.read_ptr(), buf.bytes_init()) };
+        let res = self.write_plain(src).await;
+        (res, buf)
+    }
+
+    async fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> crate::BufResult<usize, T> {
+        // # Safety
+        // The buffer is owned by the future until it returns.
+        let res = match unsafe { RawBuf::new_from_iovec(&buf_vec) } {
+            Some(raw_buf) => {
+                let src =
+                    unsafe { std::slice::from_raw_parts(raw_buf.read_ptr(), raw_buf.bytes_init()) };
+                self.write_plain(src).await
+            }
+            None => Ok(0),
+        };
+        (res, buf_vec)
+    }
+
+    async fn flush(&mut self) -> std::io::Result<()> {
+        self.session.writer().flush()?;
+        while self.session.wants_write() {
+            self.write_io().await?;
+        }
+        self.io.flush().await
+    }
+
+    async fn shutdown(&mut self) -> std::io::Result<()> {
+        self.session.send_close_notify();
+        while self.session.wants_write() {
+            self.write_io().await?;
+        }
+        self.io.shutdown().await
+    }
+}
@@ -0,0 +1,485 @@
+//! UDP related.
+
+use std::{
+    io, mem,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
+    os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+};
+
+use socket2::SockAddr;
+
+use crate::{
+    buf::{Cmsg, CmsgBuf, IoBuf, IoBufMut, SingleIoVec},
+    driver::{op::Op, shared_fd::SharedFd},
+    net::RecvFromStream,
+    BufResult,
+};
+
+/// A UDP socket.
+///
+/// Datagrams are sent to and received from any address with
+/// [`send_to`](Self::send_to) and [`recv_from`](Self::recv_from), done by
+/// sendmsg and recvmsg. After [`connect`](Self::connect), datagrams are
+/// exchanged with the connected address only by [`send`](Self::send) and
+/// [`recv`](Self::recv).
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::net::UdpSocket;
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let socket = UdpSocket::bind("127.0.0.1:8080")?;
+///     let buf = vec![0; 1500];
+///     let (res, mut buf) = socket.recv_from(buf).await;
+///     let (n, addr) = res?;
+///     // Echo the datagram back.
+///     buf.truncate(n);
+///     let (res, _) = socket.send_to(buf, addr).await;
+///     res?;
+///     Ok(())
+/// }
+/// ```
+pub struct UdpSocket {
+    fd: SharedFd,
+}
+
+impl UdpSocket {
+    pub(crate) fn from_shared_fd(fd: SharedFd) -> Self {
+        Self { fd }
+    }
+
+    /// Creates a UDP socket bound to the given address.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        std::net::UdpSocket::bind(addr).and_then(Self::from_std)
+    }
+
+    /// Connects the socket to an address, so [`send`](Self::send) sends to it
+    /// and only datagrams from it are received.
+    ///
+    /// Connecting a UDP socket does not send anything, so it is done in place.
+    pub async fn connect(&self, socket_addr: SocketAddr) -> io::Result<()> {
+        let addr = SockAddr::from(socket_addr);
+        crate::syscall!(connect(self.fd.raw_fd(), addr.as_ptr(), addr.len()))?;
+        Ok(())
+    }
+
+    /// Sends the initialized bytes of `buf` as a datagram to `socket_addr`,
+    /// returning how many bytes were sent.
+    pub async fn send_to<T: IoBuf>(&self, buf: T, socket_addr: SocketAddr) -> BufResult<usize, T> {
+        let buf_vec = SingleIoVec::new(buf);
+        let op = Op::send_msg(&self.fd, buf_vec, Some(socket_addr.into()), None).unwrap();
+        let (res, (buf_vec, _)) = op.write().await;
+        (res, buf_vec.into_inner())
+    }
+
+    /// Receives a datagram into `buf`, returning its length and source
+    /// address. A datagram longer than the buffer is truncated.
+    pub async fn recv_from<T: IoBufMut>(&self, buf: T) -> BufResult<(usize, SocketAddr), T> {
+        let buf_vec = SingleIoVec::new_mut(buf);
+        let op = Op::recv_msg(&self.fd, buf_vec, None).unwrap();
+        let (res, (buf_vec, _)) = op.read().await;
+        let res = res.and_then(|meta| {
+            let addr = meta
+                .addr
+                .as_ref()
+                .and_then(SockAddr::as_socket)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "datagram has no IP source address",
+                    )
+                })?;
+            Ok((meta.len, addr))
+        });
+        (res, buf_vec.into_inner())
+    }
+
+    /// Sends the initialized bytes of `buf` as a datagram to the connected
+    /// address, returning how many bytes were sent.
+    pub async fn send<T: IoBuf>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::send(&self.fd, buf).unwrap();
+        op.write().await
+    }
+
+    /// Receives a datagram from the connected address into `buf`, returning
+    /// its length. A datagram longer than the buffer is truncated.
+    pub async fn recv<T: IoBufMut>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::recv(&self.fd, buf).unwrap();
+        op.read().await
+    }
+
+    /// Receive the next datagram into `buf` without removing it from the
+    /// socket, so the next receive returns it again. Returns its length,
+    /// truncated to the buffer like a receive.
+    pub async fn peek<T: IoBufMut>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::peek(&self.fd, buf).unwrap();
+        op.read().await
+    }
+
+    /// Sends the initialized bytes of `buf` like [`send_to`](Self::send_to),
+    /// or like [`send`](Self::send) if `dst` is `None`, with the options of
+    /// `meta`: the ECN codepoint, the source address and the GSO segment size.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::net::{
+    ///     udp::{EcnCodepoint, SendMeta},
+    ///     UdpSocket,
+    /// };
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let socket = UdpSocket::bind("0.0.0.0:0")?;
+    ///     let meta = SendMeta {
+    ///         ecn: Some(EcnCodepoint::Ect0),
+    ///         ..Default::default()
+    ///     };
+    ///     let dst = "127.0.0.1:4433".parse().unwrap();
+    ///     let (res, _) = socket.send_msg(vec![0; 1200], Some(dst), meta).await;
+    ///     res?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn send_msg<T: IoBuf>(
+        &self,
+        buf: T,
+        dst: Option<SocketAddr>,
+        meta: SendMeta,
+    ) -> BufResult<usize, T> {
+        let control = match dst.map_or_else(|| self.local_addr(), Ok) {
+            Ok(addr) => encode_send_meta(is_ipv4(addr), &meta),
+            Err(e) => Err(e),
+        };
+        let control = match control {
+            Ok(control) => control,
+            Err(e) => return (Err(e), buf),
+        };
+        let buf_vec = SingleIoVec::new(buf);
+        let control = (!control.is_empty()).then_some(control);
+        let op = Op::send_msg(&self.fd, buf_vec, dst.map(Into::into), control).unwrap();
+        let (res, (buf_vec, _)) = op.write().await;
+        (res, buf_vec.into_inner())
+    }
+
+    /// Receives a datagram into `buf` like [`recv_from`](Self::recv_from),
+    /// along with the metadata enabled by [`set_recv_ecn`](Self::set_recv_ecn),
+    /// [`set_recv_pktinfo`](Self::set_recv_pktinfo) and `set_recv_gro`.
+    pub async fn recv_msg<T: IoBufMut>(&self, buf: T) -> BufResult<RecvMeta, T> {
+        let control = CmsgBuf::with_capacity(
+            CmsgBuf::space(mem::size_of::<libc::in_pktinfo>())
+                + CmsgBuf::space(mem::size_of::<libc::in6_pktinfo>())
+                + 3 * CmsgBuf::space(mem::size_of::<libc::c_int>()),
+        );
+        let buf_vec = SingleIoVec::new_mut(buf);
+        let op = Op::recv_msg(&self.fd, buf_vec, Some(control)).unwrap();
+        let (res, (buf_vec, control)) = op.read().await;
+        let res = res.and_then(|msg| {
+            let addr = msg
+                .addr
+                .as_ref()
+                .and_then(SockAddr::as_socket)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "datagram has no IP source address",
+                    )
+                })?;
+            let mut meta = RecvMeta {
+                len: msg.len,
+                addr,
+                ecn: None,
+                dst_ip: None,
+                segment_size: None,
+            };
+            for cmsg in control.iter().flatten() {
+                decode_recv_meta(cmsg, &mut meta);
+            }
+            Ok(meta)
+        });
+        (res, buf_vec.into_inner())
+    }
+
+    /// Report the ECN codepoint of received datagrams in [`RecvMeta::ecn`].
+    pub fn set_recv_ecn(&self, on: bool) -> io::Result<()> {
+        self.set_ip_option(
+            (libc::IPPROTO_IP, libc::IP_RECVTOS),
+            (libc::IPPROTO_IPV6, libc::IPV6_RECVTCLASS),
+            on,
+        )
+    }
+
+    /// Report the local address received datagrams are sent to in
+    /// [`RecvMeta::dst_ip`], for a socket bound to a wildcard address.
+    pub fn set_recv_pktinfo(&self, on: bool) -> io::Result<()> {
+        self.set_ip_option(
+            (libc::IPPROTO_IP, libc::IP_PKTINFO),
+            (libc::IPPROTO_IPV6, libc::IPV6_RECVPKTINFO),
+            on,
+        )
+    }
+
+    /// Let the kernel coalesce received datagrams of the same size from the
+    /// same source into one buffer (GRO), reported by
+    /// [`RecvMeta::segment_size`].
+    #[cfg(target_os = "linux")]
+    pub fn set_recv_gro(&self, on: bool) -> io::Result<()> {
+        setsockopt(self.fd.raw_fd(), libc::SOL_UDP, libc::UDP_GRO, on as _)
+    }
+
+    // A v6 socket gets both options, as it may receive IPv4 datagrams on
+    // mapped addresses.
+    fn set_ip_option(
+        &self,
+        (v4_level, v4_name): (libc::c_int, libc::c_int),
+        (v6_level, v6_name): (libc::c_int, libc::c_int),
+        on: bool,
+    ) -> io::Result<()> {
+        let fd = self.fd.raw_fd();
+        if self.local_addr()?.is_ipv4() {
+            return setsockopt(fd, v4_level, v4_name, on as _);
+        }
+        setsockopt(fd, v6_level, v6_name, on as _)?;
+        // It fails on a v6 only socket, which does not need it.
+        let _ = setsockopt(fd, v4_level, v4_name, on as _);
+        Ok(())
+    }
+
+    /// Receive datagrams into buffers of `buf_size` bytes as a stream, along
+    /// with their source addresses.
+    ///
+    /// With the io_uring driver, a ring of `count` buffers is registered for
+    /// the stream and a single multishot recvmsg fills them, see
+    /// [`RecvFromStream`].
+    ///
+    /// `count` must be in `1..=32768`.
+    pub fn recv_from_stream(
+        &self,
+        buf_size: usize,
+        count: u16,
+    ) -> io::Result<RecvFromStream<'_, SocketAddr>> {
+        RecvFromStream::new(&self.fd, buf_size, count, |addr| {
+            addr.as_socket().expect("udp socket is expected")
+        })
+    }
+
+    /// Creates new `UdpSocket` from a `std::net::UdpSocket`.
+    pub fn from_std(socket: std::net::UdpSocket) -> io::Result<Self> {
+        if crate::driver::op::non_blocking() {
+            socket.set_nonblocking(true)?;
+        }
+        let fd = socket.into_raw_fd();
+        Ok(Self::from_shared_fd(SharedFd::new(fd)?))
+    }
+
+    /// Convert to a `std::net::UdpSocket` once the in-flight ops on the socket
+    /// complete, see [`TcpStream::into_std`](crate::net::TcpStream::into_std).
+    pub async fn into_std(self) -> io::Result<std::net::UdpSocket> {
+        let fd = self.fd.drain_unwrap().await;
+        let socket = unsafe { std::net::UdpSocket::from_raw_fd(fd) };
+        socket.set_nonblocking(false)?;
+        Ok(socket)
+    }
+
+    /// Returns the local address that this socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket_ref()
+            .local_addr()
+            .map(|addr| addr.as_socket().expect("udp socket is expected"))
+    }
+
+    /// Returns the address of the peer this socket is connected to.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.socket_ref()
+            .peer_addr()
+            .map(|addr| addr.as_socket().expect("udp socket is expected"))
+    }
+
+    fn socket_ref(&self) -> socket2::SockRef<'_> {
+        socket2::SockRef::from(self)
+    }
+}
+
+/// ECN codepoint of a datagram, the low two bits of its IP traffic class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcnCodepoint {
+    /// ECN capable transport, ECT(0).
+    Ect0 = 0b10,
+    /// ECN capable transport, ECT(1).
+    Ect1 = 0b01,
+    /// Congestion experienced.
+    Ce = 0b11,
+}
+
+impl EcnCodepoint {
+    /// Get the codepoint of a traffic class, or `None` if the datagram is
+    /// not ECN capable.
+    pub fn from_bits(tos: u8) -> Option<Self> {
+        match tos & 0b11 {
+            0b10 => Some(Self::Ect0),
+            0b01 => Some(Self::Ect1),
+            0b11 => Some(Self::Ce),
+            _ => None,
+        }
+    }
+}
+
+/// Options of a datagram sent by [`UdpSocket::send_msg`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SendMeta {
+    /// ECN codepoint to mark the datagram with.
+    pub ecn: Option<EcnCodepoint>,
+    /// Source address, for a socket bound to a wildcard address.
+    pub src_ip: Option<IpAddr>,
+    /// Split the buffer into datagrams of this size, the last one may be
+    /// shorter, which the kernel or the NIC does (GSO). Only supported on
+    /// Linux.
+    pub segment_size: Option<u16>,
+}
+
+/// Metadata of a datagram received by [`UdpSocket::recv_msg`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RecvMeta {
+    /// Bytes received, a datagram longer than the buffer is truncated.
+    pub len: usize,
+    /// Source address.
+    pub addr: SocketAddr,
+    /// ECN codepoint, if enabled by
+    /// [`set_recv_ecn`](UdpSocket::set_recv_ecn).
+    pub ecn: Option<EcnCodepoint>,
+    /// Local address the datagram is sent to, if enabled by
+    /// [`set_recv_pktinfo`](UdpSocket::set_recv_pktinfo).
+    pub dst_ip: Option<IpAddr>,
+    /// Size of the datagrams coalesced in the buffer, the last one may be
+    /// shorter, if enabled by `set_recv_gro` on Linux.
+    pub segment_size: Option<usize>,
+}
+
+fn is_ipv4(addr: SocketAddr) -> bool {
+    match addr {
+        SocketAddr::V4(_) => true,
+        SocketAddr::V6(addr) => addr.ip().to_ipv4_mapped().is_some(),
+    }
+}
+
+fn encode_send_meta(ipv4: bool, meta: &SendMeta) -> io::Result<CmsgBuf> {
+    let mut control = CmsgBuf::default();
+    if let Some(ecn) = meta.ecn {
+        let tos = ecn as libc::c_int;
+        if ipv4 {
+            control.push(libc::IPPROTO_IP, libc::IP_TOS, as_bytes(&tos));
+        } else {
+            control.push(libc::IPPROTO_IPV6, libc::IPV6_TCLASS, as_bytes(&tos));
+        }
+    }
+    match meta.src_ip.map(|ip| ip.to_canonical()) {
+        None => {}
+        Some(IpAddr::V4(ip)) if ipv4 => {
+            // Safety: a zeroed `in_pktinfo` is valid.
+            let mut info: libc::in_pktinfo = unsafe { mem::zeroed() };
+            info.ipi_spec_dst.s_addr = u32::from(ip).to_be();
+            control.push(libc::IPPROTO_IP, libc::IP_PKTINFO, as_bytes(&info));
+        }
+        Some(IpAddr::V6(ip)) if !ipv4 => {
+            // Safety: a zeroed `in6_pktinfo` is valid.
+            let mut info: libc::in6_pktinfo = unsafe { mem::zeroed() };
+            info.ipi6_addr.s6_addr = ip.octets();
+            control.push(libc::IPPROTO_IPV6, libc::IPV6_PKTINFO, as_bytes(&info));
+        }
+        Some(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "source and destination address families differ",
+            ))
+        }
+    }
+    if let Some(size) = meta.segment_size {
+        #[cfg(target_os = "linux")]
+        control.push(libc::SOL_UDP, libc::UDP_SEGMENT, as_bytes(&size));
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = size;
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "segmentation offload is only supported on linux",
+            ));
+        }
+    }
+    Ok(control)
+}
+
+fn decode_recv_meta(cmsg: Cmsg<'_>, meta: &mut RecvMeta) {
+    match (cmsg.level(), cmsg.ty()) {
+        // Linux reports the traffic class of IPv4 as `IP_TOS`, the BSDs as
+        // `IP_RECVTOS`, in a byte.
+        (libc::IPPROTO_IP, libc::IP_TOS | libc::IP_RECVTOS) => {
+            meta.ecn = cmsg
+                .data()
+                .first()
+                .and_then(|tos| EcnCodepoint::from_bits(*tos));
+        }
+        (libc::IPPROTO_IPV6, libc::IPV6_TCLASS) => {
+            meta.ecn = from_bytes::<libc::c_int>(cmsg.data())
+                .and_then(|class| EcnCodepoint::from_bits(class as u8));
+        }
+        (libc::IPPROTO_IP, libc::IP_PKTINFO) => {
+            if let Some(info) = from_bytes::<libc::in_pktinfo>(cmsg.data()) {
+                let ip = Ipv4Addr::from(u32::from_be(info.ipi_addr.s_addr));
+                meta.dst_ip = Some(IpAddr::V4(ip));
+            }
+        }
+        (libc::IPPROTO_IPV6, libc::IPV6_PKTINFO) => {
+            if let Some(info) = from_bytes::<libc::in6_pktinfo>(cmsg.data()) {
+                meta.dst_ip = Some(IpAddr::V6(Ipv6Addr::from(info.ipi6_addr.s6_addr)));
+            }
+        }
+        #[cfg(target_os = "linux")]
+        (libc::SOL_UDP, libc::UDP_GRO) => {
+            meta.segment_size = from_bytes::<libc::c_int>(cmsg.data()).map(|size| size as usize);
+        }
+        _ => {}
+    }
+}
+
+fn as_bytes<T>(value: &T) -> &[u8] {
+    // Safety: the value is plain old data.
+    unsafe { std::slice::from_raw_parts((value as *const T).cast(), mem::size_of::<T>()) }
+}
+
+fn from_bytes<T: Copy>(data: &[u8]) -> Option<T> {
+    // Safety: the kernel wrote a `T`, which may not be aligned.
+    (data.len() >= mem::size_of::<T>())
+        .then(|| unsafe { data.as_ptr().cast::<T>().read_unaligned() })
+}
+
+fn setsockopt(
+    fd: RawFd,
+    level: libc::c_int,
+    name: libc::c_int,
+    value: libc::c_int,
+) -> io::Result<()> {
+    crate::syscall!(setsockopt(
+        fd,
+        level,
+        name,
+        &value as *const libc::c_int as *const libc::c_void,
+        mem::size_of::<libc::c_int>() as libc::socklen_t
+    ))?;
+    Ok(())
+}
+
+impl AsRawFd for UdpSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl std::fmt::Debug for UdpSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UdpSocket").field("fd", &self.fd).finish()
+    }
+}
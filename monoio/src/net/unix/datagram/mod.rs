@@ -4,7 +4,7 @@ use std::{
     io,
     os::unix::{
         net::UnixDatagram as StdUnixDatagram,
-        prelude::{AsRawFd, IntoRawFd, RawFd},
+        prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
     },
     path::Path,
 };
@@ -13,7 +13,12 @@ use super::{
     socket_addr::{local_addr, pair, peer_addr, socket_addr},
     SocketAddr,
 };
-use crate::driver::{op::Op, shared_fd::SharedFd};
+use crate::{
+    buf::{IoBuf, IoBufMut, SingleIoVec},
+    driver::{op::Op, shared_fd::SharedFd},
+    net::RecvFromStream,
+    BufResult,
+};
 
 /// UnixDatagram
 pub struct UnixDatagram {
@@ -41,7 +46,9 @@ impl UnixDatagram {
         Ok((Self::from_std(a)?, Self::from_std(b)?))
     }
 
-    /// Connects the socket to the specified address.
+    /// Creates a Unix datagram socket connected to the specified address,
+    /// which [`send`](Self::send) sends to and [`recv`](Self::recv) receives
+    /// from.
     pub async fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
         let (addr, addr_len) = socket_addr(path.as_ref())?;
         Self::inner_connect(addr, addr_len).await
@@ -58,19 +65,101 @@ impl UnixDatagram {
         sockaddr: libc::sockaddr_un,
         socklen: libc::socklen_t,
     ) -> io::Result<Self> {
-        let op = Op::connect_unix(sockaddr, socklen)?;
+        let op = Op::connect_unix(libc::SOCK_DGRAM, sockaddr, socklen)?;
         let completion = op.await;
         completion.meta.result?;
 
         Ok(Self::from_shared_fd(completion.data.fd))
     }
 
+    /// Sends the initialized bytes of `buf` as a datagram to the socket at
+    /// `path`, returning how many bytes were sent.
+    pub async fn send_to<T: IoBuf, P: AsRef<Path>>(&self, buf: T, path: P) -> BufResult<usize, T> {
+        match socket_addr(path.as_ref()) {
+            Ok((addr, addr_len)) => {
+                self.send_to_addr(buf, SocketAddr::from_parts(addr, addr_len))
+                    .await
+            }
+            Err(e) => (Err(e), buf),
+        }
+    }
+
+    /// Sends the initialized bytes of `buf` as a datagram to an address,
+    /// e.g. the source address returned by [`recv_from`](Self::recv_from).
+    pub async fn send_to_addr<T: IoBuf>(&self, buf: T, addr: SocketAddr) -> BufResult<usize, T> {
+        let buf_vec = SingleIoVec::new(buf);
+        let op = Op::send_msg(&self.fd, buf_vec, Some(addr.to_sock_addr()), None).unwrap();
+        let (res, (buf_vec, _)) = op.write().await;
+        (res, buf_vec.into_inner())
+    }
+
+    /// Receives a datagram into `buf`, returning its length and source
+    /// address. A datagram longer than the buffer is truncated.
+    pub async fn recv_from<T: IoBufMut>(&self, buf: T) -> BufResult<(usize, SocketAddr), T> {
+        let buf_vec = SingleIoVec::new_mut(buf);
+        let op = Op::recv_msg(&self.fd, buf_vec, None).unwrap();
+        let (res, (buf_vec, _)) = op.read().await;
+        let res = res.map(|meta| {
+            // An unnamed socket may have no address at all.
+            let addr = match meta.addr {
+                Some(addr) => SocketAddr::from_sock_addr(&addr),
+                // Safety: a zeroed `sockaddr_un` is valid.
+                None => SocketAddr::from_parts(unsafe { std::mem::zeroed() }, 0),
+            };
+            (meta.len, addr)
+        });
+        (res, buf_vec.into_inner())
+    }
+
+    /// Sends the initialized bytes of `buf` as a datagram to the connected
+    /// address, returning how many bytes were sent.
+    pub async fn send<T: IoBuf>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::send(&self.fd, buf).unwrap();
+        op.write().await
+    }
+
+    /// Receives a datagram from the connected address into `buf`, returning
+    /// its length. A datagram longer than the buffer is truncated.
+    pub async fn recv<T: IoBufMut>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::recv(&self.fd, buf).unwrap();
+        op.read().await
+    }
+
+    /// Receive datagrams into buffers of `buf_size` bytes as a stream, along
+    /// with their source addresses.
+    ///
+    /// With the io_uring driver, a ring of `count` buffers is registered for
+    /// the stream and a single multishot recvmsg fills them, see
+    /// [`RecvFromStream`].
+    ///
+    /// `count` must be in `1..=32768`.
+    pub fn recv_from_stream(
+        &self,
+        buf_size: usize,
+        count: u16,
+    ) -> io::Result<RecvFromStream<'_, SocketAddr>> {
+        RecvFromStream::new(&self.fd, buf_size, count, SocketAddr::from_sock_addr)
+    }
+
     /// Creates new `UnixDatagram` from a `std::os::unix::net::UnixDatagram`.
     pub fn from_std(datagram: StdUnixDatagram) -> io::Result<Self> {
+        if crate::driver::op::non_blocking() {
+            datagram.set_nonblocking(true)?;
+        }
         let fd = datagram.into_raw_fd();
         Ok(Self::from_shared_fd(SharedFd::new(fd)?))
     }
 
+    /// Convert to a `std::os::unix::net::UnixDatagram` once the in-flight ops
+    /// on the socket complete, see
+    /// [`TcpStream::into_std`](crate::net::TcpStream::into_std).
+    pub async fn into_std(self) -> io::Result<StdUnixDatagram> {
+        let fd = self.fd.drain_unwrap().await;
+        let datagram = unsafe { StdUnixDatagram::from_raw_fd(fd) };
+        datagram.set_nonblocking(false)?;
+        Ok(datagram)
+    }
+
     /// Returns the socket address of the local half of this connection.
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
         local_addr(self.as_raw_fd())
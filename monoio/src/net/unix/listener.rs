@@ -8,7 +8,7 @@ use std::{
 use super::{socket_addr::SocketAddr, UnixStream};
 use crate::{
     driver::{op::Op, shared_fd::SharedFd},
-    io::stream::Stream,
+    io::{stream::Stream, CancelHandle},
     net::ListenerConfig,
 };
 
@@ -66,7 +66,32 @@ impl UnixListener {
 
     /// Accept
     pub async fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
+        self.accept_with(None).await
+    }
+
+    /// Accept, or fail with `ECANCELED` once the
+    /// [`Canceller`](crate::io::Canceller) of `c` is canceled.
+    pub async fn cancelable_accept(&self, c: CancelHandle) -> io::Result<(UnixStream, SocketAddr)> {
+        c.check()?;
+        self.accept_with(Some(&c)).await
+    }
+
+    /// Convert to a `std::os::unix::net::UnixListener` once the in-flight ops
+    /// on the socket complete, see
+    /// [`TcpStream::into_std`](crate::net::TcpStream::into_std).
+    pub async fn into_std(self) -> io::Result<std::os::unix::net::UnixListener> {
+        // The listener only releases its std listener on drop.
+        let fd = self.fd.clone();
+        drop(self);
+        let fd = fd.drain_unwrap().await;
+        let listener = unsafe { std::os::unix::net::UnixListener::from_raw_fd(fd) };
+        listener.set_nonblocking(false)?;
+        Ok(listener)
+    }
+
+    async fn accept_with(&self, c: Option<&CancelHandle>) -> io::Result<(UnixStream, SocketAddr)> {
         let op = Op::accept(&self.fd)?;
+        let _guard = c.map(|c| c.associate_op(op.op_canceller()));
 
         // Await the completion of the event
         let completion = op.await;
@@ -118,6 +143,6 @@ impl AsRawFd for UnixListener {
 impl Drop for UnixListener {
     #[inline]
     fn drop(&mut self) {
-        self.sys_listener.take().unwrap().into_raw_fd();
+        let _ = self.sys_listener.take().unwrap().into_raw_fd();
     }
 }
@@ -7,6 +7,8 @@ mod pipe;
 mod socket_addr;
 mod split;
 mod stream;
+#[cfg(feature = "poll-io")]
+mod stream_poll;
 mod ucred;
 
 pub use datagram::UnixDatagram;
@@ -15,9 +17,20 @@ pub use pipe::{new_pipe, Pipe};
 pub use socket_addr::SocketAddr;
 pub use split::{UnixOwnedReadHalf, UnixOwnedWriteHalf, UnixReadHalf, UnixWriteHalf};
 pub use stream::UnixStream;
+#[cfg(feature = "poll-io")]
+pub use stream_poll::UnixStreamPoll;
 
 pub(crate) fn path_offset(sockaddr: &libc::sockaddr_un) -> usize {
     let base = sockaddr as *const _ as usize;
     let path = &sockaddr.sun_path as *const _ as usize;
     path - base
 }
+
+/// Set `O_NONBLOCK` and `FD_CLOEXEC` on platforms where they can not be passed
+/// when the fd is created.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn set_nonblock_cloexec(fd: std::os::unix::io::RawFd) -> std::io::Result<()> {
+    crate::syscall!(fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK))?;
+    crate::syscall!(fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use std::{io, os::unix::prelude::RawFd};
 
-use crate::driver::shared_fd::SharedFd;
+use crate::{
+    driver::shared_fd::SharedFd,
+    io::as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
+};
 
 /// Unix pipe.
 pub struct Pipe {
@@ -18,6 +21,20 @@ impl Pipe {
     }
 }
 
+impl AsReadFd for Pipe {
+    #[inline]
+    fn as_reader_fd(&mut self) -> &SharedFdWrapper {
+        SharedFdWrapper::new(&self.fd)
+    }
+}
+
+impl AsWriteFd for Pipe {
+    #[inline]
+    fn as_writer_fd(&mut self) -> &SharedFdWrapper {
+        SharedFdWrapper::new(&self.fd)
+    }
+}
+
 /// Create a new pair of pipe.
 pub fn new_pipe() -> io::Result<(Pipe, Pipe)> {
     let mut pipes = [0 as libc::c_int; 2];
@@ -31,7 +48,17 @@ pub fn new_pipe() -> io::Result<(Pipe, Pipe)> {
     };
     #[cfg(target_os = "linux")]
     crate::syscall!(pipe2(pipes.as_mut_ptr() as _, flag))?;
+    // Without `pipe2`, the legacy driver needs the flags set by `fcntl`.
     #[cfg(not(target_os = "linux"))]
-    crate::syscall!(pipe(pipes.as_mut_ptr() as _))?;
+    {
+        crate::syscall!(pipe(pipes.as_mut_ptr() as _))?;
+        for fd in pipes {
+            if let Err(e) = super::set_nonblock_cloexec(fd) {
+                let _ = crate::syscall!(close(pipes[0]));
+                let _ = crate::syscall!(close(pipes[1]));
+                return Err(e);
+            }
+        }
+    }
     Ok((Pipe::from_raw_fd(pipes[0]), Pipe::from_raw_fd(pipes[1])))
 }
@@ -68,6 +68,34 @@ impl SocketAddr {
         SocketAddr { sockaddr, socklen }
     }
 
+    pub(crate) fn from_sock_addr(addr: &socket2::SockAddr) -> SocketAddr {
+        let mut sockaddr = unsafe { mem::zeroed::<libc::sockaddr_un>() };
+        let socklen = (addr.len() as usize).min(mem::size_of_val(&sockaddr));
+        // Safety: both are valid for `socklen` bytes.
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                addr.as_ptr() as *const u8,
+                &mut sockaddr as *mut libc::sockaddr_un as *mut u8,
+                socklen,
+            );
+        }
+        SocketAddr::from_parts(sockaddr, socklen as libc::socklen_t)
+    }
+
+    pub(crate) fn to_sock_addr(&self) -> socket2::SockAddr {
+        let mut storage = unsafe { mem::zeroed::<libc::sockaddr_storage>() };
+        // Safety: `sockaddr_storage` is larger than `sockaddr_un`, and the
+        // address is valid for `socklen` bytes of it.
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                &self.sockaddr as *const libc::sockaddr_un as *const u8,
+                &mut storage as *mut libc::sockaddr_storage as *mut u8,
+                self.socklen as usize,
+            );
+            socket2::SockAddr::new(storage, self.socklen)
+        }
+    }
+
     pub(crate) fn into_parts(self) -> (libc::sockaddr_un, libc::socklen_t) {
         (self.sockaddr, self.socklen)
     }
@@ -189,6 +217,15 @@ where
 
     let mut fds = [-1; 2];
     crate::syscall!(socketpair(libc::AF_UNIX, flags, 0, fds.as_mut_ptr()))?;
+    // Darwin doesn't have SOCK_NONBLOCK or SOCK_CLOEXEC.
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    for fd in fds {
+        if let Err(e) = super::set_nonblock_cloexec(fd) {
+            let _ = crate::syscall!(close(fds[0]));
+            let _ = crate::syscall!(close(fds[1]));
+            return Err(e);
+        }
+    }
     let pair = unsafe { (T::from_raw_fd(fds[0]), T::from_raw_fd(fds[1])) };
     Ok(pair)
 }
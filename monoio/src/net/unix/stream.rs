@@ -1,20 +1,30 @@
 use std::{
     future::Future,
     io::{self},
-    os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+    mem,
+    os::unix::{
+        io::OwnedFd,
+        prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+    },
     path::Path,
 };
 
+#[cfg(feature = "poll-io")]
+use super::UnixStreamPoll;
 use super::{
     socket_addr::{local_addr, pair, peer_addr, socket_addr, SocketAddr},
     ucred::UCred,
 };
 use crate::{
-    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
-    driver::{op::Op, shared_fd::SharedFd},
+    buf::{CmsgBuf, IoBuf, IoBufMut, IoVecBuf, IoVecBufMut, ProvidedBuf, SingleIoVec},
+    driver::{
+        op::{shutdown::shutdown, Op},
+        shared_fd::SharedFd,
+    },
     io::{
         as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
-        AsyncReadRent, AsyncWriteRent, Split,
+        AsyncReadRent, AsyncWriteRent, CancelHandle, CancelableAsyncReadRent,
+        CancelableAsyncWriteRent, Split,
     },
 };
 
@@ -50,7 +60,7 @@ impl UnixStream {
         sockaddr: libc::sockaddr_un,
         socklen: libc::socklen_t,
     ) -> io::Result<Self> {
-        let op = Op::connect_unix(sockaddr, socklen)?;
+        let op = Op::connect_unix(libc::SOCK_STREAM, sockaddr, socklen)?;
         let completion = op.await;
         completion.meta.result?;
 
@@ -76,18 +86,140 @@ impl UnixStream {
         Ok((Self::from_std(a)?, Self::from_std(b)?))
     }
 
+    /// Shut down the read half, the write half or both halves of the stream,
+    /// see [`TcpStream::shutdown_with`](crate::net::TcpStream::shutdown_with).
+    pub async fn shutdown_with(&self, how: std::net::Shutdown) -> io::Result<()> {
+        shutdown(&self.fd, how).await
+    }
+
     /// Returns effective credentials of the process which called `connect` or
     /// `pair`.
     pub fn peer_cred(&self) -> io::Result<UCred> {
         super::ucred::get_peer_cred(self)
     }
 
+    /// Put the socket into the fixed file table set up by
+    /// [`RuntimeBuilder::with_fixed_files`](crate::RuntimeBuilder::with_fixed_files),
+    /// so its ops refer to it by slot and the kernel does not look up the fd
+    /// for every op. The slot is freed when the socket is closed.
+    ///
+    /// Returns `false` if the table is full or not set up, or with the legacy
+    /// driver.
+    pub fn register_fixed(&self) -> io::Result<bool> {
+        self.fd.register_fixed()
+    }
+
+    /// Receive up to `len` bytes into a buffer the kernel picks from the
+    /// provided buffers set up by
+    /// [`RuntimeBuilder::with_provided_buffers`](crate::RuntimeBuilder::with_provided_buffers),
+    /// so no buffer is held while waiting for data. An empty buffer is
+    /// returned on EOF.
+    ///
+    /// A heap buffer is used instead without provided buffers, when all of
+    /// them are in use, or with the legacy driver.
+    pub async fn recv_provided(&mut self, len: usize) -> io::Result<ProvidedBuf> {
+        Op::recv_provided(&self.fd, len).await
+    }
+
+    /// Send the initialized bytes of `buf` along with `fds`, which are passed
+    /// to the peer by a `SCM_RIGHTS` message. Returns how many bytes were
+    /// sent.
+    ///
+    /// The peer gets its own copies of the fds, so they can be closed once
+    /// this returns. `buf` must not be empty, as the fds are delivered along
+    /// with its bytes.
+    pub async fn send_with_fds<T: IoBuf>(
+        &mut self,
+        buf: T,
+        fds: &[RawFd],
+    ) -> crate::BufResult<usize, T> {
+        let mut control = CmsgBuf::with_capacity(CmsgBuf::space(mem::size_of_val(fds)));
+        control.push_fds(fds);
+        let op = Op::send_msg(&self.fd, SingleIoVec::new(buf), None, Some(control)).unwrap();
+        let (res, (buf_vec, _)) = op.write().await;
+        (res, buf_vec.into_inner())
+    }
+
+    /// Receive into `buf` along with up to `max_fds` fds passed by the peer
+    /// with [`send_with_fds`](Self::send_with_fds). Returns how many bytes
+    /// were received and the fds, which are set close-on-exec.
+    ///
+    /// The fds come with the first bytes sent along with them, a read which
+    /// does not reach those bytes gets none. If more than `max_fds` fds were
+    /// sent, it fails with [`io::ErrorKind::InvalidData`] and all of them are
+    /// closed, while the received bytes are still in `buf`.
+    pub async fn recv_with_fds<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        max_fds: usize,
+    ) -> crate::BufResult<(usize, Vec<OwnedFd>), T> {
+        let control = CmsgBuf::with_capacity(CmsgBuf::space(max_fds * mem::size_of::<RawFd>()));
+        // The fds are set close-on-exec by the kernel, where supported, so
+        // they can not leak into a process spawned meanwhile.
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let flags = libc::MSG_CMSG_CLOEXEC;
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let flags = 0;
+        let buf_vec = SingleIoVec::new_mut(buf);
+        let op = Op::recv_msg_with_flags(&self.fd, buf_vec, Some(control), flags).unwrap();
+        let (res, (buf_vec, control)) = op.read().await;
+        let buf = buf_vec.into_inner();
+        let meta = match res {
+            Ok(meta) => meta,
+            Err(e) => return (Err(e), buf),
+        };
+        let mut fds = Vec::new();
+        for cmsg in control.iter().flatten() {
+            if let Some(raw_fds) = cmsg.fds() {
+                // Safety: the kernel installed the fds for this process, and
+                // nothing else owns them.
+                fds.extend(
+                    raw_fds
+                        .into_iter()
+                        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd) }),
+                );
+            }
+        }
+        // The control buffer may have room for more fds than asked for, as it
+        // is aligned.
+        if meta.flags & libc::MSG_CTRUNC != 0 || fds.len() > max_fds {
+            let e = io::Error::new(
+                io::ErrorKind::InvalidData,
+                "more fds were passed than max_fds",
+            );
+            return (Err(e), buf);
+        }
+        // It can only fail for a bad fd, and the data is received anyway.
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        for fd in &fds {
+            let _ = crate::syscall!(fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC));
+        }
+        (Ok((meta.len, fds)), buf)
+    }
+
     /// Creates new `UnixStream` from a `std::os::unix::net::UnixStream`.
     pub fn from_std(stream: std::os::unix::net::UnixStream) -> io::Result<Self> {
         let fd = stream.into_raw_fd();
         Ok(Self::from_shared_fd(SharedFd::new(fd)?))
     }
 
+    /// Convert to a `std::os::unix::net::UnixStream` once the in-flight ops on
+    /// the socket complete, see
+    /// [`TcpStream::into_std`](crate::net::TcpStream::into_std).
+    pub async fn into_std(self) -> io::Result<std::os::unix::net::UnixStream> {
+        let fd = self.fd.drain_unwrap().await;
+        let stream = unsafe { std::os::unix::net::UnixStream::from_raw_fd(fd) };
+        stream.set_nonblocking(false)?;
+        Ok(stream)
+    }
+
+    /// Convert to a [`UnixStreamPoll`] which does poll-style IO over borrowed
+    /// buffers, for crates built on tokio `AsyncRead` and `AsyncWrite`.
+    #[cfg(feature = "poll-io")]
+    pub fn into_poll_io(self) -> UnixStreamPoll {
+        UnixStreamPoll::new(self)
+    }
+
     /// Returns the socket address of the local half of this connection.
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
         local_addr(self.as_raw_fd())
@@ -136,66 +268,108 @@ impl std::fmt::Debug for UnixStream {
 }
 
 impl AsyncWriteRent for UnixStream {
-    type WriteFuture<'a, B> = impl Future<Output = crate::BufResult<usize, B>> where
-        B: IoBuf + 'a;
-    type WritevFuture<'a, B> = impl Future<Output = crate::BufResult<usize, B>> where
-        B: IoVecBuf + 'a;
-    type FlushFuture<'a> = impl Future<Output = io::Result<()>>;
-    type ShutdownFuture<'a> = impl Future<Output = io::Result<()>>;
-
     #[inline]
-    fn write<T: IoBuf>(&mut self, buf: T) -> Self::WriteFuture<'_, T> {
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = crate::BufResult<usize, T>> {
         // Submit the write operation
         let op = Op::send(&self.fd, buf).unwrap();
         op.write()
     }
 
     #[inline]
-    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> Self::WritevFuture<'_, T> {
+    fn writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         let op = Op::writev(&self.fd, buf_vec).unwrap();
         op.write()
     }
 
     #[inline]
-    fn flush(&mut self) -> Self::FlushFuture<'_> {
+    async fn flush(&mut self) -> std::io::Result<()> {
         // Unix stream does not need flush.
-        async move { Ok(()) }
+        Ok(())
     }
 
-    fn shutdown(&mut self) -> Self::ShutdownFuture<'_> {
-        // We could use shutdown op here, which requires kernel 5.11+.
-        // However, for simplicity, we just close the socket using direct syscall.
-        let fd = self.as_raw_fd();
-        async move {
-            match unsafe { libc::shutdown(fd, libc::SHUT_WR) } {
-                -1 => Err(io::Error::last_os_error()),
-                _ => Ok(()),
-            }
-        }
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
+        shutdown(&self.fd, std::net::Shutdown::Write)
     }
 }
 
 impl AsyncReadRent for UnixStream {
-    type ReadFuture<'a, B> = impl std::future::Future<Output = crate::BufResult<usize, B>> where
-        B: IoBufMut + 'a;
-    type ReadvFuture<'a, B> = impl std::future::Future<Output = crate::BufResult<usize, B>> where
-        B: IoVecBufMut + 'a;
-
     #[inline]
-    fn read<T: IoBufMut>(&mut self, buf: T) -> Self::ReadFuture<'_, T> {
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = crate::BufResult<usize, T>> {
         // Submit the read operation
         let op = Op::recv(&self.fd, buf).unwrap();
         op.read()
     }
 
     #[inline]
-    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> Self::ReadvFuture<'_, T> {
+    fn readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+    ) -> impl Future<Output = crate::BufResult<usize, T>> {
         // Submit the read operation
         let op = Op::readv(&self.fd, buf).unwrap();
         op.read()
     }
 }
 
+impl CancelableAsyncReadRent for UnixStream {
+    async fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if let Err(e) = c.check() {
+            return (Err(e), buf);
+        }
+        let op = Op::recv(&self.fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.read().await
+    }
+
+    async fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if let Err(e) = c.check() {
+            return (Err(e), buf);
+        }
+        let op = Op::readv(&self.fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.read().await
+    }
+}
+
+impl CancelableAsyncWriteRent for UnixStream {
+    async fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if let Err(e) = c.check() {
+            return (Err(e), buf);
+        }
+        let op = Op::send(&self.fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.write().await
+    }
+
+    async fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if let Err(e) = c.check() {
+            return (Err(e), buf_vec);
+        }
+        let op = Op::writev(&self.fd, buf_vec).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.write().await
+    }
+}
+
 #[cfg(all(unix, feature = "legacy", feature = "tokio-compat"))]
 impl tokio::io::AsyncRead for UnixStream {
     fn poll_read(
@@ -225,7 +399,7 @@ impl tokio::io::AsyncWrite for UnixStream {
         buf: &[u8],
     ) -> std::task::Poll<Result<usize, io::Error>> {
         unsafe {
-            let raw_buf = crate::buf::RawBuf::new(buf.as_ptr() as *const u8, buf.len());
+            let raw_buf = crate::buf::RawBuf::new(buf.as_ptr(), buf.len());
             let mut send = Op::send_raw(&self.fd, raw_buf);
             let ret = ready!(crate::driver::op::PollLegacy::poll_legacy(&mut send, cx));
 
@@ -0,0 +1,147 @@
+use std::{
+    io,
+    io::IoSlice,
+    os::unix::prelude::{AsRawFd, RawFd},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::{SocketAddr, UnixStream};
+use crate::{driver::op::poll::PollIo, io::as_fd::AsReadFd};
+
+/// UnixStream with poll-style IO over borrowed buffers, which implements
+/// tokio `AsyncRead` and `AsyncWrite`. See
+/// [`TcpStreamPoll`](crate::net::tcp::TcpStreamPoll).
+///
+/// The readiness of the socket is waited for before the syscall is done on the
+/// buffer of the caller. With the io_uring driver, a poll op is submitted to
+/// wait for it when the syscall would block. Read and write timeouts of the
+/// stream do not apply.
+///
+/// Created by [`UnixStream::into_poll_io`].
+pub struct UnixStreamPoll {
+    stream: UnixStream,
+    io: PollIo,
+}
+
+impl UnixStreamPoll {
+    pub(crate) fn new(mut stream: UnixStream) -> Self {
+        let fd = stream.as_reader_fd().as_ref().clone();
+        Self {
+            stream,
+            io: PollIo::new(fd),
+        }
+    }
+
+    /// Convert back to a [`UnixStream`]. Pending readiness waits are canceled.
+    pub fn into_inner(self) -> UnixStream {
+        self.stream
+    }
+
+    /// Return the local address that this stream is bound to.
+    #[inline]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.local_addr()
+    }
+
+    /// Return the remote address that this stream is connected to.
+    #[inline]
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.peer_addr()
+    }
+
+    /// Attempt to read into `buf`, returning how many bytes were read, 0 on
+    /// EOF. The current task is woken once the socket is readable if it
+    /// returns `Poll::Pending`.
+    pub fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.io.poll_recv(cx, buf)
+    }
+
+    /// Attempt to write `buf`, returning how many bytes were written. The
+    /// current task is woken once the socket is writable if it returns
+    /// `Poll::Pending`.
+    pub fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.io.poll_send(cx, buf)
+    }
+
+    /// Like [`poll_write`](Self::poll_write), but write from several buffers.
+    pub fn poll_write_vectored(
+        &mut self,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.io.poll_sendv(cx, bufs)
+    }
+
+    /// Nothing is buffered for writes, so it is always ready.
+    pub fn poll_flush(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Shut down the write half of the stream.
+    pub fn poll_shutdown(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let fd = self.as_raw_fd();
+        Poll::Ready(crate::syscall!(shutdown(fd, libc::SHUT_WR)).map(|_| ()))
+    }
+}
+
+impl AsRawFd for UnixStreamPoll {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.stream.as_raw_fd()
+    }
+}
+
+impl std::fmt::Debug for UnixStreamPoll {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnixStreamPoll")
+            .field("fd", &self.as_raw_fd())
+            .finish()
+    }
+}
+
+impl tokio::io::AsyncRead for UnixStreamPoll {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let n = ready!(UnixStreamPoll::poll_read(
+            self.get_mut(),
+            cx,
+            buf.initialize_unfilled()
+        ))?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl tokio::io::AsyncWrite for UnixStreamPoll {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        UnixStreamPoll::poll_write(self.get_mut(), cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        UnixStreamPoll::poll_write_vectored(self.get_mut(), cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        UnixStreamPoll::poll_flush(self.get_mut(), cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        UnixStreamPoll::poll_shutdown(self.get_mut(), cx)
+    }
+}
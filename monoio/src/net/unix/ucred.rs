@@ -32,6 +32,8 @@ impl UCred {
     }
 }
 
+#[cfg(any(target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd"))]
+pub(crate) use self::impl_bsd::get_peer_cred;
 #[cfg(any(target_os = "linux", target_os = "android", target_os = "openbsd"))]
 pub(crate) use self::impl_linux::get_peer_cred;
 #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -137,3 +139,33 @@ pub(crate) mod impl_linux {
         }
     }
 }
+
+#[cfg(any(target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd"))]
+pub(crate) mod impl_bsd {
+    use std::{io, mem::MaybeUninit, os::unix::io::AsRawFd};
+
+    use libc::getpeereid;
+
+    use crate::net::unix::UnixStream;
+
+    pub(crate) fn get_peer_cred(sock: &UnixStream) -> io::Result<super::UCred> {
+        unsafe {
+            let raw_fd = sock.as_raw_fd();
+
+            let mut uid = MaybeUninit::uninit();
+            let mut gid = MaybeUninit::uninit();
+
+            let ret = getpeereid(raw_fd, uid.as_mut_ptr(), gid.as_mut_ptr());
+
+            if ret == 0 {
+                Ok(super::UCred {
+                    uid: uid.assume_init(),
+                    gid: gid.assume_init(),
+                    pid: None,
+                })
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Spawn tasks across a set of runtime threads.
+//!
+//! A [`Pool`] runs one runtime per thread. Tasks spawned with its [`Handle`]
+//! are sent to a thread picked by the [`Placement`], and run there as local
+//! tasks. It is for workloads which have no natural key to shard connections
+//! or requests by.
+//!
+//! A handle can be installed with [`set_global`], then [`spawn`] can be called
+//! anywhere.
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+    task::{Context, Poll},
+    thread,
+};
+
+use crate::task::JoinError;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static GLOBAL: OnceLock<Handle> = OnceLock::new();
+
+/// How tasks are placed on threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Placement {
+    /// Pick the threads in turn.
+    #[default]
+    RoundRobin,
+    /// Pick the thread with the fewest unfinished tasks.
+    LeastLoaded,
+}
+
+struct Worker {
+    sender: flume::Sender<Job>,
+    // Tasks sent to the worker and not finished yet.
+    load: Arc<AtomicUsize>,
+}
+
+struct Shared {
+    workers: Vec<Worker>,
+    placement: Placement,
+    next: AtomicUsize,
+}
+
+/// Handle to spawn tasks on a [`Pool`].
+#[derive(Clone)]
+pub struct Handle {
+    shared: Arc<Shared>,
+}
+
+/// A set of threads each running a runtime with timer enabled.
+///
+/// The threads exit after all handles are dropped, and the tasks not finished
+/// by then are dropped.
+pub struct Pool {
+    handle: Handle,
+    threads: Vec<thread::JoinHandle<()>>,
+}
+
+impl Pool {
+    /// Start `threads` runtime threads.
+    pub fn new(threads: usize, placement: Placement) -> io::Result<Self> {
+        if threads == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "pool requires at least 1 thread",
+            ));
+        }
+
+        let mut workers = Vec::with_capacity(threads);
+        let mut handles = Vec::with_capacity(threads);
+        for idx in 0..threads {
+            let (sender, receiver) = flume::unbounded::<Job>();
+            let (built_tx, built_rx) = std::sync::mpsc::channel();
+            let handle = thread::Builder::new()
+                .name(format!("monoio-pool-{idx}"))
+                .spawn(move || {
+                    let mut rt = match crate::RuntimeBuilder::<crate::FusionDriver>::new()
+                        .enable_timer()
+                        .build()
+                    {
+                        Ok(rt) => {
+                            let _ = built_tx.send(Ok(()));
+                            rt
+                        }
+                        Err(e) => {
+                            let _ = built_tx.send(Err(e));
+                            return;
+                        }
+                    };
+                    rt.block_on(async move {
+                        while let Ok(job) = receiver.recv_async().await {
+                            job();
+                        }
+                    });
+                })?;
+            built_rx
+                .recv()
+                .map_err(|_| io::Error::other("pool thread exited"))??;
+            workers.push(Worker {
+                sender,
+                load: Arc::new(AtomicUsize::new(0)),
+            });
+            handles.push(handle);
+        }
+
+        Ok(Self {
+            handle: Handle {
+                shared: Arc::new(Shared {
+                    workers,
+                    placement,
+                    next: AtomicUsize::new(0),
+                }),
+            },
+            threads: handles,
+        })
+    }
+
+    /// Get a handle of the pool.
+    pub fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    /// Drop the handle of the pool and wait for the threads to exit.
+    pub fn join(self) {
+        drop(self.handle);
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Handle {
+    /// Spawn a `Send` future on one of the threads.
+    pub fn spawn<F>(&self, future: F) -> RemoteJoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.spawn_with(move || future)
+    }
+
+    /// Create a future with `f` on one of the threads and spawn it there.
+    ///
+    /// The future itself does not have to be `Send`, so it can hold local IO
+    /// types.
+    pub fn spawn_with<F, Fut>(&self, f: F) -> RemoteJoinHandle<Fut::Output>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let worker = self.pick();
+        let (tx, rx) = flume::bounded(1);
+        let guard = LoadGuard::new(worker.load.clone());
+        let job: Job = Box::new(move || {
+            crate::spawn(async move {
+                let output = crate::spawn(f()).catch_panic().await;
+                // Count it finished before the output is seen.
+                drop(guard);
+                let _ = tx.send(output);
+            });
+        });
+        // The job is dropped if the thread is gone, then the join handle
+        // returns an error.
+        let _ = worker.sender.send(job);
+        RemoteJoinHandle {
+            rx: rx.into_recv_async(),
+        }
+    }
+
+    fn pick(&self) -> &Worker {
+        let workers = &self.shared.workers;
+        match self.shared.placement {
+            Placement::RoundRobin => {
+                let next = self.shared.next.fetch_add(1, Ordering::Relaxed);
+                &workers[next % workers.len()]
+            }
+            Placement::LeastLoaded => workers
+                .iter()
+                .min_by_key(|worker| worker.load.load(Ordering::Relaxed))
+                .unwrap(),
+        }
+    }
+}
+
+struct LoadGuard(Arc<AtomicUsize>);
+
+impl LoadGuard {
+    fn new(load: Arc<AtomicUsize>) -> Self {
+        load.fetch_add(1, Ordering::Relaxed);
+        Self(load)
+    }
+}
+
+impl Drop for LoadGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Join handle of a task spawned with a [`Handle`]. It can be awaited on any
+/// thread, and returns [`JoinError::Panic`] if the task panicked or
+/// [`JoinError::Canceled`] if it was dropped before finishing.
+pub struct RemoteJoinHandle<T: 'static> {
+    rx: flume::r#async::RecvFut<'static, Result<T, JoinError>>,
+}
+
+impl<T> Future for RemoteJoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx)
+            .poll(cx)
+            .map(|r| r.unwrap_or(Err(JoinError::Canceled)))
+    }
+}
+
+/// Install the global handle used by [`spawn`]. The handle is given back if
+/// one is already installed.
+pub fn set_global(handle: Handle) -> Result<(), Handle> {
+    GLOBAL.set(handle)
+}
+
+/// Spawn a `Send` future with the global handle.
+///
+/// # Panics
+///
+/// Panics if no global handle is installed by [`set_global`].
+pub fn spawn<F>(future: F) -> RemoteJoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    GLOBAL
+        .get()
+        .expect("no global pool handle, call monoio::pool::set_global first")
+        .spawn(future)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn round_robin() {
+        let pool = Pool::new(2, Placement::RoundRobin).unwrap();
+        let handle = pool.handle();
+        let names = (0..4)
+            .map(|_| handle.spawn(async { thread::current().name().unwrap().to_string() }))
+            .collect::<Vec<_>>();
+        let names = futures::executor::block_on(futures::future::join_all(names))
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(names[0], "monoio-pool-0");
+        assert_eq!(names[1], "monoio-pool-1");
+        assert_eq!(names[2], "monoio-pool-0");
+        drop(handle);
+        pool.join();
+    }
+
+    #[test]
+    fn least_loaded() {
+        let pool = Pool::new(2, Placement::LeastLoaded).unwrap();
+        let handle = pool.handle();
+        let (tx, rx) = flume::bounded::<()>(0);
+        // The first task blocks thread 0, so the others go to thread 1.
+        let blocked = handle.spawn(async move {
+            let _ = rx.recv_async().await;
+        });
+        for _ in 0..3 {
+            let name = handle.spawn_with(|| {
+                // Not Send.
+                let name = Rc::new(thread::current().name().unwrap().to_string());
+                async move { name.to_string() }
+            });
+            assert_eq!(futures::executor::block_on(name).unwrap(), "monoio-pool-1");
+        }
+        tx.send(()).unwrap();
+        futures::executor::block_on(blocked).unwrap();
+        drop(handle);
+        pool.join();
+    }
+
+    #[test]
+    fn panic() {
+        let pool = Pool::new(1, Placement::RoundRobin).unwrap();
+        let handle = pool.handle();
+        let panicked = handle.spawn(async { panic!("task panicked") });
+        assert!(matches!(
+            futures::executor::block_on(panicked),
+            Err(JoinError::Panic)
+        ));
+        // The thread keeps running tasks.
+        assert_eq!(
+            futures::executor::block_on(handle.spawn(async { 1 })).unwrap(),
+            1
+        );
+        drop(handle);
+        pool.join();
+    }
+}
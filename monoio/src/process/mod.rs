@@ -0,0 +1,293 @@
+//! Child processes.
+//!
+//! The piped stdio of a child is read and written with [`AsyncReadRent`] and
+//! [`AsyncWriteRent`], and the exit of a child is waited for through a pidfd,
+//! so neither blocks the thread. Waiting requires Linux 5.3+.
+//!
+//! [`AsyncReadRent`]: crate::io::AsyncReadRent
+//! [`AsyncWriteRent`]: crate::io::AsyncWriteRent
+
+use std::{
+    ffi::OsStr,
+    io,
+    os::unix::io::RawFd,
+    path::Path,
+    process::{ExitStatus, Output, Stdio},
+};
+
+use crate::{
+    buf::SliceMut,
+    driver::{op::Op, shared_fd::SharedFd},
+    io::AsyncReadRent,
+};
+
+mod stdio;
+pub use stdio::{ChildStderr, ChildStdin, ChildStdout};
+
+/// A process builder, like [`std::process::Command`], which spawns a [`Child`]
+/// driven by the runtime.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::process::Stdio;
+///
+/// use monoio::{io::AsyncReadRentExt, process::Command};
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let mut child = Command::new("echo")
+///         .arg("hello")
+///         .stdout(Stdio::piped())
+///         .spawn()?;
+///     let stdout = child.stdout.as_mut().unwrap();
+///     let (res, buf) = stdout.read_exact(vec![0; 6]).await;
+///     res?;
+///     assert_eq!(buf, b"hello\n");
+///     assert!(child.wait().await?.success());
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Command {
+    std: std::process::Command,
+}
+
+impl Command {
+    /// Create a `Command` to run the program at `program`, see
+    /// [`std::process::Command::new`].
+    pub fn new<S: AsRef<OsStr>>(program: S) -> Self {
+        Self {
+            std: std::process::Command::new(program),
+        }
+    }
+
+    /// Add an argument to pass to the program.
+    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Self {
+        self.std.arg(arg);
+        self
+    }
+
+    /// Add multiple arguments to pass to the program.
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.std.args(args);
+        self
+    }
+
+    /// Set an environment variable of the child.
+    pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Self
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.std.env(key, val);
+        self
+    }
+
+    /// Set multiple environment variables of the child.
+    pub fn envs<I, K, V>(&mut self, vars: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.std.envs(vars);
+        self
+    }
+
+    /// Remove an environment variable the child would inherit.
+    pub fn env_remove<K: AsRef<OsStr>>(&mut self, key: K) -> &mut Self {
+        self.std.env_remove(key);
+        self
+    }
+
+    /// Clear the environment variables the child would inherit.
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.std.env_clear();
+        self
+    }
+
+    /// Set the working directory of the child.
+    pub fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
+        self.std.current_dir(dir);
+        self
+    }
+
+    /// Set the stdin of the child. With [`Stdio::piped`], it is written
+    /// through [`Child::stdin`].
+    pub fn stdin<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.std.stdin(cfg);
+        self
+    }
+
+    /// Set the stdout of the child. With [`Stdio::piped`], it is read through
+    /// [`Child::stdout`].
+    pub fn stdout<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.std.stdout(cfg);
+        self
+    }
+
+    /// Set the stderr of the child. With [`Stdio::piped`], it is read through
+    /// [`Child::stderr`].
+    pub fn stderr<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.std.stderr(cfg);
+        self
+    }
+
+    /// Gets a reference to the underlying std `Command`.
+    pub fn as_std(&self) -> &std::process::Command {
+        &self.std
+    }
+
+    /// Gets a mutable reference to the underlying std `Command`, for the
+    /// options not covered here.
+    pub fn as_std_mut(&mut self) -> &mut std::process::Command {
+        &mut self.std
+    }
+
+    /// Spawn the child. Must be called inside a monoio runtime, which then
+    /// drives its stdio.
+    pub fn spawn(&mut self) -> io::Result<Child> {
+        Child::from_std(self.std.spawn()?)
+    }
+
+    /// Spawn the child and wait for it to exit. The stdio is inherited by
+    /// default.
+    pub async fn status(&mut self) -> io::Result<ExitStatus> {
+        self.spawn()?.wait().await
+    }
+
+    /// Spawn the child and collect all of its output. The stdout and stderr
+    /// are always piped.
+    pub async fn output(&mut self) -> io::Result<Output> {
+        self.std.stdout(Stdio::piped()).stderr(Stdio::piped());
+        self.spawn()?.wait_with_output().await
+    }
+}
+
+impl From<std::process::Command> for Command {
+    fn from(std: std::process::Command) -> Self {
+        Self { std }
+    }
+}
+
+/// A child process spawned by [`Command`].
+///
+/// Like [`std::process::Child`], the child is neither killed nor waited for
+/// when dropped.
+#[derive(Debug)]
+pub struct Child {
+    /// The stdin of the child, if it is piped.
+    pub stdin: Option<ChildStdin>,
+    /// The stdout of the child, if it is piped.
+    pub stdout: Option<ChildStdout>,
+    /// The stderr of the child, if it is piped.
+    pub stderr: Option<ChildStderr>,
+    child: std::process::Child,
+    pidfd: Option<SharedFd>,
+}
+
+impl Child {
+    fn from_std(mut child: std::process::Child) -> io::Result<Self> {
+        let stdio = (|| {
+            Ok::<_, io::Error>((
+                child.stdin.take().map(ChildStdin::new).transpose()?,
+                child.stdout.take().map(ChildStdout::new).transpose()?,
+                child.stderr.take().map(ChildStderr::new).transpose()?,
+            ))
+        })();
+        let (stdin, stdout, stderr) = match stdio {
+            Ok(stdio) => stdio,
+            Err(e) => {
+                let _ = child.kill();
+                return Err(e);
+            }
+        };
+        Ok(Self {
+            stdin,
+            stdout,
+            stderr,
+            child,
+            pidfd: None,
+        })
+    }
+
+    /// Returns the OS-assigned process identifier of the child.
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Send `SIGKILL` to the child. It does not wait for the child to exit.
+    pub fn kill(&mut self) -> io::Result<()> {
+        self.child.kill()
+    }
+
+    /// Returns the exit status if the child has exited, without waiting.
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        self.child.try_wait()
+    }
+
+    /// Wait for the child to exit. The stdin is closed before waiting, so the
+    /// child does not wait for input forever.
+    pub async fn wait(&mut self) -> io::Result<ExitStatus> {
+        drop(self.stdin.take());
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Ok(status);
+            }
+            // The pidfd turns readable once the child exits.
+            Op::poll_read(self.pidfd()?)?.await.meta.result?;
+        }
+    }
+
+    /// Wait for the child to exit while collecting its piped stdout and
+    /// stderr.
+    pub async fn wait_with_output(mut self) -> io::Result<Output> {
+        let stdout = self.stdout.take();
+        let stderr = self.stderr.take();
+        let (status, stdout, stderr) =
+            crate::try_join!(self.wait(), read_to_end(stdout), read_to_end(stderr))?;
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    fn pidfd(&mut self) -> io::Result<&SharedFd> {
+        if self.pidfd.is_none() {
+            let pid = self.child.id() as libc::pid_t;
+            let fd = crate::syscall!(syscall(libc::SYS_pidfd_open, pid, 0))? as RawFd;
+            match SharedFd::new(fd) {
+                Ok(fd) => self.pidfd = Some(fd),
+                Err(e) => {
+                    let _ = crate::syscall!(close(fd));
+                    return Err(e);
+                }
+            }
+        }
+        Ok(self.pidfd.as_ref().unwrap())
+    }
+}
+
+async fn read_to_end<T: AsyncReadRent>(io: Option<T>) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let Some(mut io) = io else {
+        return Ok(buf);
+    };
+    loop {
+        if buf.len() == buf.capacity() {
+            buf.reserve(4096);
+        }
+        let (len, cap) = (buf.len(), buf.capacity());
+        let (res, slice) = io.read(SliceMut::new(buf, len, cap)).await;
+        buf = slice.into_inner();
+        if res? == 0 {
+            return Ok(buf);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    future::Future,
+    io,
+    os::unix::io::{AsRawFd, IntoRawFd, RawFd},
+};
+
+use crate::{
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
+    driver::{op::Op, shared_fd::SharedFd},
+    io::{AsyncReadRent, AsyncWriteRent},
+    BufResult,
+};
+
+/// The stdin of a child, written with [`AsyncWriteRent`]. Drop it to close
+/// the pipe.
+#[derive(Debug)]
+pub struct ChildStdin {
+    fd: SharedFd,
+}
+
+/// The stdout of a child, read with [`AsyncReadRent`].
+#[derive(Debug)]
+pub struct ChildStdout {
+    fd: SharedFd,
+}
+
+/// The stderr of a child, read with [`AsyncReadRent`].
+#[derive(Debug)]
+pub struct ChildStderr {
+    fd: SharedFd,
+}
+
+impl ChildStdin {
+    pub(super) fn new(stdin: std::process::ChildStdin) -> io::Result<Self> {
+        pipe_fd(stdin.into_raw_fd()).map(|fd| Self { fd })
+    }
+}
+
+impl ChildStdout {
+    pub(super) fn new(stdout: std::process::ChildStdout) -> io::Result<Self> {
+        pipe_fd(stdout.into_raw_fd()).map(|fd| Self { fd })
+    }
+}
+
+impl ChildStderr {
+    pub(super) fn new(stderr: std::process::ChildStderr) -> io::Result<Self> {
+        pipe_fd(stderr.into_raw_fd()).map(|fd| Self { fd })
+    }
+}
+
+// The legacy driver waits for readiness, so the pipe must not block there.
+fn pipe_fd(fd: RawFd) -> io::Result<SharedFd> {
+    let res = if crate::driver::op::non_blocking() {
+        crate::syscall!(fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK)).and_then(|_| SharedFd::new(fd))
+    } else {
+        SharedFd::new(fd)
+    };
+    if res.is_err() {
+        let _ = crate::syscall!(close(fd));
+    }
+    res
+}
+
+impl AsyncWriteRent for ChildStdin {
+    #[inline]
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::write_stream(&self.fd, buf).unwrap();
+        op.write()
+    }
+
+    #[inline]
+    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::writev(&self.fd, buf_vec).unwrap();
+        op.write()
+    }
+
+    #[inline]
+    async fn flush(&mut self) -> io::Result<()> {
+        // Pipe does not need flush.
+        Ok(())
+    }
+
+    #[inline]
+    async fn shutdown(&mut self) -> io::Result<()> {
+        // A pipe can not be shut down, it is closed on drop.
+        Ok(())
+    }
+}
+
+impl AsyncReadRent for ChildStdout {
+    #[inline]
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::read_stream(&self.fd, buf).unwrap();
+        op.read()
+    }
+
+    #[inline]
+    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::readv(&self.fd, buf).unwrap();
+        op.read()
+    }
+}
+
+impl AsyncReadRent for ChildStderr {
+    #[inline]
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::read_stream(&self.fd, buf).unwrap();
+        op.read()
+    }
+
+    #[inline]
+    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::readv(&self.fd, buf).unwrap();
+        op.read()
+    }
+}
+
+impl AsRawFd for ChildStdin {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl AsRawFd for ChildStdout {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl AsRawFd for ChildStderr {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
@@ -1,3 +1,5 @@
+//! The runtime, and what its driver can do.
+
 use std::future::Future;
 
 #[cfg(any(all(target_os = "linux", feature = "iouring"), feature = "legacy"))]
@@ -8,15 +10,18 @@ use crate::IoUringDriver;
 use crate::LegacyDriver;
 use crate::{
     driver::Driver,
-    scheduler::{LocalScheduler, TaskQueue},
+    scheduler::{LocalScheduler, Priority, TaskQueue},
     task::{
         new_task,
-        waker_fn::{dummy_waker, set_poll, should_poll},
+        waker_fn::{dummy_waker, poll_pending, set_poll, should_poll},
         JoinHandle,
     },
     time::driver::Handle as TimeHandle,
 };
 
+mod capabilities;
+pub use capabilities::{capabilities, Capabilities};
+
 #[cfg(feature = "sync")]
 thread_local! {
     pub(crate) static DEFAULT_CTX: Context = Context {
@@ -25,7 +30,9 @@ thread_local! {
         waker_sender_cache: std::cell::RefCell::new(fxhash::FxHashMap::default()),
         tasks: Default::default(),
         time_handle: None,
+        spin: None,
         blocking_handle: crate::blocking::BlockingHandle::Empty(crate::blocking::BlockingStrategy::Panic),
+        custom: None,
     };
 }
 
@@ -50,9 +57,16 @@ pub(crate) struct Context {
     /// Time Handle
     pub(crate) time_handle: Option<TimeHandle>,
 
+    /// Busy poll duration before park
+    pub(crate) spin: Option<std::time::Duration>,
+
     /// Blocking Handle
     #[cfg(feature = "sync")]
     pub(crate) blocking_handle: crate::blocking::BlockingHandle,
+
+    /// Registration of a custom driver
+    #[cfg(feature = "sync")]
+    pub(crate) custom: Option<crate::driver::thread::CustomRegistration>,
 }
 
 impl Context {
@@ -66,7 +80,9 @@ impl Context {
             waker_sender_cache: std::cell::RefCell::new(fxhash::FxHashMap::default()),
             tasks: TaskQueue::default(),
             time_handle: None,
+            spin: None,
             blocking_handle,
+            custom: None,
         }
     }
 
@@ -78,6 +94,7 @@ impl Context {
             thread_id,
             tasks: TaskQueue::default(),
             time_handle: None,
+            spin: None,
         }
     }
 
@@ -156,7 +173,7 @@ impl<D> Runtime<D> {
                         // Consume all tasks(with max round to prevent io starvation)
                         let mut max_round = self.context.tasks.len() * 2;
                         while let Some(t) = self.context.tasks.pop() {
-                            t.run();
+                            crate::task::coop::budget(|| t.run());
                             if max_round == 0 {
                                 // maybe there's a looping task
                                 break;
@@ -165,15 +182,24 @@ impl<D> Runtime<D> {
                             }
                         }
 
+                        // All flush requests made in this round share one submission.
+                        if crate::driver::CURRENT.is_set()
+                            && crate::driver::CURRENT.with(|inner| inner.flush_requested())
+                        {
+                            let _ = self.driver.submit();
+                        }
+
                         // Check main future
                         if should_poll() {
                             // check if ready
-                            if let std::task::Poll::Ready(t) = join.as_mut().poll(cx) {
+                            if let std::task::Poll::Ready(t) =
+                                crate::task::coop::budget(|| join.as_mut().poll(cx))
+                            {
                                 return t;
                             }
                         }
 
-                        if self.context.tasks.is_empty() {
+                        if self.context.tasks.is_empty() && !poll_pending() {
                             // No task to execute, we should wait for io blockingly
                             // Hot path
                             break;
@@ -183,6 +209,23 @@ impl<D> Runtime<D> {
                         let _ = self.driver.submit();
                     }
 
+                    // Busy poll for a while before parking
+                    if let Some(spin) = self.context.spin {
+                        let start = std::time::Instant::now();
+                        while self.context.tasks.is_empty()
+                            && !poll_pending()
+                            && start.elapsed() < spin
+                        {
+                            let _ = self.driver.submit();
+                        }
+                        if !self.context.tasks.is_empty() || poll_pending() {
+                            continue;
+                        }
+                    }
+
+                    #[cfg(feature = "metrics")]
+                    crate::metrics::on_park();
+
                     // Wait and Process CQ(the error is ignored for not debug mode)
                     #[cfg(not(all(debug_assertions, feature = "debug")))]
                     let _ = self.driver.park();
@@ -191,12 +234,192 @@ impl<D> Runtime<D> {
                     if let Err(e) = self.driver.park() {
                         trace!("park error: {:?}", e);
                     }
+
+                    #[cfg(feature = "sync")]
+                    if let Some(custom) = &self.context.custom {
+                        custom.wake_received();
+                    }
                 }
             })
         })
     }
 }
 
+impl<D> Runtime<D> {
+    /// Run `f` in the runtime context, so tasks can be spawned and IO types
+    /// can be created without [`block_on`](Self::block_on).
+    pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R
+    where
+        D: Driver,
+    {
+        self.driver.with(|| CURRENT.set(&self.context, f))
+    }
+
+    /// Run the ready tasks, then process finished IO and expired timers
+    /// without blocking.
+    ///
+    /// This is used to embed the runtime into an event loop owned by the host.
+    /// Returns `true` if some tasks are still ready to run, in which case the
+    /// host should call `turn` again soon. Otherwise the host can wait until
+    /// the driver fd returned by `as_raw_fd` becomes readable, or until
+    /// [`next_timeout`](Self::next_timeout) elapses.
+    pub fn turn(&mut self) -> bool
+    where
+        D: Driver,
+    {
+        assert!(
+            !CURRENT.is_set(),
+            "Can not start a runtime inside a runtime"
+        );
+
+        self.enter(|| {
+            let mut max_round = self.context.tasks.len() * 2;
+            while let Some(t) = self.context.tasks.pop() {
+                crate::task::coop::budget(|| t.run());
+                if max_round == 0 {
+                    break;
+                } else {
+                    max_round -= 1;
+                }
+            }
+
+            #[cfg(feature = "metrics")]
+            crate::metrics::on_park();
+
+            let _ = self.driver.park_timeout(std::time::Duration::ZERO);
+            #[cfg(feature = "sync")]
+            if let Some(custom) = &self.context.custom {
+                custom.wake_received();
+            }
+            !self.context.tasks.is_empty()
+        })
+    }
+
+    /// Returns the duration until the earliest timer expires, or `None` if
+    /// there is no timer or the timer is not enabled.
+    pub fn next_timeout(&self) -> Option<std::time::Duration> {
+        self.context
+            .time_handle
+            .as_ref()
+            .and_then(|handle| handle.next_timeout())
+    }
+
+    /// Get a [`RuntimeHandle`] to spawn tasks on this runtime from other
+    /// threads.
+    #[cfg(feature = "sync")]
+    pub fn handle(&self) -> RuntimeHandle {
+        RuntimeHandle::new(self.context.thread_id)
+    }
+}
+
+/// A handle to a runtime, which can be sent to other threads to spawn tasks
+/// on it.
+///
+/// The task is delivered as a waker through the channel foreign wakers use,
+/// so the runtime is woken up the same way as a task woken on another thread.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main(timer_enabled = true)]
+/// async fn main() {
+///     let handle = monoio::RuntimeHandle::current();
+///     std::thread::spawn(move || {
+///         handle.spawn_on(|| async { println!("running on the runtime thread") });
+///     });
+///     monoio::time::sleep(std::time::Duration::from_millis(10)).await;
+/// }
+/// ```
+#[cfg(feature = "sync")]
+#[derive(Clone)]
+pub struct RuntimeHandle {
+    thread_id: usize,
+    waker_sender: flume::Sender<std::task::Waker>,
+    unpark: crate::driver::UnparkHandle,
+}
+
+#[cfg(feature = "sync")]
+impl RuntimeHandle {
+    fn new(thread_id: usize) -> Self {
+        use crate::driver::thread::{get_unpark_handle, get_waker_sender};
+        Self {
+            thread_id,
+            waker_sender: get_waker_sender(thread_id).expect("sender has not been registered"),
+            unpark: get_unpark_handle(thread_id).expect("thread to unpark has not been registered"),
+        }
+    }
+
+    /// Get the handle of the current runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a runtime.
+    pub fn current() -> Self {
+        Self::new(CURRENT.with(|ctx| ctx.thread_id))
+    }
+
+    /// Spawn the future made by `f` on the runtime of this handle. `f` is sent
+    /// to the runtime thread and called there, so the future itself need not
+    /// be `Send`.
+    ///
+    /// The task is detached. Returns `false` if the runtime is dropped, in
+    /// which case `f` is dropped without being called.
+    pub fn spawn_on<F, Fut>(&self, f: F) -> bool
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future + 'static,
+        Fut::Output: 'static,
+    {
+        use crate::driver::unpark::Unpark;
+
+        let waker = std::task::Waker::from(std::sync::Arc::new(SpawnOnWake(
+            std::sync::Mutex::new(Some(f)),
+        )));
+        if self.waker_sender.send(waker).is_err() {
+            return false;
+        }
+        let _ = self.unpark.unpark();
+        true
+    }
+}
+
+#[cfg(feature = "sync")]
+impl std::fmt::Debug for RuntimeHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RuntimeHandle")
+            .field("thread_id", &self.thread_id)
+            .finish()
+    }
+}
+
+// Woken by the runtime thread when it processes foreign wakers, which spawns
+// the task there.
+#[cfg(feature = "sync")]
+struct SpawnOnWake<F>(std::sync::Mutex<Option<F>>);
+
+#[cfg(feature = "sync")]
+impl<F, Fut> std::task::Wake for SpawnOnWake<F>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future + 'static,
+    Fut::Output: 'static,
+{
+    fn wake(self: std::sync::Arc<Self>) {
+        let f = self.0.lock().unwrap().take();
+        if let Some(f) = f {
+            spawn(f());
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<D: std::os::unix::prelude::AsRawFd> std::os::unix::prelude::AsRawFd for Runtime<D> {
+    /// The driver fd, readable when there is IO to process.
+    fn as_raw_fd(&self) -> std::os::unix::prelude::RawFd {
+        self.driver.as_raw_fd()
+    }
+}
+
 /// Fusion Runtime is a wrapper of io_uring driver or legacy driver based
 /// runtime.
 #[cfg(all(unix, feature = "legacy"))]
@@ -238,6 +461,45 @@ where
             }
         }
     }
+
+    /// Enter the runtime context, see [`Runtime::enter`].
+    pub fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        match self {
+            FusionRuntime::Uring(inner) => inner.enter(f),
+            FusionRuntime::Legacy(inner) => inner.enter(f),
+        }
+    }
+
+    /// Drive the runtime without blocking, see [`Runtime::turn`].
+    pub fn turn(&mut self) -> bool {
+        match self {
+            FusionRuntime::Uring(inner) => inner.turn(),
+            FusionRuntime::Legacy(inner) => inner.turn(),
+        }
+    }
+
+    /// Duration until the earliest timer expires, see
+    /// [`Runtime::next_timeout`].
+    pub fn next_timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            FusionRuntime::Uring(inner) => inner.next_timeout(),
+            FusionRuntime::Legacy(inner) => inner.next_timeout(),
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+impl<L, R> std::os::unix::prelude::AsRawFd for FusionRuntime<L, R>
+where
+    L: std::os::unix::prelude::AsRawFd,
+    R: std::os::unix::prelude::AsRawFd,
+{
+    fn as_raw_fd(&self) -> std::os::unix::prelude::RawFd {
+        match self {
+            FusionRuntime::Uring(inner) => inner.as_raw_fd(),
+            FusionRuntime::Legacy(inner) => inner.as_raw_fd(),
+        }
+    }
 }
 
 #[cfg(all(
@@ -258,6 +520,28 @@ where
             FusionRuntime::Legacy(inner) => inner.block_on(future),
         }
     }
+
+    /// Enter the runtime context, see [`Runtime::enter`].
+    pub fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.enter(f),
+        }
+    }
+
+    /// Drive the runtime without blocking, see [`Runtime::turn`].
+    pub fn turn(&mut self) -> bool {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.turn(),
+        }
+    }
+
+    /// Duration until the earliest timer expires, see
+    /// [`Runtime::next_timeout`].
+    pub fn next_timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.next_timeout(),
+        }
+    }
 }
 
 #[cfg(all(not(feature = "legacy"), all(target_os = "linux", feature = "iouring")))]
@@ -274,6 +558,50 @@ where
             FusionRuntime::Uring(inner) => inner.block_on(future),
         }
     }
+
+    /// Enter the runtime context, see [`Runtime::enter`].
+    pub fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        match self {
+            FusionRuntime::Uring(inner) => inner.enter(f),
+        }
+    }
+
+    /// Drive the runtime without blocking, see [`Runtime::turn`].
+    pub fn turn(&mut self) -> bool {
+        match self {
+            FusionRuntime::Uring(inner) => inner.turn(),
+        }
+    }
+
+    /// Duration until the earliest timer expires, see
+    /// [`Runtime::next_timeout`].
+    pub fn next_timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            FusionRuntime::Uring(inner) => inner.next_timeout(),
+        }
+    }
+}
+
+#[cfg(all(
+    unix,
+    feature = "legacy",
+    not(all(target_os = "linux", feature = "iouring"))
+))]
+impl<R: std::os::unix::prelude::AsRawFd> std::os::unix::prelude::AsRawFd for FusionRuntime<R> {
+    fn as_raw_fd(&self) -> std::os::unix::prelude::RawFd {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.as_raw_fd(),
+        }
+    }
+}
+
+#[cfg(all(not(feature = "legacy"), all(target_os = "linux", feature = "iouring")))]
+impl<L: std::os::unix::prelude::AsRawFd> std::os::unix::prelude::AsRawFd for FusionRuntime<L> {
+    fn as_raw_fd(&self) -> std::os::unix::prelude::RawFd {
+        match self {
+            FusionRuntime::Uring(inner) => inner.as_raw_fd(),
+        }
+    }
 }
 
 // L -> Fusion<L, R>
@@ -379,6 +707,40 @@ impl From<Runtime<TimeDriver<IoUringDriver>>> for FusionRuntime<TimeDriver<IoUri
 /// }
 /// ```
 pub fn spawn<T>(future: T) -> JoinHandle<T::Output>
+where
+    T: Future + 'static,
+    T::Output: 'static,
+{
+    spawn_with_priority(Priority::Normal, future)
+}
+
+/// Spawns a new asynchronous task with the given [`Priority`], returning a
+/// [`JoinHandle`] for it.
+///
+/// Ready tasks are polled in order of priority, so a task of higher priority
+/// runs before the tasks of lower priorities woken before it. Tasks of lower
+/// priorities do not run while tasks of higher priorities are always ready.
+///
+/// [`JoinHandle`]: monoio::task::JoinHandle
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::task::Priority;
+///
+/// #[monoio::main]
+/// async fn main() {
+///     let bulk = monoio::spawn_with_priority(Priority::Low, async {
+///         println!("runs last");
+///     });
+///     let health = monoio::spawn_with_priority(Priority::High, async {
+///         println!("runs first");
+///     });
+///     health.await;
+///     bulk.await;
+/// }
+/// ```
+pub fn spawn_with_priority<T>(priority: Priority, future: T) -> JoinHandle<T::Output>
 where
     T: Future + 'static,
     T::Output: 'static,
@@ -386,15 +748,63 @@ where
     let (task, join) = new_task(
         crate::utils::thread_id::get_current_thread_id(),
         future,
-        LocalScheduler,
+        LocalScheduler(priority),
     );
 
     CURRENT.with(|ctx| {
-        ctx.tasks.push(task);
+        ctx.tasks.push(priority, task);
     });
+    #[cfg(feature = "metrics")]
+    crate::metrics::task_spawned();
     join
 }
 
+/// Flush pending submissions to the kernel.
+///
+/// Normally io_uring entries are pushed into the submission queue and handed to
+/// the kernel in batch when the runtime has no more tasks to run. `flush` can
+/// be used when a task wants its operations to reach the kernel before doing
+/// something else. The returned future resolves after the submission.
+///
+/// Flush requests issued by different tasks in the same scheduler round are
+/// coalesced, so N tasks calling `flush` result in a single `io_uring_enter`.
+/// [`stats::driver_stats`](crate::stats::driver_stats) can be used to observe
+/// the batching behavior. To submit whenever enough entries are queued, see
+/// [`RuntimeBuilder::with_submit_watermark`](crate::RuntimeBuilder::with_submit_watermark).
+///
+/// For the legacy driver this is a no-op since operations are executed directly
+/// by syscalls.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::io::AsyncWriteRent;
+///
+/// #[monoio::main]
+/// async fn main() {
+///     let mut stream = monoio::net::TcpStream::connect("127.0.0.1:8080")
+///         .await
+///         .unwrap();
+///     // The operation is pushed into the submission queue on creation.
+///     let write = stream.write(b"ping");
+///     // Make sure it reaches the kernel before doing other things.
+///     monoio::flush().await;
+///     let (res, _) = write.await;
+///     res.unwrap();
+/// }
+/// ```
+pub async fn flush() {
+    let mut epoch = None;
+    crate::macros::support::poll_fn(|cx| {
+        // Custom drivers have nothing to flush.
+        if !crate::driver::CURRENT.is_set() {
+            return std::task::Poll::Ready(());
+        }
+        crate::driver::CURRENT.with(|inner| inner.poll_flush(&mut epoch, cx))
+    })
+    .await
+}
+
 #[cfg(feature = "sync")]
 unsafe fn spawn_without_static<T>(future: T) -> JoinHandle<T::Output>
 where
@@ -404,11 +814,11 @@ where
     let (task, join) = new_task_holding(
         crate::utils::thread_id::get_current_thread_id(),
         future,
-        LocalScheduler,
+        LocalScheduler(Priority::Normal),
     );
 
     CURRENT.with(|ctx| {
-        ctx.tasks.push(task);
+        ctx.tasks.push(Priority::Normal, task);
     });
     join
 }
@@ -459,4 +869,114 @@ mod tests {
         let eps = instant.elapsed().subsec_millis();
         assert!((eps as i32 - 200).abs() < 50);
     }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[test]
+    fn flush_coalesce() {
+        use std::os::unix::io::IntoRawFd;
+
+        use crate::driver::{op::Op, IoUringDriver};
+
+        let mut rt = crate::RuntimeBuilder::<IoUringDriver>::new()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let before = crate::stats::driver_stats();
+            // Poll all ops in one go, so they are not split across rounds.
+            futures::future::join_all((0..4).map(|_| {
+                let fd = tempfile::tempfile().unwrap().into_raw_fd();
+                async move {
+                    let op = Op::close(fd).unwrap();
+                    crate::flush().await;
+                    op.await.meta.result.unwrap();
+                }
+            }))
+            .await;
+            let after = crate::stats::driver_stats();
+            assert_eq!(after.sqes_submitted - before.sqes_submitted, 4);
+            assert_eq!(after.submit_calls - before.submit_calls, 1);
+        });
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[test]
+    fn sqpoll() {
+        use std::os::unix::io::IntoRawFd;
+
+        use crate::driver::{op::Op, IoUringDriver};
+
+        let mut rt = match crate::RuntimeBuilder::<IoUringDriver>::new()
+            .with_sqpoll(std::time::Duration::from_millis(10))
+            .with_sqpoll_cpu(0)
+            .build()
+        {
+            Ok(rt) => rt,
+            // SQPOLL may be not permitted
+            Err(_) => return,
+        };
+        rt.block_on(async {
+            for _ in 0..2 {
+                let fd = tempfile::tempfile().unwrap().into_raw_fd();
+                Op::close(fd).unwrap().await.meta.result.unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            }
+            assert!(crate::stats::driver_stats().sqpoll_wakeups >= 1);
+        });
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[test]
+    fn spin() {
+        use std::os::unix::io::IntoRawFd;
+
+        use crate::driver::{op::Op, IoUringDriver};
+
+        let mut rt = crate::RuntimeBuilder::<IoUringDriver>::new()
+            .profile(crate::Profile::LowLatency)
+            .build()
+            .unwrap();
+        assert!(rt.context.spin.is_some());
+        rt.block_on(async {
+            let fd = tempfile::tempfile().unwrap().into_raw_fd();
+            Op::close(fd).unwrap().await.meta.result.unwrap();
+        });
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[test]
+    fn turn() {
+        use std::{cell::Cell, os::unix::io::AsRawFd, rc::Rc, time::Duration};
+
+        use crate::driver::IoUringDriver;
+
+        let mut rt = crate::RuntimeBuilder::<IoUringDriver>::new()
+            .enable_timer()
+            .build()
+            .unwrap();
+        let done = Rc::new(Cell::new(false));
+        let done_clone = done.clone();
+        rt.enter(|| {
+            crate::spawn(async move {
+                crate::time::sleep(Duration::from_millis(20)).await;
+                done_clone.set(true);
+            })
+        });
+
+        // Act as the host event loop.
+        loop {
+            if rt.turn() {
+                continue;
+            }
+            if done.get() {
+                break;
+            }
+            let timeout = rt.next_timeout().map_or(-1, |d| d.as_millis() as i32);
+            let mut pfd = libc::pollfd {
+                fd: rt.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            unsafe { libc::poll(&mut pfd, 1, timeout) };
+        }
+    }
 }
@@ -0,0 +1,85 @@
+/// What the driver running on the current thread can do, see
+/// [`capabilities`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    uring: bool,
+    // Supported io_uring opcodes, indexed by opcode.
+    opcodes: [bool; 256],
+}
+
+impl Capabilities {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn uring(unsupported: &[bool; 256]) -> Self {
+        Self {
+            uring: true,
+            opcodes: unsupported.map(|unsupported| !unsupported),
+        }
+    }
+
+    pub(crate) fn legacy() -> Self {
+        Self {
+            uring: false,
+            opcodes: [false; 256],
+        }
+    }
+
+    /// Returns true if the driver is io_uring based.
+    pub fn is_uring(&self) -> bool {
+        self.uring
+    }
+
+    /// Returns true if the io_uring driver submits ops with `opcode`, the
+    /// `IORING_OP_*` value, e.g. `io_uring::opcode::Splice::CODE`.
+    ///
+    /// With the `legacy` feature, ops whose opcodes are not supported are done
+    /// as syscalls instead, counted by
+    /// [`DriverStats::syscall_fallbacks`](crate::stats::DriverStats::syscall_fallbacks).
+    /// Always false for the legacy driver.
+    pub fn supports_opcode(&self, opcode: u8) -> bool {
+        self.opcodes[opcode as usize]
+    }
+
+    /// Returns the opcodes the io_uring driver submits, see
+    /// [`supports_opcode`](Self::supports_opcode).
+    pub fn supported_opcodes(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=u8::MAX).filter(|code| self.supports_opcode(*code))
+    }
+}
+
+impl std::fmt::Debug for Capabilities {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Capabilities")
+            .field("uring", &self.uring)
+            .field("opcodes", &self.supported_opcodes().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Get the capabilities of the driver running on the current thread.
+///
+/// The io_uring driver probes the opcodes supported by the kernel with
+/// `IORING_REGISTER_PROBE` when it is created. Kernels before 5.6 can not be
+/// probed, so the opcodes added since then are treated as unsupported.
+///
+/// # Examples
+///
+/// ```
+/// #[monoio::main]
+/// async fn main() {
+///     // IORING_OP_SPLICE
+///     if monoio::runtime::capabilities().supports_opcode(30) {
+///         println!("splice is done by io_uring");
+///     }
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if called outside of a monoio runtime.
+pub fn capabilities() -> Capabilities {
+    // Custom drivers are not io_uring based.
+    if super::CURRENT.is_set() && !crate::driver::CURRENT.is_set() {
+        return Capabilities::legacy();
+    }
+    crate::driver::CURRENT.with(|inner| inner.capabilities())
+}
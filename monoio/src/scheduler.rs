@@ -2,21 +2,36 @@ use std::{cell::UnsafeCell, collections::VecDeque, marker::PhantomData};
 
 use crate::task::{Schedule, Task};
 
-pub(crate) struct LocalScheduler;
+/// Priority of a task, see [`spawn_with_priority`](crate::spawn_with_priority).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    /// Polled before all other tasks, e.g. health checks or control-plane
+    /// RPCs.
+    High,
+    /// The priority of tasks spawned with [`spawn`](crate::spawn).
+    #[default]
+    Normal,
+    /// Polled only when no other task is ready, e.g. bulk data transfer.
+    Low,
+}
+
+const PRIORITIES: usize = 3;
+
+pub(crate) struct LocalScheduler(pub(crate) Priority);
 
 impl Schedule for LocalScheduler {
     fn schedule(&self, task: Task<Self>) {
-        crate::runtime::CURRENT.with(|cx| cx.tasks.push(task));
+        crate::runtime::CURRENT.with(|cx| cx.tasks.push(self.0, task));
     }
 
     fn yield_now(&self, task: Task<Self>) {
-        crate::runtime::CURRENT.with(|cx| cx.tasks.push_front(task));
+        crate::runtime::CURRENT.with(|cx| cx.tasks.push_front(self.0, task));
     }
 }
 
 pub(crate) struct TaskQueue {
-    // Local queue.
-    queue: UnsafeCell<VecDeque<Task<LocalScheduler>>>,
+    // Local queues, by priority.
+    queues: UnsafeCell<[VecDeque<Task<LocalScheduler>>; PRIORITIES]>,
     // Make sure the type is `!Send` and `!Sync`.
     _marker: PhantomData<*const ()>,
 }
@@ -33,33 +48,43 @@ impl TaskQueue {
         Self::new_with_capacity(DEFAULT_TASK_QUEUE_SIZE)
     }
     pub(crate) fn new_with_capacity(capacity: usize) -> Self {
+        // Most tasks have the normal priority.
         Self {
-            queue: UnsafeCell::new(VecDeque::with_capacity(capacity)),
+            queues: UnsafeCell::new([
+                VecDeque::new(),
+                VecDeque::with_capacity(capacity),
+                VecDeque::new(),
+            ]),
             _marker: PhantomData,
         }
     }
 
     pub(crate) fn len(&self) -> usize {
-        unsafe { (*self.queue.get()).len() }
+        unsafe { (*self.queues.get()).iter().map(VecDeque::len).sum() }
     }
 
     pub(crate) fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
-    pub(crate) fn push(&self, runnable: Task<LocalScheduler>) {
+    pub(crate) fn push(&self, priority: Priority, runnable: Task<LocalScheduler>) {
         unsafe {
-            (*self.queue.get()).push_back(runnable);
+            (*self.queues.get())[priority as usize].push_back(runnable);
         }
     }
 
-    pub(crate) fn push_front(&self, runnable: Task<LocalScheduler>) {
+    pub(crate) fn push_front(&self, priority: Priority, runnable: Task<LocalScheduler>) {
         unsafe {
-            (*self.queue.get()).push_front(runnable);
+            (*self.queues.get())[priority as usize].push_front(runnable);
         }
     }
 
+    /// Pop a task of the highest priority.
     pub(crate) fn pop(&self) -> Option<Task<LocalScheduler>> {
-        unsafe { (*self.queue.get()).pop_front() }
+        unsafe {
+            (*self.queues.get())
+                .iter_mut()
+                .find_map(VecDeque::pop_front)
+        }
     }
 }
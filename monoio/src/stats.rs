@@ -0,0 +1,61 @@
+//! Runtime statistics.
+//!
+//! Counters here are collected by the driver of the current thread and are
+//! cheap enough to be always on. They are mainly useful to reason about how
+//! well submissions are batched.
+
+/// Statistics of the driver running on the current thread.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DriverStats {
+    /// Total number of submission queue entries handed to the kernel.
+    pub sqes_submitted: u64,
+    /// Total number of `io_uring_enter` calls which submitted entries. With
+    /// SQPOLL enabled, it counts submission attempts instead since most of
+    /// them do not need a syscall.
+    pub submit_calls: u64,
+    /// Number of times the SQPOLL thread was found sleeping and had to be
+    /// waked up on submission. A high value means the idle time is too short
+    /// for the load. Always 0 if SQPOLL is not enabled.
+    pub sqpoll_wakeups: u64,
+    /// Number of operations done by syscalls on the io_uring driver because
+    /// the kernel does not support their opcodes.
+    pub syscall_fallbacks: u64,
+    /// Number of in-flight operations canceled by the io_uring driver after
+    /// their futures were dropped, with the `async-cancel` feature or for
+    /// multishot operations. Operations which completed before their cancels
+    /// are not counted.
+    pub ops_canceled: u64,
+    /// Number of slab slots in use. For io_uring driver it is the number of
+    /// in-flight operations; for legacy driver it is the number of registered
+    /// IO sources.
+    pub slab_used: usize,
+    /// Number of slab slots allocated. The slab releases memory after bursts,
+    /// so this value goes down when load decreases.
+    pub slab_capacity: usize,
+}
+
+impl DriverStats {
+    /// Average number of submission queue entries per `io_uring_enter`.
+    ///
+    /// Returns 0 if nothing has been submitted yet. For the legacy driver it is
+    /// always 0 since there is no submission queue.
+    pub fn sqes_per_submit(&self) -> f64 {
+        if self.submit_calls == 0 {
+            return 0.0;
+        }
+        self.sqes_submitted as f64 / self.submit_calls as f64
+    }
+}
+
+/// Get statistics of the driver running on the current thread.
+///
+/// # Panics
+///
+/// Panics if called outside of a monoio runtime.
+pub fn driver_stats() -> DriverStats {
+    // Custom drivers are not tracked.
+    if crate::runtime::CURRENT.is_set() && !crate::driver::CURRENT.is_set() {
+        return DriverStats::default();
+    }
+    crate::driver::CURRENT.with(|inner| inner.stats())
+}
@@ -0,0 +1,16 @@
+//! Async synchronization primitives for tasks on the same thread.
+//!
+//! They are `!Send` and use no atomics, waiters are queued in FIFO order and
+//! only allocate when they have to wait.
+
+mod mutex;
+pub use mutex::{Mutex, MutexGuard};
+
+mod notify;
+pub use notify::Notify;
+
+mod rwlock;
+pub use rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+mod semaphore;
+pub use semaphore::{Semaphore, SemaphorePermit};
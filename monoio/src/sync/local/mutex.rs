@@ -0,0 +1,128 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use super::Semaphore;
+
+/// An async mutex for tasks on the same thread.
+///
+/// Unlike `RefCell`, the guard can be held across `.await`, other tasks
+/// wait for it in FIFO order.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use monoio::sync::local::Mutex;
+///
+/// #[monoio::main]
+/// async fn main() {
+///     let count = Rc::new(Mutex::new(0));
+///     let task = monoio::spawn({
+///         let count = count.clone();
+///         async move { *count.lock().await += 1 }
+///     });
+///     *count.lock().await += 1;
+///     task.await;
+///     assert_eq!(*count.lock().await, 2);
+/// }
+/// ```
+pub struct Mutex<T: ?Sized> {
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+impl<T> Mutex<T> {
+    /// Create an unlocked mutex.
+    pub const fn new(value: T) -> Self {
+        Self {
+            semaphore: Semaphore::new(1),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consume the mutex and return the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Lock the mutex, waiting until it is unlocked.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        self.semaphore.acquire().await.forget();
+        MutexGuard { mutex: self }
+    }
+
+    /// Lock the mutex if it is unlocked and nobody is waiting.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.semaphore.try_acquire()?.forget();
+        Some(MutexGuard { mutex: self })
+    }
+
+    /// Get the value mutably, no locking is needed since it is borrowed
+    /// mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for Mutex<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Mutex");
+        match self.try_lock() {
+            Some(guard) => d.field("value", &&*guard),
+            None => d.field("value", &format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
+
+/// Guard of a locked [`Mutex`], which unlocks it on drop.
+#[must_use = "the mutex is unlocked immediately if unused"]
+pub struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the guard holds the only permit.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: the guard holds the only permit.
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.semaphore.add_permits(1);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// Notify tasks on the same thread.
+///
+/// [`notify_one`](Self::notify_one) wakes the first waiter, or stores a
+/// permit for the next call to [`notified`](Self::notified) if nobody is
+/// waiting. [`notify_waiters`](Self::notify_waiters) wakes all current
+/// waiters and stores nothing.
+///
+/// A waiter is registered when the future returned by `notified` is first
+/// polled.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use monoio::sync::local::Notify;
+///
+/// #[monoio::main]
+/// async fn main() {
+///     let notify = Rc::new(Notify::new());
+///     let task = monoio::spawn({
+///         let notify = notify.clone();
+///         async move { notify.notified().await }
+///     });
+///     notify.notify_one();
+///     task.await;
+/// }
+/// ```
+pub struct Notify {
+    permit: Cell<bool>,
+    waiters: RefCell<VecDeque<Rc<Waiter>>>,
+}
+
+struct Waiter {
+    state: Cell<State>,
+    waker: Cell<Option<Waker>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Waiting,
+    NotifiedOne,
+    NotifiedAll,
+}
+
+impl Notify {
+    /// Create a `Notify` without permit.
+    pub const fn new() -> Self {
+        Self {
+            permit: Cell::new(false),
+            waiters: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    /// Wait for a notification.
+    pub async fn notified(&self) {
+        Notified {
+            notify: self,
+            waiter: None,
+        }
+        .await
+    }
+
+    /// Wake the first waiter, or store a permit if nobody is waiting.
+    pub fn notify_one(&self) {
+        let waiter = self.waiters.borrow_mut().pop_front();
+        match waiter {
+            Some(waiter) => {
+                waiter.state.set(State::NotifiedOne);
+                if let Some(waker) = waiter.waker.take() {
+                    waker.wake();
+                }
+            }
+            None => self.permit.set(true),
+        }
+    }
+
+    /// Wake all waiters.
+    pub fn notify_waiters(&self) {
+        let waiters = std::mem::take(&mut *self.waiters.borrow_mut());
+        for waiter in waiters {
+            waiter.state.set(State::NotifiedAll);
+            if let Some(waker) = waiter.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Notify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notify")
+            .field("permit", &self.permit.get())
+            .field("waiters", &self.waiters.borrow().len())
+            .finish()
+    }
+}
+
+struct Notified<'a> {
+    notify: &'a Notify,
+    waiter: Option<Rc<Waiter>>,
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let Some(waiter) = &this.waiter else {
+            if this.notify.permit.replace(false) {
+                return Poll::Ready(());
+            }
+            let waiter = Rc::new(Waiter {
+                state: Cell::new(State::Waiting),
+                waker: Cell::new(Some(cx.waker().clone())),
+            });
+            this.notify.waiters.borrow_mut().push_back(waiter.clone());
+            this.waiter = Some(waiter);
+            return Poll::Pending;
+        };
+        if waiter.state.get() == State::Waiting {
+            match waiter.waker.take() {
+                Some(waker) if waker.will_wake(cx.waker()) => waiter.waker.set(Some(waker)),
+                _ => waiter.waker.set(Some(cx.waker().clone())),
+            }
+            return Poll::Pending;
+        }
+        this.waiter = None;
+        Poll::Ready(())
+    }
+}
+
+impl Drop for Notified<'_> {
+    fn drop(&mut self) {
+        let Some(waiter) = self.waiter.take() else {
+            return;
+        };
+        match waiter.state.get() {
+            State::Waiting => self
+                .notify
+                .waiters
+                .borrow_mut()
+                .retain(|w| !Rc::ptr_eq(w, &waiter)),
+            // Pass the notification on so it is not lost.
+            State::NotifiedOne => self.notify.notify_one(),
+            State::NotifiedAll => {}
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// A counting semaphore for tasks on the same thread.
+///
+/// Waiters are served in the order they start waiting, so a waiter asking
+/// for many permits is not starved by later ones asking for less.
+pub struct Semaphore {
+    permits: Cell<usize>,
+    waiters: RefCell<VecDeque<Rc<Waiter>>>,
+}
+
+struct Waiter {
+    permits: usize,
+    granted: Cell<bool>,
+    waker: Cell<Option<Waker>>,
+}
+
+impl Semaphore {
+    /// Create a semaphore with the given number of permits.
+    pub const fn new(permits: usize) -> Self {
+        Self {
+            permits: Cell::new(permits),
+            waiters: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    /// Number of permits which can be acquired now.
+    pub fn available_permits(&self) -> usize {
+        self.permits.get()
+    }
+
+    /// Add permits to the semaphore, waking the waiters they are enough for.
+    pub fn add_permits(&self, n: usize) {
+        self.release(n);
+    }
+
+    /// Acquire a permit, waiting until one is available.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.acquire_many(1).await
+    }
+
+    /// Acquire `n` permits, waiting until they are available.
+    pub async fn acquire_many(&self, n: usize) -> SemaphorePermit<'_> {
+        Acquire {
+            semaphore: self,
+            permits: n,
+            waiter: None,
+        }
+        .await
+    }
+
+    /// Acquire a permit if one is available and nobody is waiting.
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.try_acquire_many(1)
+    }
+
+    /// Acquire `n` permits if they are available and nobody is waiting.
+    pub fn try_acquire_many(&self, n: usize) -> Option<SemaphorePermit<'_>> {
+        let permits = self.permits.get();
+        if permits < n || !self.waiters.borrow().is_empty() {
+            return None;
+        }
+        self.permits.set(permits - n);
+        Some(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    // Give back permits and hand them to the waiters in order.
+    fn release(&self, n: usize) {
+        let mut permits = self.permits.get() + n;
+        let mut wakers = Vec::new();
+        {
+            let mut waiters = self.waiters.borrow_mut();
+            while let Some(waiter) = waiters.front() {
+                if waiter.permits > permits {
+                    break;
+                }
+                permits -= waiter.permits;
+                waiter.granted.set(true);
+                wakers.extend(waiter.waker.take());
+                waiters.pop_front();
+            }
+        }
+        self.permits.set(permits);
+        // Wake after the queue is released in case a waker runs code which
+        // uses the semaphore.
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Default for Semaphore {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Semaphore")
+            .field("permits", &self.permits.get())
+            .field("waiters", &self.waiters.borrow().len())
+            .finish()
+    }
+}
+
+struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+    waiter: Option<Rc<Waiter>>,
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = SemaphorePermit<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let Some(waiter) = &this.waiter else {
+            if let Some(permit) = this.semaphore.try_acquire_many(this.permits) {
+                return Poll::Ready(permit);
+            }
+            let waiter = Rc::new(Waiter {
+                permits: this.permits,
+                granted: Cell::new(false),
+                waker: Cell::new(Some(cx.waker().clone())),
+            });
+            this.semaphore
+                .waiters
+                .borrow_mut()
+                .push_back(waiter.clone());
+            this.waiter = Some(waiter);
+            return Poll::Pending;
+        };
+        if !waiter.granted.get() {
+            match waiter.waker.take() {
+                Some(waker) if waker.will_wake(cx.waker()) => waiter.waker.set(Some(waker)),
+                _ => waiter.waker.set(Some(cx.waker().clone())),
+            }
+            return Poll::Pending;
+        }
+        this.waiter = None;
+        Poll::Ready(SemaphorePermit {
+            semaphore: this.semaphore,
+            permits: this.permits,
+        })
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(waiter) = self.waiter.take() else {
+            return;
+        };
+        if waiter.granted.get() {
+            // Granted but never polled, give the permits back.
+            self.semaphore.release(waiter.permits);
+            return;
+        }
+        let mut waiters = self.semaphore.waiters.borrow_mut();
+        if let Some(idx) = waiters.iter().position(|w| Rc::ptr_eq(w, &waiter)) {
+            waiters.remove(idx);
+            drop(waiters);
+            // The waiters behind may be served now.
+            if idx == 0 {
+                self.semaphore.release(0);
+            }
+        }
+    }
+}
+
+/// Permits acquired from a [`Semaphore`], which are released on drop.
+#[must_use = "the permits are released immediately if unused"]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+}
+
+impl SemaphorePermit<'_> {
+    /// Number of permits held.
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+
+    /// Drop the permit without releasing it to the semaphore.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        if self.permits > 0 {
+            self.semaphore.release(self.permits);
+        }
+    }
+}
+
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit")
+            .field("permits", &self.permits)
+            .finish()
+    }
+}
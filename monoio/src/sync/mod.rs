@@ -0,0 +1,5 @@
+//! Synchronization primitives.
+
+pub mod local;
+#[cfg(feature = "sync")]
+pub mod mpsc;
@@ -0,0 +1,190 @@
+//! A bounded multi-producer, single-consumer channel across threads.
+//!
+//! Senders may live on other runtimes or plain threads, the receiver is
+//! awaited on its runtime and woken through the cross-thread waker path.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! #[monoio::main]
+//! async fn main() {
+//!     let (tx, mut rx) = monoio::sync::mpsc::channel(16);
+//!     std::thread::spawn(move || {
+//!         for i in 0..100 {
+//!             tx.blocking_send(i).unwrap();
+//!         }
+//!     });
+//!     while let Some(i) = rx.recv().await {
+//!         println!("received {i}");
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+
+/// Create a bounded channel which holds at most `capacity` values. Sending
+/// waits when the channel is full.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = flume::bounded(capacity);
+    (Sender { inner: tx }, Receiver { inner: rx })
+}
+
+/// The sending half, which can be cloned and sent to other threads.
+pub struct Sender<T> {
+    inner: flume::Sender<T>,
+}
+
+/// The receiving half.
+pub struct Receiver<T> {
+    inner: flume::Receiver<T>,
+}
+
+/// Error of sending to a closed channel, which returns the value.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+/// Error of [`Sender::try_send`].
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum TrySendError<T> {
+    /// The channel is full.
+    Full(T),
+    /// The receiver is dropped.
+    Closed(T),
+}
+
+/// Error of [`Receiver::try_recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// The channel is empty.
+    Empty,
+    /// All senders are dropped and the channel is empty.
+    Closed,
+}
+
+impl<T> Sender<T> {
+    /// Send a value, waiting for capacity if the channel is full.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.inner
+            .send_async(value)
+            .await
+            .map_err(|e| SendError(e.into_inner()))
+    }
+
+    /// Send a value if the channel has capacity.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(value).map_err(|e| match e {
+            flume::TrySendError::Full(v) => TrySendError::Full(v),
+            flume::TrySendError::Disconnected(v) => TrySendError::Closed(v),
+        })
+    }
+
+    /// Send a value, blocking the thread until the channel has capacity.
+    ///
+    /// It is for senders outside of a runtime, calling it in a runtime
+    /// blocks all tasks on the thread.
+    pub fn blocking_send(&self, value: T) -> Result<(), SendError<T>> {
+        self.inner
+            .send(value)
+            .map_err(|e| SendError(e.into_inner()))
+    }
+
+    /// Returns `true` if the receiver is dropped.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_disconnected()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("len", &self.inner.len())
+            .finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Receive a value, or `None` if all senders are dropped and the channel
+    /// is empty.
+    pub async fn recv(&mut self) -> Option<T> {
+        self.inner.recv_async().await.ok()
+    }
+
+    /// Receive a value if there is one.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.inner.try_recv().map_err(|e| match e {
+            flume::TryRecvError::Empty => TryRecvError::Empty,
+            flume::TryRecvError::Disconnected => TryRecvError::Closed,
+        })
+    }
+
+    /// Number of values in the channel.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the channel is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.inner.len())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SendError(..)")
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => f.write_str("Full(..)"),
+            TrySendError::Closed(_) => f.write_str("Closed(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => f.write_str("channel full"),
+            TrySendError::Closed(_) => f.write_str("channel closed"),
+        }
+    }
+}
+
+impl<T> std::error::Error for TrySendError<T> {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("channel empty"),
+            TryRecvError::Closed => f.write_str("channel closed"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
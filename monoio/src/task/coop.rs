@@ -0,0 +1,92 @@
+//! Cooperative scheduling budget.
+// Borrowed from tokio.
+// Copyright (c) 2021 Tokio Contributors, licensed under the MIT license.
+//
+// A task is given a budget each time it is polled, and every op completion it
+// consumes takes one unit. Once the budget is used up, ops return `Pending`
+// and wake the task, so a task finding ops always ready, e.g. reading a socket
+// which is never drained, yields to the other tasks on the thread.
+
+use std::{
+    cell::Cell,
+    task::{Context, Poll},
+};
+
+/// Ops a task may complete in one poll.
+const BUDGET: u8 = 128;
+
+thread_local! {
+    // `None` outside of a task poll, where ops are not limited.
+    static CURRENT: Cell<Option<u8>> = const { Cell::new(None) };
+}
+
+/// Run `f` with a fresh budget, restoring the previous one after.
+#[inline]
+pub(crate) fn budget<R>(f: impl FnOnce() -> R) -> R {
+    struct ResetGuard(Option<u8>);
+
+    impl Drop for ResetGuard {
+        fn drop(&mut self) {
+            CURRENT.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _guard = ResetGuard(CURRENT.with(|cell| cell.replace(Some(BUDGET))));
+    f()
+}
+
+/// Use up the budget of the running task, if it has one.
+#[inline]
+pub(crate) fn stop() {
+    CURRENT.with(|cell| {
+        if cell.get().is_some() {
+            cell.set(Some(0));
+        }
+    });
+}
+
+/// Returns false if the budget of the running task is used up.
+#[inline]
+pub(crate) fn has_budget_remaining() -> bool {
+    CURRENT.with(|cell| cell.get() != Some(0))
+}
+
+/// Take one unit of the budget, or wake the task and return `Pending` if it
+/// is used up. The unit is given back when the returned guard is dropped,
+/// unless [`RestoreOnPending::made_progress`] is called.
+#[inline]
+pub(crate) fn poll_proceed(cx: &mut Context<'_>) -> Poll<RestoreOnPending> {
+    CURRENT.with(|cell| match cell.get() {
+        Some(0) => {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+        Some(n) => {
+            cell.set(Some(n - 1));
+            Poll::Ready(RestoreOnPending(Cell::new(true)))
+        }
+        None => Poll::Ready(RestoreOnPending(Cell::new(false))),
+    })
+}
+
+pub(crate) struct RestoreOnPending(Cell<bool>);
+
+impl RestoreOnPending {
+    /// Keep the unit taken, the op completed.
+    #[inline]
+    pub(crate) fn made_progress(&self) {
+        self.0.set(false);
+    }
+}
+
+impl Drop for RestoreOnPending {
+    fn drop(&mut self) {
+        if self.0.get() {
+            CURRENT.with(|cell| {
+                if let Some(n) = cell.get() {
+                    cell.set(Some(n + 1));
+                }
+            });
+        }
+    }
+}
@@ -31,7 +31,7 @@ pub(crate) struct CoreStage<T: Future> {
 
 pub(crate) enum Stage<T: Future> {
     Running(T),
-    Finished(T::Output),
+    Finished(super::Result<T::Output>),
     Consumed,
 }
 
@@ -118,7 +118,7 @@ impl<T: Future> CoreStage<T> {
     /// # Safety
     ///
     /// The caller must ensure it is safe to mutate the `stage` field.
-    pub(crate) fn store_output(&self, output: T::Output) {
+    pub(crate) fn store_output(&self, output: super::Result<T::Output>) {
         // Safety: the caller ensures mutual exclusion to the field.
         unsafe {
             self.set_stage(Stage::Finished(output));
@@ -130,7 +130,7 @@ impl<T: Future> CoreStage<T> {
     /// # Safety
     ///
     /// The caller must ensure it is safe to mutate the `stage` field.
-    pub(crate) fn take_output(&self) -> T::Output {
+    pub(crate) fn take_output(&self) -> super::Result<T::Output> {
         use std::mem;
 
         self.with_mut(|ptr| {
@@ -0,0 +1,247 @@
+use std::{
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use super::JoinHandle;
+
+/// A group of tasks spawned on the current thread, which are aborted when the
+/// group is dropped, so no task outlives the code that manages them.
+///
+/// The outputs are collected with [`join_all`](Self::join_all), or with
+/// [`try_join_all`](Self::try_join_all) which aborts the other tasks on the
+/// first error. A panic of a task is resumed when its output is joined, and
+/// the other tasks are aborted as the group is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::task::TaskGroup;
+///
+/// #[monoio::main]
+/// async fn main() {
+///     let mut group = TaskGroup::new();
+///     for i in 0..3 {
+///         group.spawn(async move { i * 2 });
+///     }
+///     assert_eq!(group.join_all().await, vec![0, 2, 4]);
+/// }
+/// ```
+pub struct TaskGroup<T> {
+    // Unfinished tasks by slot, and the free slots.
+    tasks: Vec<Option<Child<T>>>,
+    free: Vec<usize>,
+    len: usize,
+    next_id: usize,
+    // Slots of the tasks woken since they were polled, so joining does not
+    // poll every task.
+    ready: Arc<Ready>,
+}
+
+struct Child<T> {
+    // Order the task is spawned in
+    id: usize,
+    handle: JoinHandle<T>,
+    waker: Arc<ChildWaker>,
+}
+
+#[derive(Default)]
+struct Ready {
+    state: Mutex<ReadyState>,
+}
+
+#[derive(Default)]
+struct ReadyState {
+    slots: Vec<usize>,
+    // Task joining the group, woken once a slot is queued.
+    waker: Option<Waker>,
+}
+
+// Waker of a task in the group, queueing its slot when woken.
+struct ChildWaker {
+    slot: usize,
+    queued: AtomicBool,
+    ready: Arc<Ready>,
+}
+
+impl Wake for ChildWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if self.queued.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let mut state = self.ready.state.lock().unwrap();
+        state.slots.push(self.slot);
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: 'static> TaskGroup<T> {
+    /// Create an empty group.
+    pub fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            next_id: 0,
+            ready: Arc::default(),
+        }
+    }
+
+    /// Spawn a task in the group.
+    pub fn spawn<F>(&mut self, future: F)
+    where
+        F: Future<Output = T> + 'static,
+    {
+        let slot = self.free.pop().unwrap_or(self.tasks.len());
+        let waker = Arc::new(ChildWaker {
+            slot,
+            queued: AtomicBool::new(false),
+            ready: self.ready.clone(),
+        });
+        // Polled once to register its waker.
+        waker.wake_by_ref();
+        let child = Child {
+            id: self.next_id,
+            handle: crate::spawn(future),
+            waker,
+        };
+        match self.tasks.get_mut(slot) {
+            Some(entry) => *entry = Some(child),
+            None => self.tasks.push(Some(child)),
+        }
+        self.len += 1;
+        self.next_id += 1;
+    }
+
+    /// Number of tasks whose output is not joined yet.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if all tasks are joined.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Wait for the next task to finish and return its output, or `None` if
+    /// the group is empty.
+    pub async fn join_next(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_join_next(cx))
+            .await
+            .map(|(_, output)| output)
+    }
+
+    /// Wait for all tasks and return their outputs in the order they are
+    /// spawned in.
+    pub async fn join_all(mut self) -> Vec<T> {
+        let mut outputs = Vec::with_capacity(self.len);
+        while let Some(output) = poll_fn(|cx| self.poll_join_next(cx)).await {
+            outputs.push(output);
+        }
+        outputs.sort_unstable_by_key(|(id, _)| *id);
+        outputs.into_iter().map(|(_, output)| output).collect()
+    }
+
+    /// Abort all tasks which are not joined.
+    pub fn abort_all(&mut self) {
+        self.abort_tasks();
+    }
+
+    // Poll the woken tasks only, each with its own waker.
+    fn poll_join_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<(usize, T)>> {
+        if self.len == 0 {
+            return Poll::Ready(None);
+        }
+        let slots = {
+            let mut state = self.ready.state.lock().unwrap();
+            if state.slots.is_empty() {
+                state.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            std::mem::take(&mut state.slots)
+        };
+        let mut slots = slots.into_iter();
+        while let Some(slot) = slots.next() {
+            // The slot may be queued by the waker of a joined task.
+            let Some(child) = self.tasks.get_mut(slot).and_then(Option::as_mut) else {
+                continue;
+            };
+            child.waker.queued.store(false, Ordering::Release);
+            let waker = Waker::from(child.waker.clone());
+            if let Poll::Ready(output) =
+                Pin::new(&mut child.handle).poll(&mut Context::from_waker(&waker))
+            {
+                let id = child.id;
+                self.tasks[slot] = None;
+                self.free.push(slot);
+                self.len -= 1;
+                // Left for the next join.
+                self.ready.state.lock().unwrap().slots.extend(slots);
+                return Poll::Ready(Some((id, output)));
+            }
+        }
+        // Tasks woken while being polled are polled on the next wake.
+        let mut state = self.ready.state.lock().unwrap();
+        if state.slots.is_empty() {
+            state.waker = Some(cx.waker().clone());
+        } else {
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> TaskGroup<T> {
+    fn abort_tasks(&mut self) {
+        for child in self.tasks.drain(..).flatten() {
+            child.handle.abort();
+        }
+        self.free.clear();
+        self.len = 0;
+    }
+}
+
+impl<T: 'static, E: 'static> TaskGroup<Result<T, E>> {
+    /// Wait for all tasks and return their outputs in the order they are
+    /// spawned in. On the first error the other tasks are aborted and the
+    /// error is returned.
+    pub async fn try_join_all(mut self) -> Result<Vec<T>, E> {
+        let mut outputs = Vec::with_capacity(self.len);
+        while let Some((id, output)) = poll_fn(|cx| self.poll_join_next(cx)).await {
+            outputs.push((id, output?));
+        }
+        outputs.sort_unstable_by_key(|(id, _)| *id);
+        Ok(outputs.into_iter().map(|(_, output)| output).collect())
+    }
+}
+
+impl<T: 'static> Default for TaskGroup<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for TaskGroup<T> {
+    fn drop(&mut self) {
+        self.abort_tasks();
+    }
+}
+
+impl<T> std::fmt::Debug for TaskGroup<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskGroup").field("len", &self.len).finish()
+    }
+}
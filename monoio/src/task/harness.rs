@@ -1,6 +1,6 @@
 use std::{
     future::Future,
-    panic,
+    mem, panic,
     ptr::NonNull,
     task::{Context, Poll, Waker},
 };
@@ -52,11 +52,19 @@ where
     /// Polls the inner future.
     pub(super) fn poll(self) {
         trace!("MONOIO DEBUG[Harness]:: poll");
+        // Ops submitted in the poll are in this span.
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("poll", task = self.cell.as_ptr() as usize).entered();
         match self.poll_inner() {
             PollFuture::Notified => {
-                // We should re-schedule the task.
+                // We should re-schedule the task. A task out of budget goes
+                // behind the others.
                 self.header().state.ref_inc();
-                self.core().scheduler.yield_now(self.get_new_task());
+                if super::coop::has_budget_remaining() {
+                    self.core().scheduler.yield_now(self.get_new_task());
+                } else {
+                    self.core().scheduler.schedule(self.get_new_task());
+                }
             }
             PollFuture::Complete => {
                 self.complete();
@@ -73,6 +81,12 @@ where
         // notified -> running
         self.header().state.transition_to_running();
 
+        // An aborted task completes without output.
+        if self.header().state.load().is_cancelled() {
+            self.core().stage.drop_future_or_output();
+            return PollFuture::Complete;
+        }
+
         // poll the future
         let waker_ref = waker_ref::<T, S>(self.header());
         let cx = Context::from_waker(&waker_ref);
@@ -107,14 +121,14 @@ where
     pub(super) fn finish(self, val: <T as Future>::Output) {
         trace!("MONOIO DEBUG[Harness]:: finish");
         self.header().state.transition_to_running();
-        self.core().stage.store_output(val);
+        self.core().stage.store_output(Ok(val));
         self.complete();
     }
 
     // ===== join handle =====
 
     /// Read the task output into `dst`.
-    pub(super) fn try_read_output(self, dst: &mut Poll<T::Output>, waker: &Waker) {
+    pub(super) fn try_read_output(self, dst: &mut Poll<super::Result<T::Output>>, waker: &Waker) {
         trace!("MONOIO DEBUG[Harness]:: try_read_output");
         if can_read_output(self.header(), self.trailer(), waker) {
             *dst = Poll::Ready(self.core().stage.take_output());
@@ -151,6 +165,17 @@ where
         }
     }
 
+    /// Abort the task, so its future is dropped on the next poll instead of
+    /// polled.
+    pub(super) fn abort(self) {
+        trace!("MONOIO DEBUG[Harness]:: abort");
+        if self.header().state.transition_to_cancelled() {
+            // # Ref Count: +1 -> task
+            self.header().state.ref_inc();
+            self.core().scheduler.schedule(self.get_new_task());
+        }
+    }
+
     // ===== waker behavior =====
 
     /// This call consumes a ref-count and notifies the task. This will create a
@@ -404,41 +429,36 @@ enum PollFuture {
 /// Poll the future. If the future completes, the output is written to the
 /// stage field.
 fn poll_future<T: Future>(core: &CoreStage<T>, cx: Context<'_>) -> Poll<()> {
-    // CHIHAI: For efficiency we do not catch.
-
-    // Poll the future.
-    // let output = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-    //     struct Guard<'a, T: Future> {
-    //         core: &'a CoreStage<T>,
-    //     }
-    //     impl<'a, T: Future> Drop for Guard<'a, T> {
-    //         fn drop(&mut self) {
-    //             // If the future panics on poll, we drop it inside the panic
-    //             // guard.
-    //             self.core.drop_future_or_output();
-    //         }
-    //     }
-    //     let guard = Guard { core };
-    //     let res = guard.core.poll(cx);
-    //     mem::forget(guard);
-    //     res
-    // }));
-    let output = core.poll(cx);
+    // Poll the future. A panic is caught so it does not unwind through the
+    // executor, and is given to the `JoinHandle` instead.
+    let output = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        struct Guard<'a, T: Future> {
+            core: &'a CoreStage<T>,
+        }
+        impl<T: Future> Drop for Guard<'_, T> {
+            fn drop(&mut self) {
+                // If the future panics on poll, we drop it inside the panic
+                // guard.
+                self.core.drop_future_or_output();
+            }
+        }
+        let guard = Guard { core };
+        let res = guard.core.poll(cx);
+        mem::forget(guard);
+        res
+    }));
 
     // Prepare output for being placed in the core stage.
     let output = match output {
-        // Ok(Poll::Pending) => return Poll::Pending,
-        // Ok(Poll::Ready(output)) => Ok(output),
-        // Err(panic) => Err(JoinError::panic(panic)),
-        Poll::Pending => return Poll::Pending,
-        Poll::Ready(output) => output,
+        Ok(Poll::Pending) => return Poll::Pending,
+        Ok(Poll::Ready(output)) => Ok(output),
+        Err(panic) => Err(panic),
     };
 
     // Catch and ignore panics if the future panics on drop.
-    // let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-    //     core.store_output(output);
-    // }));
-    core.store_output(output);
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        core.store_output(output);
+    }));
 
     Poll::Ready(())
 }
@@ -1,13 +1,30 @@
 use std::{
     future::Future,
     marker::PhantomData,
+    panic,
     pin::Pin,
     task::{Context, Poll},
 };
 
 use super::raw::RawTask;
 
-/// JoinHandle
+/// Error on waiting a task.
+#[derive(Debug, Clone, Copy)]
+pub enum JoinError {
+    /// Task is canceled.
+    Canceled,
+    /// Task panicked.
+    Panic,
+}
+
+/// JoinHandle of a spawned task. Awaiting it returns the output of the task.
+///
+/// A panic of the task is caught by the runtime, so other tasks keep running,
+/// and is resumed when the handle is awaited. Use
+/// [`catch_panic`](Self::catch_panic) to get it as an error instead.
+///
+/// Dropping the handle detaches the task: it keeps running and its output is
+/// dropped when it finishes.
 pub struct JoinHandle<T> {
     raw: Option<RawTask>,
     _p: PhantomData<T>,
@@ -20,12 +37,36 @@ impl<T> JoinHandle<T> {
             _p: PhantomData,
         }
     }
-}
 
-impl<T> Future for JoinHandle<T> {
-    type Output = T;
+    /// Returns `true` if the task has finished, so awaiting the handle is
+    /// ready immediately.
+    pub fn is_finished(&self) -> bool {
+        self.raw
+            .is_none_or(|raw| raw.header().state.load().is_complete())
+    }
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    /// Abort the task. Its future is dropped the next time the runtime gets
+    /// to the task instead of being polled, or when it yields if the task
+    /// aborts itself. The output of a finished task is dropped.
+    ///
+    /// The handle is consumed since an aborted task has no output.
+    pub fn abort(self) {
+        if let Some(raw) = self.raw {
+            raw.abort();
+        }
+    }
+
+    /// Detach the task, so it keeps running without a handle and its output is
+    /// dropped when it finishes. Same as dropping the handle.
+    pub fn detach(self) {}
+
+    /// Wait for the task, returning [`JoinError::Panic`] if it panicked
+    /// instead of resuming the panic.
+    pub fn catch_panic(self) -> CatchPanic<T> {
+        CatchPanic { handle: self }
+    }
+
+    fn poll_output(&self, cx: &mut Context<'_>) -> Poll<super::Result<T>> {
         let mut ret = Poll::Pending;
 
         // Raw should always be set. If it is not, this is due to polling after
@@ -53,6 +94,34 @@ impl<T> Future for JoinHandle<T> {
     }
 }
 
+impl<T> Unpin for JoinHandle<T> {}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_output(cx).map(|output| match output {
+            Ok(output) => output,
+            Err(panic) => panic::resume_unwind(panic),
+        })
+    }
+}
+
+/// Future returned by [`JoinHandle::catch_panic`].
+pub struct CatchPanic<T> {
+    handle: JoinHandle<T>,
+}
+
+impl<T> Future for CatchPanic<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.handle
+            .poll_output(cx)
+            .map(|output| output.map_err(|_| JoinError::Panic))
+    }
+}
+
 impl<T> Drop for JoinHandle<T> {
     fn drop(&mut self) {
         if let Some(raw) = self.raw.take() {
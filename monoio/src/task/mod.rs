@@ -5,15 +5,24 @@
 mod utils;
 pub(crate) mod waker_fn;
 
+pub(crate) mod coop;
+
 mod core;
 use self::core::{Cell, Header};
 
 mod harness;
 use self::harness::Harness;
 
+mod group;
+pub use self::group::TaskGroup;
+
 mod join;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
-pub use self::join::JoinHandle;
+pub use self::join::{CatchPanic, JoinError, JoinHandle};
+
+mod yield_now;
+pub use self::yield_now::yield_now;
+pub use crate::scheduler::Priority;
 
 mod raw;
 use self::raw::RawTask;
@@ -22,7 +31,10 @@ mod state;
 
 mod waker;
 
-use std::{future::Future, marker::PhantomData, ptr::NonNull};
+use std::{any::Any, future::Future, marker::PhantomData, ptr::NonNull};
+
+/// Output of a task, or the payload of its panic.
+pub(crate) type Result<T> = std::result::Result<T, Box<dyn Any + Send + 'static>>;
 
 /// An owned handle to the task, tracked by ref count, not sendable
 #[repr(transparent)]
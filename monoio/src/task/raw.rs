@@ -12,7 +12,7 @@ pub(crate) struct RawTask {
 
 impl Clone for RawTask {
     fn clone(&self) -> Self {
-        RawTask { ptr: self.ptr }
+        *self
     }
 }
 
@@ -30,6 +30,9 @@ pub(crate) struct Vtable {
     /// The join handle has been dropped
     pub(crate) drop_join_handle_slow: unsafe fn(NonNull<Header>),
 
+    /// Abort the task
+    pub(crate) abort: unsafe fn(NonNull<Header>),
+
     /// Set future output
     #[cfg(feature = "sync")]
     pub(crate) finish: unsafe fn(NonNull<Header>, *mut ()),
@@ -42,6 +45,7 @@ pub(super) fn vtable<T: Future, S: Schedule>() -> &'static Vtable {
         dealloc: dealloc::<T, S>,
         try_read_output: try_read_output::<T, S>,
         drop_join_handle_slow: drop_join_handle_slow::<T, S>,
+        abort: abort::<T, S>,
         #[cfg(feature = "sync")]
         finish: finish::<T, S>,
     }
@@ -92,6 +96,11 @@ impl RawTask {
         unsafe { (vtable.drop_join_handle_slow)(self.ptr) }
     }
 
+    pub(crate) fn abort(self) {
+        let vtable = self.header().vtable;
+        unsafe { (vtable.abort)(self.ptr) }
+    }
+
     #[cfg(feature = "sync")]
     pub(crate) unsafe fn finish(self, val_slot: *mut ()) {
         let vtable = self.header().vtable;
@@ -121,7 +130,7 @@ unsafe fn try_read_output<T: Future, S: Schedule>(
     dst: *mut (),
     waker: &Waker,
 ) {
-    let out = &mut *(dst as *mut Poll<T::Output>);
+    let out = &mut *(dst as *mut Poll<super::Result<T::Output>>);
 
     let harness = Harness::<T, S>::from_raw(ptr);
     harness.try_read_output(out, waker);
@@ -131,3 +140,8 @@ unsafe fn drop_join_handle_slow<T: Future, S: Schedule>(ptr: NonNull<Header>) {
     let harness = Harness::<T, S>::from_raw(ptr);
     harness.drop_join_handle_slow()
 }
+
+unsafe fn abort<T: Future, S: Schedule>(ptr: NonNull<Header>) {
+    let harness = Harness::<T, S>::from_raw(ptr);
+    harness.abort()
+}
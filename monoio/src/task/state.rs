@@ -36,8 +36,12 @@ const JOIN_INTEREST: usize = 0b1_000;
 #[allow(clippy::unusual_byte_groupings)] // https://github.com/rust-lang/rust-clippy/issues/6556
 const JOIN_WAKER: usize = 0b10_000;
 
+/// The task is aborted, its future is dropped instead of polled
+#[allow(clippy::unusual_byte_groupings)] // https://github.com/rust-lang/rust-clippy/issues/6556
+const CANCELLED: usize = 0b100_000;
+
 /// All bits
-const STATE_MASK: usize = LIFECYCLE_MASK | NOTIFIED | JOIN_INTEREST | JOIN_WAKER;
+const STATE_MASK: usize = LIFECYCLE_MASK | NOTIFIED | JOIN_INTEREST | JOIN_WAKER | CANCELLED;
 
 /// Bits used by the ref count portion of the state.
 const REF_COUNT_MASK: usize = !STATE_MASK;
@@ -136,6 +140,22 @@ impl State {
         action
     }
 
+    /// Sets the `CANCELLED` bit, and `NOTIFIED` so the task is polled once more
+    /// to drop its future. Returns `true` if the task must be submitted.
+    pub(super) fn transition_to_cancelled(&self) -> bool {
+        let mut submit = false;
+        let _ = self.fetch_update(|mut curr| {
+            if curr.is_complete() || curr.is_cancelled() {
+                return None;
+            }
+            submit = curr.is_idle() && !curr.is_notified();
+            curr.set_cancelled();
+            curr.set_notified();
+            Some(curr)
+        });
+        submit
+    }
+
     /// Optimistically tries to swap the state assuming the join handle is
     /// __immediately__ dropped on spawn
     pub(super) fn drop_join_handle_fast(&self) -> Result<(), ()> {
@@ -302,6 +322,14 @@ impl Snapshot {
         self.0 & COMPLETE == COMPLETE
     }
 
+    pub(super) fn is_cancelled(self) -> bool {
+        self.0 & CANCELLED == CANCELLED
+    }
+
+    fn set_cancelled(&mut self) {
+        self.0 |= CANCELLED;
+    }
+
     pub(super) fn is_join_interested(self) -> bool {
         self.0 & JOIN_INTEREST == JOIN_INTEREST
     }
@@ -340,6 +368,7 @@ impl fmt::Debug for Snapshot {
             .field("is_running", &self.is_running())
             .field("is_complete", &self.is_complete())
             .field("is_notified", &self.is_notified())
+            .field("is_cancelled", &self.is_cancelled())
             .field("is_join_interested", &self.is_join_interested())
             .field("has_join_waker", &self.has_join_waker())
             .field("ref_count", &self.ref_count())
@@ -28,7 +28,7 @@ pub(crate) fn dummy_waker() -> Waker {
 }
 
 thread_local! {
-    pub(crate) static SHOULD_POLL: Cell<bool> = Cell::new(true);
+    pub(crate) static SHOULD_POLL: Cell<bool> = const { Cell::new(true) };
 }
 
 #[inline]
@@ -36,6 +36,12 @@ pub(crate) fn should_poll() -> bool {
     SHOULD_POLL.with(|b| b.replace(false))
 }
 
+/// Like `should_poll`, but does not clear the flag.
+#[inline]
+pub(crate) fn poll_pending() -> bool {
+    SHOULD_POLL.with(|b| b.get())
+}
+
 #[inline]
 pub(crate) fn set_poll() {
     SHOULD_POLL.with(|b| {
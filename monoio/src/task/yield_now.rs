@@ -0,0 +1,45 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Yield to the other tasks of the thread, so a CPU-heavy loop can share it.
+///
+/// The current task is put behind the other ready tasks of its priority, and
+/// runs again once the tasks woken before it have run.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() {
+///     for i in 0..1_000_000u64 {
+///         if i % 1024 == 0 {
+///             monoio::task::yield_now().await;
+///         }
+///     }
+/// }
+/// ```
+pub async fn yield_now() {
+    YieldNow { yielded: false }.await
+}
+
+struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        // A task out of budget is scheduled behind the others.
+        super::coop::stop();
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
@@ -114,6 +114,7 @@ impl StateCell {
         }
     }
 
+    #[allow(dead_code)]
     fn is_pending(&self) -> bool {
         self.state.get() == STATE_PENDING_FIRE
     }
@@ -524,6 +525,7 @@ impl TimerHandle {
         unsafe { self.inner.as_ref().sync_when() }
     }
 
+    #[allow(dead_code)]
     pub(super) unsafe fn is_pending(&self) -> bool {
         unsafe { self.inner.as_ref().state.is_pending() }
     }
@@ -2,12 +2,11 @@
 // in the future, this will change to the reverse. For now, suppress this
 // warning and generally stick with being explicit about unsafety.
 #![allow(unused_unsafe)]
-#![cfg_attr(not(feature = "rt"), allow(dead_code))]
 
 //! Time driver
 
 mod entry;
-pub(self) use self::entry::{EntryList, TimerEntry, TimerHandle, TimerShared};
+use self::entry::{EntryList, TimerEntry, TimerHandle, TimerShared};
 
 mod handle;
 pub(crate) use self::handle::Handle;
@@ -93,7 +92,7 @@ pub struct TimeDriver<D: 'static> {
 
 /// A structure which handles conversion from Instants to u64 timestamps.
 #[derive(Debug, Clone)]
-pub(self) struct ClockTime {
+struct ClockTime {
     clock: super::clock::Clock,
     start_time: Instant,
 }
@@ -179,6 +178,7 @@ where
     /// can either be created directly or the `Handle` instance can be passed to
     /// `with_default`, setting the timer as the default timer for the execution
     /// context.
+    #[allow(dead_code)]
     pub(crate) fn handle(&self) -> Handle {
         self.handle.clone()
     }
@@ -226,6 +226,16 @@ where
 }
 
 impl Handle {
+    /// Returns the duration until the earliest timer expires.
+    pub(crate) fn next_timeout(&self) -> Option<Duration> {
+        let when = self.get().state.borrow().wheel.next_expiration_time()?;
+        let now = self.time_source().now();
+        Some(
+            self.time_source()
+                .tick_to_duration(when.saturating_sub(now)),
+        )
+    }
+
     /// Runs timer related logic, and returns the next wakeup time
     pub(self) fn process(&self) {
         let now = self.time_source().now();
@@ -347,6 +357,16 @@ where
     }
 }
 
+#[cfg(unix)]
+impl<D> std::os::unix::prelude::AsRawFd for TimeDriver<D>
+where
+    D: std::os::unix::prelude::AsRawFd,
+{
+    fn as_raw_fd(&self) -> std::os::unix::prelude::RawFd {
+        self.park.as_raw_fd()
+    }
+}
+
 impl<D> Drop for TimeDriver<D>
 where
     D: 'static,
@@ -150,13 +150,9 @@ impl Wheel {
             }
 
             // under what circumstances is poll.expiration Some vs. None?
-            let expiration = self.next_expiration().and_then(|expiration| {
-                if expiration.deadline > now {
-                    None
-                } else {
-                    Some(expiration)
-                }
-            });
+            let expiration = self
+                .next_expiration()
+                .filter(|expiration| expiration.deadline <= now);
 
             match expiration {
                 Some(ref expiration) if expiration.deadline > now => return None,
@@ -306,7 +302,7 @@ fn level_for(elapsed: u64, when: u64) -> usize {
     significant / 6
 }
 
-#[cfg(all(test, not(loom)))]
+#[cfg(test)]
 mod test {
     use super::*;
 
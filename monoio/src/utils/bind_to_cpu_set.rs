@@ -30,6 +30,28 @@ pub fn bind_to_cpu_set(_: impl IntoIterator<Item = usize>) -> BindError<()> {
     Ok(())
 }
 
+/// List the cpus the current thread is allowed to run on, e.g. to bind one
+/// runtime per core with [`RuntimeBuilder::bind_to_cpu`](crate::RuntimeBuilder::bind_to_cpu).
+#[cfg(any(target_os = "android", target_os = "dragonfly", target_os = "linux"))]
+pub fn available_cpus() -> BindError<Vec<usize>> {
+    let pid = nix::unistd::Pid::from_raw(0);
+    let cpuset = nix::sched::sched_getaffinity(pid)?;
+    let mut cpus = Vec::new();
+    for cpu in 0..nix::sched::CpuSet::count() {
+        if cpuset.is_set(cpu)? {
+            cpus.push(cpu);
+        }
+    }
+    Ok(cpus)
+}
+
+/// List the cpus of the machine(affinity is not queried for non-linux)
+#[cfg(not(any(target_os = "android", target_os = "dragonfly", target_os = "linux")))]
+pub fn available_cpus() -> BindError<Vec<usize>> {
+    let count = std::thread::available_parallelism().map_or(1, |n| n.get());
+    Ok((0..count).collect())
+}
+
 #[cfg(all(test, feature = "utils"))]
 mod tests {
     use super::*;
@@ -43,4 +65,11 @@ mod tests {
         ))]
         assert!(bind_to_cpu_set(Some(100000)).is_err());
     }
+
+    #[test]
+    fn list_cpus() {
+        let cpus = available_cpus().unwrap();
+        assert!(!cpus.is_empty());
+        assert!(bind_to_cpu_set(cpus).is_ok());
+    }
 }
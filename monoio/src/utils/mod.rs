@@ -1,6 +1,7 @@
 //! Common utils
 
 pub(crate) mod linked_list;
+#[cfg(any(feature = "legacy", all(target_os = "linux", feature = "iouring")))]
 pub(crate) mod slab;
 pub(crate) mod thread_id;
 pub(crate) mod uring_detect;
@@ -12,4 +13,4 @@ pub use uring_detect::detect_uring;
 #[cfg(feature = "utils")]
 mod bind_to_cpu_set;
 #[cfg(feature = "utils")]
-pub use bind_to_cpu_set::{bind_to_cpu_set, BindError};
+pub use bind_to_cpu_set::{available_cpus, bind_to_cpu_set, BindError};
@@ -7,7 +7,8 @@ use std::{
 };
 
 /// Pre-allocated storage for a uniform data type
-#[derive(Default)]
+///
+/// Slots live in pages doubling in size, so growing never moves elements.
 pub(crate) struct Slab<T> {
     // pages of continued memory
     pages: [Option<Page<T>>; NUM_PAGES],
@@ -15,6 +16,17 @@ pub(crate) struct Slab<T> {
     w_page_id: usize,
     // current generation
     generation: u32,
+    // number of leading pages kept by compaction
+    reserved_pages: usize,
+    // max number of elements
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    limit: usize,
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 const NUM_PAGES: usize = 26;
@@ -31,9 +43,30 @@ impl<T> Slab<T> {
             ],
             w_page_id: 0,
             generation: 0,
+            reserved_pages: 1,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            limit: usize::MAX,
         }
     }
 
+    /// Allocate the pages for at least `capacity` elements. They are kept
+    /// when compacting.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn reserve(&mut self, capacity: usize) {
+        let mut page_id = 0;
+        while page_id < NUM_PAGES && self.capacity() < capacity {
+            self.page(page_id);
+            page_id += 1;
+        }
+        self.reserved_pages = self.reserved_pages.max(page_id);
+    }
+
+    /// Limit the number of elements, see [`try_insert`](Self::try_insert).
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
     /// Get slab len.
     #[allow(unused)]
     pub(crate) fn len(&self) -> usize {
@@ -43,14 +76,19 @@ impl<T> Slab<T> {
         })
     }
 
+    /// Get the number of allocated slots.
+    pub(crate) fn capacity(&self) -> usize {
+        self.pages.iter().fold(0, |acc, page| match page {
+            Some(page) => acc + page.slots.len(),
+            None => acc,
+        })
+    }
+
     pub(crate) fn get(&mut self, key: usize) -> Option<Ref<'_, T>> {
         let page_id = get_page_id(key);
         // here we make 2 mut ref so we must make it safe.
         let slab = unsafe { &mut *(self as *mut Slab<T>) };
-        let page = match unsafe { self.pages.get_unchecked_mut(page_id) } {
-            Some(page) => page,
-            None => return None,
-        };
+        let page = unsafe { self.pages.get_unchecked_mut(page_id) }.as_mut()?;
         let index = key - page.prev_len;
         match page.get_entry_mut(index) {
             None => None,
@@ -61,42 +99,50 @@ impl<T> Slab<T> {
         }
     }
 
+    // Get the page, allocating it if needed.
+    fn page(&mut self, page_id: usize) -> &mut Page<T> {
+        let page = unsafe { self.pages.get_unchecked_mut(page_id) };
+        page.get_or_insert_with(|| {
+            Page::new(
+                PAGE_INITIAL_SIZE << page_id,
+                (PAGE_INITIAL_SIZE << page_id) - PAGE_INITIAL_SIZE,
+            )
+        })
+    }
+
     /// Insert an element into slab. The key is returned.
     /// Note: If the slab is out of slot, it will panic.
     pub(crate) fn insert(&mut self, val: T) -> usize {
         let begin_id = self.w_page_id;
         for i in begin_id..NUM_PAGES {
             unsafe {
-                let page = match self.pages.get_unchecked_mut(i) {
-                    Some(page) => page,
-                    None => {
-                        let page = Page::new(
-                            PAGE_INITIAL_SIZE << i,
-                            (PAGE_INITIAL_SIZE << i) - PAGE_INITIAL_SIZE,
-                        );
-                        let r = self.pages.get_unchecked_mut(i);
-                        *r = Some(page);
-                        r.as_mut().unwrap_unchecked()
-                    }
-                };
+                let page = self.page(i);
                 if let Some(slot) = page.alloc() {
                     page.set(slot, val);
+                    let key = slot + page.prev_len;
                     self.w_page_id = i;
-                    return slot + page.prev_len;
+                    return key;
                 }
             }
         }
         panic!("out of slot");
     }
 
+    /// Insert an element into slab, or give it back if the slab holds as many
+    /// elements as its limit.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn try_insert(&mut self, val: T) -> Result<usize, T> {
+        if self.limit != usize::MAX && self.len() >= self.limit {
+            return Err(val);
+        }
+        Ok(self.insert(val))
+    }
+
     /// Remove an element from slab.
     #[allow(unused)]
     pub(crate) fn remove(&mut self, key: usize) -> Option<T> {
         let page_id = get_page_id(key);
-        let page = match unsafe { self.pages.get_unchecked_mut(page_id) } {
-            Some(page) => page,
-            None => return None,
-        };
+        let page = unsafe { self.pages.get_unchecked_mut(page_id) }.as_mut()?;
         let val = page.remove(key - page.prev_len);
         self.mark_remove();
         val
@@ -105,21 +151,14 @@ impl<T> Slab<T> {
     pub(crate) fn mark_remove(&mut self) {
         // compact
         self.generation = self.generation.wrapping_add(1);
-        if self.generation % COMPACT_INTERVAL == 0 {
+        if self.generation.is_multiple_of(COMPACT_INTERVAL) {
             // reset write page index
             self.w_page_id = 0;
-            // find the last allocated page and try to drop
-            if let Some((id, last_page)) = self
-                .pages
-                .iter_mut()
-                .enumerate()
-                .rev()
-                .find_map(|(id, p)| p.as_mut().map(|p| (id, p)))
-            {
-                if last_page.is_empty() && id > 0 {
-                    unsafe {
-                        *self.pages.get_unchecked_mut(id) = None;
-                    }
+            // drop all empty pages except the reserved ones, so the memory can
+            // be released after a burst
+            for page in self.pages.iter_mut().skip(self.reserved_pages) {
+                if matches!(page, Some(p) if p.is_empty()) {
+                    *page = None;
                 }
             }
         }
@@ -336,7 +375,7 @@ impl<T> Drop for Page<T> {
             } else {
                 // slow drop
                 to_drop.set_len(self.initialized);
-                std::mem::transmute::<_, Vec<Entry<T>>>(to_drop);
+                std::mem::transmute::<Vec<MaybeUninit<Entry<T>>>, Vec<Entry<T>>>(to_drop);
             }
         }
     }
@@ -387,6 +426,52 @@ mod tests {
         assert!(slab.remove(usize::MAX).is_none());
     }
 
+    #[test]
+    fn shrink_after_burst() {
+        let mut slab = Slab::default();
+        let keys = (0..100_000).map(|i| slab.insert(i)).collect::<Vec<_>>();
+        let peak = slab.capacity();
+        assert!(peak >= 100_000);
+        for key in keys {
+            slab.remove(key);
+        }
+        // make sure compaction happens
+        for _ in 0..COMPACT_INTERVAL {
+            let key = slab.insert(0);
+            slab.remove(key);
+        }
+        assert_eq!(slab.len(), 0);
+        assert_eq!(slab.capacity(), PAGE_INITIAL_SIZE);
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[test]
+    fn reserve_and_limit() {
+        let mut slab = Slab::new();
+        slab.reserve(1000);
+        let reserved = slab.capacity();
+        assert!(reserved >= 1000);
+
+        slab.set_limit(1000);
+        let keys = (0..1000)
+            .map(|i| slab.try_insert(i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(slab.try_insert(1000), Err(1000));
+        // no page is allocated within the reserved capacity
+        assert_eq!(slab.capacity(), reserved);
+        for key in keys {
+            slab.remove(key);
+        }
+        assert!(slab.try_insert(0).is_ok());
+
+        // reserved pages are kept by compaction
+        for _ in 0..COMPACT_INTERVAL {
+            let key = slab.insert(0);
+            slab.remove(key);
+        }
+        assert_eq!(slab.capacity(), reserved);
+    }
+
     #[test]
     fn insert_remove_big() {
         let mut slab = Slab::default();
@@ -0,0 +1,59 @@
+#![cfg(unix)]
+
+use std::{
+    future::Future,
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    pin::pin,
+    task::Poll,
+};
+
+use monoio::io::AsyncFd;
+
+// Poll the future once, returning whether it is pending.
+async fn is_pending(fut: impl Future) -> bool {
+    let mut fut = pin!(fut);
+    std::future::poll_fn(|cx| Poll::Ready(fut.as_mut().poll(cx).is_pending())).await
+}
+
+#[monoio::test_all]
+async fn readable_writable() {
+    let (a, mut b) = UnixStream::pair().unwrap();
+    a.set_nonblocking(true).unwrap();
+    let a = AsyncFd::new(a).unwrap();
+    a.writable().await.unwrap();
+
+    // Nothing to read yet.
+    assert!(is_pending(a.readable()).await);
+    b.write_all(b"ping").unwrap();
+    a.readable().await.unwrap();
+    let mut buf = [0; 8];
+    assert_eq!(a.get_ref().read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"ping");
+
+    // Drained, so it is not readable anymore.
+    assert_eq!(
+        a.get_ref().read(&mut buf).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    assert!(is_pending(a.readable()).await);
+
+    // Eof is readable.
+    drop(b);
+    a.readable().await.unwrap();
+    assert_eq!(a.get_ref().read(&mut buf).unwrap(), 0);
+}
+
+#[monoio::test_all]
+async fn into_inner_keeps_fd() {
+    let (a, mut b) = UnixStream::pair().unwrap();
+    let a = AsyncFd::new(a).unwrap();
+    b.write_all(b"pong").unwrap();
+    a.readable().await.unwrap();
+
+    // The fd of the inner value is still open after the wrapper is gone.
+    let mut a = a.into_inner();
+    let mut buf = [0; 4];
+    a.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"pong");
+}
@@ -0,0 +1,72 @@
+use monoio::{
+    buf::{pool, IoBufMut},
+    fs::File,
+    io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+async fn file_round_trip() {
+    let tempfile = tempfile::NamedTempFile::new().unwrap();
+    let file = File::create(tempfile.path()).await.unwrap();
+    let mut buf = pool::get(64);
+    buf.extend_from_slice(b"hello pool");
+    let (res, _) = file.write_all_at(buf, 0).await;
+    res.unwrap();
+    file.sync_all().await.unwrap();
+
+    let file = File::open(tempfile.path()).await.unwrap();
+    // Read into a slice of the buffer.
+    let (res, buf) = file.read_at(pool::get(64).slice_mut(0..5), 6).await;
+    assert_eq!(res.unwrap(), 4);
+    assert_eq!(&buf.into_inner()[..], b"pool");
+}
+
+async fn tcp_round_trip() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let echo = monoio::spawn(async move {
+        let (mut conn, _) = listener.accept().await.unwrap();
+        let (res, buf) = conn.read(pool::get(64)).await;
+        let n = res.unwrap();
+        assert_eq!(n, 4);
+        conn.write_all(buf).await.0.unwrap();
+    });
+    let mut conn = TcpStream::connect(addr).await.unwrap();
+    let mut buf = pool::get(64);
+    buf.extend_from_slice(b"ping");
+    conn.write_all(buf).await.0.unwrap();
+    let (res, buf) = conn.read_exact(pool::get(64).slice_mut(0..4)).await;
+    res.unwrap();
+    assert_eq!(&buf.into_inner()[..], b"ping");
+    echo.await;
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn registered_buffers() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .with_buffer_pool(4096, 4)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let bufs = (0..4).map(|_| pool::get(4096)).collect::<Vec<_>>();
+        assert!(bufs.iter().all(|buf| buf.is_registered()));
+        // All taken, or too large.
+        assert!(!pool::get(1).is_registered());
+        drop(bufs);
+        assert!(!pool::get(8192).is_registered());
+
+        file_round_trip().await;
+        tcp_round_trip().await;
+    });
+    drop(rt);
+    assert!(!pool::get(1).is_registered());
+}
+
+#[monoio::test_all]
+async fn heap_fallback() {
+    // No buffer pool is configured, so buffers come from the heap.
+    assert!(!pool::get(64).is_registered());
+    file_round_trip().await;
+    tcp_round_trip().await;
+}
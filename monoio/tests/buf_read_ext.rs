@@ -0,0 +1,66 @@
+use monoio::io::{stream::Stream, AsyncBufReadExt, AsyncReadRentExt, BufReader};
+
+#[monoio::test_all]
+async fn read_until() {
+    // A small buffer makes the delimiter span several fills.
+    let mut reader = BufReader::with_capacity(4, &b"hello,world,"[..]);
+    let (res, buf) = reader.read_until(b',', Vec::new()).await;
+    assert_eq!(res.unwrap(), 6);
+    assert_eq!(buf, b"hello,");
+    let (res, buf) = reader.read_until(b',', buf).await;
+    assert_eq!(res.unwrap(), 6);
+    assert_eq!(buf, b"hello,world,");
+    let (res, buf) = reader.read_until(b',', Vec::new()).await;
+    assert_eq!(res.unwrap(), 0);
+    assert!(buf.is_empty());
+}
+
+#[monoio::test_all]
+async fn read_until_limited() {
+    let mut reader = BufReader::with_capacity(4, &b"toolongline\nok\n"[..]);
+    let (res, buf) = reader.read_until_limited(b'\n', Vec::new(), 5).await;
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(buf, b"toolo");
+    // The rest of the line is left in the reader.
+    let (res, buf) = reader.read_exact(vec![0; 7]).await;
+    res.unwrap();
+    assert_eq!(buf, b"ngline\n");
+    // The delimiter counts in the limit.
+    let (res, buf) = reader.read_until_limited(b'\n', Vec::new(), 3).await;
+    assert_eq!(res.unwrap(), 3);
+    assert_eq!(buf, b"ok\n");
+}
+
+#[monoio::test_all]
+async fn read_line() {
+    let mut reader = BufReader::with_capacity(4, &b"first\r\n\xffbad\nlast"[..]);
+    let (res, line) = reader.read_line(String::from(">")).await;
+    assert_eq!(res.unwrap(), 7);
+    assert_eq!(line, ">first\r\n");
+    let (res, line) = reader.read_line(line).await;
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(line, ">first\r\n");
+    let (res, line) = reader.read_line(String::new()).await;
+    assert_eq!(res.unwrap(), 4);
+    assert_eq!(line, "last");
+    let (res, line) = reader.read_line_limited(String::new(), 1).await;
+    assert_eq!(res.unwrap(), 0);
+    assert!(line.is_empty());
+}
+
+#[monoio::test_all]
+async fn lines() {
+    let reader = BufReader::with_capacity(4, &b"a\r\nbb\n\nccc"[..]);
+    let mut lines = reader.lines();
+    assert_eq!(lines.next().await.unwrap().unwrap(), "a");
+    assert_eq!(lines.next_line().await.unwrap().unwrap(), "bb");
+    assert_eq!(lines.next().await.unwrap().unwrap(), "");
+    assert_eq!(lines.next().await.unwrap().unwrap(), "ccc");
+    assert!(lines.next().await.is_none());
+
+    let reader = BufReader::with_capacity(4, &b"ok\ntoo long\n"[..]);
+    let mut lines = reader.lines().limit(4);
+    assert_eq!(lines.next().await.unwrap().unwrap(), "ok");
+    let err = lines.next().await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
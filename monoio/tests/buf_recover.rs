@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use monoio::{
+    buf::Recoverable,
+    io::{AsyncReadRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+#[monoio::test_all(timer_enabled = true)]
+async fn recover_after_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    let (mut stream, _) = listener.accept().await.unwrap();
+
+    let (buf, handle) = Recoverable::new(Vec::with_capacity(1024));
+    let timeout = monoio::select! {
+        _ = stream.read(buf) => false,
+        _ = monoio::time::sleep(Duration::from_millis(10)) => true,
+    };
+    assert!(timeout);
+    let buf = handle.await.unwrap();
+    assert_eq!(buf.capacity(), 1024);
+
+    // The stream is still usable after the read is canceled.
+    let (res, _) = client.write_all(b"hello").await;
+    res.unwrap();
+    let (res, buf) = stream.read(buf).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+}
+
+#[monoio::test_all]
+async fn into_inner() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    let (mut stream, _) = listener.accept().await.unwrap();
+
+    let (res, _) = client.write_all(b"hello").await;
+    res.unwrap();
+    let (buf, handle) = Recoverable::new(Vec::with_capacity(1024));
+    let (res, buf) = stream.read(buf).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&buf.into_inner(), b"hello");
+    assert!(handle.await.is_none());
+}
@@ -0,0 +1,228 @@
+use monoio::{FusionDriver, RuntimeBuilder};
+
+#[test]
+fn build_auto() {
+    let mut rt = RuntimeBuilder::<FusionDriver>::new()
+        .enable_timer()
+        .build_auto()
+        .unwrap();
+    rt.block_on(async {
+        monoio::time::sleep(std::time::Duration::from_millis(1)).await;
+    });
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+#[test]
+fn build_auto_fallback() {
+    use monoio::FusionRuntime;
+
+    // The buffer pool is too large to register, so the ring can not be set
+    // up.
+    let builder = RuntimeBuilder::<FusionDriver>::new().with_buffer_pool(1 << 20, 2048);
+    assert!(builder.build().is_err());
+    let mut rt = builder.build_auto().unwrap();
+    assert!(matches!(rt, FusionRuntime::Legacy(_)));
+    assert_eq!(rt.block_on(async { 1 }), 1);
+}
+
+#[cfg(all(target_os = "linux", feature = "utils"))]
+#[test]
+fn bind_to_cpu() {
+    let cpus = monoio::utils::available_cpus().unwrap();
+    let cpu = *cpus.last().unwrap();
+    std::thread::spawn(move || {
+        let mut rt = RuntimeBuilder::<FusionDriver>::new()
+            .bind_to_cpu(cpu)
+            .enable_timer()
+            .build()
+            .unwrap();
+        rt.block_on(async {});
+        assert_eq!(monoio::utils::available_cpus().unwrap(), vec![cpu]);
+    })
+    .join()
+    .unwrap();
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+type Driver = monoio::IoUringDriver;
+#[cfg(not(all(target_os = "linux", feature = "iouring")))]
+type Driver = monoio::LegacyDriver;
+
+#[test]
+fn start_threads() {
+    use monoio::time::TimeDriver;
+
+    let outputs = monoio::start_threads::<TimeDriver<Driver>, _, _>(3, |idx| async move {
+        monoio::time::sleep(std::time::Duration::from_millis(1)).await;
+        (idx, std::thread::current().name().unwrap().to_string())
+    });
+    assert_eq!(
+        outputs,
+        (0..3)
+            .map(|idx| (idx, format!("monoio-worker-{idx}")))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[cfg(all(target_os = "linux", feature = "utils"))]
+#[test]
+fn start_threads_pinned() {
+    let cpus = monoio::utils::available_cpus().unwrap();
+    let outputs = monoio::start_threads_pinned::<Driver, _, _>(2, |_| async {
+        monoio::utils::available_cpus().unwrap()
+    });
+    assert_eq!(outputs[0], vec![cpus[0]]);
+    assert_eq!(outputs[1], vec![cpus[1 % cpus.len()]]);
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn uring_builder_passthrough() {
+    use monoio::IoUringDriver;
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_builder(|b| b.setup_cqsize(4096).setup_clamp())
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let (res, _) = monoio::fs::File::open("/dev/null")
+            .await
+            .unwrap()
+            .read_at(vec![0; 8], 0)
+            .await;
+        assert_eq!(res.unwrap(), 0);
+    });
+
+    // Attaching to the workers of a ring which does not exist fails.
+    let builder = RuntimeBuilder::<IoUringDriver>::new().uring_builder(|b| b.setup_attach_wq(-1));
+    assert!(builder.build().is_err());
+
+    // Parking on the eventfd does not work with deferred completions.
+    #[cfg(feature = "sync")]
+    {
+        let builder = RuntimeBuilder::<IoUringDriver>::new()
+            .uring_builder(|b| b.setup_single_issuer().setup_defer_taskrun());
+        let err = builder.build().err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn submit_watermark() {
+    use monoio::{
+        io::AsyncWriteRent,
+        net::{TcpListener, TcpStream},
+        IoUringDriver,
+    };
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_submit_watermark(2)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let (mut server, _) = listener.accept().await.unwrap();
+
+        let before = monoio::stats::driver_stats();
+        let ping = client.write(b"ping");
+        // One entry is queued, below the watermark.
+        assert_eq!(
+            monoio::stats::driver_stats().submit_calls,
+            before.submit_calls
+        );
+        let pong = server.write(b"pong");
+        // The second one reaches it, so both are submitted before awaiting.
+        let after = monoio::stats::driver_stats();
+        assert_eq!(after.submit_calls, before.submit_calls + 1);
+        assert_eq!(after.sqes_submitted, before.sqes_submitted + 2);
+        let ((ping, _), (pong, _)) = monoio::join!(ping, pong);
+        ping.unwrap();
+        pong.unwrap();
+    });
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn op_slab_limit() {
+    use monoio::{
+        io::{AsyncReadRent, AsyncWriteRent},
+        net::{TcpListener, TcpStream},
+        IoUringDriver,
+    };
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_op_slab(1024, Some(1))
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let (mut server, _) = listener.accept().await.unwrap();
+        let stats = monoio::stats::driver_stats();
+        assert!(stats.slab_capacity >= 1024);
+
+        // The pending read takes the only slot.
+        let read = server.read(vec![0; 4]);
+        let (res, buf) = client.write(b"ping").await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBUSY));
+        assert_eq!(buf, b"ping");
+
+        // The slot is given back once the read is canceled.
+        drop(read);
+        let mut written = None;
+        for _ in 0..100 {
+            if let (Ok(n), _) = client.write(b"ping").await {
+                written = Some(n);
+                break;
+            }
+            monoio::task::yield_now().await;
+        }
+        assert_eq!(written, Some(4));
+        assert_eq!(
+            monoio::stats::driver_stats().slab_capacity,
+            stats.slab_capacity
+        );
+    });
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn op_slab_limit_close() {
+    use std::os::unix::io::AsRawFd;
+
+    use monoio::{
+        io::AsyncReadRent,
+        net::{TcpListener, TcpStream},
+        IoUringDriver,
+    };
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_op_slab(1024, Some(1))
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).await.unwrap();
+        let (mut server, _) = listener.accept().await.unwrap();
+
+        // The pending read takes the only slot, so the close op of the
+        // dropped stream is turned down and the fd is closed in place.
+        let read = monoio::spawn(async move { server.read(vec![0; 4]).await });
+        monoio::task::yield_now().await;
+        let fd = client.as_raw_fd();
+        drop(client);
+        assert_eq!(unsafe { libc::fcntl(fd, libc::F_GETFD) }, -1);
+        assert_eq!(
+            std::io::Error::last_os_error().raw_os_error(),
+            Some(libc::EBADF)
+        );
+        // Closing the client ends the read.
+        let (res, _) = read.await;
+        assert_eq!(res.unwrap(), 0);
+    });
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use monoio::{
+    io::{
+        AsyncWriteRentExt, CancelableAsyncReadRent, CancelableAsyncWriteRent, Canceller, Splitable,
+    },
+    net::{TcpListener, TcpStream},
+};
+
+async fn tcp_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let conn = TcpStream::connect(addr).await.unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+    (conn, peer)
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn cancel_read_from_another_task() {
+    let (conn, mut peer) = tcp_pair().await;
+    let (mut read, _write) = conn.into_split();
+    let canceller = Canceller::new();
+    let handle = canceller.handle();
+    let reader = monoio::spawn(async move {
+        let (res, buf) = read.cancelable_read(Vec::with_capacity(64), handle).await;
+        (res, buf, read)
+    });
+
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    canceller.cancel();
+    let (res, buf, mut read) = reader.await;
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+    assert_eq!(buf.capacity(), 64);
+
+    // Later ops with a canceled handle fail right away.
+    let (res, _) = read
+        .cancelable_read(Vec::with_capacity(64), canceller.handle())
+        .await;
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+
+    // The stream is still usable with another canceller.
+    peer.write_all(b"hello").await.0.unwrap();
+    let (res, buf) = read
+        .cancelable_read(Vec::with_capacity(64), Canceller::new().handle())
+        .await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn cancel_accept() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let canceller = Canceller::new();
+    let handle = canceller.handle();
+    let acceptor = monoio::spawn(async move {
+        let res = listener.cancelable_accept(handle).await;
+        (res.map(|_| ()), listener)
+    });
+
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    canceller.cancel();
+    let (res, listener) = acceptor.await;
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+
+    let _conn = TcpStream::connect(addr).await.unwrap();
+    listener.accept().await.unwrap();
+}
+
+#[monoio::test_all]
+async fn not_canceled() {
+    let (mut conn, mut peer) = tcp_pair().await;
+    let canceller = Canceller::new();
+
+    let (res, _) = conn
+        .cancelable_write(b"hello".to_vec(), canceller.handle())
+        .await;
+    assert_eq!(res.unwrap(), 5);
+    let (res, buf) = peer
+        .cancelable_read(Vec::with_capacity(64), canceller.handle())
+        .await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+
+    // Canceling without pending ops only affects later ones.
+    canceller.cancel();
+    assert!(canceller.is_canceled());
+    let (res, _) = conn
+        .cancelable_write(b"hello".to_vec(), canceller.handle())
+        .await;
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+}
@@ -0,0 +1,117 @@
+#![cfg(all(unix, feature = "bytes"))]
+
+use std::io;
+
+use bytes::{Buf, BufMut, BytesMut};
+use monoio::{
+    codec::{Decoder, Encoder, Framed, FramedRead, FramedWrite},
+    io::{
+        sink::{Sink, SinkExt},
+        stream::Stream,
+        AsyncWriteRent, Splitable,
+    },
+    net::UnixStream,
+};
+
+// Frames prefixed by a u16 length.
+struct LengthCodec;
+
+impl Decoder for LengthCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Vec<u8>>> {
+        if src.len() < 2 {
+            return Ok(None);
+        }
+        let len = u16::from_be_bytes([src[0], src[1]]) as usize;
+        if src.len() < 2 + len {
+            src.reserve(2 + len - src.len());
+            return Ok(None);
+        }
+        src.advance(2);
+        Ok(Some(src.split_to(len).to_vec()))
+    }
+}
+
+impl Encoder<Vec<u8>> for LengthCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Vec<u8>, dst: &mut BytesMut) -> io::Result<()> {
+        dst.put_u16(item.len() as u16);
+        dst.put_slice(&item);
+        Ok(())
+    }
+}
+
+#[monoio::test_all]
+async fn framed_echo() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let server = monoio::spawn(async move {
+        let mut framed = Framed::new(b, LengthCodec);
+        while let Some(frame) = framed.next().await {
+            let frame = frame.unwrap();
+            framed.send_and_flush(frame).await.unwrap();
+        }
+        framed.close().await.unwrap();
+    });
+
+    let mut framed = Framed::with_capacity(a, LengthCodec, 4);
+    let big = vec![1; 1000];
+    framed.send(b"hello".to_vec()).await.unwrap();
+    // Nothing is written before the flush.
+    assert_eq!(framed.write_buffer().len(), 7);
+    framed.send(big.clone()).await.unwrap();
+    framed.flush().await.unwrap();
+    assert!(framed.write_buffer().is_empty());
+    assert_eq!(framed.next().await.unwrap().unwrap(), b"hello");
+    assert_eq!(framed.next().await.unwrap().unwrap(), big);
+
+    framed.get_mut().shutdown().await.unwrap();
+    assert!(framed.next().await.is_none());
+    server.await;
+}
+
+#[monoio::test_all]
+async fn framed_read_write() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let (_, write_half) = a.into_split();
+    let mut reader = FramedRead::new(b, LengthCodec);
+    let mut writer = FramedWrite::new(write_half, LengthCodec);
+    writer.set_backpressure_boundary(8);
+
+    // The buffer is written out once it reaches the boundary.
+    writer.send(b"abc".to_vec()).await.unwrap();
+    assert_eq!(writer.write_buffer().len(), 5);
+    writer.send(b"defg".to_vec()).await.unwrap();
+    assert!(writer.write_buffer().is_empty());
+    assert_eq!(reader.next().await.unwrap().unwrap(), b"abc");
+    assert_eq!(reader.next().await.unwrap().unwrap(), b"defg");
+
+    // An incomplete frame at EOF is an error.
+    writer.write_buffer_mut().put_slice(&[0, 5, 1]);
+    writer.close().await.unwrap();
+    let err = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(reader.read_buffer().len(), 3);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn dropped_next_keeps_bytes() {
+    use std::time::Duration;
+
+    use monoio::io::AsyncWriteRentExt;
+
+    let (mut a, b) = UnixStream::pair().unwrap();
+    let mut reader = FramedRead::new(b, LengthCodec);
+
+    a.write_all(vec![0, 5, b'h', b'e']).await.0.unwrap();
+    // The partial frame is read, then `next` waits for the rest.
+    monoio::select! {
+        _ = reader.next() => panic!("the frame is not complete"),
+        _ = monoio::time::sleep(Duration::from_millis(20)) => {}
+    }
+    assert_eq!(reader.read_buffer().len(), 4);
+    a.write_all(b"llo".to_vec()).await.0.unwrap();
+    assert_eq!(reader.next().await.unwrap().unwrap(), b"hello");
+}
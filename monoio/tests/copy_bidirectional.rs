@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use monoio::{
+    io::{
+        zero_copy_bidirectional, zero_copy_bidirectional_with_idle_timeout, AsyncReadRent,
+        AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt,
+    },
+    net::{TcpListener, TcpStream},
+};
+
+const REQUEST: &[u8] = b"ping from client";
+const RESPONSE: &[u8] = b"pong from server, a bit longer";
+
+// Accept a client and connect to the server, returning both streams.
+async fn proxy_pair(front: &TcpListener, backend: &TcpListener) -> (TcpStream, TcpStream) {
+    let (client, _) = front.accept().await.unwrap();
+    let server = TcpStream::connect(backend.local_addr().unwrap())
+        .await
+        .unwrap();
+    (client, server)
+}
+
+#[monoio::test_all]
+async fn copy_bidirectional() {
+    let front = TcpListener::bind("127.0.0.1:0").unwrap();
+    let backend = TcpListener::bind("127.0.0.1:0").unwrap();
+    let front_addr = front.local_addr().unwrap();
+    let backend = std::rc::Rc::new(backend);
+
+    let server = monoio::spawn({
+        let backend = backend.clone();
+        async move {
+            let (mut conn, _) = backend.accept().await.unwrap();
+            let buf = vec![0; REQUEST.len()];
+            let (res, buf) = conn.read_exact(buf).await;
+            res.unwrap();
+            assert_eq!(buf, REQUEST);
+            conn.write_all(RESPONSE).await.0.unwrap();
+            // The client shut down its write side, so eof is forwarded.
+            let (res, _) = conn.read(vec![0; 1]).await;
+            assert_eq!(res.unwrap(), 0);
+        }
+    });
+    let client = monoio::spawn(async move {
+        let mut conn = TcpStream::connect(front_addr).await.unwrap();
+        conn.write_all(REQUEST).await.0.unwrap();
+        conn.shutdown().await.unwrap();
+        let buf = vec![0; RESPONSE.len()];
+        let (res, buf) = conn.read_exact(buf).await;
+        res.unwrap();
+        assert_eq!(buf, RESPONSE);
+        let (res, _) = conn.read(vec![0; 1]).await;
+        assert_eq!(res.unwrap(), 0);
+    });
+
+    let (mut a, mut b) = proxy_pair(&front, &backend).await;
+    let (a_to_b, b_to_a) = zero_copy_bidirectional(&mut a, &mut b).await.unwrap();
+    assert_eq!(a_to_b, REQUEST.len() as u64);
+    assert_eq!(b_to_a, RESPONSE.len() as u64);
+    client.await;
+    server.await;
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn copy_bidirectional_idle_timeout() {
+    let front = TcpListener::bind("127.0.0.1:0").unwrap();
+    let backend = TcpListener::bind("127.0.0.1:0").unwrap();
+    let front_addr = front.local_addr().unwrap();
+
+    // Both peers stay connected without sending anything.
+    let client = monoio::spawn(async move {
+        let conn = TcpStream::connect(front_addr).await.unwrap();
+        monoio::time::sleep(Duration::from_millis(500)).await;
+        drop(conn);
+    });
+    let (mut a, mut b) = proxy_pair(&front, &backend).await;
+    let (_server, _) = backend.accept().await.unwrap();
+
+    let begin = std::time::Instant::now();
+    let err = zero_copy_bidirectional_with_idle_timeout(&mut a, &mut b, Duration::from_millis(100))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert!(begin.elapsed() < Duration::from_millis(400));
+    client.await;
+}
+
+#[cfg(unix)]
+#[monoio::test_all]
+async fn copy_bidirectional_tcp_unix() {
+    use monoio::{io::AsyncReadRentExt, net::UnixStream};
+
+    const LEN: usize = 256 * 1024;
+    let front = TcpListener::bind("127.0.0.1:0").unwrap();
+    let front_addr = front.local_addr().unwrap();
+    let (mut a_unix, mut backend) = UnixStream::pair().unwrap();
+
+    // The backend answers only after the request is half-closed, and the
+    // response is larger than a pipe.
+    let server = monoio::spawn(async move {
+        let (res, buf) = backend.read_exact(vec![0; REQUEST.len()]).await;
+        res.unwrap();
+        assert_eq!(buf, REQUEST);
+        let (res, _) = backend.read(vec![0; 1]).await;
+        assert_eq!(res.unwrap(), 0);
+        backend.write_all(vec![9u8; LEN]).await.0.unwrap();
+        backend.shutdown().await.unwrap();
+    });
+    let client = monoio::spawn(async move {
+        let mut conn = TcpStream::connect(front_addr).await.unwrap();
+        conn.write_all(REQUEST).await.0.unwrap();
+        conn.shutdown().await.unwrap();
+        let mut read = 0;
+        loop {
+            let (res, buf) = conn.read(vec![0; 4096]).await;
+            let n = res.unwrap();
+            if n == 0 {
+                break;
+            }
+            assert!(buf[..n].iter().all(|b| *b == 9));
+            read += n;
+        }
+        assert_eq!(read, LEN);
+    });
+
+    let (mut a_tcp, _) = front.accept().await.unwrap();
+    let (a_to_b, b_to_a) = zero_copy_bidirectional(&mut a_tcp, &mut a_unix)
+        .await
+        .unwrap();
+    assert_eq!(a_to_b, REQUEST.len() as u64);
+    assert_eq!(b_to_a, LEN as u64);
+    client.await;
+    server.await;
+}
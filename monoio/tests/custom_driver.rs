@@ -0,0 +1,81 @@
+use std::{cell::Cell, io, rc::Rc, time::Duration};
+
+use monoio::{Driver, RuntimeBuilder};
+
+// A driver without IO, parking the thread.
+struct ParkDriver;
+
+impl Driver for ParkDriver {
+    fn with<R>(&self, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+
+    fn submit(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn park(&self) -> io::Result<()> {
+        std::thread::park();
+        Ok(())
+    }
+
+    fn park_timeout(&self, duration: Duration) -> io::Result<()> {
+        std::thread::park_timeout(duration);
+        Ok(())
+    }
+
+    #[cfg(feature = "sync")]
+    type Unpark = ThreadUnpark;
+
+    #[cfg(feature = "sync")]
+    fn unpark(&self) -> Self::Unpark {
+        ThreadUnpark(std::thread::current())
+    }
+}
+
+#[cfg(feature = "sync")]
+struct ThreadUnpark(std::thread::Thread);
+
+#[cfg(feature = "sync")]
+impl monoio::Unpark for ThreadUnpark {
+    fn unpark(&self) -> io::Result<()> {
+        self.0.unpark();
+        Ok(())
+    }
+}
+
+#[test]
+fn spawn_and_timer() {
+    let mut rt = RuntimeBuilder::<ParkDriver>::new().build_with_timer(ParkDriver);
+    let count = rt.block_on(async {
+        let count = Rc::new(Cell::new(0));
+        let tasks = (0..3)
+            .map(|i| {
+                let count = count.clone();
+                monoio::spawn(async move {
+                    monoio::time::sleep(Duration::from_millis(10 * i)).await;
+                    count.set(count.get() + 1);
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await;
+        }
+        monoio::flush().await;
+        count.get()
+    });
+    assert_eq!(count, 3);
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn remote_wake() {
+    let mut rt = RuntimeBuilder::<ParkDriver>::new().build_with(ParkDriver);
+    let (tx, rx) = futures::channel::oneshot::channel();
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        tx.send(1).unwrap();
+    });
+    assert_eq!(rt.block_on(rx).unwrap(), 1);
+    thread.join().unwrap();
+}
@@ -0,0 +1,52 @@
+use monoio::buf::DmaBuf;
+
+#[test]
+fn alignment() {
+    let buf = DmaBuf::with_align(1000, 512);
+    assert_eq!(buf.capacity(), 1024);
+    assert_eq!(buf.align(), 512);
+    assert_eq!(buf.as_ptr() as usize % 512, 0);
+    assert!(buf.is_empty());
+
+    let mut buf = DmaBuf::new(1);
+    assert_eq!(buf.capacity(), DmaBuf::DEFAULT_ALIGN);
+    assert_eq!(buf.as_ptr() as usize % DmaBuf::DEFAULT_ALIGN, 0);
+    buf.extend_from_slice(b"hello");
+    buf.set_len(8);
+    assert_eq!(&buf[..], b"hello\0\0\0");
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn direct_round_trip() {
+    use std::os::unix::io::AsRawFd;
+
+    use monoio::fs::OpenOptions;
+
+    // tmpfs does not support O_DIRECT.
+    let tempfile = tempfile::NamedTempFile::new_in(env!("CARGO_TARGET_TMPDIR")).unwrap();
+    let file = match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .direct(true)
+        .open(tempfile.path())
+        .await
+    {
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => return,
+        res => res.unwrap(),
+    };
+    let flags = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) };
+    assert_ne!(flags & libc::O_DIRECT, 0);
+
+    let mut buf = DmaBuf::new(4096);
+    buf.extend_from_slice(b"hello direct");
+    buf.set_len(4096);
+    let (res, _) = file.write_at(buf, 4096).await;
+    assert_eq!(res.unwrap(), 4096);
+
+    let (res, buf) = file.read_at(DmaBuf::new(8192), 0).await;
+    assert_eq!(res.unwrap(), 8192);
+    assert_eq!(&buf[..4096], &[0; 4096][..]);
+    assert_eq!(&buf[4096..4108], b"hello direct");
+    file.close().await.unwrap();
+}
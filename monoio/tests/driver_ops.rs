@@ -0,0 +1,193 @@
+//! Ops submitted, polled and dropped through the common driver path, on both
+//! drivers.
+
+use std::{future::Future, pin::pin, task::Poll};
+
+use monoio::{
+    buf::{IoBufMut, VecBuf},
+    fs::{File, OpenOptions},
+    io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+// Poll the future once and drop it, so a pending op is dropped.
+async fn poll_once(fut: impl Future) {
+    let mut fut = pin!(fut);
+    std::future::poll_fn(|cx| {
+        let _ = fut.as_mut().poll(cx);
+        Poll::Ready(())
+    })
+    .await;
+}
+
+#[monoio::test_all]
+async fn socket_ops() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let peer = monoio::spawn(async move {
+        let (mut conn, _) = listener.accept().await.unwrap();
+        let (res, buf) = conn.read_exact(vec![0; 5]).await;
+        res.unwrap();
+        conn.write_all(buf).await.0.unwrap();
+        let (res, buf) = conn.read_exact(vec![0; 6]).await;
+        res.unwrap();
+        assert_eq!(buf, b"vector");
+    });
+
+    let mut conn = TcpStream::connect(addr).await.unwrap();
+    // Nothing to read yet, the pending recv is dropped.
+    poll_once(conn.read(Vec::with_capacity(8))).await;
+
+    conn.write_all(b"hello").await.0.unwrap();
+    let (res, buf) = conn.read_exact(vec![0; 5].slice_mut(..)).await;
+    res.unwrap();
+    assert_eq!(buf.into_inner(), b"hello");
+
+    let (res, _) = conn
+        .writev(VecBuf::from(vec![b"vec".to_vec(), b"tor".to_vec()]))
+        .await;
+    assert_eq!(res.unwrap(), 6);
+    peer.await;
+}
+
+#[monoio::test_all]
+async fn file_ops() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ops");
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .open(&path)
+        .await
+        .unwrap();
+    file.write_all_at(&b"world"[..], 6).await.0.unwrap();
+    file.write_all_at(&b"hello "[..], 0).await.0.unwrap();
+    file.sync_data().await.unwrap();
+    file.sync_all().await.unwrap();
+    file.close().await.unwrap();
+
+    let file = File::open(&path).await.unwrap();
+    let (res, buf) = file.read_at(Vec::with_capacity(5), 6).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(buf, b"world");
+    let (res, buf) = file.read_exact_at(vec![0; 11], 0).await;
+    res.unwrap();
+    assert_eq!(buf, b"hello world");
+    file.close().await.unwrap();
+}
+
+#[monoio::test(driver = "uring")]
+async fn close_through_ring() {
+    const CONNS: usize = 32;
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut clients = Vec::with_capacity(CONNS);
+    let mut servers = Vec::with_capacity(CONNS);
+    for _ in 0..CONNS {
+        clients.push(TcpStream::connect(addr).await.unwrap());
+        servers.push(listener.accept().await.unwrap().0);
+    }
+
+    let before = monoio::stats::driver_stats();
+    drop(clients);
+    let after = monoio::stats::driver_stats();
+    // The closes are queued to the ring, not done by syscalls in place.
+    assert_eq!(after.syscall_fallbacks, before.syscall_fallbacks);
+    assert_eq!(after.ops_canceled, before.ops_canceled);
+
+    // The peers see the connections closed once the closes are submitted.
+    for mut conn in servers {
+        let (res, _) = conn.read(vec![0; 1]).await;
+        assert_eq!(res.unwrap(), 0);
+    }
+    let after = monoio::stats::driver_stats();
+    assert!(after.sqes_submitted >= before.sqes_submitted + CONNS as u64);
+    assert_eq!(after.ops_canceled, before.ops_canceled);
+}
+
+#[cfg(feature = "async-cancel")]
+#[monoio::test_all]
+async fn into_std_after_dropped_op() {
+    use std::io::Read;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut conn = TcpStream::connect(addr).await.unwrap();
+    let (mut peer, _) = listener.accept().await.unwrap();
+
+    // With the uring driver, the dropped recv is in flight until canceled.
+    poll_once(conn.read(Vec::with_capacity(8))).await;
+    let mut conn = conn.into_std().await.unwrap();
+
+    peer.write_all(b"hello").await.0.unwrap();
+    let mut buf = [0; 5];
+    conn.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+}
+
+#[cfg(feature = "async-cancel")]
+#[monoio::test_all]
+async fn into_std_drops_waker() {
+    use std::{
+        sync::Arc,
+        task::{Context, Waker},
+    };
+
+    // Wakes the task polling it, and counts the clones kept of it.
+    struct Forward(Waker);
+    impl futures::task::ArcWake for Forward {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.wake_by_ref();
+        }
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut conn = TcpStream::connect(addr).await.unwrap();
+    let _peer = listener.accept().await.unwrap();
+
+    poll_once(conn.read(Vec::with_capacity(8))).await;
+    let mut into_std = pin!(conn.into_std());
+    let mut forward = None;
+    let res = std::future::poll_fn(|cx| {
+        let forward = forward.get_or_insert_with(|| Arc::new(Forward(cx.waker().clone())));
+        let waker = futures::task::waker(forward.clone());
+        into_std.as_mut().poll(&mut Context::from_waker(&waker))
+    })
+    .await;
+    res.unwrap();
+    // The waker kept while waiting for the dropped recv is released.
+    assert_eq!(Arc::strong_count(&forward.unwrap()), 1);
+}
+
+#[cfg(feature = "async-cancel")]
+#[monoio::test_all]
+async fn listener_into_std_after_dropped_accept() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    poll_once(listener.accept()).await;
+    let listener = listener.into_std().await.unwrap();
+    let _conn = std::net::TcpStream::connect(addr).unwrap();
+    listener.accept().unwrap();
+}
+
+#[monoio::test(driver = "uring")]
+async fn uring_capabilities() {
+    // IORING_OP_NOP and IORING_OP_READ
+    const NOP: u8 = 0;
+    const READ: u8 = 22;
+
+    let caps = monoio::runtime::capabilities();
+    assert!(caps.is_uring());
+    assert!(caps.supports_opcode(NOP));
+    assert!(caps.supports_opcode(READ));
+    assert!(!caps.supports_opcode(u8::MAX));
+}
+
+#[cfg(feature = "legacy")]
+#[monoio::test(driver = "legacy")]
+async fn legacy_capabilities() {
+    let caps = monoio::runtime::capabilities();
+    assert!(!caps.is_uring());
+    assert_eq!(caps.supported_opcodes().count(), 0);
+}
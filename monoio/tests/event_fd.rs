@@ -0,0 +1,41 @@
+#![cfg(target_os = "linux")]
+
+use std::{future::Future, pin::pin, task::Poll};
+
+use monoio::io::EventFd;
+
+// Poll the future once, returning whether it is pending.
+async fn is_pending(fut: impl Future) -> bool {
+    let mut fut = pin!(fut);
+    std::future::poll_fn(|cx| Poll::Ready(fut.as_mut().poll(cx).is_pending())).await
+}
+
+#[monoio::test_all]
+async fn read_write() {
+    let event = EventFd::new(1).unwrap();
+    event.write(2).await.unwrap();
+    event.writer().write(3).unwrap();
+    assert_eq!(event.read().await.unwrap(), 6);
+    // The counter is reset by the read.
+    assert!(is_pending(event.read()).await);
+}
+
+#[monoio::test_all]
+async fn write_from_thread() {
+    let event = EventFd::new(0).unwrap();
+    let writer = event.writer();
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        writer.write(7).unwrap();
+    });
+    assert_eq!(event.read().await.unwrap(), 7);
+    thread.join().unwrap();
+}
+
+#[monoio::test_all]
+async fn writer_outlives_event_fd() {
+    let event = EventFd::new(0).unwrap();
+    let writer = event.writer();
+    drop(event);
+    writer.write(1).unwrap();
+}
@@ -0,0 +1,63 @@
+use monoio::{buf::FixedBufPool, fs::File};
+
+async fn round_trip(pool: &FixedBufPool) {
+    let tempfile = tempfile::NamedTempFile::new().unwrap();
+    let file = File::create(tempfile.path()).await.unwrap();
+    let mut buf = pool.get().await;
+    buf.extend_from_slice(b"hello fixed");
+    let (res, buf) = file.write_fixed_at(buf, 0).await;
+    assert_eq!(res.unwrap(), 11);
+    drop(buf);
+    file.close().await.unwrap();
+
+    let file = File::open(tempfile.path()).await.unwrap();
+    let (res, buf) = file.read_fixed_at(pool.get().await, 6).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&buf[..], b"fixed");
+    file.close().await.unwrap();
+}
+
+#[monoio::test_all]
+async fn fixed_round_trip() {
+    let pool = FixedBufPool::new(4096, 2).unwrap();
+    pool.register().unwrap();
+    round_trip(&pool).await;
+    pool.unregister().unwrap();
+    assert!(!pool.is_registered());
+    // Unregistered buffers are read and written as regular memory.
+    round_trip(&pool).await;
+}
+
+#[monoio::test_all]
+async fn wait_for_buffer() {
+    let pool = FixedBufPool::new(16, 1).unwrap();
+    let buf = pool.try_get().unwrap();
+    assert!(pool.try_get().is_none());
+    // The task runs when the pool has no buffer left.
+    let task = monoio::spawn(async move { drop(buf) });
+    let buf = pool.get().await;
+    assert_eq!(buf.capacity(), 16);
+    task.await;
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn registered_with_ring() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let pool = FixedBufPool::new(4096, 2).unwrap();
+        pool.register().unwrap();
+        assert!(pool.is_registered());
+        // A ring holds one set of registered buffers.
+        let other = FixedBufPool::new(4096, 2).unwrap();
+        assert!(other.register().is_err());
+        round_trip(&pool).await;
+
+        // Dropping the pool unregisters the buffers.
+        drop(pool);
+        other.register().unwrap();
+        round_trip(&other).await;
+    });
+}
@@ -0,0 +1,104 @@
+#![cfg(all(target_os = "linux", feature = "iouring"))]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    future::{poll_fn, Future},
+    task::Poll,
+};
+
+use monoio::{buf::FixedBufPool, fs::OpenOptions};
+
+// Counts the allocations of the current thread.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|n| n.get())
+}
+
+fn runtime() -> monoio::Runtime<monoio::time::TimeDriver<monoio::IoUringDriver>> {
+    monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .with_fixed_files(1)
+        .enable_timer()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn no_allocation() {
+    runtime().block_on(async {
+        let tempfile = tempfile::NamedTempFile::new().unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        assert!(file.register_fixed().unwrap());
+        let pool = FixedBufPool::new(4096, 1).unwrap();
+        pool.register().unwrap();
+
+        let mut buf = pool.try_get().unwrap();
+        buf.extend_from_slice(b"hello fast path");
+        // Warm up the op slab.
+        let (res, mut buf) = file.write_fixed_at(buf, 0).await;
+        res.unwrap();
+
+        let before = allocations();
+        for _ in 0..64 {
+            let (res, written) = file.write_fixed_at(buf, 0).await;
+            assert_eq!(res.unwrap(), 15);
+            let (res, read) = file.read_fixed_at(written, 0).await;
+            assert_eq!(res.unwrap(), 15);
+            buf = read;
+        }
+        assert_eq!(allocations(), before);
+        assert_eq!(&buf[..], b"hello fast path");
+    });
+}
+
+#[test]
+fn drop_in_flight() {
+    runtime().block_on(async {
+        let tempfile = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(tempfile.path(), b"hello").unwrap();
+        let file = OpenOptions::new()
+            .read(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        assert!(file.register_fixed().unwrap());
+        let pool = FixedBufPool::new(4096, 1).unwrap();
+        pool.register().unwrap();
+
+        let mut read = Box::pin(file.read_fixed_at(pool.try_get().unwrap(), 0));
+        poll_fn(|cx| {
+            assert!(read.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        drop(read);
+
+        // The op holds the file and the buffer until it completes.
+        file.close().await.unwrap();
+        assert!(pool.try_get().is_some());
+    });
+}
@@ -0,0 +1,81 @@
+use monoio::{
+    fs::File,
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+async fn file_round_trip(file: &File) {
+    file.write_all_at(&b"hello fixed"[..], 0).await.0.unwrap();
+    file.sync_data().await.unwrap();
+    let (res, buf) = file.read_exact_at(vec![0; 5], 6).await;
+    res.unwrap();
+    assert_eq!(buf, b"fixed");
+}
+
+async fn tcp_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let conn = TcpStream::connect(addr).await.unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+    (conn, peer)
+}
+
+async fn echo(conn: &mut TcpStream, peer: &mut TcpStream) {
+    conn.write_all(b"ping").await.0.unwrap();
+    let (res, buf) = peer.read_exact(vec![0; 4]).await;
+    res.unwrap();
+    peer.write_all(buf).await.0.unwrap();
+    let (res, buf) = conn.read_exact(vec![0; 4]).await;
+    res.unwrap();
+    assert_eq!(buf, b"ping");
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn fixed_file_slots() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .with_fixed_files(2)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let tempfile = tempfile::NamedTempFile::new().unwrap();
+        let file = monoio::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        assert!(file.register_fixed().unwrap());
+        // Registering again keeps the slot.
+        assert!(file.register_fixed().unwrap());
+        file_round_trip(&file).await;
+
+        let (mut conn, mut peer) = tcp_pair().await;
+        assert!(conn.register_fixed().unwrap());
+        // The table is full.
+        assert!(!peer.register_fixed().unwrap());
+        echo(&mut conn, &mut peer).await;
+
+        // Closing the file frees its slot.
+        file.close().await.unwrap();
+        assert!(peer.register_fixed().unwrap());
+        echo(&mut conn, &mut peer).await;
+    });
+}
+
+#[monoio::test_all]
+async fn no_fixed_file_table() {
+    let tempfile = tempfile::NamedTempFile::new().unwrap();
+    let file = monoio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(tempfile.path())
+        .await
+        .unwrap();
+    assert!(!file.register_fixed().unwrap());
+    file_round_trip(&file).await;
+
+    let (mut conn, mut peer) = tcp_pair().await;
+    assert!(!conn.register_fixed().unwrap());
+    echo(&mut conn, &mut peer).await;
+}
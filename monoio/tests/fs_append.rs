@@ -0,0 +1,76 @@
+use std::{io::Write, time::Duration};
+
+use monoio::fs::{AppendWriter, AppendWriterBuilder, OpenOptions};
+use tempfile::NamedTempFile;
+
+const HEADER: &[u8] = b"header\n";
+
+async fn open(tempfile: &NamedTempFile) -> monoio::fs::File {
+    OpenOptions::new()
+        .write(true)
+        .open(tempfile.path())
+        .await
+        .unwrap()
+}
+
+#[monoio::test_all]
+async fn append_from_tasks() {
+    let mut tempfile = NamedTempFile::new().unwrap();
+    tempfile.write_all(HEADER).unwrap();
+
+    // A tiny queue makes appends wait for the writes.
+    let writer = AppendWriterBuilder::new()
+        .queue_bytes(16)
+        .build(open(&tempfile).await, HEADER.len() as u64);
+    let tasks = (0..8)
+        .map(|i| {
+            let writer = writer.clone();
+            monoio::spawn(async move {
+                let mut offsets = Vec::new();
+                for j in 0..8 {
+                    let record = format!("{i}-{j}\n");
+                    offsets.push((writer.append(record.as_bytes()).await.unwrap(), record));
+                }
+                offsets
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut records = Vec::new();
+    for task in tasks {
+        records.extend(task.await);
+    }
+    writer.sync().await.unwrap();
+
+    let content = std::fs::read(tempfile.path()).unwrap();
+    assert_eq!(&content[..HEADER.len()], HEADER);
+    assert_eq!(content.len(), HEADER.len() + records.len() * 4);
+    for (offset, record) in records {
+        let offset = offset as usize;
+        assert_eq!(&content[offset..offset + record.len()], record.as_bytes());
+    }
+    writer.close().await.unwrap();
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn append_with_sync_interval() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let writer = AppendWriterBuilder::new()
+        .sync_interval(Duration::from_millis(10))
+        .build(open(&tempfile).await, 0);
+    writer.append(b"first").await.unwrap();
+    monoio::time::sleep(Duration::from_millis(50)).await;
+    writer.append(b" second").await.unwrap();
+    writer.close().await.unwrap();
+    assert_eq!(std::fs::read(tempfile.path()).unwrap(), b"first second");
+}
+
+#[monoio::test_all]
+async fn append_error() {
+    let tempfile = NamedTempFile::new().unwrap();
+    // Opened read only, so the write fails.
+    let file = monoio::fs::File::open(tempfile.path()).await.unwrap();
+    let writer = AppendWriter::new(file, 0);
+    writer.append(b"data").await.unwrap();
+    writer.flush().await.unwrap_err();
+    writer.append(b"data").await.unwrap_err();
+}
@@ -0,0 +1,58 @@
+#![cfg(unix)]
+
+use std::{os::unix::fs::PermissionsExt, path::Path};
+
+use monoio::fs;
+
+// Larger than the copy buffer, so it takes more than one read.
+fn content() -> Vec<u8> {
+    (0..200_000u32).map(|i| i as u8).collect()
+}
+
+async fn check_copy(dir: &Path) {
+    let from = dir.join("from");
+    let to = dir.join("to");
+    std::fs::write(&from, content()).unwrap();
+    std::fs::set_permissions(&from, std::fs::Permissions::from_mode(0o640)).unwrap();
+    // An existing destination is truncated.
+    std::fs::write(&to, vec![1; 300_000]).unwrap();
+
+    assert_eq!(fs::copy(&from, &to).await.unwrap(), 200_000);
+    assert_eq!(std::fs::read(&to).unwrap(), content());
+    let mode = std::fs::metadata(&to).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+
+    let empty = dir.join("empty");
+    std::fs::write(&empty, b"").unwrap();
+    assert_eq!(fs::copy(&empty, dir.join("empty_copy")).await.unwrap(), 0);
+}
+
+#[monoio::test_all]
+async fn copy() {
+    let dir = tempfile::tempdir().unwrap();
+    check_copy(dir.path()).await;
+
+    let err = fs::copy(dir.path(), dir.path().join("dir_copy"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let err = fs::copy(dir.path().join("missing"), dir.path().join("missing_copy"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn copy_on_thread_pool() {
+    use std::sync::Arc;
+
+    use monoio::blocking::DefaultThreadPool;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .attach_thread_pool(Arc::new(DefaultThreadPool::new(2)))
+        .build()
+        .unwrap();
+    rt.block_on(check_copy(dir.path()));
+}
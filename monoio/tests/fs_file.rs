@@ -1,6 +1,6 @@
 use std::io::prelude::*;
 #[cfg(unix)]
-use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 
 use monoio::fs::File;
 use tempfile::NamedTempFile;
@@ -63,6 +63,23 @@ async fn basic_write_all() {
     let file = std::fs::read(tempfile.path()).unwrap();
     assert_eq!(file, HELLO);
 }
+#[cfg(all(unix, feature = "bytes"))]
+#[monoio::test_all]
+async fn bytes_roundtrip() {
+    let tempfile = tempfile();
+
+    let file = File::create(tempfile.path()).await.unwrap();
+    let (res, _) = file.write_all_at(bytes::Bytes::from_static(HELLO), 0).await;
+    res.unwrap();
+
+    let file = File::open(tempfile.path()).await.unwrap();
+    let buf = bytes::BytesMut::with_capacity(HELLO.len());
+    let (res, buf) = file.read_exact_at(buf, 0).await;
+    res.unwrap();
+    assert_eq!(&buf[..], HELLO);
+    // The buffer can be frozen and handed on without copying.
+    assert_eq!(buf.freeze(), HELLO);
+}
 #[cfg(unix)]
 #[monoio::test(driver = "uring")]
 async fn cancel_read() {
@@ -76,6 +93,37 @@ async fn cancel_read() {
 
     read_hello(&file).await;
 }
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn allocate_and_set_len() {
+    let tempfile = tempfile();
+    let file = File::create(tempfile.path()).await.unwrap();
+
+    file.allocate(0, 8192).await.unwrap();
+    let metadata = std::fs::metadata(tempfile.path()).unwrap();
+    assert_eq!(metadata.len(), 8192);
+    // Space is reserved, unlike a sparse extension.
+    assert!(std::os::unix::fs::MetadataExt::blocks(&metadata) * 512 >= 8192);
+
+    file.set_len(5).await.unwrap();
+    assert_eq!(std::fs::metadata(tempfile.path()).unwrap().len(), 5);
+    file.set_len(100).await.unwrap();
+    assert_eq!(std::fs::metadata(tempfile.path()).unwrap().len(), 100);
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[monoio::test(driver = "uring")]
+async fn open_through_ring() {
+    let tempfile = tempfile();
+    let before = monoio::stats::driver_stats();
+    File::open(tempfile.path()).await.unwrap();
+    let after = monoio::stats::driver_stats();
+    // The open is submitted to the ring, not done by a syscall in place.
+    assert_eq!(after.syscall_fallbacks, before.syscall_fallbacks);
+    assert!(after.sqes_submitted > before.sqes_submitted);
+}
+
 #[cfg(unix)]
 #[monoio::test_all]
 async fn explicit_close() {
@@ -103,6 +151,19 @@ async fn drop_open() {
     drop(file_w);
 }
 #[cfg(unix)]
+#[monoio::test_all]
+async fn raw_fd_roundtrip() {
+    let mut tempfile = tempfile();
+    tempfile.write_all(HELLO).unwrap();
+
+    let std_file = std::fs::File::open(tempfile.path()).unwrap();
+    let file = unsafe { File::from_raw_fd(std_file.as_raw_fd()) };
+    read_hello(&file).await;
+    assert_eq!(file.into_raw_fd(), std_file.as_raw_fd());
+    // The fd is not closed.
+    std_file.metadata().unwrap();
+}
+#[cfg(unix)]
 #[test]
 fn drop_off_runtime() {
     let tempfile = tempfile();
@@ -133,6 +194,63 @@ async fn sync_doesnt_kill_anything() {
     file.sync_data().await.unwrap();
 }
 
+#[cfg(unix)]
+#[monoio::test_all]
+async fn sync_barrier_after_pending_writes() {
+    let tempfile = tempfile();
+    let file = File::create(tempfile.path()).await.unwrap();
+
+    let first = file.write_at(&b"foo"[..], 0);
+    let second = file.write_at(&b"bar"[..], 3);
+    let (first, second, all, data) = monoio::join!(
+        first,
+        second,
+        file.sync_all_barrier(),
+        file.sync_data_barrier()
+    );
+    assert_eq!(first.0.unwrap(), 3);
+    assert_eq!(second.0.unwrap(), 3);
+    all.unwrap();
+    data.unwrap();
+    assert_eq!(std::fs::read(tempfile.path()).unwrap(), b"foobar");
+}
+
+#[cfg(unix)]
+#[monoio::test_all]
+async fn positional_io_traits() {
+    use monoio::io::{AsyncReadRentAt, AsyncWriteRentAt};
+
+    async fn write_blocks<W: AsyncWriteRentAt>(file: &W) {
+        // Blocks at different offsets are written concurrently.
+        let (a, b, c) = monoio::join!(
+            file.write_at(vec![b'a'; 4096], 0),
+            file.write_at(vec![b'b'; 4096], 4096),
+            file.write_at(vec![b'c'; 4096], 8192)
+        );
+        for (res, _) in [a, b, c] {
+            assert_eq!(res.unwrap(), 4096);
+        }
+    }
+
+    async fn read_block<R: AsyncReadRentAt>(file: &mut R, pos: usize) -> Vec<u8> {
+        let (res, buf) = file.read_at(Vec::with_capacity(4096), pos).await;
+        assert_eq!(res.unwrap(), 4096);
+        buf
+    }
+
+    let tempfile = tempfile();
+    let mut file = monoio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(tempfile.path())
+        .await
+        .unwrap();
+    write_blocks(&file).await;
+    assert_eq!(read_block(&mut file, 4096).await, vec![b'b'; 4096]);
+    assert_eq!(read_block(&mut file, 0).await, vec![b'a'; 4096]);
+    assert_eq!(read_block(&mut file, 8192).await, vec![b'c'; 4096]);
+}
+
 #[cfg(unix)]
 fn tempfile() -> NamedTempFile {
     NamedTempFile::new().expect("unable to create tempfile")
@@ -154,12 +272,13 @@ async fn poll_once(future: impl std::future::Future) {
     })
     .await;
 }
-#[cfg(unix)]
 
+#[cfg(unix)]
 fn assert_invalid_fd(fd: RawFd) {
     use std::fs::File;
 
-    let mut f = unsafe { File::from_raw_fd(fd) };
+    // The fd is closed, so it must not be closed again on drop.
+    let mut f = std::mem::ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
     let mut buf = vec![];
 
     assert!(f.read_to_end(&mut buf).is_err());
@@ -0,0 +1,43 @@
+#![cfg(unix)]
+
+use std::{io::Write, os::unix::fs::MetadataExt};
+
+use monoio::fs::{self, File};
+
+#[monoio::test_all]
+async fn file_metadata() {
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile.write_all(b"hello").unwrap();
+    let expected = std::fs::metadata(tempfile.path()).unwrap();
+
+    let by_path = fs::metadata(tempfile.path()).await.unwrap();
+    let file = File::open(tempfile.path()).await.unwrap();
+    let by_file = file.metadata().await.unwrap();
+    for metadata in [by_path, by_file] {
+        assert!(metadata.is_file());
+        assert!(!metadata.is_dir());
+        assert_eq!(metadata.len(), 5);
+        assert_eq!(metadata.ino(), expected.ino());
+        assert_eq!(metadata.dev(), expected.dev());
+        assert_eq!(metadata.mode(), expected.mode());
+        assert_eq!(metadata.permissions(), expected.permissions());
+        assert_eq!(metadata.modified().unwrap(), expected.modified().unwrap());
+        assert_eq!(metadata.accessed().unwrap(), expected.accessed().unwrap());
+    }
+}
+
+#[monoio::test_all]
+async fn dir_and_symlink_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(fs::metadata(dir.path()).await.unwrap().is_dir());
+
+    let link = dir.path().join("link");
+    std::os::unix::fs::symlink(dir.path(), &link).unwrap();
+    assert!(fs::metadata(&link).await.unwrap().is_dir());
+    let metadata = fs::symlink_metadata(&link).await.unwrap();
+    assert!(metadata.is_symlink());
+    assert!(metadata.file_type().is_symlink());
+
+    let err = fs::metadata(dir.path().join("missing")).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,81 @@
+#![cfg(unix)]
+
+use std::io::Write;
+
+use monoio::{
+    buf::IoBuf,
+    fs::{Advice, File, Mmap, MmapMut, OpenOptions},
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+const DATA: &[u8] = b"hello mapped world";
+
+fn tempfile() -> tempfile::NamedTempFile {
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile.write_all(DATA).unwrap();
+    tempfile
+}
+
+#[monoio::test_all]
+async fn map_ranges() {
+    let tempfile = tempfile();
+    let file = File::open(tempfile.path()).await.unwrap();
+
+    // Safety: the temporary file is only changed by the tests.
+    unsafe {
+        let map = Mmap::map(&file).unwrap();
+        map.advise(Advice::Sequential).unwrap();
+        assert_eq!(&map[..], DATA);
+        // The offset does not have to be page aligned.
+        let map = Mmap::map_range(&file, 6, 6).unwrap();
+        assert_eq!(&map[..], b"mapped");
+        assert!(Mmap::map_range(&file, 0, 0).unwrap().is_empty());
+    }
+
+    let err = unsafe { Mmap::map_range(&file, 6, DATA.len()) }.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[monoio::test_all]
+async fn write_mapped_to_socket() {
+    let tempfile = tempfile();
+    let file = File::open(tempfile.path()).await.unwrap();
+    let map = unsafe { Mmap::map(&file) }.unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let client = monoio::spawn(async move {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(map.clone().slice(6..)).await.0.unwrap();
+        stream.write_all(map).await.0.unwrap();
+    });
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let expected = [&DATA[6..], DATA].concat();
+    let (res, buf) = stream.read_exact(vec![0; expected.len()]).await;
+    res.unwrap();
+    assert_eq!(buf, expected);
+    client.await;
+}
+
+#[monoio::test_all]
+async fn flush_mut() {
+    let tempfile = tempfile();
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(tempfile.path())
+        .await
+        .unwrap();
+
+    let mut map = unsafe { MmapMut::map_range(&file, 6, 6) }.unwrap();
+    map.copy_from_slice(b"MAPPED");
+    map.flush().await.unwrap();
+    assert_eq!(
+        std::fs::read(tempfile.path()).unwrap(),
+        b"hello MAPPED world"
+    );
+
+    let map = map.make_read_only().unwrap();
+    assert_eq!(&map[..], b"MAPPED");
+}
@@ -0,0 +1,68 @@
+#![cfg(unix)]
+
+use std::{collections::BTreeSet, ffi::OsString, path::Path};
+
+use monoio::{fs, io::stream::Stream};
+
+// More entries than a batch, so the directory is read more than once.
+const FILES: usize = 100;
+
+fn fill_dir(dir: &Path) {
+    for i in 0..FILES {
+        std::fs::write(dir.join(format!("file{i}")), b"").unwrap();
+    }
+    std::fs::create_dir(dir.join("sub")).unwrap();
+}
+
+async fn list_dir(dir: &Path) -> BTreeSet<OsString> {
+    let mut names = BTreeSet::new();
+    let mut entries = fs::read_dir(dir).await.unwrap();
+    while let Some(entry) = entries.next().await {
+        let entry = entry.unwrap();
+        assert_eq!(entry.path(), dir.join(entry.file_name()));
+        let is_dir = entry.file_name() == "sub";
+        assert_eq!(entry.file_type().unwrap().is_dir(), is_dir);
+        assert_eq!(entry.metadata().await.unwrap().is_dir(), is_dir);
+        assert!(names.insert(entry.file_name()));
+    }
+    // The stream stays ended.
+    assert!(entries.next().await.is_none());
+    names
+}
+
+fn expected_names() -> BTreeSet<OsString> {
+    (0..FILES)
+        .map(|i| format!("file{i}").into())
+        .chain(Some("sub".into()))
+        .collect()
+}
+
+#[monoio::test_all]
+async fn read_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    fill_dir(dir.path());
+    assert_eq!(list_dir(dir.path()).await, expected_names());
+
+    let empty = dir.path().join("sub");
+    assert!(list_dir(&empty).await.is_empty());
+
+    let err = fs::read_dir(dir.path().join("missing")).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn read_dir_on_thread_pool() {
+    use std::sync::Arc;
+
+    use monoio::blocking::DefaultThreadPool;
+
+    let dir = tempfile::tempdir().unwrap();
+    fill_dir(dir.path());
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .attach_thread_pool(Arc::new(DefaultThreadPool::new(2)))
+        .build()
+        .unwrap();
+    let names = rt.block_on(list_dir(dir.path()));
+    assert_eq!(names, expected_names());
+}
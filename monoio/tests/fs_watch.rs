@@ -0,0 +1,53 @@
+#![cfg(target_os = "linux")]
+
+use monoio::{
+    fs::{EventMask, Watcher},
+    io::stream::Stream,
+};
+
+#[monoio::test_all]
+async fn watch_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut watcher = monoio::fs::watch(dir.path()).unwrap();
+
+    let path = dir.path().join("config");
+    std::fs::write(&path, b"a = 1").unwrap();
+    let event = watcher.next().await.unwrap().unwrap();
+    assert!(event.mask.contains(EventMask::CREATE));
+    assert_eq!(event.name.as_deref(), Some("config".as_ref()));
+
+    // The write is followed by the close.
+    let mut mask = EventMask::from_bits(0);
+    while !mask.contains(EventMask::CLOSE_WRITE) {
+        mask |= watcher.next_event().await.unwrap().mask;
+    }
+    assert!(mask.contains(EventMask::MODIFY));
+
+    std::fs::rename(&path, dir.path().join("config.old")).unwrap();
+    let from = watcher.next_event().await.unwrap();
+    let to = watcher.next_event().await.unwrap();
+    assert!(from.mask.contains(EventMask::MOVED_FROM));
+    assert!(to.mask.contains(EventMask::MOVED_TO));
+    assert_eq!(from.cookie, to.cookie);
+    assert_eq!(to.name.as_deref(), Some("config.old".as_ref()));
+}
+
+#[monoio::test_all]
+async fn add_and_remove() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("file");
+    std::fs::write(&path, b"").unwrap();
+
+    let mut watcher = Watcher::new().unwrap();
+    let wd = watcher.add(&path, EventMask::ATTRIB).unwrap();
+    std::fs::set_permissions(&path, std::os::unix::fs::PermissionsExt::from_mode(0o600)).unwrap();
+    let event = watcher.next_event().await.unwrap();
+    assert_eq!(event.wd, wd);
+    assert_eq!(event.mask, EventMask::ATTRIB);
+    assert_eq!(event.name, None);
+
+    watcher.remove(wd).unwrap();
+    let event = watcher.next_event().await.unwrap();
+    assert_eq!(event.wd, wd);
+    assert!(event.mask.contains(EventMask::IGNORED));
+}
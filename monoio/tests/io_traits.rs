@@ -0,0 +1,48 @@
+use monoio::{
+    io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+async fn ping<S: AsyncReadRent + AsyncWriteRent>(mut stream: S) {
+    let (res, _) = stream.write_all(b"ping").await;
+    res.unwrap();
+    let (res, buf) = stream.read_exact(vec![0; 4]).await;
+    res.unwrap();
+    assert_eq!(&buf, b"pong");
+}
+
+async fn pong<S: AsyncReadRent + AsyncWriteRent>(mut stream: S) {
+    let (res, buf) = stream.read_exact(vec![0; 4]).await;
+    res.unwrap();
+    assert_eq!(&buf, b"ping");
+    let (res, _) = stream.write_all(b"pong").await;
+    res.unwrap();
+}
+
+#[monoio::test_all]
+async fn mut_ref_and_box() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = monoio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = Box::new(stream);
+        pong(&mut stream).await;
+        pong(stream).await;
+    });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    ping(&mut client).await;
+    ping(Box::new(client)).await;
+    server.await;
+}
+
+#[monoio::test_all]
+async fn slice_reader() {
+    let mut reader: &[u8] = b"hello";
+    let (res, buf) = (&mut reader).read(Vec::with_capacity(2)).await;
+    assert_eq!(res.unwrap(), 2);
+    assert_eq!(&buf, b"he");
+    let (res, buf) = Box::new(reader).read(Vec::with_capacity(8)).await;
+    assert_eq!(res.unwrap(), 3);
+    assert_eq!(&buf, b"llo");
+}
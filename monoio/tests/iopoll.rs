@@ -0,0 +1,58 @@
+#![cfg(all(target_os = "linux", feature = "iouring"))]
+
+use std::time::{Duration, Instant};
+
+use monoio::{buf::pool, fs::OpenOptions, IoUringDriver, RuntimeBuilder};
+
+#[test]
+fn iopoll_park_and_timer() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_iopoll()
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let begin = Instant::now();
+        monoio::time::sleep(Duration::from_millis(20)).await;
+        assert!(begin.elapsed() >= Duration::from_millis(20));
+
+        let task = monoio::spawn(async { 1 });
+        assert_eq!(task.await, 1);
+    });
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn iopoll_direct_read() {
+    let tempfile = tempfile::NamedTempFile::new_in(".").unwrap();
+    std::fs::write(tempfile.path(), vec![7; 4096]).unwrap();
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_iopoll()
+        .with_buffer_pool(4096, 1)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let file = match OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(tempfile.path())
+            .await
+        {
+            Ok(file) => file,
+            // The filesystem does not support O_DIRECT.
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => return,
+            Err(e) => panic!("{e}"),
+        };
+        let (res, buf) = file.read_at(pool::get(4096), 0).await;
+        match res {
+            Ok(n) => {
+                assert_eq!(n, 4096);
+                assert!(buf.iter().all(|b| *b == 7));
+            }
+            // The device does not support polling.
+            Err(e) => assert_eq!(e.raw_os_error(), Some(libc::EOPNOTSUPP)),
+        }
+        file.close().await.unwrap();
+    });
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "metrics")]
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use metrics::{
+    Counter, CounterFn, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+};
+use monoio::net::{TcpListener, TcpStream};
+
+#[derive(Default)]
+struct TestCounter(AtomicU64);
+
+impl CounterFn for TestCounter {
+    fn increment(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default)]
+struct TestRecorder {
+    counters: Mutex<HashMap<String, Arc<TestCounter>>>,
+}
+
+impl TestRecorder {
+    fn get(&self, name: &str) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(name)
+            .map(|c| c.0.load(Ordering::Relaxed))
+            .unwrap_or_default()
+    }
+}
+
+impl Recorder for TestRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let counter = self
+            .counters
+            .lock()
+            .unwrap()
+            .entry(key.name().to_string())
+            .or_default()
+            .clone();
+        Counter::from_arc(counter)
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[monoio::test_all]
+async fn report_counters() {
+    use monoio::metrics::*;
+
+    let recorder = TestRecorder::default();
+    metrics::with_local_recorder(&recorder, monoio::metrics::report);
+    let spawned = recorder.get(RUNTIME_TASKS_SPAWNED);
+    let accepted = recorder.get(NET_TCP_ACCEPTED);
+    let connected = recorder.get(NET_TCP_CONNECTED);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let client = monoio::spawn(async move { TcpStream::connect(addr).await.unwrap() });
+    let _server = listener.accept().await.unwrap();
+    let _client = client.await;
+    for _ in 0..2 {
+        monoio::spawn(async {}).await;
+    }
+
+    metrics::with_local_recorder(&recorder, monoio::metrics::report);
+    assert_eq!(recorder.get(RUNTIME_TASKS_SPAWNED) - spawned, 3);
+    assert_eq!(recorder.get(NET_TCP_ACCEPTED) - accepted, 1);
+    assert_eq!(recorder.get(NET_TCP_CONNECTED) - connected, 1);
+}
@@ -0,0 +1,128 @@
+//! Dropped in-flight ops are canceled with the `async-cancel` feature, so
+//! their buffers are released without waiting for the fd to be ready.
+#![cfg(all(target_os = "linux", feature = "iouring", feature = "async-cancel"))]
+
+use std::{cell::Cell, future::Future, pin::Pin, rc::Rc, task::Poll, time::Duration};
+
+use monoio::{
+    buf::{IoBuf, IoBufMut},
+    io::{AsyncReadRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+    stats::driver_stats,
+    IoUringDriver, RuntimeBuilder,
+};
+
+// A buffer which records when it is dropped.
+struct TrackedBuf {
+    buf: Vec<u8>,
+    dropped: Rc<Cell<bool>>,
+}
+
+impl Drop for TrackedBuf {
+    fn drop(&mut self) {
+        self.dropped.set(true);
+    }
+}
+
+unsafe impl IoBuf for TrackedBuf {
+    fn read_ptr(&self) -> *const u8 {
+        self.buf.read_ptr()
+    }
+
+    fn bytes_init(&self) -> usize {
+        self.buf.bytes_init()
+    }
+}
+
+unsafe impl IoBufMut for TrackedBuf {
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.buf.write_ptr()
+    }
+
+    fn bytes_total(&mut self) -> usize {
+        self.buf.bytes_total()
+    }
+
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.buf.set_init(pos)
+    }
+}
+
+// Poll every future once, so their ops are submitted.
+async fn poll_once<F: Future>(futs: &mut [Pin<Box<F>>]) {
+    std::future::poll_fn(|cx| {
+        for fut in futs.iter_mut() {
+            assert!(fut.as_mut().poll(cx).is_pending());
+        }
+        Poll::Ready(())
+    })
+    .await;
+}
+
+#[test]
+fn drop_pending_read() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let before = driver_stats();
+
+        let dropped = Rc::new(Cell::new(false));
+        let buf = TrackedBuf {
+            buf: Vec::with_capacity(64),
+            dropped: dropped.clone(),
+        };
+        let mut read = [Box::pin(stream.read(buf))];
+        poll_once(&mut read).await;
+        drop(read);
+        // The buffer is held until the canceled op completes.
+        assert!(!dropped.get());
+
+        // Nothing is written by the peer, the read only completes if canceled.
+        monoio::time::sleep(Duration::from_millis(10)).await;
+        assert!(dropped.get());
+        let after = driver_stats();
+        assert_eq!(after.ops_canceled, before.ops_canceled + 1);
+        assert_eq!(after.slab_used, before.slab_used);
+
+        // No data is taken by the canceled read.
+        client.write_all(b"hello").await.0.unwrap();
+        let (res, buf) = stream.read(Vec::with_capacity(64)).await;
+        assert_eq!(res.unwrap(), 5);
+        assert_eq!(&buf, b"hello");
+    });
+}
+
+#[test]
+fn drop_more_ops_than_sq_entries() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_entries(4)
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let before = driver_stats();
+
+        let mut accepts = (0..16)
+            .map(|_| Box::pin(listener.accept()))
+            .collect::<Vec<_>>();
+        poll_once(&mut accepts).await;
+        // Cancels are pushed while the SQ is full.
+        drop(accepts);
+
+        monoio::time::sleep(Duration::from_millis(10)).await;
+        let after = driver_stats();
+        assert_eq!(after.ops_canceled, before.ops_canceled + 16);
+        assert_eq!(after.slab_used, before.slab_used);
+
+        let _client = TcpStream::connect(addr).await.unwrap();
+        listener.accept().await.unwrap();
+    });
+}
@@ -0,0 +1,94 @@
+#![cfg(all(unix, feature = "poll-io"))]
+
+use std::future::poll_fn;
+
+use monoio::{
+    io::{AsyncReadRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream, UnixStream},
+};
+
+const LEN: usize = 1 << 20;
+
+#[monoio::test_all]
+async fn tcp_poll_io() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = monoio::spawn(async move {
+        let (conn, _) = listener.accept().await.unwrap();
+        let mut conn = conn.into_poll_io();
+        // Large enough to fill the socket buffer, so both sides wait for
+        // readiness.
+        let data = vec![7u8; LEN];
+        let mut written = 0;
+        while written < LEN {
+            written += poll_fn(|cx| conn.poll_write(cx, &data[written..]))
+                .await
+                .unwrap();
+        }
+        poll_fn(|cx| conn.poll_shutdown(cx)).await.unwrap();
+    });
+
+    let mut conn = TcpStream::connect(addr).await.unwrap().into_poll_io();
+    let mut buf = vec![0; 4096];
+    let mut read = 0;
+    loop {
+        let n = poll_fn(|cx| conn.poll_read(cx, &mut buf)).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        assert!(buf[..n].iter().all(|b| *b == 7));
+        read += n;
+    }
+    assert_eq!(read, LEN);
+    server.await;
+}
+
+#[monoio::test_all]
+async fn tcp_poll_io_read_buffer() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    let (mut srv, _) = listener.accept().await.unwrap();
+    let (r, _) = cli.write_all(b"hello world").await;
+    r.unwrap();
+
+    // Bytes held by the read buffer are read first.
+    srv.enable_read_buffer(64);
+    let (r, buf) = srv.read(vec![0; 5]).await;
+    assert_eq!(r.unwrap(), 5);
+    assert_eq!(buf, b"hello");
+    let mut srv = srv.into_poll_io();
+    let mut buf = [0; 64];
+    let n = poll_fn(|cx| srv.poll_read(cx, &mut buf)).await.unwrap();
+    assert_eq!(&buf[..n], b" world");
+
+    let (r, _) = cli.write_all(b"again").await;
+    r.unwrap();
+    let n = poll_fn(|cx| srv.poll_read(cx, &mut buf)).await.unwrap();
+    assert_eq!(&buf[..n], b"again");
+    assert_eq!(srv.peer_addr().unwrap(), cli.local_addr().unwrap());
+    assert_eq!(srv.into_inner().read_buffered(), 0);
+}
+
+#[monoio::test_all]
+async fn unix_poll_io() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let mut a = a.into_poll_io();
+    let mut b = b.into_poll_io();
+    let bufs = [
+        std::io::IoSlice::new(b"hello "),
+        std::io::IoSlice::new(b"world"),
+    ];
+    let n = poll_fn(|cx| a.poll_write_vectored(cx, &bufs))
+        .await
+        .unwrap();
+    assert_eq!(n, 11);
+    poll_fn(|cx| a.poll_flush(cx)).await.unwrap();
+    poll_fn(|cx| a.poll_shutdown(cx)).await.unwrap();
+
+    let mut buf = [0; 64];
+    let n = poll_fn(|cx| b.poll_read(cx, &mut buf)).await.unwrap();
+    assert_eq!(&buf[..n], b"hello world");
+    let n = poll_fn(|cx| b.poll_read(cx, &mut buf)).await.unwrap();
+    assert_eq!(n, 0);
+}
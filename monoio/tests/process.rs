@@ -0,0 +1,75 @@
+#![cfg(target_os = "linux")]
+
+use std::{os::unix::process::ExitStatusExt, process::Stdio};
+
+use monoio::{
+    io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRentExt},
+    process::Command,
+};
+
+#[monoio::test_all]
+async fn status() {
+    let status = Command::new("sh")
+        .args(["-c", "exit 3"])
+        .status()
+        .await
+        .unwrap();
+    assert_eq!(status.code(), Some(3));
+}
+
+#[monoio::test_all]
+async fn piped_stdio() {
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let (res, _) = stdin.write_all(b"hello".to_vec()).await;
+    res.unwrap();
+    drop(stdin);
+
+    let stdout = child.stdout.as_mut().unwrap();
+    let (res, buf) = stdout.read_exact(vec![0; 5]).await;
+    res.unwrap();
+    assert_eq!(buf, b"hello");
+    let (res, _) = stdout.read(vec![0; 5]).await;
+    assert_eq!(res.unwrap(), 0);
+    assert!(child.wait().await.unwrap().success());
+}
+
+#[monoio::test_all]
+async fn output() {
+    let output = Command::new("sh")
+        .args(["-c", "echo out; echo err >&2"])
+        .env("LANG", "C")
+        .output()
+        .await
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"out\n");
+    assert_eq!(output.stderr, b"err\n");
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn wait_does_not_block() {
+    let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    let pid = child.id() as libc::pid_t;
+    let wait = monoio::spawn(async move { child.wait().await });
+    // Other tasks keep running while the child is waited for.
+    monoio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!wait.is_finished());
+
+    unsafe { libc::kill(pid, libc::SIGTERM) };
+    let status = wait.await.unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGTERM));
+}
+
+#[monoio::test_all]
+async fn kill() {
+    let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    assert!(child.try_wait().unwrap().is_none());
+    child.kill().unwrap();
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGKILL));
+}
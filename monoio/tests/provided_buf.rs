@@ -0,0 +1,76 @@
+use monoio::{
+    fs::File,
+    io::AsyncWriteRentExt,
+    net::{TcpListener, TcpStream},
+};
+
+async fn tcp_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let conn = TcpStream::connect(addr).await.unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+    (conn, peer)
+}
+
+async fn file_with(data: &'static [u8]) -> (tempfile::NamedTempFile, File) {
+    let tempfile = tempfile::NamedTempFile::new().unwrap();
+    let file = File::create(tempfile.path()).await.unwrap();
+    file.write_all_at(data, 0).await.0.unwrap();
+    file.close().await.unwrap();
+    let file = File::open(tempfile.path()).await.unwrap();
+    (tempfile, file)
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn kernel_picked_buffers() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .with_provided_buffers(64, 2)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let (mut conn, mut peer) = tcp_pair().await;
+        peer.write_all(b"hello").await.0.unwrap();
+        let a = conn.recv_provided(64).await.unwrap();
+        assert!(a.is_provided());
+        assert_eq!(&a[..], b"hello");
+
+        let (_tmp, file) = file_with(b"hello provided").await;
+        let b = file.read_provided_at(5, 6).await.unwrap();
+        assert!(b.is_provided());
+        assert_eq!(&b[..], b"provi");
+
+        // All buffers are held, so a heap buffer is used.
+        peer.write_all(b"world").await.0.unwrap();
+        let c = conn.recv_provided(64).await.unwrap();
+        assert!(!c.is_provided());
+        assert_eq!(&c[..], b"world");
+
+        // Dropped buffers are picked again.
+        drop((a, b));
+        peer.write_all(b"again").await.0.unwrap();
+        let d = conn.recv_provided(64).await.unwrap();
+        assert!(d.is_provided());
+        assert_eq!(&d[..], b"again");
+
+        assert!(file.read_provided_at(64, 14).await.unwrap().is_empty());
+        drop(peer);
+        assert!(conn.recv_provided(64).await.unwrap().is_empty());
+    });
+}
+
+#[monoio::test_all]
+async fn heap_fallback() {
+    // No provided buffers are set up, so buffers come from the heap.
+    let (mut conn, mut peer) = tcp_pair().await;
+    peer.write_all(b"hello").await.0.unwrap();
+    let buf = conn.recv_provided(64).await.unwrap();
+    assert!(!buf.is_provided());
+    assert_eq!(&buf[..], b"hello");
+    drop(peer);
+    assert!(conn.recv_provided(64).await.unwrap().is_empty());
+
+    let (_tmp, file) = file_with(b"hello provided").await;
+    let buf = file.read_provided_at(64, 6).await.unwrap();
+    assert_eq!(&buf[..], b"provided");
+}
@@ -0,0 +1,52 @@
+#![cfg(unix)]
+
+use std::os::unix::net::UnixDatagram as StdUnixDatagram;
+
+use monoio::{io::stream::Stream, net::UnixDatagram};
+
+#[monoio::test_all]
+async fn recv_datagrams() {
+    let dir = tempfile::tempdir().unwrap();
+    let (rx_path, tx_path) = (dir.path().join("rx"), dir.path().join("tx"));
+    let rx = UnixDatagram::bind(&rx_path).unwrap();
+    let tx = StdUnixDatagram::bind(&tx_path).unwrap();
+    for data in [&b"hello"[..], b"", b"longer than the buffer"] {
+        tx.send_to(data, &rx_path).unwrap();
+    }
+
+    let mut stream = rx.recv_from_stream(8, 4).unwrap();
+    for expected in [&b"hello"[..], b"", b"longer t"] {
+        let (buf, addr) = stream.next().await.unwrap().unwrap();
+        assert_eq!(&buf[..], expected);
+        assert_eq!(addr.as_pathname(), Some(tx_path.as_path()));
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn rearm_when_buffers_run_out() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let (rx, tx) = StdUnixDatagram::pair().unwrap();
+        rx.set_nonblocking(true).unwrap();
+        let rx = UnixDatagram::from_std(rx).unwrap();
+        let mut stream = rx.recv_from_stream(4, 2).unwrap();
+        tx.send(b"a").unwrap();
+        tx.send(b"b").unwrap();
+        // Hold both buffers, so the kernel runs out of them.
+        let (a, _) = stream.next().await.unwrap().unwrap();
+        let (b, _) = stream.next().await.unwrap().unwrap();
+        assert_eq!((&a[..], &b[..]), (&b"a"[..], &b"b"[..]));
+        tx.send(b"c").unwrap();
+        tx.send(b"d").unwrap();
+        drop((a, b));
+
+        for expected in [b"c", b"d"] {
+            let (buf, addr) = stream.next().await.unwrap().unwrap();
+            assert_eq!(&buf[..], expected);
+            assert!(addr.is_unnamed());
+        }
+    });
+}
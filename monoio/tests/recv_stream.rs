@@ -0,0 +1,94 @@
+use std::{future::Future, pin::pin, task::Poll};
+
+use monoio::{
+    io::{stream::Stream, AsyncReadRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+async fn tcp_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let conn = TcpStream::connect(addr).await.unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+    (conn, peer)
+}
+
+#[monoio::test_all]
+async fn recv_until_eof() {
+    let (mut conn, mut peer) = tcp_pair().await;
+    let writer = monoio::spawn(async move {
+        for i in 0..16_u8 {
+            peer.write_all(vec![i; 100]).await.0.unwrap();
+        }
+    });
+
+    let mut received = Vec::new();
+    let mut stream = conn.recv_stream(64, 4).unwrap();
+    while let Some(buf) = stream.next().await {
+        let buf = buf.unwrap();
+        assert!(!buf.is_empty() && buf.len() <= 64);
+        received.extend_from_slice(&buf);
+    }
+    writer.await;
+    let expected = (0..16_u8).flat_map(|i| [i; 100]).collect::<Vec<_>>();
+    assert_eq!(received, expected);
+}
+
+#[monoio::test_all]
+async fn buffered_bytes_first() {
+    let (mut conn, mut peer) = tcp_pair().await;
+    conn.enable_read_buffer(64);
+    peer.write_all(b"hello").await.0.unwrap();
+    let (res, buf) = conn.read(vec![0; 1]).await;
+    assert_eq!(res.unwrap(), 1);
+    assert_eq!(buf, b"h");
+
+    let mut stream = conn.recv_stream(64, 4).unwrap();
+    assert_eq!(&stream.next().await.unwrap().unwrap()[..], b"ello");
+    peer.write_all(b"world").await.0.unwrap();
+    assert_eq!(&stream.next().await.unwrap().unwrap()[..], b"world");
+}
+
+#[monoio::test_all]
+async fn drop_in_flight() {
+    let (mut conn, mut peer) = tcp_pair().await;
+    {
+        let mut stream = conn.recv_stream(64, 4).unwrap();
+        // Nothing to receive yet, the stream is dropped while waiting.
+        let mut next = pin!(stream.next());
+        std::future::poll_fn(|cx| {
+            assert!(next.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+    }
+    peer.write_all(b"after").await.0.unwrap();
+    let (res, buf) = conn.read(Vec::with_capacity(8)).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(buf, b"after");
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn rearm_when_buffers_run_out() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let (mut conn, mut peer) = tcp_pair().await;
+        let mut stream = conn.recv_stream(4, 2).unwrap();
+        peer.write_all(vec![1; 8]).await.0.unwrap();
+        // Hold both buffers, so the kernel runs out of them.
+        let a = stream.next().await.unwrap().unwrap();
+        let b = stream.next().await.unwrap().unwrap();
+        assert_eq!((a.len(), b.len()), (4, 4));
+        peer.write_all(vec![2; 8]).await.0.unwrap();
+        drop((a, b));
+
+        let mut received = Vec::new();
+        while received.len() < 8 {
+            received.extend_from_slice(&stream.next().await.unwrap().unwrap());
+        }
+        assert_eq!(received, [2; 8]);
+    });
+}
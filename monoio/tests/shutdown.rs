@@ -0,0 +1,66 @@
+#![cfg(unix)]
+
+use std::net::Shutdown;
+
+use monoio::{
+    io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream, UnixStream},
+};
+
+#[monoio::test_all]
+async fn tcp_shutdown_write() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    let (mut srv, _) = listener.accept().await.unwrap();
+
+    cli.write_all(b"request").await.0.unwrap();
+    cli.shutdown().await.unwrap();
+
+    // The peer reads eof after the data, and can still answer.
+    let (res, buf) = srv.read_exact(vec![0; 7]).await;
+    res.unwrap();
+    assert_eq!(buf, b"request");
+    let (res, _) = srv.read(vec![0; 1]).await;
+    assert_eq!(res.unwrap(), 0);
+    srv.write_all(b"response").await.0.unwrap();
+    srv.shutdown_with(Shutdown::Write).await.unwrap();
+
+    let (res, buf) = cli.read_exact(vec![0; 8]).await;
+    res.unwrap();
+    assert_eq!(buf, b"response");
+    let (res, _) = cli.read(vec![0; 1]).await;
+    assert_eq!(res.unwrap(), 0);
+}
+
+#[monoio::test_all]
+async fn tcp_shutdown_read() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let cli = TcpStream::connect(addr).await.unwrap();
+    let (mut srv, _) = listener.accept().await.unwrap();
+
+    srv.shutdown_with(Shutdown::Read).await.unwrap();
+    let (res, _) = srv.read(vec![0; 1]).await;
+    assert_eq!(res.unwrap(), 0);
+    // The write half is still open.
+    srv.write_all(b"still open").await.0.unwrap();
+    drop(cli);
+}
+
+#[monoio::test_all]
+async fn unix_shutdown_write() {
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+
+    a.write_all(b"hello").await.0.unwrap();
+    a.shutdown_with(Shutdown::Write).await.unwrap();
+    let (res, buf) = b.read_exact(vec![0; 5]).await;
+    res.unwrap();
+    assert_eq!(buf, b"hello");
+    let (res, _) = b.read(vec![0; 1]).await;
+    assert_eq!(res.unwrap(), 0);
+
+    // Writing after the shutdown fails.
+    let (res, _) = a.write(b"again").await;
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::BrokenPipe);
+}
@@ -0,0 +1,95 @@
+use std::{
+    cell::RefCell,
+    convert::Infallible,
+    future::{ready, Ready},
+    rc::Rc,
+    time::Duration,
+};
+
+use monoio::io::{
+    sink::{Sink, SinkExt},
+    stream::{iter, Stream, StreamExt},
+};
+
+#[derive(Default)]
+struct VecSink {
+    items: Vec<u32>,
+    flushed: usize,
+    closed: bool,
+}
+
+impl Sink<u32> for VecSink {
+    type Error = Infallible;
+
+    type SendFuture<'a> = Ready<Result<(), Infallible>>;
+    type FlushFuture<'a> = Ready<Result<(), Infallible>>;
+    type CloseFuture<'a> = Ready<Result<(), Infallible>>;
+
+    fn send(&mut self, item: u32) -> Self::SendFuture<'_> {
+        self.items.push(item);
+        ready(Ok(()))
+    }
+
+    fn flush(&mut self) -> Self::FlushFuture<'_> {
+        self.flushed = self.items.len();
+        ready(Ok(()))
+    }
+
+    fn close(&mut self) -> Self::CloseFuture<'_> {
+        self.flushed = self.items.len();
+        self.closed = true;
+        ready(Ok(()))
+    }
+}
+
+async fn collect<S: Stream>(mut stream: S) -> Vec<S::Item> {
+    let mut items = Vec::new();
+    while let Some(item) = stream.next().await {
+        items.push(item);
+    }
+    items
+}
+
+#[monoio::test_all]
+async fn filter_map_chunks() {
+    let chunks = iter(0..10).filter(|i| i % 2 == 0).map(|i| i * 10).chunks(2);
+    assert_eq!(
+        collect(chunks).await,
+        vec![vec![0, 20], vec![40, 60], vec![80]]
+    );
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn buffered() {
+    let started = Rc::new(RefCell::new(Vec::new()));
+    let started_clone = started.clone();
+    // Later futures finish first, but outputs keep the order.
+    let mut stream = iter(0..4u64)
+        .map(move |i| {
+            let started = started_clone.clone();
+            async move {
+                started.borrow_mut().push(i);
+                monoio::time::sleep(Duration::from_millis(40 - 10 * i)).await;
+                i
+            }
+        })
+        .buffered(2);
+    assert_eq!(stream.next().await, Some(0));
+    // The first 2 futures run together, and the third is pulled once the
+    // first one is taken.
+    assert_eq!(*started.borrow(), vec![0, 1]);
+    assert_eq!(collect(stream).await, vec![1, 2, 3]);
+}
+
+#[monoio::test_all]
+async fn forward_and_send_all() {
+    let mut sink = VecSink::default();
+    sink.send_all(iter(0..3)).await.unwrap();
+    assert_eq!(sink.items, vec![0, 1, 2]);
+    assert_eq!(sink.flushed, 3);
+    assert!(!sink.closed);
+
+    iter(3..5).forward(&mut sink).await.unwrap();
+    assert_eq!(sink.items, vec![0, 1, 2, 3, 4]);
+    assert!(sink.closed);
+}
@@ -0,0 +1,129 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use monoio::{
+    sync::local::{Mutex, Notify, RwLock, Semaphore},
+    time::sleep,
+};
+
+#[monoio::test_all(timer_enabled = true)]
+async fn mutex() {
+    let mutex = Rc::new(Mutex::new(0));
+    let mut tasks = Vec::new();
+    for _ in 0..10 {
+        let mutex = mutex.clone();
+        tasks.push(monoio::spawn(async move {
+            let mut guard = mutex.lock().await;
+            let value = *guard;
+            // Held across an await point.
+            sleep(Duration::from_millis(1)).await;
+            *guard = value + 1;
+        }));
+    }
+    for task in tasks {
+        task.await;
+    }
+    assert_eq!(*mutex.lock().await, 10);
+
+    let guard = mutex.lock().await;
+    assert!(mutex.try_lock().is_none());
+    drop(guard);
+    assert!(mutex.try_lock().is_some());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn rwlock_writer_not_starved() {
+    let lock = Rc::new(RwLock::new(0));
+    let read = lock.read().await;
+    assert!(lock.try_read().is_some());
+
+    let writer = monoio::spawn({
+        let lock = lock.clone();
+        async move { *lock.write().await += 1 }
+    });
+    sleep(Duration::from_millis(1)).await;
+    // The writer is waiting, so new readers queue behind it.
+    assert!(lock.try_read().is_none());
+    let reader = monoio::spawn({
+        let lock = lock.clone();
+        async move { *lock.read().await }
+    });
+    sleep(Duration::from_millis(1)).await;
+    drop(read);
+    writer.await;
+    assert_eq!(reader.await, 1);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn semaphore_fifo_and_cancel() {
+    let semaphore = Rc::new(Semaphore::new(2));
+    let permit = semaphore.acquire().await;
+    assert_eq!(semaphore.available_permits(), 1);
+
+    // A waiter asking for two permits is not passed by one asking for one.
+    let order = Rc::new(Cell::new(0));
+    let many = monoio::spawn({
+        let (semaphore, order) = (semaphore.clone(), order.clone());
+        async move {
+            let _permits = semaphore.acquire_many(2).await;
+            order.set(order.get() * 10 + 2);
+        }
+    });
+    sleep(Duration::from_millis(1)).await;
+    let one = monoio::spawn({
+        let (semaphore, order) = (semaphore.clone(), order.clone());
+        async move {
+            let _permit = semaphore.acquire().await;
+            order.set(order.get() * 10 + 1);
+        }
+    });
+    sleep(Duration::from_millis(1)).await;
+    assert_eq!(order.get(), 0);
+    drop(permit);
+    many.await;
+    one.await;
+    assert_eq!(order.get(), 21);
+    assert_eq!(semaphore.available_permits(), 2);
+
+    // A cancelled waiter does not block the ones behind it.
+    let permit = semaphore.acquire().await;
+    monoio::select! {
+        _ = semaphore.acquire_many(2) => unreachable!(),
+        _ = sleep(Duration::from_millis(1)) => {}
+    }
+    assert!(semaphore.try_acquire().is_some());
+    permit.forget();
+    assert_eq!(semaphore.available_permits(), 1);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn notify() {
+    let notify = Rc::new(Notify::new());
+    // The permit is stored when nobody is waiting.
+    notify.notify_one();
+    notify.notified().await;
+
+    let woken = Rc::new(Cell::new(0));
+    let mut tasks = Vec::new();
+    for _ in 0..3 {
+        let (notify, woken) = (notify.clone(), woken.clone());
+        tasks.push(monoio::spawn(async move {
+            notify.notified().await;
+            woken.set(woken.get() + 1);
+        }));
+    }
+    sleep(Duration::from_millis(1)).await;
+    notify.notify_one();
+    sleep(Duration::from_millis(1)).await;
+    assert_eq!(woken.get(), 1);
+    notify.notify_waiters();
+    for task in tasks {
+        task.await;
+    }
+    assert_eq!(woken.get(), 3);
+
+    // `notify_waiters` stores no permit.
+    monoio::select! {
+        _ = notify.notified() => unreachable!(),
+        _ = sleep(Duration::from_millis(1)) => {}
+    }
+}
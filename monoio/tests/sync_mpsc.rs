@@ -0,0 +1,53 @@
+#![cfg(feature = "sync")]
+
+use monoio::sync::mpsc::{channel, TryRecvError, TrySendError};
+
+#[monoio::test_all]
+async fn cross_thread() {
+    let (tx, mut rx) = channel(1);
+
+    // An async sender on another runtime.
+    let async_tx = tx.clone();
+    let runtime_thread = std::thread::spawn(move || {
+        let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+            .build()
+            .unwrap();
+        rt.block_on(async move {
+            for i in 0..100 {
+                async_tx.send(i).await.unwrap();
+            }
+        })
+    });
+    // A blocking sender on a plain thread.
+    let plain_thread = std::thread::spawn(move || {
+        for i in 100..200 {
+            tx.blocking_send(i).unwrap();
+        }
+    });
+
+    let mut received = Vec::new();
+    while let Some(i) = rx.recv().await {
+        received.push(i);
+    }
+    runtime_thread.join().unwrap();
+    plain_thread.join().unwrap();
+
+    // Values of each sender keep their order.
+    let (first, second): (Vec<_>, Vec<_>) = received.into_iter().partition(|i| *i < 100);
+    assert_eq!(first, (0..100).collect::<Vec<_>>());
+    assert_eq!(second, (100..200).collect::<Vec<_>>());
+}
+
+#[monoio::test_all]
+async fn full_and_closed() {
+    let (tx, mut rx) = channel(1);
+    tx.try_send(1).unwrap();
+    assert!(matches!(tx.try_send(2), Err(TrySendError::Full(2))));
+    assert_eq!(rx.len(), 1);
+    assert_eq!(rx.recv().await, Some(1));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(3).await.unwrap_err().0, 3);
+}
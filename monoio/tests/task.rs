@@ -0,0 +1,258 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use monoio::time::sleep;
+
+struct SetOnDrop(Rc<Cell<bool>>);
+
+impl Drop for SetOnDrop {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn abort_pending() {
+    let dropped = Rc::new(Cell::new(false));
+    let finished = Rc::new(Cell::new(false));
+    let guard = SetOnDrop(dropped.clone());
+    let done = finished.clone();
+    let handle = monoio::spawn(async move {
+        let _guard = guard;
+        sleep(Duration::from_secs(10)).await;
+        done.set(true);
+    });
+    // Let the task start and wait on the sleep.
+    sleep(Duration::from_millis(1)).await;
+    assert!(!handle.is_finished());
+
+    handle.abort();
+    sleep(Duration::from_millis(1)).await;
+    assert!(dropped.get());
+    assert!(!finished.get());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn abort_before_poll() {
+    let started = Rc::new(Cell::new(false));
+    let flag = started.clone();
+    let handle = monoio::spawn(async move { flag.set(true) });
+    handle.abort();
+    sleep(Duration::from_millis(1)).await;
+    assert!(!started.get());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn finished_and_detached() {
+    let handle = monoio::spawn(async { 1 });
+    assert!(!handle.is_finished());
+    sleep(Duration::from_millis(1)).await;
+    assert!(handle.is_finished());
+    assert_eq!(handle.await, 1);
+
+    let finished = Rc::new(Cell::new(false));
+    let done = finished.clone();
+    monoio::spawn(async move {
+        sleep(Duration::from_millis(1)).await;
+        done.set(true);
+    })
+    .detach();
+    sleep(Duration::from_millis(10)).await;
+    assert!(finished.get());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn panic_caught() {
+    let handle = monoio::spawn(async { panic!("task panicked") });
+    assert!(matches!(
+        handle.catch_panic().await,
+        Err(monoio::task::JoinError::Panic)
+    ));
+
+    // Awaiting the handle resumes the panic in the awaiting task.
+    let outer = monoio::spawn(async {
+        let inner: monoio::task::JoinHandle<()> = monoio::spawn(async { panic!("task panicked") });
+        inner.await;
+    });
+    assert!(outer.catch_panic().await.is_err());
+
+    // Other tasks keep running.
+    assert_eq!(monoio::spawn(async { 1 }).await, 1);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn task_group() {
+    use monoio::task::TaskGroup;
+
+    let mut group = TaskGroup::new();
+    for i in 0..3u64 {
+        // The later spawned ones finish first.
+        group.spawn(async move {
+            sleep(Duration::from_millis(3 - i)).await;
+            i
+        });
+    }
+    assert_eq!(group.len(), 3);
+    assert_eq!(group.join_all().await, vec![0, 1, 2]);
+
+    let mut group = TaskGroup::new();
+    group.spawn(async { 1 });
+    assert_eq!(group.join_next().await, Some(1));
+    assert_eq!(group.join_next().await, None);
+
+    // The other tasks are aborted on error.
+    let dropped = Rc::new(Cell::new(false));
+    let guard = SetOnDrop(dropped.clone());
+    let mut group = TaskGroup::new();
+    group.spawn(async move {
+        let _guard = guard;
+        sleep(Duration::from_secs(10)).await;
+        Ok(())
+    });
+    group.spawn(async { Err("failed") });
+    assert_eq!(group.try_join_all().await, Err("failed"));
+    sleep(Duration::from_millis(1)).await;
+    assert!(dropped.get());
+
+    // And when the group is dropped.
+    let dropped = Rc::new(Cell::new(false));
+    let guard = SetOnDrop(dropped.clone());
+    let mut group = TaskGroup::new();
+    group.spawn(async move {
+        let _guard = guard;
+        sleep(Duration::from_secs(10)).await;
+    });
+    drop(group);
+    sleep(Duration::from_millis(1)).await;
+    assert!(dropped.get());
+}
+
+#[monoio::test_all]
+async fn task_group_many() {
+    use monoio::task::TaskGroup;
+
+    let mut group = TaskGroup::new();
+    for i in 0..1000u64 {
+        group.spawn(async move {
+            monoio::task::yield_now().await;
+            i
+        });
+    }
+    let mut sum = 0;
+    for _ in 0..500 {
+        sum += group.join_next().await.unwrap();
+    }
+    // The slots of the joined tasks are taken again.
+    for i in 1000..1500u64 {
+        group.spawn(async move { i });
+    }
+    assert_eq!(group.len(), 1000);
+    let outputs = group.join_all().await;
+    assert_eq!(outputs.len(), 1000);
+    assert!(outputs.windows(2).all(|w| w[0] < w[1]));
+    sum += outputs.iter().sum::<u64>();
+    assert_eq!(sum, (0..1500).sum());
+}
+
+#[cfg(feature = "sync")]
+#[monoio::test_all(timer_enabled = true)]
+async fn spawn_on_from_other_thread() {
+    let handle = monoio::RuntimeHandle::current();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let runtime_thread = std::thread::current().id();
+    std::thread::spawn(move || {
+        assert!(handle.spawn_on(move || async move {
+            // The future is made on the runtime thread, so it may hold an `Rc`.
+            let on_runtime = Rc::new(std::thread::current().id() == runtime_thread);
+            sleep(Duration::from_millis(1)).await;
+            tx.send(*on_runtime).unwrap();
+        }));
+    })
+    .join()
+    .unwrap();
+
+    loop {
+        if let Ok(on_runtime) = rx.try_recv() {
+            assert!(on_runtime);
+            break;
+        }
+        sleep(Duration::from_millis(1)).await;
+    }
+}
+
+// With the io_uring driver every read waits for its completion, so only reads
+// of the legacy driver, which complete right away on a ready socket, rely on
+// the budget to yield.
+#[cfg(feature = "legacy")]
+#[monoio::test(driver = "legacy", timer_enabled = true)]
+async fn ready_io_yields_to_other_tasks() {
+    use std::io::Write;
+
+    use monoio::{io::AsyncReadRent, net::TcpListener};
+
+    const READS: usize = 1000;
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut peer = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    peer.write_all(&[0; READS]).unwrap();
+    let (mut conn, _) = listener.accept().await.unwrap();
+    // Let all the data arrive, so every read below finds it ready.
+    sleep(Duration::from_millis(10)).await;
+
+    let ran = Rc::new(Cell::new(false));
+    let _task = monoio::spawn({
+        let ran = ran.clone();
+        async move { ran.set(true) }
+    });
+    let mut buf = vec![0; 1];
+    for _ in 0..READS {
+        let (res, b) = conn.read(buf).await;
+        assert_eq!(res.unwrap(), 1);
+        buf = b;
+    }
+    // The reader is made to yield once its budget is used up.
+    assert!(ran.get());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn yield_now_interleaves() {
+    let order = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let handles: Vec<_> = ['a', 'b']
+        .into_iter()
+        .map(|name| {
+            let order = order.clone();
+            monoio::spawn(async move {
+                for _ in 0..3 {
+                    order.borrow_mut().push(name);
+                    monoio::task::yield_now().await;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await;
+    }
+    // Each task goes behind the other when it yields.
+    assert_eq!(*order.borrow(), ['a', 'b', 'a', 'b', 'a', 'b']);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn priority_order() {
+    use monoio::task::Priority;
+
+    let order = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let handles: Vec<_> = [Priority::Low, Priority::Normal, Priority::High]
+        .into_iter()
+        .map(|priority| {
+            let order = order.clone();
+            monoio::spawn_with_priority(priority, async move {
+                order.borrow_mut().push(priority);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await;
+    }
+    assert_eq!(
+        *order.borrow(),
+        [Priority::High, Priority::Normal, Priority::Low]
+    );
+}
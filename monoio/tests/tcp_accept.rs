@@ -1,8 +1,8 @@
 use std::net::{IpAddr, SocketAddr};
 
 use monoio::net::{TcpListener, TcpStream};
-#[cfg(unix)]
 
+#[cfg(unix)]
 macro_rules! test_accept {
     ($(($ident:ident, $target:expr),)*) => {
         $(
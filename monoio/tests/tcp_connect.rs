@@ -1,8 +1,8 @@
 use std::net::{IpAddr, SocketAddr};
 
 use monoio::net::{TcpListener, TcpStream};
-#[cfg(unix)]
 
+#[cfg(unix)]
 macro_rules! test_connect_ip {
     ($(($ident:ident, $target:expr, $addr_f:path),)*) => {
         $(
@@ -35,8 +35,8 @@ test_connect_ip! {
     (connect_v4, "127.0.0.1:0", SocketAddr::is_ipv4),
     (connect_v6, "[::1]:0", SocketAddr::is_ipv6),
 }
-#[cfg(unix)]
 
+#[cfg(unix)]
 macro_rules! test_connect {
     ($(($ident:ident, $mapping:tt),)*) => {
         $(
@@ -109,6 +109,38 @@ async fn connect_invalid_dst() {
     assert!(TcpStream::connect("127.0.0.1:1").await.is_err());
 }
 
+#[cfg(unix)]
+#[monoio::test_all(timer_enabled = true)]
+async fn connect_timeout() {
+    use std::time::Duration;
+
+    let timeout = Duration::from_millis(200);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let stream = TcpStream::connect_timeout(addr, timeout).await.unwrap();
+    assert_eq!(stream.peer_addr().unwrap(), addr);
+
+    // Connects hang once the accept queue of a listener is full.
+    let socket = monoio::net::TcpSocket::new_v4().unwrap();
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let _listener = socket.listen(0).unwrap();
+    let mut streams = Vec::new();
+    let err = loop {
+        match TcpStream::connect_timeout(addr, timeout).await {
+            Ok(stream) => streams.push(stream),
+            Err(e) => break e,
+        }
+        assert!(streams.len() < 8);
+    };
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+
+    let err = TcpStream::connect_timeout(addr, Duration::ZERO)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
 #[derive(Default, Clone)]
 struct DropFlag(std::rc::Rc<std::cell::RefCell<bool>>);
 
@@ -1,10 +1,10 @@
 use std::{
-    io::{Error, ErrorKind, Read, Result, Write},
+    io::{Error, Read, Result, Write},
     net, thread,
 };
 
 use monoio::{
-    io::{AsyncReadRent, AsyncWriteRentExt, Splitable},
+    io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt, Splitable},
     net::{TcpListener, TcpStream},
     try_join,
 };
@@ -93,10 +93,7 @@ async fn drop_write() -> Result<()> {
         let mut read_buf = [0u8; 32];
         let res = match stream.read(&mut read_buf) {
             Ok(0) => Ok(()),
-            Ok(len) => Err(Error::new(
-                ErrorKind::Other,
-                format!("Unexpected read: {len} bytes."),
-            )),
+            Ok(len) => Err(Error::other(format!("Unexpected read: {len} bytes."))),
             Err(err) => Err(err),
         };
 
@@ -125,3 +122,39 @@ async fn drop_write() -> Result<()> {
     handle.join().unwrap().unwrap();
     Ok(())
 }
+
+/// Test that the owned halves can be moved into separately spawned tasks.
+#[monoio::test_all]
+async fn spawn_halves() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let (mut client, (server, _)) = try_join! {
+        TcpStream::connect(&addr),
+        listener.accept(),
+    }?;
+
+    let (mut read_half, mut write_half) = server.into_split();
+    let reader = monoio::spawn(async move {
+        let mut received = Vec::new();
+        loop {
+            let (res, buf) = read_half.read(Vec::with_capacity(64)).await;
+            if res.unwrap() == 0 {
+                return received;
+            }
+            received.extend_from_slice(&buf);
+        }
+    });
+    let writer = monoio::spawn(async move {
+        write_half.write_all(b"world".to_vec()).await.0.unwrap();
+        write_half.shutdown().await.unwrap();
+    });
+
+    client.write_all(b"hello".to_vec()).await.0?;
+    client.shutdown().await?;
+    let (res, buf) = client.read_exact(vec![0; 5]).await;
+    res?;
+    assert_eq!(buf, b"world");
+    assert_eq!(reader.await, b"hello");
+    writer.await;
+    Ok(())
+}
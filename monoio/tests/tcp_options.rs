@@ -0,0 +1,114 @@
+#[cfg(target_os = "linux")]
+use std::os::unix::io::AsRawFd;
+use std::time::Duration;
+
+use monoio::net::{tcp::TcpKeepalive, ListenerConfig, TcpListener, TcpStream};
+
+#[monoio::test_all]
+async fn stream_options() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (cli, srv) = monoio::join!(TcpStream::connect(addr), listener.accept());
+    let (cli, _srv) = (cli.unwrap(), srv.unwrap());
+
+    cli.set_nodelay(true).unwrap();
+    assert!(cli.nodelay().unwrap());
+    cli.set_nodelay(false).unwrap();
+    assert!(!cli.nodelay().unwrap());
+
+    cli.set_ttl(42).unwrap();
+    assert_eq!(cli.ttl().unwrap(), 42);
+
+    assert_eq!(cli.linger().unwrap(), None);
+    cli.set_linger(Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(cli.linger().unwrap(), Some(Duration::from_secs(1)));
+    cli.set_linger(None).unwrap();
+
+    assert!(cli.take_error().unwrap().is_none());
+}
+
+#[monoio::test_all]
+async fn listener_options() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_ttl(42).unwrap();
+    assert_eq!(listener.ttl().unwrap(), 42);
+    assert!(listener.take_error().unwrap().is_none());
+
+    // Accepted streams inherit the TTL.
+    let addr = listener.local_addr().unwrap();
+    let (_cli, srv) = monoio::join!(TcpStream::connect(addr), listener.accept());
+    let (srv, _) = srv.unwrap();
+    assert_eq!(srv.ttl().unwrap(), 42);
+}
+
+#[cfg(target_os = "linux")]
+fn keepalive_params(fd: std::os::unix::io::RawFd) -> [libc::c_int; 3] {
+    [libc::TCP_KEEPIDLE, libc::TCP_KEEPINTVL, libc::TCP_KEEPCNT].map(|opt| {
+        let mut value: libc::c_int = 0;
+        let mut len = std::mem::size_of_val(&value) as libc::socklen_t;
+        let res = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::IPPROTO_TCP,
+                opt,
+                &mut value as *mut _ as *mut _,
+                &mut len,
+            )
+        };
+        assert_eq!(res, 0);
+        value
+    })
+}
+
+#[monoio::test_all]
+async fn keepalive() {
+    let keepalive = TcpKeepalive::new()
+        .with_time(Duration::from_secs(60))
+        .with_interval(Duration::from_secs(10))
+        .with_retries(3);
+    let config = ListenerConfig::default().tcp_keepalive(keepalive);
+    let listener = TcpListener::bind_with_config("127.0.0.1:0", &config).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (cli, srv) = monoio::join!(TcpStream::connect(addr), listener.accept());
+    let (cli, (srv, _)) = (cli.unwrap(), srv.unwrap());
+
+    // Set on accepted streams by the listener.
+    assert!(srv.keepalive().unwrap());
+    #[cfg(target_os = "linux")]
+    assert_eq!(keepalive_params(srv.as_raw_fd()), [60, 10, 3]);
+
+    assert!(!cli.keepalive().unwrap());
+    cli.set_keepalive(Some(keepalive.with_time(Duration::from_secs(30))))
+        .unwrap();
+    assert!(cli.keepalive().unwrap());
+    #[cfg(target_os = "linux")]
+    assert_eq!(keepalive_params(cli.as_raw_fd()), [30, 10, 3]);
+    cli.set_keepalive(None).unwrap();
+    assert!(!cli.keepalive().unwrap());
+}
+
+#[cfg(unix)]
+#[monoio::test_all]
+async fn reuse_port() {
+    let first = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = first.local_addr().unwrap();
+    // Another shard binds the same address.
+    let second = TcpListener::bind(addr).unwrap();
+    assert_eq!(second.local_addr().unwrap(), addr);
+
+    // Without it the address is in use.
+    let config = ListenerConfig::default().reuse_port(false);
+    let err = TcpListener::bind_with_config(addr, &config).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+    // A connection goes to either listener.
+    let accept = async {
+        monoio::select! {
+            res = first.accept() => res,
+            res = second.accept() => res,
+        }
+    };
+    let (cli, srv) = monoio::join!(TcpStream::connect(addr), accept);
+    let (cli, (srv, _)) = (cli.unwrap(), srv.unwrap());
+    assert_eq!(cli.local_addr().unwrap(), srv.peer_addr().unwrap());
+}
@@ -0,0 +1,208 @@
+use monoio::{
+    io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+#[monoio::test_all]
+async fn small_reads_are_buffered() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let client = monoio::spawn(async move {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let (res, _) = stream.write_all(b"hello world").await;
+        res.unwrap();
+    });
+
+    let (mut stream, _) = listener.accept().await.unwrap();
+    stream.enable_read_buffer(64);
+    client.await;
+
+    let (res, buf) = stream.read_exact(vec![0; 5]).await;
+    res.unwrap();
+    assert_eq!(&buf, b"hello");
+    assert_eq!(stream.read_buffered(), 6);
+
+    let (res, buf) = stream.read(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap(), 6);
+    assert_eq!(&buf, b" world");
+    assert_eq!(stream.read_buffered(), 0);
+
+    // peer closed
+    let (res, _) = stream.read(vec![0; 4]).await;
+    assert_eq!(res.unwrap(), 0);
+}
+
+#[monoio::test_all]
+async fn peek() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, accepted) = monoio::join!(TcpStream::connect(addr), listener.accept());
+    let (mut client, (mut stream, _)) = (client.unwrap(), accepted.unwrap());
+
+    client.write_all(b"\x16\x03\x01 hello").await.0.unwrap();
+    // Peeking does not consume the data.
+    let (res, buf) = stream.peek(vec![0; 1]).await;
+    assert_eq!(res.unwrap(), 1);
+    assert_eq!(buf, b"\x16");
+    let (res, buf) = stream.read_exact(vec![0; 9]).await;
+    res.unwrap();
+    assert_eq!(buf, b"\x16\x03\x01 hello");
+
+    // Bytes in the read buffer are peeked first.
+    stream.enable_read_buffer(64);
+    client.write_all(b"buffered").await.0.unwrap();
+    let (res, buf) = stream.read_exact(vec![0; 3]).await;
+    res.unwrap();
+    assert_eq!(buf, b"buf");
+    let (res, buf) = stream.peek(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(buf, b"fered");
+    assert_eq!(stream.read_buffered(), 5);
+
+    // EOF
+    drop(client);
+    let (res, _) = stream.read(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap(), 5);
+    let (res, _) = stream.peek(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap(), 0);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn stop_keeps_order() {
+    use std::{io::Read, time::Duration};
+
+    use monoio::io::stream::Stream;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, accepted) = monoio::join!(TcpStream::connect(addr), listener.accept());
+    let (mut client, (mut stream, _)) = (client.unwrap(), accepted.unwrap());
+    stream.enable_read_buffer(64);
+
+    client.write_all(b"one").await.0.unwrap();
+    let (res, buf) = stream.read_exact(vec![0; 3]).await;
+    res.unwrap();
+    assert_eq!(buf, b"one");
+    // Wait for data while the buffer is empty.
+    client.write_all(b"two").await.0.unwrap();
+    let (res, buf) = stream.peek(vec![0; 3]).await;
+    assert_eq!(res.unwrap(), 3);
+    assert_eq!(buf, b"two");
+
+    // The buffer is refilled by single recvs with a read timeout.
+    stream
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .unwrap();
+    client.write_all(b"three").await.0.unwrap();
+    let (res, buf) = stream.read_exact(vec![0; 8]).await;
+    res.unwrap();
+    assert_eq!(buf, b"twothree");
+    stream.set_read_timeout(None).unwrap();
+
+    client.write_all(b"four").await.0.unwrap();
+    let (res, buf) = stream.read_exact(vec![0; 1]).await;
+    res.unwrap();
+    assert_eq!(buf, b"f");
+    {
+        let mut recv = stream.recv_stream(64, 4).unwrap();
+        assert_eq!(&recv.next().await.unwrap().unwrap()[..], b"our");
+        client.write_all(b"five").await.0.unwrap();
+        assert_eq!(&recv.next().await.unwrap().unwrap()[..], b"five");
+    }
+
+    client.write_all(b"six").await.0.unwrap();
+    let (res, buf) = stream.read_exact(vec![0; 3]).await;
+    res.unwrap();
+    assert_eq!(buf, b"six");
+    let mut stream = stream.into_std().await.unwrap();
+    client.write_all(b"seven").await.0.unwrap();
+    let mut buf = [0; 5];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"seven");
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn multishot_refill() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (client, accepted) = monoio::join!(TcpStream::connect(addr), listener.accept());
+        let (mut client, (mut stream, _)) = (client.unwrap(), accepted.unwrap());
+        stream.enable_read_buffer(64);
+
+        let before = monoio::stats::driver_stats();
+        for i in 0..16_u8 {
+            client.write_all(vec![i; 4]).await.0.unwrap();
+            let (res, buf) = stream.read_exact(vec![0; 4]).await;
+            res.unwrap();
+            assert_eq!(buf, [i; 4]);
+        }
+        // One send per message, and the recv is only armed once.
+        let after = monoio::stats::driver_stats();
+        assert_eq!(after.sqes_submitted - before.sqes_submitted, 17);
+    });
+}
+
+#[monoio::test_all]
+async fn proxy_forwards_buffered() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, accepted) = monoio::join!(TcpStream::connect(addr), listener.accept());
+    let (mut client, (mut stream, _)) = (client.unwrap(), accepted.unwrap());
+    let backend = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = backend.local_addr().unwrap();
+    let (upstream, accepted) = monoio::join!(TcpStream::connect(addr), backend.accept());
+    let (mut upstream, (mut backend, _)) = (upstream.unwrap(), accepted.unwrap());
+    stream.enable_read_buffer(64);
+
+    client.write_all(b"GET / more").await.0.unwrap();
+    let (res, buf) = stream.read_exact(vec![0; 6]).await;
+    res.unwrap();
+    assert_eq!(buf, b"GET / ");
+    assert_eq!(stream.read_buffered(), 4);
+    // Received while the buffer is waiting for data.
+    client.write_all(b" and more").await.0.unwrap();
+    client.shutdown().await.unwrap();
+
+    let proxy = monoio::spawn(async move {
+        monoio::io::zero_copy_bidirectional(&mut stream, &mut upstream).await
+    });
+    let (res, buf) = backend.read_exact(vec![0; 13]).await;
+    res.unwrap();
+    assert_eq!(buf, b"more and more");
+    let (res, _) = backend.read(vec![0; 4]).await;
+    assert_eq!(res.unwrap(), 0);
+    drop(backend);
+    assert_eq!(proxy.await.unwrap(), (13, 0));
+}
+
+#[cfg(all(target_os = "linux", feature = "splice"))]
+#[monoio::test_all]
+async fn splice_forwards_buffered() {
+    use monoio::io::splice::splice;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, accepted) = monoio::join!(TcpStream::connect(addr), listener.accept());
+    let (mut client, (mut stream, _)) = (client.unwrap(), accepted.unwrap());
+    let (mut tx, mut rx) = monoio::net::UnixStream::pair().unwrap();
+    stream.enable_read_buffer(64);
+
+    client.write_all(b"onetwo").await.0.unwrap();
+    let (res, buf) = stream.read_exact(vec![0; 3]).await;
+    res.unwrap();
+    assert_eq!(buf, b"one");
+    client.write_all(b"three").await.0.unwrap();
+
+    // Only part of the buffered bytes.
+    assert_eq!(splice(&mut stream, &mut tx, 2).await.unwrap(), 2);
+    assert_eq!(splice(&mut stream, &mut tx, 6).await.unwrap(), 6);
+    let (res, buf) = rx.read_exact(vec![0; 8]).await;
+    res.unwrap();
+    assert_eq!(buf, b"twothree");
+}
@@ -0,0 +1,58 @@
+#![cfg(unix)]
+
+use std::time::Duration;
+
+use monoio::{
+    io::{AsyncReadRent, AsyncWriteRentExt},
+    net::{tcp::TcpKeepalive, TcpSocket},
+};
+
+#[monoio::test_all]
+async fn listen_and_connect() {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_reuseaddr(true).unwrap();
+    assert!(socket.reuseaddr().unwrap());
+    socket.set_reuseport(true).unwrap();
+    assert!(socket.reuseport().unwrap());
+    socket.set_recv_buffer_size(1 << 16).unwrap();
+    assert!(socket.recv_buffer_size().unwrap() >= 1 << 16);
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let listener = socket.listen(16).unwrap();
+    assert_eq!(listener.local_addr().unwrap(), addr);
+
+    let client = TcpSocket::new_v4().unwrap();
+    client.set_nodelay(true).unwrap();
+    client.set_linger(Some(Duration::from_secs(1))).unwrap();
+    client
+        .set_keepalive(Some(TcpKeepalive::new().with_time(Duration::from_secs(60))))
+        .unwrap();
+    client.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let client_addr = client.local_addr().unwrap();
+
+    let (cli, srv) = monoio::join!(client.connect(addr), listener.accept());
+    let (mut cli, (mut srv, peer)) = (cli.unwrap(), srv.unwrap());
+    assert_eq!(peer, client_addr);
+    // Options set before connecting stay on the stream.
+    assert!(cli.nodelay().unwrap());
+    assert_eq!(cli.linger().unwrap(), Some(Duration::from_secs(1)));
+    assert!(cli.keepalive().unwrap());
+
+    cli.write_all(b"hello").await.0.unwrap();
+    let (res, buf) = srv.read(Vec::with_capacity(8)).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(buf, b"hello");
+}
+
+#[monoio::test_all]
+async fn connect_refused() {
+    // Take a free port and close it again.
+    let addr = {
+        let socket = TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        socket.local_addr().unwrap()
+    };
+    let socket = TcpSocket::new_v4().unwrap();
+    let err = socket.connect(addr).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+}
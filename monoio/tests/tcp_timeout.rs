@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use monoio::{
+    io::{AsyncReadRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+async fn tcp_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let conn = TcpStream::connect(addr).await.unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+    (conn, peer)
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn read_timeout() {
+    let (mut conn, mut peer) = tcp_pair().await;
+    conn.set_read_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+    assert_eq!(conn.read_timeout(), Some(Duration::from_millis(50)));
+
+    let (res, buf) = conn.read(Vec::with_capacity(8)).await;
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::TimedOut);
+
+    // The timed out read is gone, so nothing sent later is lost.
+    peer.write_all(b"hello").await.0.unwrap();
+    let (res, buf) = conn.read(buf).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(buf, b"hello");
+
+    conn.set_read_timeout(None).unwrap();
+    assert_eq!(conn.read_timeout(), None);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn buffered_read_timeout() {
+    let (mut conn, mut peer) = tcp_pair().await;
+    conn.enable_read_buffer(64);
+    conn.set_read_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+    let (res, buf) = conn.read(Vec::with_capacity(8)).await;
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::TimedOut);
+
+    peer.write_all(b"hello").await.0.unwrap();
+    let (res, buf) = conn.read(buf).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(buf, b"hello");
+}
+
+#[monoio::test_all]
+async fn zero_timeout() {
+    let (mut conn, _peer) = tcp_pair().await;
+    let err = conn.set_read_timeout(Some(Duration::ZERO)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(conn.set_write_timeout(Some(Duration::ZERO)).is_err());
+    conn.set_write_timeout(Some(Duration::from_secs(1)))
+        .unwrap();
+    assert_eq!(conn.write_timeout(), Some(Duration::from_secs(1)));
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn cancel_read_with_timeout() {
+    use monoio::io::{CancelableAsyncReadRent, Canceller};
+
+    let (mut conn, _peer) = tcp_pair().await;
+    conn.set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let canceller = Canceller::new();
+    let handle = canceller.handle();
+    let reader = monoio::spawn(async move {
+        let (res, _) = conn.cancelable_read(Vec::with_capacity(64), handle).await;
+        res
+    });
+
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    canceller.cancel();
+    // Canceled rather than timed out.
+    assert_eq!(
+        reader.await.unwrap_err().raw_os_error(),
+        Some(libc::ECANCELED)
+    );
+}
@@ -0,0 +1,137 @@
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[monoio::test(driver = "uring", timer_enabled = true)]
+async fn many_sleeps_share_park_timeout() {
+    use std::time::Duration;
+
+    const SLEEPS: u64 = 10_000;
+
+    let before = monoio::stats::driver_stats();
+    let handles: Vec<_> = (0..SLEEPS)
+        .map(|i| monoio::spawn(monoio::time::sleep(Duration::from_millis(1 + i % 50))))
+        .collect();
+    for handle in handles {
+        handle.await;
+    }
+    let after = monoio::stats::driver_stats();
+    // Sleeps are kept in the timer wheel, the ring only gets one timeout for
+    // the nearest deadline each time the driver parks.
+    assert!(after.sqes_submitted - before.sqes_submitted < SLEEPS / 10);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn timeout_and_timeout_at() {
+    use std::{rc::Rc, time::Duration};
+
+    use monoio::time::{sleep, timeout, timeout_at, Instant};
+
+    // The futures hold an `Rc`, they need not be `Send`.
+    let value = Rc::new(1);
+    let fut = async {
+        sleep(Duration::from_millis(1)).await;
+        *value
+    };
+    assert_eq!(timeout(Duration::from_secs(1), fut).await.unwrap(), 1);
+
+    let fut = async {
+        sleep(Duration::from_secs(1)).await;
+        *value
+    };
+    timeout(Duration::from_millis(10), fut).await.unwrap_err();
+
+    let deadline = Instant::now() + Duration::from_millis(10);
+    let fut = async {
+        sleep(Duration::from_secs(1)).await;
+        *value
+    };
+    timeout_at(deadline, fut).await.unwrap_err();
+    assert!(Instant::now() >= deadline);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn select_local_futures() {
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    use monoio::time::sleep;
+
+    let polled = Rc::new(Cell::new(0));
+    let ready = |n| {
+        let polled = polled.clone();
+        async move {
+            polled.set(polled.get() + 1);
+            n
+        }
+    };
+
+    // With `biased;` the branches are polled in order, so the first ready
+    // one always wins and the later ones are never polled.
+    for _ in 0..10 {
+        let n = monoio::select! {
+            biased;
+            n = ready(1) => n,
+            n = ready(2) => n,
+        };
+        assert_eq!(n, 1);
+    }
+    assert_eq!(polled.get(), 10);
+
+    let n = monoio::select! {
+        _ = sleep(Duration::from_secs(1)) => 0,
+        n = ready(3) => n,
+    };
+    assert_eq!(n, 3);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn try_join_returns_early() {
+    use std::{rc::Rc, time::Duration};
+
+    use monoio::time::{sleep, Instant};
+
+    let value = Rc::new(1);
+    let (a, b) = monoio::join!(async { *value }, async {
+        sleep(Duration::from_millis(1)).await;
+        *value + 1
+    });
+    assert_eq!((a, b), (1, 2));
+
+    let start = Instant::now();
+    let res: Result<((), ()), &str> = monoio::try_join!(
+        async {
+            sleep(Duration::from_secs(10)).await;
+            Ok(())
+        },
+        async {
+            sleep(Duration::from_millis(1)).await;
+            Err("failed")
+        },
+    );
+    assert_eq!(res, Err("failed"));
+    // The slow branch is dropped instead of awaited.
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[cfg(all(unix, feature = "legacy"))]
+#[monoio::test(driver = "legacy", timer_enabled = true)]
+async fn legacy_short_sleeps() {
+    use std::time::Duration;
+
+    use monoio::time::{interval, sleep, Instant};
+
+    // Each sleep parks the driver for about a millisecond.
+    let start = Instant::now();
+    for _ in 0..100 {
+        sleep(Duration::from_millis(1)).await;
+    }
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+
+    let start = Instant::now();
+    let mut interval = interval(Duration::from_millis(2));
+    for _ in 0..50 {
+        interval.tick().await;
+    }
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(98));
+    assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+}
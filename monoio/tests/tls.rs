@@ -0,0 +1,186 @@
+#![cfg(feature = "rustls")]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use monoio::{
+    io::{AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt},
+    net::{
+        tls::{
+            rustls::{
+                server::{ServerSessionMemoryCache, StoresServerSessions},
+                Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName,
+            },
+            TlsAcceptor, TlsConnector,
+        },
+        TcpListener, TcpStream,
+    },
+};
+
+// Session cache which counts resumed sessions.
+struct CountingCache {
+    inner: Arc<ServerSessionMemoryCache>,
+    resumed: Arc<AtomicUsize>,
+}
+
+impl StoresServerSessions for CountingCache {
+    fn put(&self, key: Vec<u8>, value: Vec<u8>) -> bool {
+        self.inner.put(key, value)
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.inner.get(key)
+    }
+
+    fn take(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let r = self.inner.take(key);
+        if r.is_some() {
+            self.resumed.fetch_add(1, Ordering::Relaxed);
+        }
+        r
+    }
+
+    fn can_cache(&self) -> bool {
+        true
+    }
+}
+
+#[monoio::test_all]
+async fn tls_echo_with_alpn_and_resumption() {
+    const MSG: &[u8] = b"hello tls";
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+    let cert_der = Certificate(cert.serialize_der().unwrap());
+    let key_der = PrivateKey(cert.serialize_private_key_der());
+
+    let resumed = Arc::new(AtomicUsize::new(0));
+    let mut server_config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert_der.clone()], key_der)
+        .unwrap();
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    server_config.session_storage = Arc::new(CountingCache {
+        inner: ServerSessionMemoryCache::new(16),
+        resumed: resumed.clone(),
+    });
+    let acceptor = TlsAcceptor::from(server_config);
+
+    let mut roots = RootCertStore::empty();
+    roots.add(&cert_der).unwrap();
+    let mut client_config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    client_config.alpn_protocols = vec![b"h2".to_vec()];
+    let connector = TlsConnector::from(client_config);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    monoio::spawn(async move {
+        for _ in 0..2 {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = acceptor.accept(stream).await.unwrap();
+            assert_eq!(stream.alpn_protocol(), Some(&b"h2"[..]));
+            let (r, buf) = stream.read_exact(vec![0; MSG.len()]).await;
+            r.unwrap();
+            let (r, _) = stream.write_all(buf).await;
+            r.unwrap();
+            stream.shutdown().await.unwrap();
+        }
+    });
+
+    for _ in 0..2 {
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let domain = ServerName::try_from("localhost").unwrap();
+        let mut stream = connector.connect(domain, stream).await.unwrap();
+        assert_eq!(stream.alpn_protocol(), Some(&b"h2"[..]));
+        let (r, _) = stream.write_all(MSG).await;
+        r.unwrap();
+        let (r, buf) = stream.read_exact(vec![0; MSG.len()]).await;
+        r.unwrap();
+        assert_eq!(buf, MSG);
+        // Server closed the session with close_notify.
+        let (r, _) = monoio::io::AsyncReadRent::read(&mut stream, vec![0; 8]).await;
+        assert_eq!(r.unwrap(), 0);
+    }
+    assert_eq!(resumed.load(Ordering::Relaxed), 1);
+}
+
+fn configs() -> (TlsAcceptor, TlsConnector) {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+    let cert_der = Certificate(cert.serialize_der().unwrap());
+    let key_der = PrivateKey(cert.serialize_private_key_der());
+    let server_config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert_der.clone()], key_der)
+        .unwrap();
+    let mut roots = RootCertStore::empty();
+    roots.add(&cert_der).unwrap();
+    let client_config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    (
+        TlsAcceptor::from(server_config),
+        TlsConnector::from(client_config),
+    )
+}
+
+#[monoio::test_all]
+async fn tls_transfer_over_many_records() {
+    // Far more than a record and the internal buffers hold.
+    const LEN: usize = 1024 * 1024;
+
+    let (acceptor, connector) = configs();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = monoio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = acceptor.accept(stream).await.unwrap();
+        let (r, buf) = stream.read_exact(vec![0; LEN]).await;
+        r.unwrap();
+        let (r, _) = stream.write_all(buf).await;
+        r.unwrap();
+        stream.shutdown().await.unwrap();
+    });
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let domain = ServerName::try_from("localhost").unwrap();
+    let mut stream = connector.connect(domain, stream).await.unwrap();
+    let data: Vec<u8> = (0..LEN).map(|i| i as u8).collect();
+    let (r, data) = stream.write_all(data).await;
+    r.unwrap();
+    let (r, buf) = stream.read_exact(vec![0; LEN]).await;
+    r.unwrap();
+    assert!(buf == data);
+    server.await;
+}
+
+#[monoio::test_all]
+async fn tls_untrusted_certificate() {
+    let (acceptor, _) = configs();
+    // Trusts a certificate other than the one of the server.
+    let (_, connector) = configs();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = monoio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        acceptor.accept(stream).await.map(|_| ()).unwrap_err()
+    });
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let domain = ServerName::try_from("localhost").unwrap();
+    let err = connector
+        .connect(domain, stream)
+        .await
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    // The server sees the alert sent by the client.
+    assert_eq!(server.await.kind(), std::io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    fmt::Write as _,
+    os::unix::io::AsRawFd,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use monoio::io::{AsyncReadRentExt, AsyncWriteRentExt};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// Records spans and events as `name field=value ...` lines.
+#[derive(Default)]
+struct Recorder {
+    lines: Mutex<Vec<String>>,
+    next_id: AtomicU64,
+}
+
+struct Fields<'a>(&'a mut String);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let _ = write!(self.0, " {}={:?}", field.name(), value);
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+        let mut line = attrs.metadata().name().to_string();
+        attrs.record(&mut Fields(&mut line));
+        self.lines.lock().unwrap().push(line);
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = String::from("event");
+        event.record(&mut Fields(&mut line));
+        self.lines.lock().unwrap().push(line);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[monoio::test_all]
+async fn op_spans() {
+    let recorder = Arc::new(Recorder::default());
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let listener = monoio::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut client = monoio::net::TcpStream::connect(addr).await.unwrap();
+    let (mut server, _) = listener.accept().await.unwrap();
+    client.write_all(b"hello").await.0.unwrap();
+    let (res, _) = server.read_exact(vec![0; 5]).await;
+    res.unwrap();
+
+    let lines = recorder.lines.lock().unwrap();
+    let fd = client.as_raw_fd();
+    assert!(lines
+        .iter()
+        .any(|line| line == &format!("op op=\"Send\" fd={fd}")));
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("completed") && line.contains("result=Ok(5) latency_us=")));
+}
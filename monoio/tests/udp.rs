@@ -0,0 +1,185 @@
+#![cfg(unix)]
+
+use monoio::net::{
+    udp::{EcnCodepoint, SendMeta},
+    UdpSocket,
+};
+
+#[monoio::test_all]
+async fn send_to_and_recv_from() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let b_addr = b.local_addr().unwrap();
+
+    let (res, _) = a.send_to(b"hello", b_addr).await;
+    assert_eq!(res.unwrap(), 5);
+    let (res, buf) = b.recv_from(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap(), (5, a_addr));
+    assert_eq!(buf, b"hello");
+
+    // A datagram longer than the buffer is truncated.
+    let (res, _) = b.send_to(b"hello world", a_addr).await;
+    assert_eq!(res.unwrap(), 11);
+    let (res, buf) = a.recv_from(Vec::with_capacity(5)).await;
+    assert_eq!(res.unwrap(), (5, b_addr));
+    assert_eq!(buf, b"hello");
+}
+
+#[monoio::test_all]
+async fn connect_send_and_recv() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let b_addr = b.local_addr().unwrap();
+    assert!(a.peer_addr().is_err());
+
+    a.connect(b_addr).await.unwrap();
+    b.connect(a_addr).await.unwrap();
+    assert_eq!(a.peer_addr().unwrap(), b_addr);
+
+    let (res, _) = a.send(b"ping").await;
+    assert_eq!(res.unwrap(), 4);
+    let (res, buf) = b.recv(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap(), 4);
+    assert_eq!(buf, b"ping");
+
+    let (res, _) = b.send(b"pong").await;
+    assert_eq!(res.unwrap(), 4);
+    let (res, buf) = a.recv_from(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap(), (4, b_addr));
+    assert_eq!(buf, b"pong");
+}
+
+#[monoio::test_all]
+async fn peek() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+
+    let (res, _) = b.send_to(b"hello", a_addr).await;
+    res.unwrap();
+    let (res, buf) = a.peek(Vec::with_capacity(2)).await;
+    assert_eq!(res.unwrap(), 2);
+    assert_eq!(buf, b"he");
+    // The datagram is still there.
+    let (res, buf) = a.recv_from(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap().0, 5);
+    assert_eq!(buf, b"hello");
+}
+
+fn meta_ce() -> SendMeta {
+    SendMeta {
+        ecn: Some(EcnCodepoint::Ce),
+        ..Default::default()
+    }
+}
+
+#[monoio::test_all]
+async fn ecn_and_pktinfo() {
+    let a = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let b = UdpSocket::bind("0.0.0.0:0").unwrap();
+    b.set_recv_ecn(true).unwrap();
+    b.set_recv_pktinfo(true).unwrap();
+    let b_addr = format!("127.0.0.1:{}", b.local_addr().unwrap().port());
+
+    let meta = SendMeta {
+        ecn: Some(EcnCodepoint::Ect0),
+        src_ip: Some("127.0.0.1".parse().unwrap()),
+        ..Default::default()
+    };
+    let (res, _) = a
+        .send_msg(b"hello", Some(b_addr.parse().unwrap()), meta)
+        .await;
+    assert_eq!(res.unwrap(), 5);
+    let (res, buf) = b.recv_msg(Vec::with_capacity(16)).await;
+    let meta = res.unwrap();
+    assert_eq!(buf, b"hello");
+    assert_eq!(meta.len, 5);
+    assert_eq!(meta.addr.port(), a.local_addr().unwrap().port());
+    assert_eq!(meta.ecn, Some(EcnCodepoint::Ect0));
+    assert_eq!(meta.dst_ip, Some("127.0.0.1".parse().unwrap()));
+    assert_eq!(meta.segment_size, None);
+
+    // Without the options, no metadata is reported.
+    b.set_recv_ecn(false).unwrap();
+    b.set_recv_pktinfo(false).unwrap();
+    let (res, _) = a
+        .send_msg(b"world", Some(b_addr.parse().unwrap()), meta_ce())
+        .await;
+    res.unwrap();
+    let (res, _) = b.recv_msg(Vec::with_capacity(16)).await;
+    let meta = res.unwrap();
+    assert_eq!((meta.ecn, meta.dst_ip), (None, None));
+}
+
+#[monoio::test_all]
+async fn ecn_v6() {
+    let a = UdpSocket::bind("[::1]:0").unwrap();
+    let b = UdpSocket::bind("[::1]:0").unwrap();
+    b.set_recv_ecn(true).unwrap();
+
+    let (res, _) = a
+        .send_msg(b"hello", Some(b.local_addr().unwrap()), meta_ce())
+        .await;
+    res.unwrap();
+    let (res, _) = b.recv_msg(Vec::with_capacity(16)).await;
+    let meta = res.unwrap();
+    assert_eq!(meta.addr, a.local_addr().unwrap());
+    assert_eq!(meta.ecn, Some(EcnCodepoint::Ce));
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn segmentation_offload() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    // The buffer is sent as datagrams of 1000 bytes.
+    let meta = SendMeta {
+        segment_size: Some(1000),
+        ..Default::default()
+    };
+    let (res, _) = a
+        .send_msg(vec![1; 2500], Some(b.local_addr().unwrap()), meta)
+        .await;
+    assert_eq!(res.unwrap(), 2500);
+    for len in [1000, 1000, 500] {
+        let (res, _) = b.recv_msg(Vec::with_capacity(4096)).await;
+        let meta = res.unwrap();
+        assert_eq!(meta.len, len);
+        assert_eq!(meta.segment_size, None);
+    }
+
+    // With GRO, they may be received in one buffer.
+    b.set_recv_gro(true).unwrap();
+    let (res, _) = a
+        .send_msg(vec![1; 2500], Some(b.local_addr().unwrap()), meta)
+        .await;
+    res.unwrap();
+    let mut received = 0;
+    while received < 2500 {
+        let (res, _) = b.recv_msg(Vec::with_capacity(4096)).await;
+        let meta = res.unwrap();
+        if meta.len > 1000 {
+            assert_eq!(meta.segment_size, Some(1000));
+        }
+        received += meta.len;
+    }
+    assert_eq!(received, 2500);
+}
+
+#[monoio::test_all]
+async fn into_std() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let a = a.into_std().await.unwrap();
+
+    let (res, _) = b.send_to(b"hello", a_addr).await;
+    assert_eq!(res.unwrap(), 5);
+    let mut buf = [0; 16];
+    let (n, addr) = a.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"hello");
+    assert_eq!(addr, b.local_addr().unwrap());
+}
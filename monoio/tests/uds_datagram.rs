@@ -0,0 +1,84 @@
+#![cfg(unix)]
+
+use monoio::net::UnixDatagram;
+
+#[monoio::test_all]
+async fn send_to_and_recv_from() {
+    let dir = tempfile::tempdir().unwrap();
+    let a_path = dir.path().join("a.sock");
+    let b_path = dir.path().join("b.sock");
+    let a = UnixDatagram::bind(&a_path).unwrap();
+    let b = UnixDatagram::bind(&b_path).unwrap();
+
+    let (res, _) = a.send_to(b"hello", &b_path).await;
+    assert_eq!(res.unwrap(), 5);
+    let (res, buf) = b.recv_from(Vec::with_capacity(16)).await;
+    let (n, addr) = res.unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(addr.as_pathname(), Some(a_path.as_path()));
+    assert_eq!(buf, b"hello");
+
+    // Reply to the source address.
+    let (res, _) = b.send_to_addr(b"hello world", addr).await;
+    assert_eq!(res.unwrap(), 11);
+    // A datagram longer than the buffer is truncated.
+    let (res, buf) = a.recv_from(Vec::with_capacity(5)).await;
+    let (n, addr) = res.unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(addr.as_pathname(), Some(b_path.as_path()));
+    assert_eq!(buf, b"hello");
+
+    let (res, _) = a.send_to(b"lost", dir.path().join("missing.sock")).await;
+    assert!(res.is_err());
+}
+
+#[monoio::test_all]
+async fn pair_send_and_recv() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+
+    let (res, _) = a.send(b"ping").await;
+    assert_eq!(res.unwrap(), 4);
+    let (res, buf) = b.recv(Vec::with_capacity(16)).await;
+    assert_eq!(res.unwrap(), 4);
+    assert_eq!(buf, b"ping");
+
+    let (res, _) = b.send(b"pong").await;
+    assert_eq!(res.unwrap(), 4);
+    let (res, buf) = a.recv_from(Vec::with_capacity(16)).await;
+    let (n, addr) = res.unwrap();
+    assert_eq!(n, 4);
+    assert!(addr.is_unnamed());
+    assert_eq!(buf, b"pong");
+}
+
+#[monoio::test_all]
+async fn connect_and_send() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("server.sock");
+    let server = UnixDatagram::bind(&path).unwrap();
+    let client = UnixDatagram::connect(&path).await.unwrap();
+    assert_eq!(
+        client.peer_addr().unwrap().as_pathname(),
+        Some(path.as_path())
+    );
+
+    let (res, _) = client.send(b"notify").await;
+    assert_eq!(res.unwrap(), 6);
+    let (res, buf) = server.recv_from(Vec::with_capacity(16)).await;
+    let (n, addr) = res.unwrap();
+    assert_eq!(n, 6);
+    assert!(addr.is_unnamed());
+    assert_eq!(buf, b"notify");
+}
+
+#[monoio::test_all]
+async fn into_std() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    let a = a.into_std().await.unwrap();
+
+    let (res, _) = b.send(b"hello").await;
+    assert_eq!(res.unwrap(), 5);
+    let mut buf = [0; 16];
+    let n = a.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"hello");
+}
@@ -27,8 +27,8 @@ async fn split() -> std::io::Result<()> {
 
     Ok(())
 }
-#[cfg(unix)]
 
+#[cfg(unix)]
 async fn send_recv_all<R: AsyncReadRent, W: AsyncWriteRent>(
     read: &mut R,
     write: &mut W,
@@ -42,3 +42,56 @@ async fn send_recv_all<R: AsyncReadRent, W: AsyncWriteRent>(
     assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
     Ok(buf)
 }
+
+/// Checks that the owned halves of `UnixStream` can be moved into separately
+/// spawned tasks, and that dropping the write half shuts down the stream.
+#[cfg(unix)]
+#[monoio::test_all]
+async fn into_split() -> std::io::Result<()> {
+    let (a, mut b) = UnixStream::pair()?;
+    let (mut read_half, write_half) = a.into_split();
+    assert!(write_half.local_addr()?.is_unnamed());
+
+    let reader = monoio::spawn(async move {
+        let (res, buf) = read_half.read_exact(vec![0; 5]).await;
+        res.unwrap();
+        buf
+    });
+    let writer = monoio::spawn(async move {
+        let mut write_half = write_half;
+        write_half.write_all(b"hello").await.0.unwrap();
+    });
+
+    b.write_all(b"world").await.0?;
+    writer.await;
+    let (res, buf) = b.read(Vec::with_capacity(16)).await;
+    assert_eq!(res?, 5);
+    assert_eq!(buf, b"hello");
+    let (res, _) = b.read(Vec::with_capacity(16)).await;
+    assert_eq!(res?, 0);
+    assert_eq!(reader.await, b"world");
+    Ok(())
+}
+
+/// Checks that reuniting the halves gives back a stream which is still
+/// writable.
+#[cfg(unix)]
+#[monoio::test_all]
+async fn reunite() -> std::io::Result<()> {
+    let (a, mut b) = UnixStream::pair()?;
+    let (c, _d) = UnixStream::pair()?;
+    let (read_a, write_a) = a.into_split();
+    let (_read_c, write_c) = c.into_split();
+
+    let read_a = match read_a.reunite(write_c) {
+        Ok(_) => panic!("Reunite should not succeed"),
+        Err(err) => err.0,
+    };
+    let mut a = read_a.reunite(write_a).expect("Reunite should succeed");
+
+    a.write_all(b"hello").await.0?;
+    let (res, buf) = b.read_exact(vec![0; 5]).await;
+    res?;
+    assert_eq!(buf, b"hello");
+    Ok(())
+}
@@ -51,3 +51,66 @@ async fn shutdown() -> std::io::Result<()> {
     assert_eq!(n, 0);
     Ok(())
 }
+
+#[cfg(unix)]
+#[monoio::test_all]
+async fn pass_fds() -> std::io::Result<()> {
+    use std::{
+        fs::File,
+        io::{Read, Seek, Write},
+        os::unix::io::AsRawFd,
+    };
+
+    let (mut a, mut b) = UnixStream::pair()?;
+    let mut file = tempfile::tempfile()?;
+    file.write_all(b"passed")?;
+
+    let (res, _) = a.send_with_fds(b"fd", &[file.as_raw_fd()]).await;
+    assert_eq!(res?, 2);
+    drop(file);
+    let (res, buf) = b.recv_with_fds(Vec::with_capacity(8), 4).await;
+    let (n, mut fds) = res?;
+    assert_eq!(n, 2);
+    assert_eq!(buf, b"fd");
+    assert_eq!(fds.len(), 1);
+    let flags = unsafe { libc::fcntl(fds[0].as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags & libc::FD_CLOEXEC, 0);
+
+    // The received fd refers to the same file.
+    let mut file = File::from(fds.pop().unwrap());
+    file.rewind()?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)?;
+    assert_eq!(content, "passed");
+
+    // Data sent without fds comes with none.
+    a.write_all(b"plain").await.0?;
+    let (res, buf) = b.recv_with_fds(Vec::with_capacity(8), 4).await;
+    let (n, fds) = res?;
+    assert_eq!(n, 5);
+    assert_eq!(buf, b"plain");
+    assert!(fds.is_empty());
+
+    // More fds than asked for fail the receive, but keep the data.
+    let (res, _) = a.send_with_fds(b"two", &[0, 1, 2]).await;
+    assert_eq!(res?, 3);
+    let (res, buf) = b.recv_with_fds(Vec::with_capacity(8), 1).await;
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(buf, b"two");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[monoio::test_all]
+async fn listener_into_std() -> std::io::Result<()> {
+    let dir = tempfile::Builder::new()
+        .prefix("monoio-uds-tests")
+        .tempdir()
+        .unwrap();
+    let sock_path = dir.path().join("into_std.sock");
+
+    let listener = UnixListener::bind(&sock_path)?.into_std().await?;
+    let _client = std::os::unix::net::UnixStream::connect(&sock_path)?;
+    listener.accept()?;
+    Ok(())
+}
@@ -61,3 +61,106 @@ async fn zero_copy_for_uds() {
     assert_eq!(zero_copy(&mut rx, &mut tx).await.unwrap(), MSG.len() as u64);
     c_tx.closed().await;
 }
+
+#[cfg(all(target_os = "linux", feature = "splice"))]
+async fn tcp_pair() -> (monoio::net::TcpStream, monoio::net::TcpStream) {
+    let listener = monoio::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let conn = monoio::net::TcpStream::connect(addr).await.unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+    (conn, peer)
+}
+
+#[cfg(all(target_os = "linux", feature = "splice"))]
+#[monoio::test_all]
+async fn splice_file_to_tcp() {
+    use monoio::{
+        fs::File,
+        io::{splice::splice, AsyncReadRentExt},
+    };
+
+    // More than a pipe holds, so it takes several rounds.
+    let content: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+    let tempfile = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(tempfile.path(), &content).unwrap();
+
+    let (mut conn, mut peer) = tcp_pair().await;
+    let expected = content.clone();
+    let reader = monoio::spawn(async move {
+        let (res, buf) = peer.read_exact(vec![0; 150_000]).await;
+        res.unwrap();
+        assert_eq!(buf, expected[..150_000]);
+        // The rest of the file.
+        let (res, buf) = peer.read_exact(vec![0; 50_000]).await;
+        res.unwrap();
+        assert_eq!(buf, expected[150_000..]);
+    });
+
+    let mut file = File::open(tempfile.path()).await.unwrap();
+    // Stops at `len`, and the next splice goes on from the file offset.
+    assert_eq!(
+        splice(&mut file, &mut conn, 150_000).await.unwrap(),
+        150_000
+    );
+    assert_eq!(
+        splice(&mut file, &mut conn, u64::MAX).await.unwrap(),
+        50_000
+    );
+    reader.await;
+}
+
+#[cfg(all(target_os = "linux", feature = "splice"))]
+#[monoio::test_all]
+async fn splice_tcp_to_file() {
+    use monoio::{
+        fs::File,
+        io::{splice::splice, AsyncWriteRentExt},
+    };
+
+    let content: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+    let tempfile = tempfile::NamedTempFile::new().unwrap();
+
+    let (mut conn, mut peer) = tcp_pair().await;
+    let writer = monoio::spawn({
+        let content = content.clone();
+        async move {
+            peer.write_all(content).await.0.unwrap();
+            // Closing it ends the splice.
+        }
+    });
+
+    let mut file = File::create(tempfile.path()).await.unwrap();
+    assert_eq!(
+        splice(&mut conn, &mut file, u64::MAX).await.unwrap(),
+        content.len() as u64
+    );
+    writer.await;
+    assert_eq!(std::fs::read(tempfile.path()).unwrap(), content);
+}
+
+#[cfg(all(target_os = "linux", feature = "splice"))]
+#[monoio::test_all]
+async fn tee_pipe() {
+    use monoio::{
+        fs::File,
+        io::splice::{tee, SpliceDestination, SpliceSource},
+        net::unix::new_pipe,
+    };
+
+    let content: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("src"), &content).unwrap();
+    let mut src = File::open(dir.path().join("src")).await.unwrap();
+    let (mut pr, mut pw) = new_pipe().unwrap();
+    let (mut mirror_pr, mut mirror_pw) = new_pipe().unwrap();
+
+    assert_eq!(src.splice_to_pipe(&mut pw, 1000).await.unwrap(), 1000);
+    assert_eq!(tee(&mut pr, &mut mirror_pw, 1000).await.unwrap(), 1000);
+
+    // Both pipes hold the data.
+    for (pipe, name) in [(&mut pr, "dst"), (&mut mirror_pr, "mirror")] {
+        let mut dst = File::create(dir.path().join(name)).await.unwrap();
+        assert_eq!(dst.splice_from_pipe(pipe, 1000).await.unwrap(), 1000);
+        assert_eq!(std::fs::read(dir.path().join(name)).unwrap(), content);
+    }
+}